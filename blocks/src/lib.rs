@@ -754,6 +754,7 @@ define_blocks! {
         offset None,
         material Material {
             absorbed_light: 2,
+            liquid: Some(material::Liquid::Water),
             ..material::TRANSPARENT
         },
         model { ("minecraft", "flowing_water") },
@@ -767,6 +768,7 @@ define_blocks! {
         data Some(level as usize),
         material Material {
             absorbed_light: 2,
+            liquid: Some(material::Liquid::Water),
             ..material::TRANSPARENT
         },
         model { ("minecraft", "water") },
@@ -782,6 +784,7 @@ define_blocks! {
         material Material {
             absorbed_light: 15,
             emitted_light: 15,
+            liquid: Some(material::Liquid::Lava),
             ..material::NON_SOLID
         },
         model { ("minecraft", "flowing_lava") },
@@ -796,6 +799,7 @@ define_blocks! {
         material Material {
             absorbed_light: 15,
             emitted_light: 15,
+            liquid: Some(material::Liquid::Lava),
             ..material::NON_SOLID
         },
         model { ("minecraft", "lava") },
@@ -1091,7 +1095,7 @@ define_blocks! {
                   + (facing.horizontal_offset() * (2 * 2))
                   + (if occupied { 0 } else { 2 })
                   + (if part == BedPart::Head { 0 } else { 1 })),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "bed") },
         variant format!("facing={},part={}", facing.as_string(), part.as_string()),
         collision vec![Aabb3::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 9.0/16.0, 1.0))],
@@ -1165,7 +1169,10 @@ define_blocks! {
     }
     Web {
         props {},
-        material material::NON_SOLID,
+        material Material {
+            stuck_speed: Some((0.25, 0.05)),
+            ..material::NON_SOLID
+        },
         model { ("minecraft", "web") },
         collision vec![],
         hardness 4.0,
@@ -1696,7 +1703,7 @@ define_blocks! {
         offset Some(if waterlogged { 0 } else { 1 } +
             type_.offset() * 2 +
             facing.horizontal_offset() * (2 * 3)),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "chest") },
         hardness 2.5,
         best_tools [ Tool::Axe(_), ],
@@ -1924,9 +1931,13 @@ define_blocks! {
         },
         data if !waterlogged { Some(facing.index()) } else { None },
         offset Some(if waterlogged { 0 } else { 1 } + facing.horizontal_offset() * 2),
-        material material::NON_SOLID,
+        material Material {
+            climbable: true,
+            ..material::NON_SOLID
+        },
         model { ("minecraft", "ladder") },
         variant format!("facing={}", facing.as_string()),
+        collision ladder_collision(facing),
         hardness 0.4,
         best_tools [ Tool::Axe(_), ],
     }
@@ -2263,6 +2274,7 @@ define_blocks! {
         props {},
         material Material {
             absorbed_light: 2,
+            slipperiness: 0.98,
             ..material::TRANSPARENT
         },
         model { ("minecraft", "ice") },
@@ -2382,7 +2394,10 @@ define_blocks! {
     }
     SoulSand {
         props {},
-        material material::NON_SOLID,
+        material Material {
+            speed_factor: 0.4,
+            ..material::NON_SOLID
+        },
         model { ("minecraft", "soul_sand") },
         collision vec![Aabb3::new(
             Point3::new(0.0, 0.0, 0.0),
@@ -2988,7 +3003,10 @@ define_blocks! {
                     if south { 0 } else { 1<<2 } +
                     if north { 0 } else { 1<<3 } +
                     if east { 0 } else { 1<<4 }),
-        material material::NON_SOLID,
+        material Material {
+            climbable: true,
+            ..material::NON_SOLID
+        },
         model { ("minecraft", "vine") },
         variant format!("east={},north={},south={},up={},west={}", east, north, south, up, west),
         tint TintType::Foliage,
@@ -3454,7 +3472,7 @@ define_blocks! {
         offset Some(if waterlogged { 0 } else { 1 } + facing.horizontal_offset() * 2),
         material Material {
             emitted_light: 7,
-            ..material::NON_SOLID
+            ..material::INVISIBLE
         },
         model { ("minecraft", "ender_chest") },
         variant format!("facing={}", facing.as_string()),
@@ -3822,7 +3840,7 @@ define_blocks! {
         },
         data if !nodrop { Some(facing.index()) } else { None },
         offset if !nodrop && facing != Direction::Up { Some(facing.horizontal_offset()) } else { None },
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "skull") },
         variant format!("facing={},nodrop={}", facing.as_string(), nodrop),
         collision {
@@ -3849,7 +3867,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(rotation as usize),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "skull") },
         collision {
             let (min_x, min_y, min_z, max_x, max_y, max_z) = (0.25, 0.0, 0.25, 0.75, 0.5, 0.75);
@@ -3872,7 +3890,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(facing.horizontal_offset()),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "skull") },
         collision {
             let (min_x, min_y, min_z, max_x, max_y, max_z) = match facing {
@@ -3896,7 +3914,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(rotation as usize),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "skull") },
         collision {
             let (min_x, min_y, min_z, max_x, max_y, max_z) = (0.25, 0.0, 0.25, 0.75, 0.5, 0.75);
@@ -3919,7 +3937,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(facing.horizontal_offset()),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "zombie_wall_head") },
         hardness 1.0,
     }
@@ -3929,7 +3947,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(rotation as usize),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "zombie_head") },
         hardness 1.0,
     }
@@ -3944,7 +3962,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(facing.horizontal_offset()),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "player_wall_head") },
         hardness 1.0,
     }
@@ -3954,7 +3972,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(rotation as usize),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "player_head") },
         hardness 1.0,
     }
@@ -3969,7 +3987,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(facing.horizontal_offset()),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "creeper_wall_head") },
         hardness 1.0,
     }
@@ -3979,7 +3997,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(rotation as usize),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "creeper_head") },
         hardness 1.0,
     }
@@ -3994,7 +4012,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(facing.horizontal_offset()),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "dragon_wall_head") },
         hardness 1.0,
     }
@@ -4004,7 +4022,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(rotation as usize),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "dragon_head") },
         hardness 1.0,
     }
@@ -4057,7 +4075,7 @@ define_blocks! {
         offset Some(if waterlogged { 0 } else { 1 } +
             type_.offset() * 2 +
             facing.horizontal_offset() * (2 * 3)),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "trapped_chest") },
         variant format!("facing={}", facing.as_string()),
         collision vec![Aabb3::new(
@@ -4462,7 +4480,11 @@ define_blocks! {
     }
     Slime {
         props {},
-        material material::TRANSPARENT,
+        material Material {
+            slipperiness: 0.8,
+            bouncy: true,
+            ..material::TRANSPARENT
+        },
         model { ("minecraft", "slime") },
         hardness 0.0,
     }
@@ -4650,6 +4672,10 @@ define_blocks! {
     }
     PackedIce {
         props {},
+        material Material {
+            slipperiness: 0.98,
+            ..material::SOLID
+        },
         model { ("minecraft", "packed_ice") },
         hardness 0.5,
         best_tools [ Tool::Pickaxe(_), ],
@@ -4721,7 +4747,7 @@ define_blocks! {
         },
         data if color != ColoredVariant::White { None } else { Some(rotation.data()) },
         offset Some(rotation.data() + color.data() * 16),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "standing_banner") },
         variant format!("rotation={}", rotation.as_string()),
         hardness 1.0,
@@ -4756,7 +4782,7 @@ define_blocks! {
         },
         data if color != ColoredVariant::White { None } else { Some(facing.index()) },
         offset Some(facing.horizontal_offset() + color.data() * 4),
-        material material::NON_SOLID,
+        material material::INVISIBLE,
         model { ("minecraft", "wall_banner") },
         variant format!("facing={}", facing.as_string()),
         hardness 1.0,
@@ -5637,6 +5663,11 @@ define_blocks! {
         },
         data if age == 0 { Some(0) } else { None },
         offset Some(age as usize),
+        material Material {
+            absorbed_light: 2,
+            slipperiness: 0.98,
+            ..material::TRANSPARENT
+        },
         model { ("minecraft", "frosted_ice") },
         hardness 0.5,
     }
@@ -5703,9 +5734,8 @@ define_blocks! {
         harvest_tools [ Tool::Pickaxe(_), ],
         best_tools [ Tool::Pickaxe(_), ],
     }
-    // TODO: Shulker box textures (1.11+), since there is no model, we use wool for now
-    // The textures should be built from textures/blocks/shulker_top_<color>.png
-    // and textures/entity/shulker/shulker_<color>.png
+    // Shulker boxes (1.11+) have no block model, they are invisible here and
+    // drawn as block entities from textures/entity/shulker/shulker_<color>.png
     ShulkerBox {
         props {
             facing: Direction = [
@@ -5719,6 +5749,7 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "sponge") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5736,6 +5767,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "white_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5753,6 +5785,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "orange_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5770,6 +5803,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "magenta_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5787,6 +5821,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "light_blue_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5804,6 +5839,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "yellow_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5821,6 +5857,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "lime_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5838,6 +5875,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "pink_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5855,6 +5893,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "gray_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5872,6 +5911,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "light_gray_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5889,6 +5929,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "cyan_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5906,6 +5947,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "purple_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5923,6 +5965,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "blue_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5940,6 +5983,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "brown_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5957,6 +6001,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "green_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5974,6 +6019,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "red_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -5991,6 +6037,7 @@ define_blocks! {
         },
         data Some(facing.index()),
         offset Some(facing.offset()),
+        material material::INVISIBLE,
         model { ("minecraft", "black_wool") },
         hardness 2.0,
         best_tools [ Tool::Pickaxe(_), ],
@@ -6470,6 +6517,10 @@ define_blocks! {
         props {},
         data None::<usize>,
         offset Some(0),
+        material Material {
+            slipperiness: 0.989,
+            ..material::SOLID
+        },
         model { ("minecraft", "blue_ice") },
         hardness 2.8,
         best_tools [ Tool::Pickaxe(_), ],
@@ -6514,7 +6565,13 @@ define_blocks! {
         },
         data None::<usize>,
         offset Some(if drag { 0 } else { 1 }),
+        material Material {
+            absorbed_light: 2,
+            liquid: Some(material::Liquid::Water),
+            ..material::TRANSPARENT
+        },
         model { ("minecraft", "bubble_column") },
+        collision vec![],
         hardness 0.0,
     }
     Missing253 {
@@ -7251,6 +7308,21 @@ fn piston_collision(extended: bool, facing: Direction) -> Vec<Aabb3<f64>> {
     )]
 }
 
+fn ladder_collision(facing: Direction) -> Vec<Aabb3<f64>> {
+    let (min_x, min_z, max_x, max_z) = match facing {
+        Direction::North => (0.0, 13.0 / 16.0, 1.0, 1.0),
+        Direction::South => (0.0, 0.0, 1.0, 3.0 / 16.0),
+        Direction::West => (13.0 / 16.0, 0.0, 1.0, 1.0),
+        Direction::East => (0.0, 0.0, 3.0 / 16.0, 1.0),
+        _ => unreachable!(),
+    };
+
+    vec![Aabb3::new(
+        Point3::new(min_x, 0.0, min_z),
+        Point3::new(max_x, 1.0, max_z),
+    )]
+}
+
 fn trapdoor_collision(facing: Direction, half: BlockHalf, open: bool) -> Vec<Aabb3<f64>> {
     let (min_x, min_y, min_z, max_x, max_y, max_z) = if open {
         match facing {
@@ -8894,7 +8966,7 @@ impl CoralVariant {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ToolMaterial {
     Wood,
     Stone,
@@ -8917,7 +8989,7 @@ impl ToolMaterial {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Tool {
     Pickaxe(ToolMaterial),
     Axe(ToolMaterial),
@@ -1,3 +1,10 @@
+/// A liquid that entities can swim in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Liquid {
+    Water,
+    Lava,
+}
+
 pub struct Material {
     pub renderable: bool,
     pub should_cull_against: bool,
@@ -7,6 +14,17 @@ pub struct Material {
     pub absorbed_light: u8,
     pub emitted_light: u8,
     pub collidable: bool,
+    /// How much speed is kept each tick by entities on top of the block
+    pub slipperiness: f64,
+    /// Multiplies the horizontal speed of entities on top of the block
+    pub speed_factor: f64,
+    /// Entities inside the block move at this fraction of their horizontal
+    /// and vertical speed and lose their momentum
+    pub stuck_speed: Option<(f64, f64)>,
+    pub liquid: Option<Liquid>,
+    pub climbable: bool,
+    /// Entities landing on the block bounce back up
+    pub bouncy: bool,
 }
 
 pub const INVISIBLE: Material = Material {
@@ -18,6 +36,12 @@ pub const INVISIBLE: Material = Material {
     absorbed_light: 0, // Special because of sky light
     emitted_light: 0,
     collidable: true,
+    slipperiness: 0.6,
+    speed_factor: 1.0,
+    stuck_speed: None,
+    liquid: None,
+    climbable: false,
+    bouncy: false,
 };
 
 pub const SOLID: Material = Material {
@@ -29,6 +53,7 @@ pub const SOLID: Material = Material {
     absorbed_light: 15,
     emitted_light: 0,
     collidable: true,
+    ..INVISIBLE
 };
 
 pub const NON_SOLID: Material = Material {
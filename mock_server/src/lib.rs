@@ -0,0 +1,487 @@
+//! A scripted server for testing clients without a real server or an
+//! internet connection.
+//!
+//! The server accepts one client on localhost, logs it in offline (with
+//! compression and encryption if asked to) and then sends it the scripted
+//! packets, recording everything the client sends back.
+
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+use steven_protocol::format;
+use steven_protocol::protocol::packet::handshake::serverbound::Handshake;
+use steven_protocol::protocol::packet::login::clientbound::*;
+use steven_protocol::protocol::packet::status::clientbound::*;
+use steven_protocol::protocol::packet::Packet;
+use steven_protocol::protocol::{
+    Conn, Context, Error, LenPrefixedBytes, PacketType, State, VarInt, UUID,
+};
+
+mod rsa;
+pub mod script;
+
+/// How long the server waits for the client to connect or send anything.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the server checks whether it has been finished while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+const VERIFY_TOKEN: [u8; 4] = [0x5e, 0x7e, 0x4e, 0x11];
+
+/// A server that hasn't been started yet, listening on a free port.
+pub struct MockServer {
+    listener: TcpListener,
+    protocol_version: i32,
+    compression_threshold: Option<i32>,
+    encryption: bool,
+    script: Vec<Scripted>,
+}
+
+/// Something sent to the client once it is playing.
+enum Scripted {
+    Packet(Packet),
+    Raw(RawPacket),
+}
+
+/// A packet id followed by whatever bytes, to send packets the client
+/// can't read.
+struct RawPacket {
+    id: i32,
+    data: Vec<u8>,
+}
+
+impl PacketType for RawPacket {
+    fn packet_id(&self, _protocol_version: i32) -> i32 {
+        self.id
+    }
+
+    fn write<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
+        buf.write_all(&self.data)?;
+        Ok(())
+    }
+}
+
+impl MockServer {
+    /// Starts listening on localhost for a client of the protocol version.
+    pub fn bind(protocol_version: i32) -> io::Result<MockServer> {
+        Ok(MockServer {
+            listener: TcpListener::bind("127.0.0.1:0")?,
+            protocol_version,
+            compression_threshold: None,
+            encryption: false,
+            script: Vec::new(),
+        })
+    }
+
+    /// Returns the address clients should connect to.
+    pub fn address(&self) -> String {
+        // Only fails if the listener was closed
+        self.listener.local_addr().unwrap().to_string()
+    }
+
+    /// Compresses packets over the threshold after login. Ignored for
+    /// versions without compression.
+    pub fn compression(mut self, threshold: i32) -> MockServer {
+        self.compression_threshold = Some(threshold);
+        self
+    }
+
+    /// Asks the client to encrypt the connection during login.
+    pub fn encryption(mut self) -> MockServer {
+        self.encryption = true;
+        self
+    }
+
+    /// Adds a packet to send once the client is playing. Packets are sent
+    /// in the order they were added.
+    pub fn send(mut self, packet: Packet) -> MockServer {
+        self.script.push(Scripted::Packet(packet));
+        self
+    }
+
+    /// Adds a packet with the id and body as given, without checking that
+    /// the client could read it.
+    pub fn send_raw(mut self, id: i32, data: Vec<u8>) -> MockServer {
+        self.script.push(Scripted::Raw(RawPacket { id, data }));
+        self
+    }
+
+    /// Serves a single client on another thread.
+    pub fn spawn(self) -> Running {
+        let finished = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let finished = finished.clone();
+            thread::spawn(move || self.serve(&finished))
+        };
+        Running { finished, thread }
+    }
+
+    fn serve(self, finished: &AtomicBool) -> Result<Session, Error> {
+        let stream = self.accept(finished)?;
+        let probe = stream.try_clone()?;
+        let mut conn = Conn::accepted(stream, self.protocol_version)?;
+
+        let handshake = match conn.read_packet()? {
+            Packet::Handshake(val) => val,
+            val => return Err(unexpected(&val)),
+        };
+        match handshake.next.0 {
+            1 => {
+                conn.state = State::Status;
+                self.status(&mut conn)?;
+                Ok(Session::default())
+            }
+            2 => {
+                conn.state = State::Login;
+                self.play(conn, &handshake, &probe, finished)
+            }
+            next => Err(Error::Err(format!("unknown next state {}", next))),
+        }
+    }
+
+    fn accept(&self, finished: &AtomicBool) -> Result<TcpStream, Error> {
+        // Polled so that finishing without a client doesn't have to wait
+        // for the timeout
+        self.listener.set_nonblocking(true)?;
+        let start = Instant::now();
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    debug!("Mock server accepted {}", address);
+                    stream.set_nonblocking(false)?;
+                    stream.set_read_timeout(Some(TIMEOUT))?;
+                    return Ok(stream);
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                Err(err) => return Err(err.into()),
+            }
+            if finished.load(Ordering::Relaxed) || start.elapsed() > TIMEOUT {
+                return Err(Error::Err("no client connected".to_owned()));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn status(&self, conn: &mut Conn) -> Result<(), Error> {
+        match conn.read_packet()? {
+            Packet::StatusRequest(_) => {}
+            val => return Err(unexpected(&val)),
+        }
+        let status = format!(
+            r#"{{"version":{{"name":"Mock","protocol":{}}},"players":{{"max":20,"online":0}},"description":{{"text":"Mock server"}}}}"#,
+            self.protocol_version
+        );
+        conn.write_packet(StatusResponse { status })?;
+        let ping = match conn.read_packet()? {
+            Packet::StatusPing(val) => val.ping,
+            val => return Err(unexpected(&val)),
+        };
+        conn.write_packet(StatusPong { ping })
+    }
+
+    fn play(
+        self,
+        mut conn: Conn,
+        handshake: &Handshake,
+        probe: &TcpStream,
+        finished: &AtomicBool,
+    ) -> Result<Session, Error> {
+        let version = self.protocol_version;
+        if handshake.protocol_version.0 != version {
+            let reason = format!(
+                "Mock server is on protocol version {}, not {}",
+                version, handshake.protocol_version.0
+            );
+            conn.write_packet(LoginDisconnect {
+                reason: format::Component::from_string(&reason),
+            })?;
+            return Err(Error::Err(reason));
+        }
+
+        let username = match conn.read_packet()? {
+            Packet::LoginStart(val) => val.username,
+            val => return Err(unexpected(&val)),
+        };
+
+        let mut read = if self.encryption {
+            let shared = Self::encrypt(&mut conn)?;
+            let mut read = conn.clone();
+            read.enable_encyption(&shared, true);
+            conn.enable_encyption(&shared, false);
+            read
+        } else {
+            conn.clone()
+        };
+        let mut write = conn;
+
+        if let Some(threshold) = self.compression_threshold.filter(|_| version >= 47) {
+            write.write_packet(SetInitialCompression {
+                threshold: VarInt(threshold),
+            })?;
+            read.set_compresssion(threshold);
+            write.set_compresssion(threshold);
+        }
+
+        let uuid = UUID::from_name(&username);
+        let success = script::first_supported(
+            version,
+            vec![
+                Packet::LoginSuccess_UUID(LoginSuccess_UUID {
+                    uuid: uuid.clone(),
+                    username: username.clone(),
+                }),
+                Packet::LoginSuccess_String(LoginSuccess_String {
+                    uuid: uuid.to_string(),
+                    username: username.clone(),
+                }),
+            ],
+        )
+        .expect("no login success packet in protocol version");
+        write.write_packet(success)?;
+        read.state = State::Play;
+        write.state = State::Play;
+
+        for scripted in self.script {
+            match scripted {
+                Scripted::Packet(packet) => write.write_packet(packet)?,
+                Scripted::Raw(packet) => write.write_packet(packet)?,
+            }
+        }
+
+        let mut session = Session {
+            username: Some(username),
+            received: Vec::new(),
+        };
+        let mut last_packet = Instant::now();
+        loop {
+            // Waiting for the start of a packet in short steps means being
+            // finished doesn't cut off what the client has already sent
+            probe.set_read_timeout(Some(POLL_INTERVAL))?;
+            let waiting = probe.peek(&mut [0]);
+            probe.set_read_timeout(Some(TIMEOUT))?;
+            match waiting {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut =>
+                {
+                    if finished.load(Ordering::Relaxed) || last_packet.elapsed() > TIMEOUT {
+                        break;
+                    }
+                    continue;
+                }
+                Err(err) => {
+                    debug!("Mock server stopped reading: {}", err);
+                    break;
+                }
+            }
+            match read.read_packet() {
+                Ok(packet) => {
+                    last_packet = Instant::now();
+                    session.received.push(packet);
+                }
+                Err(Error::IOError(err)) => {
+                    debug!("Mock server stopped reading: {}", err);
+                    break;
+                }
+                Err(err) => warn!("Mock server couldn't read packet: {}", err),
+            }
+        }
+        Ok(session)
+    }
+
+    /// Asks the client to encrypt the connection, returning the shared
+    /// secret it picked.
+    fn encrypt(conn: &mut Conn) -> Result<Vec<u8>, Error> {
+        let public_key = rsa::public_key();
+        if conn.protocol_version >= 47 {
+            conn.write_packet(EncryptionRequest {
+                server_id: String::new(),
+                public_key: LenPrefixedBytes::new(public_key),
+                verify_token: LenPrefixedBytes::new(VERIFY_TOKEN.to_vec()),
+            })?;
+        } else {
+            conn.write_packet(EncryptionRequest_i16 {
+                server_id: String::new(),
+                public_key: LenPrefixedBytes::new(public_key),
+                verify_token: LenPrefixedBytes::new(VERIFY_TOKEN.to_vec()),
+            })?;
+        }
+        let (shared, token) = match conn.read_packet()? {
+            Packet::EncryptionResponse(val) => (val.shared_secret.data, val.verify_token.data),
+            Packet::EncryptionResponse_i16(val) => (val.shared_secret.data, val.verify_token.data),
+            val => return Err(unexpected(&val)),
+        };
+        if rsa::decrypt(&token)? != VERIFY_TOKEN {
+            return Err(Error::Err("verify token doesn't match".to_owned()));
+        }
+        rsa::decrypt(&shared)
+    }
+}
+
+fn unexpected(packet: &Packet) -> Error {
+    Error::Err(format!("unexpected packet: {:?}", packet))
+}
+
+/// A server serving its client.
+pub struct Running {
+    finished: Arc<AtomicBool>,
+    thread: thread::JoinHandle<Result<Session, Error>>,
+}
+
+impl Running {
+    /// Disconnects the client once the packets it already sent have been
+    /// read, and returns what happened.
+    pub fn finish(self) -> Result<Session, Error> {
+        self.finished.store(true, Ordering::Relaxed);
+        self.wait()
+    }
+
+    /// Waits for the client to leave, or to not send anything for the
+    /// timeout, and returns what happened.
+    pub fn wait(self) -> Result<Session, Error> {
+        self.thread.join().expect("mock server panicked")
+    }
+}
+
+/// What happened while serving a client.
+#[derive(Debug, Default)]
+pub struct Session {
+    /// The name the client logged in with, if it did
+    pub username: Option<String>,
+    /// Packets the client sent while playing
+    pub received: Vec<Packet>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use steven_protocol::protocol::packet::login::serverbound::*;
+    use steven_protocol::protocol::packet::play::serverbound::TeleportConfirm;
+    use steven_protocol::protocol::SUPPORTED_PROTOCOLS;
+
+    /// Logs in like a client would, returning the connections to read and
+    /// write with.
+    fn login(address: &str, protocol_version: i32) -> Result<(Conn, Conn), Error> {
+        let mut conn = Conn::new(address, protocol_version)?;
+        conn.write_packet(Handshake {
+            protocol_version: VarInt(protocol_version),
+            host: conn.host.clone(),
+            port: conn.port,
+            next: VarInt(2),
+        })?;
+        conn.state = State::Login;
+        conn.write_packet(LoginStart {
+            username: "Tester".to_owned(),
+        })?;
+        let mut read = conn.clone();
+        loop {
+            match read.read_packet()? {
+                Packet::EncryptionRequest(val) => {
+                    assert_eq!(val.public_key.data, rsa::public_key());
+                    let shared = [3; 16];
+                    conn.write_packet(EncryptionResponse {
+                        shared_secret: LenPrefixedBytes::new(rsa::encrypt(&shared)),
+                        verify_token: LenPrefixedBytes::new(rsa::encrypt(&val.verify_token.data)),
+                    })?;
+                    read.enable_encyption(&shared, true);
+                    conn.enable_encyption(&shared, false);
+                }
+                Packet::SetInitialCompression(val) => {
+                    read.set_compresssion(val.threshold.0);
+                    conn.set_compresssion(val.threshold.0);
+                }
+                Packet::LoginSuccess_String(val) => {
+                    assert_eq!(val.uuid, UUID::from_name("Tester").to_string());
+                    break;
+                }
+                Packet::LoginSuccess_UUID(val) => {
+                    assert_eq!(val.uuid, UUID::from_name("Tester"));
+                    break;
+                }
+                Packet::LoginDisconnect(val) => return Err(Error::Disconnect(val.reason)),
+                val => return Err(unexpected(&val)),
+            }
+        }
+        read.state = State::Play;
+        conn.state = State::Play;
+        Ok((read, conn))
+    }
+
+    #[test]
+    fn status() {
+        let server = MockServer::bind(758).unwrap();
+        let address = server.address();
+        let running = server.spawn();
+        let (status, _) = Conn::new(&address, 758).unwrap().do_status().unwrap();
+        assert_eq!(status.version.protocol, 758);
+        assert_eq!(status.players.max, 20);
+        let session = running.wait().unwrap();
+        assert_eq!(session.username, None);
+    }
+
+    #[test]
+    fn login_all_versions() {
+        for &version in SUPPORTED_PROTOCOLS.iter() {
+            let teleport = script::teleport(version, 1.0, 2.0, 3.0);
+            let server = MockServer::bind(version)
+                .unwrap()
+                .compression(16)
+                .send(teleport.clone());
+            let address = server.address();
+            let running = server.spawn();
+
+            let (mut read, mut write) = login(&address, version).unwrap();
+            assert_eq!(read.read_packet().unwrap(), teleport);
+            let confirm = Packet::TeleportConfirm(TeleportConfirm {
+                teleport_id: VarInt(1),
+            });
+            let confirms = confirm.id(version).is_some();
+            if confirms {
+                write.write_packet(confirm.clone()).unwrap();
+            }
+            drop(read);
+            drop(write);
+
+            let session = running.wait().unwrap();
+            assert_eq!(session.username.as_deref(), Some("Tester"));
+            assert_eq!(session.received.contains(&confirm), confirms);
+        }
+    }
+
+    #[test]
+    fn login_encrypted() {
+        let server = MockServer::bind(758).unwrap().encryption().compression(256);
+        let address = server.address();
+        let running = server.spawn();
+        login(&address, 758).unwrap();
+        let session = running.finish().unwrap();
+        assert_eq!(session.username.as_deref(), Some("Tester"));
+    }
+
+    #[test]
+    fn wrong_version() {
+        let server = MockServer::bind(758).unwrap();
+        let address = server.address();
+        let running = server.spawn();
+        assert!(matches!(login(&address, 757), Err(Error::Disconnect(_))));
+        assert!(running.wait().is_err());
+    }
+
+    #[test]
+    fn chunk_fixtures() {
+        for &version in SUPPORTED_PROTOCOLS.iter() {
+            if let Some(fixture) = script::ChunkFixture::for_version(version) {
+                let packet = fixture.packet().unwrap();
+                assert!(packet.id(version).is_some());
+            }
+            script::join_game(version, 1, 0);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! The key logins to the mock server are encrypted with. It's fixed and
+//! public, which is fine for tests but nothing else.
+
+use num_bigint::BigUint;
+use steven_protocol::protocol::Error;
+
+const MODULUS: &str = "cb0e36df783e1cb562dd5dab2b082d6eb9fcd5c4bc573bac69990b0cef129082\
+                       bcbeeb6aa1798a6df34d0730c2e1d87636330bc06c752816b26c19150b04ffeb\
+                       7886a84a567dd0294bf657432703b44bda67b20f1610982987c9c6e6a6016056\
+                       f91c65d49d49e7906cd21a878bab53715f2c82a13c5819291c460be145541c9b";
+const PRIVATE_EXPONENT: &str = "9920390540da26302b4420750a587a5a7beb4d11b5ae5b13e3f643bd1734fac1\
+                                7cb0c53b7e55f7ac7ec3e1de45b8a6d24e8124259224af7c9e44a28d99e7c54d\
+                                6574d6abc116c714d9cfb6edfacfda0b17efec2f5fd46510761d8ad83b00b893\
+                                06202957e8c5a22b63e51529ed4e1127d814201ec75ef948a5dcdf1a1f7575e1";
+#[cfg(test)]
+const PUBLIC_EXPONENT: u32 = 65537;
+const KEY_SIZE: usize = 128;
+
+// What comes before and after the modulus in the DER encoding of a 1024 bit
+// key with the usual public exponent
+const DER_PREFIX: &str = "30819f300d06092a864886f70d010101050003818d0030818902818100";
+const DER_SUFFIX: &str = "0203010001";
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn modulus() -> BigUint {
+    BigUint::from_bytes_be(&from_hex(MODULUS))
+}
+
+/// Returns the public key, encoded the way encryption requests send it.
+pub fn public_key() -> Vec<u8> {
+    let mut der = from_hex(DER_PREFIX);
+    der.extend(from_hex(MODULUS));
+    der.extend(from_hex(DER_SUFFIX));
+    der
+}
+
+/// Decrypts something the client encrypted with the public key, removing
+/// its PKCS#1 v1.5 padding.
+pub fn decrypt(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::Err("invalid encrypted data".to_owned());
+    if data.len() != KEY_SIZE {
+        return Err(invalid());
+    }
+    let exponent = BigUint::from_bytes_be(&from_hex(PRIVATE_EXPONENT));
+    // The leading zero byte of the padding is lost in the conversion
+    let padded = BigUint::from_bytes_be(data)
+        .modpow(&exponent, &modulus())
+        .to_bytes_be();
+    if padded.len() != KEY_SIZE - 1 || padded[0] != 2 {
+        return Err(invalid());
+    }
+    // The random padding is ended by a zero
+    let start = padded.iter().position(|&b| b == 0).ok_or_else(invalid)? + 1;
+    Ok(padded[start..].to_vec())
+}
+
+/// Encrypts like a client would, with a padding that is not at all random.
+#[cfg(test)]
+pub fn encrypt(data: &[u8]) -> Vec<u8> {
+    let mut padded = vec![0, 2];
+    padded.resize(KEY_SIZE - data.len() - 1, 0xff);
+    padded.push(0);
+    padded.extend_from_slice(data);
+    let encrypted = BigUint::from_bytes_be(&padded)
+        .modpow(&BigUint::from(PUBLIC_EXPONENT), &modulus())
+        .to_bytes_be();
+    let mut out = vec![0; KEY_SIZE - encrypted.len()];
+    out.extend(encrypted);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let secret = [7; 16];
+        let encrypted = encrypt(&secret);
+        assert_eq!(encrypted.len(), KEY_SIZE);
+        assert_ne!(&encrypted[KEY_SIZE - 16..], &secret);
+        assert_eq!(decrypt(&encrypted).unwrap(), secret);
+        assert!(decrypt(&secret).is_err());
+    }
+
+    #[test]
+    fn public_key_encoding() {
+        let key = public_key();
+        // SEQUENCE covering the rest of the key
+        assert_eq!(&key[..3], &[0x30, 0x81, 0x9f]);
+        assert_eq!(key.len(), 3 + 0x9f);
+    }
+}
@@ -0,0 +1,320 @@
+//! Packets for scripting what the mock server sends, built for whichever
+//! protocol version is being served.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use steven_protocol::nbt;
+use steven_protocol::protocol::packet::play::clientbound::*;
+use steven_protocol::protocol::packet::Packet;
+use steven_protocol::protocol::{LenPrefixed, LenPrefixedBytes, VarInt};
+
+/// Returns the first of the packets that exists in the protocol version.
+///
+/// Most packets have a variant per layout they've had over the versions,
+/// so this picks the right one when given one of each.
+pub fn first_supported(protocol_version: i32, packets: Vec<Packet>) -> Option<Packet> {
+    packets
+        .into_iter()
+        .find(|p| p.id(protocol_version).is_some())
+}
+
+/// Returns the packet that spawns the player into an overworld.
+pub fn join_game(protocol_version: i32, entity_id: i32, gamemode: u8) -> Packet {
+    let world_names = || LenPrefixed::new(vec!["minecraft:overworld".to_owned()]);
+    let world_name = || "minecraft:overworld".to_owned();
+    let level_type = || "default".to_owned();
+    // Only the height of the world is read by the client
+    let mut dimension = nbt::Tag::new_compound();
+    if protocol_version >= 757 {
+        dimension.put("min_y", nbt::Tag::Int(-64));
+        dimension.put("height", nbt::Tag::Int(384));
+    } else {
+        dimension.put("min_y", nbt::Tag::Int(0));
+        dimension.put("height", nbt::Tag::Int(256));
+    }
+    let dimension = || Some(nbt::NamedTag(String::new(), dimension.clone()));
+
+    first_supported(
+        protocol_version,
+        vec![
+            Packet::JoinGame_WorldNames_IsHard_SimDist(JoinGame_WorldNames_IsHard_SimDist {
+                entity_id,
+                gamemode,
+                previous_gamemode: gamemode,
+                world_names: world_names(),
+                dimension: dimension(),
+                world_name: world_name(),
+                max_players: VarInt(20),
+                view_distance: VarInt(2),
+                simulation_distance: VarInt(2),
+                ..Default::default()
+            }),
+            Packet::JoinGame_WorldNames_IsHard(JoinGame_WorldNames_IsHard {
+                entity_id,
+                gamemode,
+                previous_gamemode: gamemode,
+                world_names: world_names(),
+                dimension: dimension(),
+                world_name: world_name(),
+                max_players: VarInt(20),
+                view_distance: VarInt(2),
+                ..Default::default()
+            }),
+            Packet::JoinGame_WorldNames(JoinGame_WorldNames {
+                entity_id,
+                gamemode,
+                previous_gamemode: gamemode,
+                world_names: world_names(),
+                dimension: world_name(),
+                world_name: world_name(),
+                max_players: 20,
+                view_distance: VarInt(2),
+                ..Default::default()
+            }),
+            Packet::JoinGame_HashedSeed_Respawn(JoinGame_HashedSeed_Respawn {
+                entity_id,
+                gamemode,
+                max_players: 20,
+                level_type: level_type(),
+                view_distance: VarInt(2),
+                ..Default::default()
+            }),
+            Packet::JoinGame_i32_ViewDistance(JoinGame_i32_ViewDistance {
+                entity_id,
+                gamemode,
+                max_players: 20,
+                level_type: level_type(),
+                view_distance: VarInt(2),
+                ..Default::default()
+            }),
+            Packet::JoinGame_i32(JoinGame_i32 {
+                entity_id,
+                gamemode,
+                max_players: 20,
+                level_type: level_type(),
+                ..Default::default()
+            }),
+            Packet::JoinGame_i8(JoinGame_i8 {
+                entity_id,
+                gamemode,
+                max_players: 20,
+                level_type: level_type(),
+                ..Default::default()
+            }),
+            Packet::JoinGame_i8_NoDebug(JoinGame_i8_NoDebug {
+                entity_id,
+                gamemode,
+                max_players: 20,
+                level_type: level_type(),
+                ..Default::default()
+            }),
+        ],
+    )
+    .expect("no join game packet in protocol version")
+}
+
+/// Returns the packet that moves the player to an absolute position. Where
+/// the protocol has them, the teleport id is 1.
+pub fn teleport(protocol_version: i32, x: f64, y: f64, z: f64) -> Packet {
+    first_supported(
+        protocol_version,
+        vec![
+            Packet::TeleportPlayer_WithDismount(TeleportPlayer_WithDismount {
+                x,
+                y,
+                z,
+                teleport_id: VarInt(1),
+                ..Default::default()
+            }),
+            Packet::TeleportPlayer_WithConfirm(TeleportPlayer_WithConfirm {
+                x,
+                y,
+                z,
+                teleport_id: VarInt(1),
+                ..Default::default()
+            }),
+            Packet::TeleportPlayer_NoConfirm(TeleportPlayer_NoConfirm {
+                x,
+                y,
+                z,
+                ..Default::default()
+            }),
+            Packet::TeleportPlayer_OnGround(TeleportPlayer_OnGround {
+                x,
+                eyes_y: y + 1.62,
+                z,
+                ..Default::default()
+            }),
+        ],
+    )
+    .expect("no teleport packet in protocol version")
+}
+
+/// Returns the packet that loads a whole chunk column from its encoded
+/// sections, or `None` if the protocol version only sends chunks in a
+/// compressed format.
+///
+/// The bitmask says which sections are in the data. Since 1.18 all of the
+/// sections are always sent, so it is ignored.
+pub fn chunk_data(
+    protocol_version: i32,
+    x: i32,
+    z: i32,
+    bitmask: u64,
+    data: Vec<u8>,
+) -> Option<Packet> {
+    let data = || LenPrefixedBytes::new(data.clone());
+    first_supported(
+        protocol_version,
+        vec![
+            Packet::ChunkData_AndLight(ChunkData_AndLight {
+                chunk_x: x,
+                chunk_z: z,
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData_Biomes3D_Bitmasks(ChunkData_Biomes3D_Bitmasks {
+                chunk_x: x,
+                chunk_z: z,
+                bitmasks: LenPrefixed::new(vec![bitmask as i64]),
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData_Biomes3D_VarInt(ChunkData_Biomes3D_VarInt {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: VarInt(bitmask as i32),
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData_Biomes3D_bool(ChunkData_Biomes3D_bool {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: VarInt(bitmask as i32),
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData_Biomes3D(ChunkData_Biomes3D {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: VarInt(bitmask as i32),
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData_HeightMap(ChunkData_HeightMap {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: VarInt(bitmask as i32),
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData(ChunkData {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: VarInt(bitmask as i32),
+                data: data(),
+                ..Default::default()
+            }),
+            Packet::ChunkData_NoEntities(ChunkData_NoEntities {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: VarInt(bitmask as i32),
+                data: data(),
+            }),
+            Packet::ChunkData_NoEntities_u16(ChunkData_NoEntities_u16 {
+                chunk_x: x,
+                chunk_z: z,
+                new: true,
+                bitmask: bitmask as u16,
+                data: data(),
+            }),
+        ],
+    )
+}
+
+/// A chunk column captured from a vanilla server, from the `test` directory
+/// at the root of the repository.
+#[derive(Clone, Copy, Debug)]
+pub struct ChunkFixture {
+    pub protocol_version: i32,
+    pub name: &'static str,
+    pub x: i32,
+    pub z: i32,
+    pub bitmask: u64,
+}
+
+const ALL_SECTIONS: u64 = 0xffffff;
+
+const CHUNK_FIXTURES: &[ChunkFixture] = &[
+    fixture(340, "1.12.2", 7, 8, 63),
+    fixture(404, "1.13.2", -20, -7, 31),
+    fixture(451, "18w50a", -25, -18, 31),
+    fixture(452, "19w02a", -10, -26, 15),
+    fixture(477, "1.14", -14, 0, 63),
+    fixture(480, "1.14.1", 2, -25, 31),
+    fixture(485, "1.14.2", 1, 5, 15),
+    fixture(490, "1.14.3", -9, -25, 31),
+    fixture(498, "1.14.4", 2, -14, 31),
+    fixture(575, "1.15.1", -10, -10, 63),
+    fixture(578, "1.15.2", -19, -18, 31),
+    fixture(735, "1.16", 2, -26, 63),
+    fixture(736, "1.16.1", -6, -5, 31),
+    fixture(751, "1.16.2", -22, -20, 15),
+    fixture(753, "1.16.3", 4, 2, 63),
+    fixture(754, "1.16.4", -10, -8, 15),
+    fixture(756, "1.17.1", -3, -25, 31),
+    fixture(757, "1.18.1", -14, -5, ALL_SECTIONS),
+    fixture(758, "1.18.2", -10, -8, ALL_SECTIONS),
+];
+
+const fn fixture(
+    protocol_version: i32,
+    name: &'static str,
+    x: i32,
+    z: i32,
+    bitmask: u64,
+) -> ChunkFixture {
+    ChunkFixture {
+        protocol_version,
+        name,
+        x,
+        z,
+        bitmask,
+    }
+}
+
+impl ChunkFixture {
+    /// Returns the fixture captured with the protocol version, if there is
+    /// one.
+    pub fn for_version(protocol_version: i32) -> Option<ChunkFixture> {
+        CHUNK_FIXTURES
+            .iter()
+            .find(|f| f.protocol_version == protocol_version)
+            .copied()
+    }
+
+    /// Reads the encoded sections of the chunk.
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../test")
+            .join(format!("chunk_{}.bin", self.name));
+        fs::read(path)
+    }
+
+    /// Returns the packet that loads the chunk.
+    pub fn packet(&self) -> io::Result<Packet> {
+        let data = self.read()?;
+        Ok(
+            chunk_data(self.protocol_version, self.x, self.z, self.bitmask, data)
+                .expect("fixture version has no chunk data packet"),
+        )
+    }
+}
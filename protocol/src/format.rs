@@ -94,7 +94,9 @@ impl Component {
     }
 
     pub fn to_value(&self) -> serde_json::Value {
-        unimplemented!()
+        match *self {
+            Component::Text(ref txt) => txt.to_value(),
+        }
     }
 }
 
@@ -155,7 +157,32 @@ impl Modifier {
     }
 
     pub fn to_value(&self) -> serde_json::Value {
-        unimplemented!()
+        let mut v = serde_json::Map::new();
+        let flags = [
+            ("bold", self.bold),
+            ("italic", self.italic),
+            ("underlined", self.underlined),
+            ("strikethrough", self.strikethrough),
+            ("obfuscated", self.obfuscated),
+        ];
+        for (name, flag) in flags {
+            if let Some(flag) = flag {
+                v.insert(name.to_owned(), serde_json::Value::Bool(flag));
+            }
+        }
+        if let Some(color) = self.color {
+            v.insert(
+                "color".to_owned(),
+                serde_json::Value::String(color.to_string()),
+            );
+        }
+        if let Some(ref extra) = self.extra {
+            v.insert(
+                "extra".to_owned(),
+                serde_json::Value::Array(extra.iter().map(|v| v.to_value()).collect()),
+            );
+        }
+        serde_json::Value::Object(v)
     }
 }
 
@@ -183,7 +210,9 @@ impl TextComponent {
     }
 
     pub fn to_value(&self) -> serde_json::Value {
-        unimplemented!()
+        let mut v = self.modifier.to_value();
+        v["text"] = serde_json::Value::String(self.text.clone());
+        v
     }
 }
 
@@ -333,6 +362,25 @@ fn test_color_from() {
     }
 }
 
+#[test]
+fn test_component_to_value() {
+    let mut modifier = Modifier {
+        bold: Some(true),
+        color: Some(Color::RGB(0x12, 0x34, 0x56)),
+        ..Default::default()
+    };
+    modifier.extra = Some(vec![Component::Text(TextComponent::new("world"))]);
+    let component = Component::Text(TextComponent {
+        text: "hello ".to_owned(),
+        modifier,
+    });
+    let value = component.to_value();
+    assert_eq!(value["text"], "hello ");
+    assert_eq!(value["color"], "#123456");
+    assert!(value.get("italic").is_none());
+    assert_eq!(Component::from_value(&value), component);
+}
+
 const LEGACY_CHAR: char = '§';
 
 pub fn convert_legacy(c: &mut Component) {
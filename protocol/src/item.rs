@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::nbt;
-use crate::protocol::{self, Serializable};
+use crate::protocol::{self, Context, Serializable};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use std::io;
 
@@ -37,8 +37,11 @@ impl Default for Stack {
 }
 
 impl Serializable for Option<Stack> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Option<Stack>, protocol::Error> {
-        let protocol_version = protocol::current_protocol_version();
+    fn read_from_ctx<R: io::Read>(
+        buf: &mut R,
+        ctx: &Context,
+    ) -> Result<Option<Stack>, protocol::Error> {
+        let protocol_version = ctx.protocol_version;
 
         if protocol_version >= 404 {
             let present = buf.read_u8()? != 0;
@@ -48,7 +51,7 @@ impl Serializable for Option<Stack> {
         }
 
         let id = if protocol_version >= 404 {
-            protocol::VarInt::read_from(buf)?.0 as isize
+            protocol::VarInt::read_from_ctx(buf, ctx)?.0 as isize
         } else {
             buf.read_i16::<BigEndian>()? as isize
         };
@@ -65,7 +68,7 @@ impl Serializable for Option<Stack> {
         };
 
         let tag: Option<nbt::NamedTag> = if protocol_version >= 47 {
-            Serializable::read_from(buf)?
+            Serializable::read_from_ctx(buf, ctx)?
         } else {
             // 1.7 uses a different slot data format described on https://wiki.vg/index.php?title=Slot_Data&diff=6056&oldid=4753
             let tag_size = buf.read_i16::<BigEndian>()?;
@@ -87,15 +90,19 @@ impl Serializable for Option<Stack> {
             tag,
         }))
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), protocol::Error> {
-        let protocol_version = protocol::current_protocol_version();
+    fn write_to_ctx<W: io::Write>(
+        &self,
+        buf: &mut W,
+        ctx: &Context,
+    ) -> Result<(), protocol::Error> {
+        let protocol_version = ctx.protocol_version;
         if protocol_version >= 404 {
             match *self {
                 Some(ref val) => {
                     buf.write_u8(1)?; //present
-                    crate::protocol::VarInt(val.id as i32).write_to(buf)?;
+                    crate::protocol::VarInt(val.id as i32).write_to_ctx(buf, ctx)?;
                     buf.write_u8(val.count as u8)?;
-                    val.tag.write_to(buf)?;
+                    val.tag.write_to_ctx(buf, ctx)?;
                 }
                 None => {
                     buf.write_u8(0)?; // not present
@@ -108,7 +115,7 @@ impl Serializable for Option<Stack> {
                     buf.write_u8(val.count as u8)?;
                     buf.write_i16::<BigEndian>(val.damage.unwrap_or(0) as i16)?;
                     // TODO: compress zlib NBT if 1.7
-                    val.tag.write_to(buf)?;
+                    val.tag.write_to_ctx(buf, ctx)?;
                 }
                 None => buf.write_i16::<BigEndian>(-1)?,
             }
@@ -17,7 +17,7 @@ use std::io;
 use std::io::Read;
 
 use super::protocol;
-use super::protocol::Serializable;
+use super::protocol::{Context, Serializable};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -236,11 +236,15 @@ impl Tag {
 }
 
 impl Serializable for Tag {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Tag, protocol::Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<Tag, protocol::Error> {
         Tag::read_type(10, buf)
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), protocol::Error> {
+    fn write_to_ctx<W: io::Write>(
+        &self,
+        buf: &mut W,
+        ctx: &Context,
+    ) -> Result<(), protocol::Error> {
         match *self {
             Tag::End => {}
             Tag::Byte(val) => buf.write_i8(val)?,
@@ -250,7 +254,7 @@ impl Serializable for Tag {
             Tag::Float(val) => buf.write_f32::<BigEndian>(val)?,
             Tag::Double(val) => buf.write_f64::<BigEndian>(val)?,
             Tag::ByteArray(ref val) => {
-                (val.len() as i32).write_to(buf)?;
+                (val.len() as i32).write_to_ctx(buf, ctx)?;
                 buf.write_all(val)?;
             }
             Tag::String(ref val) => write_string(buf, val)?,
@@ -262,28 +266,28 @@ impl Serializable for Tag {
                     buf.write_u8(val[0].internal_id())?;
                     buf.write_i32::<BigEndian>(val.len() as i32)?;
                     for e in val {
-                        e.write_to(buf)?;
+                        e.write_to_ctx(buf, ctx)?;
                     }
                 }
             }
             Tag::Compound(ref val) => {
                 for (k, v) in val {
-                    v.internal_id().write_to(buf)?;
+                    v.internal_id().write_to_ctx(buf, ctx)?;
                     write_string(buf, k)?;
-                    v.write_to(buf)?;
+                    v.write_to_ctx(buf, ctx)?;
                 }
                 buf.write_u8(0)?;
             }
             Tag::IntArray(ref val) => {
-                (val.len() as i32).write_to(buf)?;
+                (val.len() as i32).write_to_ctx(buf, ctx)?;
                 for v in val {
-                    v.write_to(buf)?;
+                    v.write_to_ctx(buf, ctx)?;
                 }
             }
             Tag::LongArray(ref val) => {
-                (val.len() as i32).write_to(buf)?;
+                (val.len() as i32).write_to_ctx(buf, ctx)?;
                 for v in val {
-                    v.write_to(buf)?;
+                    v.write_to_ctx(buf, ctx)?;
                 }
             }
         }
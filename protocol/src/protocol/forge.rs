@@ -3,7 +3,7 @@ use log::debug;
 /// Implements https://wiki.vg/Minecraft_Forge_Handshake
 use std::io;
 
-use super::{Error, LenPrefixed, Serializable, VarInt};
+use super::{Context, Error, LenPrefixed, Serializable, VarInt};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Phase {
@@ -22,8 +22,8 @@ pub enum Phase {
 
 impl Serializable for Phase {
     /// Read server handshake state from server
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let phase: i8 = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let phase: i8 = Serializable::read_from_ctx(buf, ctx)?;
         Ok(match phase {
             2 => Phase::WaitingCAck,
             3 => Phase::Complete,
@@ -32,7 +32,7 @@ impl Serializable for Phase {
     }
 
     /// Send client handshake state from client
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_u8(match self {
             Phase::WaitingServerData => 2,
             Phase::WaitingServerComplete => 3,
@@ -51,16 +51,16 @@ pub struct ForgeMod {
 }
 
 impl Serializable for ForgeMod {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(ForgeMod {
-            modid: Serializable::read_from(buf)?,
-            version: Serializable::read_from(buf)?,
+            modid: Serializable::read_from_ctx(buf, ctx)?,
+            version: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.modid.write_to(buf)?;
-        self.version.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.modid.write_to_ctx(buf, ctx)?;
+        self.version.write_to_ctx(buf, ctx)
     }
 }
 
@@ -71,16 +71,16 @@ pub struct ModIdMapping {
 }
 
 impl Serializable for ModIdMapping {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(ModIdMapping {
-            name: Serializable::read_from(buf)?,
-            id: Serializable::read_from(buf)?,
+            name: Serializable::read_from_ctx(buf, ctx)?,
+            id: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.name.write_to(buf)?;
-        self.id.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.name.write_to_ctx(buf, ctx)?;
+        self.id.write_to_ctx(buf, ctx)
     }
 }
 
@@ -118,14 +118,14 @@ pub enum FmlHs {
 }
 
 impl Serializable for FmlHs {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let discriminator: u8 = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let discriminator: u8 = Serializable::read_from_ctx(buf, ctx)?;
 
         match discriminator {
             0 => {
-                let fml_protocol_version: i8 = Serializable::read_from(buf)?;
+                let fml_protocol_version: i8 = Serializable::read_from_ctx(buf, ctx)?;
                 let override_dimension = if fml_protocol_version > 1 {
-                    let dimension: i32 = Serializable::read_from(buf)?;
+                    let dimension: i32 = Serializable::read_from_ctx(buf, ctx)?;
                     Some(dimension)
                 } else {
                     None
@@ -143,49 +143,49 @@ impl Serializable for FmlHs {
             }
             1 => panic!("Received unexpected FML|HS ClientHello from server"),
             2 => Ok(FmlHs::ModList {
-                mods: Serializable::read_from(buf)?,
+                mods: Serializable::read_from_ctx(buf, ctx)?,
             }),
             3 => {
-                let protocol_version = super::current_protocol_version();
+                let protocol_version = ctx.protocol_version;
 
                 if protocol_version >= 47 {
                     Ok(FmlHs::RegistryData {
-                        has_more: Serializable::read_from(buf)?,
-                        name: Serializable::read_from(buf)?,
-                        ids: Serializable::read_from(buf)?,
-                        substitutions: Serializable::read_from(buf)?,
-                        dummies: Serializable::read_from(buf)?,
+                        has_more: Serializable::read_from_ctx(buf, ctx)?,
+                        name: Serializable::read_from_ctx(buf, ctx)?,
+                        ids: Serializable::read_from_ctx(buf, ctx)?,
+                        substitutions: Serializable::read_from_ctx(buf, ctx)?,
+                        dummies: Serializable::read_from_ctx(buf, ctx)?,
                     })
                 } else {
                     Ok(FmlHs::ModIdData {
-                        mappings: Serializable::read_from(buf)?,
-                        block_substitutions: Serializable::read_from(buf)?,
-                        item_substitutions: Serializable::read_from(buf)?,
+                        mappings: Serializable::read_from_ctx(buf, ctx)?,
+                        block_substitutions: Serializable::read_from_ctx(buf, ctx)?,
+                        item_substitutions: Serializable::read_from_ctx(buf, ctx)?,
                     })
                 }
             }
             255 => Ok(FmlHs::HandshakeAck {
-                phase: Serializable::read_from(buf)?,
+                phase: Serializable::read_from_ctx(buf, ctx)?,
             }),
             _ => panic!("Unhandled FML|HS packet: discriminator={}", discriminator),
         }
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         match self {
             FmlHs::ClientHello {
                 fml_protocol_version,
             } => {
                 buf.write_u8(1)?;
-                fml_protocol_version.write_to(buf)
+                fml_protocol_version.write_to_ctx(buf, ctx)
             }
             FmlHs::ModList { mods } => {
                 buf.write_u8(2)?;
-                mods.write_to(buf)
+                mods.write_to_ctx(buf, ctx)
             }
             FmlHs::HandshakeAck { phase } => {
                 buf.write_u8(255)?;
-                phase.write_to(buf)
+                phase.write_to_ctx(buf, ctx)
             }
             _ => unimplemented!(),
         }
@@ -203,16 +203,16 @@ pub mod fml2 {
     }
 
     impl Serializable for Channel {
-        fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+        fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
             Ok(Channel {
-                name: Serializable::read_from(buf)?,
-                version: Serializable::read_from(buf)?,
+                name: Serializable::read_from_ctx(buf, ctx)?,
+                version: Serializable::read_from_ctx(buf, ctx)?,
             })
         }
 
-        fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-            self.name.write_to(buf)?;
-            self.version.write_to(buf)
+        fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+            self.name.write_to_ctx(buf, ctx)?;
+            self.version.write_to_ctx(buf, ctx)
         }
     }
 
@@ -223,16 +223,16 @@ pub mod fml2 {
     }
 
     impl Serializable for Registry {
-        fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+        fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
             Ok(Registry {
-                name: Serializable::read_from(buf)?,
+                name: Serializable::read_from_ctx(buf, ctx)?,
                 marker: "".to_string(), // not in ModList
             })
         }
 
-        fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-            self.name.write_to(buf)?;
-            self.marker.write_to(buf)
+        fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+            self.name.write_to_ctx(buf, ctx)?;
+            self.marker.write_to_ctx(buf, ctx)
         }
     }
 
@@ -287,23 +287,23 @@ pub mod fml2 {
     }
 
     impl Serializable for FmlHandshake {
-        fn read_from<R: io::Read>(_buf: &mut R) -> Result<Self, Error> {
+        fn read_from_ctx<R: io::Read>(_buf: &mut R, _ctx: &Context) -> Result<Self, Error> {
             unimplemented!()
         }
 
-        fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+        fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
             match self {
                 FmlHandshake::ModListReply {
                     mod_names,
                     channels,
                     registries,
                 } => {
-                    VarInt(2).write_to(buf)?;
-                    mod_names.write_to(buf)?;
-                    channels.write_to(buf)?;
-                    registries.write_to(buf)
+                    VarInt(2).write_to_ctx(buf, ctx)?;
+                    mod_names.write_to_ctx(buf, ctx)?;
+                    channels.write_to_ctx(buf, ctx)?;
+                    registries.write_to_ctx(buf, ctx)
                 }
-                FmlHandshake::Acknowledgement => VarInt(99).write_to(buf),
+                FmlHandshake::Acknowledgement => VarInt(99).write_to_ctx(buf, ctx),
                 _ => unimplemented!(),
             }
         }
@@ -22,6 +22,7 @@ use std_or_web::fs;
 
 pub mod forge;
 pub mod mojang;
+pub mod resolve;
 
 use crate::format;
 use crate::nbt;
@@ -47,6 +48,9 @@ pub const SUPPORTED_PROTOCOLS: [i32; 27] = [
 static CURRENT_PROTOCOL_VERSION: AtomicI32 = AtomicI32::new(SUPPORTED_PROTOCOLS[0]);
 static NETWORK_DEBUG: AtomicBool = AtomicBool::new(false);
 
+/// Returns the protocol version of the most recently opened connection.
+/// Values read or written for a particular connection should use its
+/// `Context` instead, this is only kept for callers without one.
 pub fn current_protocol_version() -> i32 {
     CURRENT_PROTOCOL_VERSION.load(Ordering::Relaxed)
 }
@@ -86,6 +90,91 @@ macro_rules! state_packets {
         )+
         }
 
+        impl Packet {
+            /// Returns the id of the packet in the given protocol version, or
+            /// `None` if the version doesn't have the packet.
+            pub fn id(&self, version: i32) -> Option<i32> {
+                match *self {
+                $(
+                    $(
+                        $(
+                    Packet::$name(_) => packet::versions::lookup_internal_packet_id_for_version(version, State::$stateName, Direction::$dirName, $state::$dir::internal_ids::$name, false),
+                        )*
+                    )+
+                )+
+                }
+            }
+
+            /// Returns the state of the connection the packet is sent in.
+            pub fn state(&self) -> State {
+                match *self {
+                $(
+                    $(
+                        $(
+                    Packet::$name(_) => State::$stateName,
+                        )*
+                    )+
+                )+
+                }
+            }
+
+            /// Returns which way the packet is sent.
+            pub fn direction(&self) -> Direction {
+                match *self {
+                $(
+                    $(
+                        $(
+                    Packet::$name(_) => Direction::$dirName,
+                        )*
+                    )+
+                )+
+                }
+            }
+
+            /// Writes the fields of the packet as the given protocol version
+            /// encodes them, the reverse of `packet_by_id`. The id isn't
+            /// written.
+            pub fn write_to<W: io::Write>(&self, version: i32, buf: &mut W) -> Result<(), Error> {
+                let ctx = &Context::new(version);
+                match *self {
+                $(
+                    $(
+                        $(
+                    Packet::$name(ref packet) => packet.write(buf, ctx),
+                        )*
+                    )+
+                )+
+                }
+            }
+
+            /// Every packet, with all of its fields left at their defaults.
+            #[cfg(test)]
+            pub(crate) fn all_defaults() -> Vec<Packet> {
+                vec![
+                $(
+                    $(
+                        $(
+                    Packet::$name(Default::default()),
+                        )*
+                    )+
+                )+
+                ]
+            }
+        }
+
+        impl PacketType for Packet {
+            fn packet_id(&self, version: i32) -> i32 {
+                match self.id(version) {
+                    Some(id) => id,
+                    None => panic!("{:?} {:?} packet not in protocol version {}", self.state(), self.direction(), version),
+                }
+            }
+
+            fn write<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+                self.write_to(ctx.protocol_version, buf)
+            }
+        }
+
         $(
         pub mod $state {
 
@@ -112,16 +201,28 @@ macro_rules! state_packets {
                         $($(#[$fattr])* pub $field: $field_type),+,
                     }
 
+                    impl $name {
+                        pub(crate) fn read_packet<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<packet::Packet, Error> {
+                            let mut packet = $name::default();
+                            $(
+                                if true $(&& ($cond(&packet)))* {
+                                    packet.$field = Serializable::read_from_ctx(buf, ctx)?;
+                                }
+                            )+
+                            Result::Ok(packet::Packet::$name(packet))
+                        }
+                    }
+
                     impl PacketType for $name {
 
                         fn packet_id(&self, version: i32) -> i32 {
                             packet::versions::translate_internal_packet_id_for_version(version, State::$stateName, Direction::$dirName, internal_ids::$name, false)
                         }
 
-                        fn write<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+                        fn write<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
                             $(
                                 if true $(&& ($cond(&self)))* {
-                                    self.$field.write_to(buf)?;
+                                    self.$field.write_to_ctx(buf, ctx)?;
                                 }
                             )+
 
@@ -136,35 +237,34 @@ macro_rules! state_packets {
 
         /// Returns the packet for the given state, direction and id after parsing the fields
         /// from the buffer.
-        pub fn packet_by_id<R: io::Read>(version: i32, state: State, dir: Direction, id: i32, mut buf: &mut R) -> Result<Option<Packet>, Error> {
-            match state {
+        pub fn packet_by_id<R: io::Read>(version: i32, state: State, dir: Direction, id: i32, buf: &mut R) -> Result<Option<Packet>, Error> {
+            let ctx = &Context::new(version);
+            // Only the reader is picked by the match. Reading every packet in
+            // its own arm needs more stack than a test thread has in debug
+            // builds.
+            let read: fn(&mut R, &Context) -> Result<Packet, Error> = match state {
                 $(
                     State::$stateName => {
                         match dir {
                             $(
                                 Direction::$dirName => {
-                                    let internal_id = packet::versions::translate_internal_packet_id_for_version(version, state, dir, id, true);
+                                    let internal_id = match packet::versions::lookup_internal_packet_id_for_version(version, state, dir, id, true) {
+                                        Some(internal_id) => internal_id,
+                                        None => return Result::Ok(Option::None),
+                                    };
                                     match internal_id {
                                     $(
-                                        self::$state::$dir::internal_ids::$name => {
-                                            use self::$state::$dir::$name;
-                                            let mut packet : $name = $name::default();
-                                            $(
-                                                if true $(&& ($cond(&packet)))* {
-                                                    packet.$field = Serializable::read_from(&mut buf)?;
-                                                }
-                                            )+
-                                            Result::Ok(Option::Some(Packet::$name(packet)))
-                                        },
+                                        self::$state::$dir::internal_ids::$name => self::$state::$dir::$name::read_packet,
                                     )*
-                                        _ => Result::Ok(Option::None)
+                                        _ => return Result::Ok(Option::None),
                                     }
                                 }
                             )+
                         }
                     }
                 )+
-            }
+            };
+            read(buf, ctx).map(Option::Some)
         }
     }
 }
@@ -181,7 +281,10 @@ macro_rules! protocol_packet_ids {
     })+) => {
         use crate::protocol::*;
 
-        pub fn translate_internal_packet_id(state: State, dir: Direction, id: i32, to_internal: bool) -> i32 {
+        /// Maps between the ids this version uses on the wire and the
+        /// internal ids of the packets, returning `None` for ids the
+        /// version doesn't have.
+        pub fn lookup_internal_packet_id(state: State, dir: Direction, id: i32, to_internal: bool) -> Option<i32> {
             match state {
                 $(
                     State::$stateName => {
@@ -191,16 +294,16 @@ macro_rules! protocol_packet_ids {
                                     if to_internal {
                                         match id {
                                         $(
-                                            $id => crate::protocol::packet::$state::$dir::internal_ids::$name,
+                                            $id => Some(crate::protocol::packet::$state::$dir::internal_ids::$name),
                                         )*
-                                            _ => panic!("bad packet id 0x{:x} in {:?} {:?}", id, dir, state),
+                                            _ => None,
                                         }
                                     } else {
                                         match id {
                                         $(
-                                            crate::protocol::packet::$state::$dir::internal_ids::$name => $id,
+                                            crate::protocol::packet::$state::$dir::internal_ids::$name => Some($id),
                                         )*
-                                            _ => panic!("bad packet internal id 0x{:x} in {:?} {:?}", id, dir, state),
+                                            _ => None,
                                         }
                                     }
                                 }
@@ -215,40 +318,75 @@ macro_rules! protocol_packet_ids {
 
 pub mod packet;
 pub mod versions;
+
+/// What values are being read or written for. Some values are encoded
+/// differently between versions of the protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Context {
+    pub protocol_version: i32,
+}
+
+impl Context {
+    pub fn new(protocol_version: i32) -> Context {
+        Context { protocol_version }
+    }
+
+    /// Returns the context of the most recently opened connection, for
+    /// callers that don't have a connection of their own to hand.
+    pub fn current() -> Context {
+        Context::new(current_protocol_version())
+    }
+}
+
 pub trait Serializable: Sized {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error>;
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error>;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error>;
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error>;
+
+    /// Reads the value as encoded by the protocol version of the most
+    /// recently opened connection.
+    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+        Self::read_from_ctx(buf, &Context::current())
+    }
+
+    /// Writes the value as encoded by the protocol version of the most
+    /// recently opened connection.
+    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+        self.write_to_ctx(buf, &Context::current())
+    }
 }
 
 impl Serializable for Vec<u8> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Vec<u8>, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<Vec<u8>, Error> {
         let mut v = Vec::new();
         buf.read_to_end(&mut v)?;
         Ok(v)
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_all(&self[..]).map_err(|v| v.into())
     }
 }
 
 impl Serializable for Option<nbt::NamedTag> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Option<nbt::NamedTag>, Error> {
+    fn read_from_ctx<R: io::Read>(
+        buf: &mut R,
+        ctx: &Context,
+    ) -> Result<Option<nbt::NamedTag>, Error> {
         let ty = buf.read_u8()?;
         if ty == 0 {
             Result::Ok(None)
         } else {
             let name = nbt::read_string(buf)?;
-            let tag = nbt::Tag::read_from(buf)?;
+            let tag = nbt::Tag::read_from_ctx(buf, ctx)?;
             Result::Ok(Some(nbt::NamedTag(name, tag)))
         }
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         match *self {
             Some(ref val) => {
                 buf.write_u8(10)?;
                 nbt::write_string(buf, &val.0)?;
-                val.1.write_to(buf)?;
+                val.1.write_to_ctx(buf, ctx)?;
             }
             None => buf.write_u8(0)?,
         }
@@ -260,20 +398,20 @@ impl<T> Serializable for Option<T>
 where
     T: Serializable,
 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Option<T>, Error> {
-        Result::Ok(Some(T::read_from(buf)?))
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Option<T>, Error> {
+        Result::Ok(Some(T::read_from_ctx(buf, ctx)?))
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         if self.is_some() {
-            self.as_ref().unwrap().write_to(buf)?;
+            self.as_ref().unwrap().write_to_ctx(buf, ctx)?;
         }
         Result::Ok(())
     }
 }
 
 impl Serializable for String {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<String, Error> {
-        let len = VarInt::read_from(buf)?.0;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<String, Error> {
+        let len = VarInt::read_from_ctx(buf, ctx)?.0;
         debug_assert!(len >= 0, "Negative string length: {}", len);
         debug_assert!(len <= 65536, "String length too big: {}", len);
         let mut bytes = Vec::<u8>::new();
@@ -281,135 +419,135 @@ impl Serializable for String {
         let ret = String::from_utf8(bytes).unwrap();
         Result::Ok(ret)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         let bytes = self.as_bytes();
-        VarInt(bytes.len() as i32).write_to(buf)?;
+        VarInt(bytes.len() as i32).write_to_ctx(buf, ctx)?;
         buf.write_all(bytes)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for format::Component {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let len = VarInt::read_from(buf)?.0;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let len = VarInt::read_from_ctx(buf, ctx)?.0;
         let mut bytes = Vec::<u8>::new();
         buf.take(len as u64).read_to_end(&mut bytes)?;
         let ret = String::from_utf8(bytes).unwrap();
         Result::Ok(Self::from_string(&ret[..]))
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         let val = serde_json::to_string(&self.to_value()).unwrap();
         let bytes = val.as_bytes();
-        VarInt(bytes.len() as i32).write_to(buf)?;
+        VarInt(bytes.len() as i32).write_to_ctx(buf, ctx)?;
         buf.write_all(bytes)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for () {
-    fn read_from<R: io::Read>(_: &mut R) -> Result<(), Error> {
+    fn read_from_ctx<R: io::Read>(_: &mut R, _ctx: &Context) -> Result<(), Error> {
         Result::Ok(())
     }
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, _: &mut W, _ctx: &Context) -> Result<(), Error> {
         Result::Ok(())
     }
 }
 
 impl Serializable for bool {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<bool, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<bool, Error> {
         Result::Ok(buf.read_u8()? != 0)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_u8(if *self { 1 } else { 0 })?;
         Result::Ok(())
     }
 }
 
 impl Serializable for i8 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<i8, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<i8, Error> {
         Result::Ok(buf.read_i8()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_i8(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for i16 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<i16, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<i16, Error> {
         Result::Ok(buf.read_i16::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_i16::<BigEndian>(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for i32 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<i32, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<i32, Error> {
         Result::Ok(buf.read_i32::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_i32::<BigEndian>(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for i64 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<i64, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<i64, Error> {
         Result::Ok(buf.read_i64::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_i64::<BigEndian>(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for u8 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<u8, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<u8, Error> {
         Result::Ok(buf.read_u8()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_u8(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for u16 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<u16, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<u16, Error> {
         Result::Ok(buf.read_u16::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_u16::<BigEndian>(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for u64 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<u64, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<u64, Error> {
         Result::Ok(buf.read_u64::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_u64::<BigEndian>(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for f32 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<f32, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<f32, Error> {
         Result::Ok(buf.read_f32::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_f32::<BigEndian>(*self)?;
         Result::Ok(())
     }
 }
 
 impl Serializable for f64 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<f64, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<f64, Error> {
         Result::Ok(buf.read_f64::<BigEndian>()?)
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_f64::<BigEndian>(*self)?;
         Result::Ok(())
     }
@@ -449,6 +587,42 @@ impl std::str::FromStr for UUID {
     }
 }
 
+impl fmt::Display for UUID {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            self.0 >> 32,
+            (self.0 >> 16) & 0xffff,
+            self.0 & 0xffff,
+            self.1 >> 48,
+            self.1 & 0xffff_ffff_ffff
+        )
+    }
+}
+
+impl UUID {
+    /// Creates a name based (version 5) UUID, used to identify players on
+    /// servers which only send their names.
+    pub fn from_name(name: &str) -> UUID {
+        use sha1::{Digest, Sha1};
+        let mut hasher = Sha1::new();
+        hasher.update(b"OfflinePlayer:");
+        hasher.update(name.as_bytes());
+        let hash = hasher.finalize();
+        let mut high = 0u64;
+        let mut low = 0u64;
+        for i in 0..8 {
+            high |= (hash[i] as u64) << (56 - i * 8);
+            low |= (hash[i + 8] as u64) << (56 - i * 8);
+        }
+        // Set the version and variant bits
+        high = (high & !0xf000) | 0x5000;
+        low = (low & !(0xc000u64 << 48)) | (0x8000u64 << 48);
+        UUID(high, low)
+    }
+}
+
 impl Default for UUID {
     fn default() -> Self {
         UUID(0, 0)
@@ -456,13 +630,13 @@ impl Default for UUID {
 }
 
 impl Serializable for UUID {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<UUID, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<UUID, Error> {
         Result::Ok(UUID(
             buf.read_u64::<BigEndian>()?,
             buf.read_u64::<BigEndian>()?,
         ))
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         buf.write_u64::<BigEndian>(self.0)?;
         buf.write_u64::<BigEndian>(self.1)?;
         Result::Ok(())
@@ -491,19 +665,21 @@ impl Default for Biomes3D {
 }
 
 impl Serializable for Biomes3D {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Biomes3D, Error> {
-        let data: [i32; 1024] = [0; 1024];
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Biomes3D, Error> {
+        let mut data: [i32; 1024] = [0; 1024];
 
         // Non-length-prefixed three-dimensional biome data
-        for item in &mut data.to_vec() {
-            let b: i32 = Serializable::read_from(buf)?;
-            *item = b;
+        for item in data.iter_mut() {
+            *item = Serializable::read_from_ctx(buf, ctx)?;
         }
 
         Result::Ok(Biomes3D { data })
     }
-    fn write_to<W: io::Write>(&self, _buf: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        for item in self.data.iter() {
+            item.write_to_ctx(buf, ctx)?;
+        }
+        Result::Ok(())
     }
 }
 
@@ -512,7 +688,7 @@ pub trait Lengthable: Serializable + Copy + Default {
     fn from_len(_: usize) -> Self;
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct LenPrefixed<L: Lengthable, V> {
     len: L,
     pub data: Vec<V>,
@@ -528,12 +704,12 @@ impl<L: Lengthable, V: Default> LenPrefixed<L, V> {
 }
 
 impl<L: Lengthable, V: Serializable> Serializable for LenPrefixed<L, V> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<LenPrefixed<L, V>, Error> {
-        let len_data: L = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<LenPrefixed<L, V>, Error> {
+        let len_data: L = Serializable::read_from_ctx(buf, ctx)?;
         let len: usize = len_data.into_len();
         let mut data: Vec<V> = Vec::with_capacity(len);
         for _ in 0..len {
-            data.push(Serializable::read_from(buf)?);
+            data.push(Serializable::read_from_ctx(buf, ctx)?);
         }
         Result::Ok(LenPrefixed {
             len: len_data,
@@ -541,12 +717,12 @@ impl<L: Lengthable, V: Serializable> Serializable for LenPrefixed<L, V> {
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         let len_data: L = L::from_len(self.data.len());
-        len_data.write_to(buf)?;
+        len_data.write_to_ctx(buf, ctx)?;
         let data = &self.data;
         for val in data {
-            val.write_to(buf)?;
+            val.write_to_ctx(buf, ctx)?;
         }
         Result::Ok(())
     }
@@ -561,6 +737,15 @@ impl<L: Lengthable, V: Default> Default for LenPrefixed<L, V> {
     }
 }
 
+// The length read is only kept around, values built with `new` don't have it
+impl<L: Lengthable, V: PartialEq> PartialEq for LenPrefixed<L, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<L: Lengthable, V: Eq> Eq for LenPrefixed<L, V> {}
+
 impl<L: Lengthable, V: fmt::Debug> fmt::Debug for LenPrefixed<L, V> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.data.fmt(f)
@@ -568,7 +753,7 @@ impl<L: Lengthable, V: fmt::Debug> fmt::Debug for LenPrefixed<L, V> {
 }
 
 // Optimization
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct LenPrefixedBytes<L: Lengthable> {
     len: L,
     pub data: Vec<u8>,
@@ -584,8 +769,11 @@ impl<L: Lengthable> LenPrefixedBytes<L> {
 }
 
 impl<L: Lengthable> Serializable for LenPrefixedBytes<L> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<LenPrefixedBytes<L>, Error> {
-        let len_data: L = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(
+        buf: &mut R,
+        ctx: &Context,
+    ) -> Result<LenPrefixedBytes<L>, Error> {
+        let len_data: L = Serializable::read_from_ctx(buf, ctx)?;
         let len: usize = len_data.into_len();
         let mut data: Vec<u8> = Vec::with_capacity(len);
         buf.take(len as u64).read_to_end(&mut data)?;
@@ -595,9 +783,9 @@ impl<L: Lengthable> Serializable for LenPrefixedBytes<L> {
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         let len_data: L = L::from_len(self.data.len());
-        len_data.write_to(buf)?;
+        len_data.write_to_ctx(buf, ctx)?;
         buf.write_all(&self.data[..])?;
         Result::Ok(())
     }
@@ -612,6 +800,14 @@ impl<L: Lengthable> Default for LenPrefixedBytes<L> {
     }
 }
 
+impl<L: Lengthable> PartialEq for LenPrefixedBytes<L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<L: Lengthable> Eq for LenPrefixedBytes<L> {}
+
 impl<L: Lengthable> fmt::Debug for LenPrefixedBytes<L> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.data.fmt(f)
@@ -669,12 +865,12 @@ use num_traits::cast::{cast, NumCast};
 pub struct FixedPoint5<T>(T);
 
 impl<T: Serializable> Serializable for FixedPoint5<T> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        Ok(Self(Serializable::read_from(buf)?))
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        Ok(Self(Serializable::read_from_ctx(buf, ctx)?))
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.0.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.0.write_to_ctx(buf, ctx)
     }
 }
 
@@ -715,12 +911,12 @@ where
 pub struct FixedPoint12<T>(T);
 
 impl<T: Serializable> Serializable for FixedPoint12<T> {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        Ok(Self(Serializable::read_from(buf)?))
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        Ok(Self(Serializable::read_from_ctx(buf, ctx)?))
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.0.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.0.write_to_ctx(buf, ctx)
     }
 }
 
@@ -773,7 +969,7 @@ impl Lengthable for VarInt {
 
 impl Serializable for VarInt {
     /// Decodes a `VarInt` from the Reader
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<VarInt, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<VarInt, Error> {
         const PART: u32 = 0x7F;
         let mut size = 0;
         let mut val = 0u32;
@@ -793,7 +989,7 @@ impl Serializable for VarInt {
     }
 
     /// Encodes a `VarInt` into the Writer
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         const PART: u32 = 0x7F;
         let mut val = self.0 as u32;
         loop {
@@ -835,7 +1031,7 @@ impl Lengthable for VarShort {
 }
 
 impl Serializable for VarShort {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<VarShort, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<VarShort, Error> {
         let low = buf.read_u16::<BigEndian>()? as u32;
         let val = if (low & 0x8000) != 0 {
             let high = buf.read_u8()? as u32;
@@ -848,7 +1044,7 @@ impl Serializable for VarShort {
         Result::Ok(VarShort(val as i32))
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         assert!(
             self.0 >= 0 && self.0 <= 0x7fffff,
             "VarShort invalid value: {}",
@@ -899,7 +1095,7 @@ impl Lengthable for VarLong {
 
 impl Serializable for VarLong {
     /// Decodes a `VarLong` from the Reader
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<VarLong, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, _ctx: &Context) -> Result<VarLong, Error> {
         const PART: u64 = 0x7F;
         let mut size = 0;
         let mut val = 0u64;
@@ -919,7 +1115,7 @@ impl Serializable for VarLong {
     }
 
     /// Encodes a `VarLong` into the Writer
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, _ctx: &Context) -> Result<(), Error> {
         const PART: u64 = 0x7F;
         let mut val = self.0 as u64;
         loop {
@@ -946,9 +1142,9 @@ impl fmt::Debug for VarLong {
 }
 
 impl Serializable for Position {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Position, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Position, Error> {
         let pos = buf.read_u64::<BigEndian>()?;
-        let protocol_version = current_protocol_version();
+        let protocol_version = ctx.protocol_version;
         if protocol_version < 477 {
             Ok(Position::new(
                 ((pos as i64) >> 38) as i32,
@@ -963,9 +1159,9 @@ impl Serializable for Position {
             ))
         }
     }
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
         let pos;
-        let protocol_version = current_protocol_version();
+        let protocol_version = ctx.protocol_version;
         if protocol_version < 477 {
             pos = (((self.x as u64) & 0x3FFFFFF) << 38)
                 | (((self.y as u64) & 0xFFF) << 26)
@@ -1008,6 +1204,15 @@ pub enum Error {
     Json(serde_json::Error),
     #[cfg(not(target_arch = "wasm32"))]
     Reqwest(reqwest::Error),
+    /// A packet that couldn't be decoded, with what is needed to find out why
+    Packet {
+        state: State,
+        id: i32,
+        protocol_version: i32,
+        /// The bytes of the packet that hadn't been read when it failed
+        bytes_left: usize,
+        error: Box<Error>,
+    },
 }
 
 impl convert::From<io::Error> for Error {
@@ -1040,6 +1245,17 @@ impl ::std::fmt::Display for Error {
             Error::Json(ref e) => e.fmt(f),
             #[cfg(not(target_arch = "wasm32"))]
             Error::Reqwest(ref e) => e.fmt(f),
+            Error::Packet {
+                state,
+                id,
+                protocol_version,
+                bytes_left,
+                ref error,
+            } => write!(
+                f,
+                "failed to read packet 0x{:X} in state {:?} (protocol version {}, {} bytes left): {}",
+                id, state, protocol_version, bytes_left, error
+            ),
         }
     }
 }
@@ -1061,21 +1277,24 @@ pub struct Conn {
 
 impl Conn {
     pub fn new(target: &str, protocol_version: i32) -> Result<Conn, Error> {
+        Conn::connect_with(&resolve::Resolver::new(), target, protocol_version)
+    }
+
+    /// Connects to the server at the address, finding it with the resolver.
+    pub fn connect_with(
+        resolver: &resolve::Resolver,
+        target: &str,
+        protocol_version: i32,
+    ) -> Result<Conn, Error> {
+        // Only for `Serializable::read_from` and `write_to`, connections
+        // use their own version
         CURRENT_PROTOCOL_VERSION.store(protocol_version, Ordering::Relaxed);
 
-        // TODO SRV record support
-        let mut parts = target.split(':').collect::<Vec<&str>>();
-        let address = if parts.len() == 1 {
-            parts.push("25565");
-            format!("{}:25565", parts[0])
-        } else {
-            format!("{}:{}", parts[0], parts[1])
-        };
-        let stream = TcpStream::connect(&*address)?;
+        let (stream, host, port) = resolver.connect(target)?;
         Result::Ok(Conn {
             stream,
-            host: parts[0].to_owned(),
-            port: parts[1].parse().unwrap(),
+            host,
+            port,
             direction: Direction::Serverbound,
             state: State::Handshaking,
             protocol_version,
@@ -1084,10 +1303,34 @@ impl Conn {
         })
     }
 
+    /// Wraps a connection accepted from a client, for servers. Unlike
+    /// `connect_with` this doesn't change the global protocol version, so
+    /// a client and server can share a process.
+    pub fn accepted(stream: TcpStream, protocol_version: i32) -> Result<Conn, Error> {
+        let address = stream.peer_addr()?;
+        Result::Ok(Conn {
+            stream,
+            host: address.ip().to_string(),
+            port: address.port(),
+            direction: Direction::Clientbound,
+            state: State::Handshaking,
+            protocol_version,
+            cipher: Option::None,
+            compression_threshold: -1,
+        })
+    }
+
+    /// Returns the context values sent over this connection are read and
+    /// written in.
+    pub fn context(&self) -> Context {
+        Context::new(self.protocol_version)
+    }
+
     pub fn write_packet<T: PacketType>(&mut self, packet: T) -> Result<(), Error> {
+        let ctx = &self.context();
         let mut buf = Vec::new();
-        VarInt(packet.packet_id(self.protocol_version)).write_to(&mut buf)?;
-        packet.write(&mut buf)?;
+        VarInt(packet.packet_id(self.protocol_version)).write_to_ctx(&mut buf, ctx)?;
+        packet.write(&mut buf, ctx)?;
 
         let mut extra = if self.compression_threshold >= 0 {
             1
@@ -1098,7 +1341,7 @@ impl Conn {
             extra = 0;
             let uncompressed_size = buf.len();
             let mut new = Vec::new();
-            VarInt(uncompressed_size as i32).write_to(&mut new)?;
+            VarInt(uncompressed_size as i32).write_to_ctx(&mut new, ctx)?;
             let mut write = ZlibEncoder::new(io::Cursor::new(buf), Compression::default());
             write.read_to_end(&mut new)?;
             if is_network_debug() {
@@ -1113,9 +1356,9 @@ impl Conn {
             buf = new;
         }
 
-        VarInt(buf.len() as i32 + extra).write_to(self)?;
+        VarInt(buf.len() as i32 + extra).write_to_ctx(self, ctx)?;
         if self.compression_threshold >= 0 && extra == 1 {
-            VarInt(0).write_to(self)?;
+            VarInt(0).write_to_ctx(self, ctx)?;
         }
         self.write_all(&buf)?;
 
@@ -1147,7 +1390,7 @@ impl Conn {
 
     pub fn write_fmlhs_plugin_message(&mut self, msg: &forge::FmlHs) -> Result<(), Error> {
         let mut buf: Vec<u8> = vec![];
-        msg.write_to(&mut buf)?;
+        msg.write_to_ctx(&mut buf, &self.context())?;
 
         self.write_plugin_message("FML|HS", &buf)
     }
@@ -1178,13 +1421,16 @@ impl Conn {
         msg: Option<&forge::fml2::FmlHandshake>,
     ) -> Result<(), Error> {
         if let Some(msg) = msg {
+            let ctx = &self.context();
             let mut inner_buf: Vec<u8> = vec![];
-            msg.write_to(&mut inner_buf)?;
+            msg.write_to_ctx(&mut inner_buf, ctx)?;
 
             let mut outer_buf: Vec<u8> = vec![];
-            "fml:handshake".to_string().write_to(&mut outer_buf)?;
-            VarInt(inner_buf.len() as i32).write_to(&mut outer_buf)?;
-            inner_buf.write_to(&mut outer_buf)?;
+            "fml:handshake"
+                .to_string()
+                .write_to_ctx(&mut outer_buf, ctx)?;
+            VarInt(inner_buf.len() as i32).write_to_ctx(&mut outer_buf, ctx)?;
+            inner_buf.write_to_ctx(&mut outer_buf, ctx)?;
 
             self.write_login_plugin_response(message_id, true, &outer_buf)
         } else {
@@ -1245,30 +1491,31 @@ impl Conn {
             fs::File::create("last-packet")?.write_all(buf.get_ref())?;
         }
 
-        let packet = packet::packet_by_id(self.protocol_version, self.state, dir, id, &mut buf)?;
+        let packet = packet::packet_by_id(self.protocol_version, self.state, dir, id, &mut buf);
 
         if is_network_debug() {
             debug!("packet = {:?}", packet);
         }
 
-        match packet {
-            Some(val) => {
-                let pos = buf.position() as usize;
-                let ibuf = buf.into_inner();
-                if ibuf.len() != pos {
-                    debug!("pos = {:?}", pos);
-                    debug!("ibuf = {:?}", ibuf);
-                    return Result::Err(Error::Err(format!(
-                        "Failed to read all of packet 0x{:X}, \
-                                                           had {} bytes left",
-                        id,
-                        ibuf.len() - pos
-                    )));
-                }
-                Result::Ok(val)
+        let pos = buf.position() as usize;
+        let bytes_left = buf.get_ref().len() - pos;
+        let error = match packet {
+            Ok(Some(val)) if bytes_left == 0 => return Ok(val),
+            Ok(Some(_)) => {
+                debug!("pos = {:?}", pos);
+                debug!("ibuf = {:?}", buf.get_ref());
+                Error::Err("failed to read all of the packet".to_owned())
             }
-            None => Result::Err(Error::Err("missing packet".to_owned())),
-        }
+            Ok(None) => Error::Err("missing packet".to_owned()),
+            Err(err) => err,
+        };
+        Err(Error::Packet {
+            state: self.state,
+            id,
+            protocol_version: self.protocol_version,
+            bytes_left,
+            error: Box::new(error),
+        })
     }
 
     pub fn enable_encyption(&mut self, key: &[u8], _decrypt: bool) {
@@ -1424,7 +1671,9 @@ pub fn try_parse_packet(ibuf: Vec<u8>, protocol_version: i32) {
 
     let mut buf = io::Cursor::new(ibuf);
 
-    let id = VarInt::read_from(&mut buf).unwrap().0;
+    let id = VarInt::read_from_ctx(&mut buf, &Context::new(protocol_version))
+        .unwrap()
+        .0;
     let dir = Direction::Clientbound;
     let state = State::Play; // TODO: allow parsing other states
 
@@ -1538,5 +1787,385 @@ impl Clone for Conn {
 pub trait PacketType {
     fn packet_id(&self, protocol_version: i32) -> i32;
 
-    fn write<W: io::Write>(&self, buf: &mut W) -> Result<(), Error>;
+    fn write<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: Serializable>(value: &T, ctx: &Context) -> (Vec<u8>, T) {
+        let mut buf = vec![];
+        value.write_to_ctx(&mut buf, ctx).unwrap();
+        let read = T::read_from_ctx(&mut io::Cursor::new(&buf), ctx).unwrap();
+        (buf, read)
+    }
+
+    #[test]
+    fn position_by_context() {
+        let pos = Position::new(1, 2, 3);
+        let (old, old_read) = round_trip(&pos, &Context::new(404));
+        let (new, new_read) = round_trip(&pos, &Context::new(477));
+        assert_eq!(old_read, pos);
+        assert_eq!(new_read, pos);
+        // 1.14 moved y to the bottom bits
+        assert_ne!(old, new);
+        assert_eq!(new[7], 2);
+    }
+
+    #[test]
+    fn item_by_context() {
+        let stack = Some(crate::item::Stack {
+            id: 1,
+            count: 2,
+            damage: None,
+            tag: None,
+        });
+        let (old, _) = round_trip(&stack, &Context::new(340));
+        let (new, new_read) = round_trip(&stack, &Context::new(757));
+        // Present flag, VarInt id, count and no tag
+        assert_eq!(new, vec![1, 1, 2, 0]);
+        assert_eq!(new_read, stack);
+        // i16 id, count, damage and no tag
+        assert_eq!(old, vec![0, 1, 2, 0, 0, 0]);
+    }
+
+    // Some packets only make sense with their optional fields filled in for
+    // the default mode, so they're switched to a mode that has none.
+    fn complete(packet: packet::Packet) -> packet::Packet {
+        use packet::play::clientbound::*;
+        use packet::Packet;
+        match packet {
+            Packet::Teams_VarInt(p) => Packet::Teams_VarInt(Teams_VarInt { mode: 1, ..p }),
+            Packet::Teams_u8(p) => Packet::Teams_u8(Teams_u8 { mode: 1, ..p }),
+            Packet::Teams_NoCollision(p) => {
+                Packet::Teams_NoCollision(Teams_NoCollision { mode: 1, ..p })
+            }
+            Packet::Teams_NoVisColor(p) => {
+                Packet::Teams_NoVisColor(Teams_NoVisColor { mode: 1, ..p })
+            }
+            Packet::UpdateScore_VarInt(p) => Packet::UpdateScore_VarInt(UpdateScore_VarInt {
+                action: VarInt(1),
+                ..p
+            }),
+            Packet::UpdateScore_u8(p) => Packet::UpdateScore_u8(UpdateScore_u8 { action: 1, ..p }),
+            Packet::UpdateScore_i32(p) => {
+                Packet::UpdateScore_i32(UpdateScore_i32 { action: 1, ..p })
+            }
+            // Hide
+            Packet::Title(p) => Packet::Title(Title {
+                action: VarInt(4),
+                ..p
+            }),
+            Packet::Title_notext(p) => Packet::Title_notext(Title_notext {
+                action: VarInt(3),
+                ..p
+            }),
+            Packet::WorldBorder(p) => Packet::WorldBorder(WorldBorder {
+                new_radius: Some(0.0),
+                ..p
+            }),
+            Packet::EntityEquipment_Array(p) => {
+                Packet::EntityEquipment_Array(EntityEquipment_Array {
+                    equipments: packet::EntityEquipments {
+                        equipments: vec![Default::default(); 2],
+                    },
+                    ..p
+                })
+            }
+            packet => packet,
+        }
+    }
+
+    // Packets with their nested types filled in, which the defaults leave
+    // empty.
+    fn populated(version: i32) -> Vec<packet::Packet> {
+        use crate::format;
+        use crate::item;
+        use crate::types::{self, ParticleData, PoseData, VillagerData};
+        use packet::play::clientbound::*;
+        use packet::{CommandNode, CommandProperty, PlayerDetail, PlayerProperty};
+        use packet::{Packet, Recipe, RecipeData};
+
+        let stack = |id| {
+            Some(item::Stack {
+                id,
+                count: 1,
+                // Damage moved into the tag in 1.13.2
+                damage: if version >= 404 { None } else { Some(0) },
+                tag: None,
+            })
+        };
+
+        let nodes = vec![
+            CommandNode {
+                flags: 0,
+                children: LenPrefixed::new(vec![VarInt(1), VarInt(4)]),
+                ..Default::default()
+            },
+            CommandNode {
+                flags: 0x01,
+                children: LenPrefixed::new(vec![VarInt(2)]),
+                name: Some("give".to_owned()),
+                ..Default::default()
+            },
+            CommandNode {
+                flags: 0x02,
+                children: LenPrefixed::new(vec![VarInt(3)]),
+                name: Some("target".to_owned()),
+                parser: Some("minecraft:entity".to_owned()),
+                properties: Some(CommandProperty::Entity { flags: 0x02 }),
+                ..Default::default()
+            },
+            CommandNode {
+                flags: 0x02 | 0x04 | 0x10,
+                name: Some("count".to_owned()),
+                parser: Some("brigadier:integer".to_owned()),
+                properties: Some(CommandProperty::Integer {
+                    flags: 0x03,
+                    min: Some(1),
+                    max: Some(64),
+                }),
+                suggestions_type: Some("minecraft:ask_server".to_owned()),
+                ..Default::default()
+            },
+            CommandNode {
+                flags: 0x01 | 0x08,
+                redirect_node: Some(VarInt(1)),
+                name: Some("gift".to_owned()),
+                ..Default::default()
+            },
+        ];
+
+        let mut recipes = vec![
+            Recipe {
+                id: "minecraft:stick".to_owned(),
+                ty: "minecraft:crafting_shaped".to_owned(),
+                data: RecipeData::Shaped {
+                    width: VarInt(1),
+                    height: VarInt(2),
+                    group: "sticks".to_owned(),
+                    ingredients: vec![
+                        LenPrefixed::new(vec![stack(5)]),
+                        LenPrefixed::new(vec![stack(5), stack(6)]),
+                    ],
+                    result: stack(280),
+                },
+            },
+            Recipe {
+                id: "minecraft:oak_planks".to_owned(),
+                ty: "minecraft:crafting_shapeless".to_owned(),
+                data: RecipeData::Shapeless {
+                    group: "planks".to_owned(),
+                    ingredients: LenPrefixed::new(vec![LenPrefixed::new(vec![stack(17)])]),
+                    result: stack(5),
+                },
+            },
+            Recipe {
+                id: "minecraft:glass".to_owned(),
+                ty: "minecraft:smelting".to_owned(),
+                data: RecipeData::Smelting {
+                    group: String::new(),
+                    ingredient: LenPrefixed::new(vec![stack(12)]),
+                    result: stack(20),
+                    experience: 0.1,
+                    cooking_time: VarInt(200),
+                },
+            },
+            Recipe {
+                id: "minecraft:armor_dye".to_owned(),
+                ty: "minecraft:crafting_special_armordye".to_owned(),
+                data: RecipeData::ArmorDye,
+            },
+        ];
+        // Stonecutters came with 1.14 and smithing tables with 1.16
+        if version >= 477 {
+            recipes.push(Recipe {
+                id: "minecraft:stone_slab_from_stonecutting".to_owned(),
+                ty: "minecraft:stonecutting".to_owned(),
+                data: RecipeData::Stonecutting {
+                    group: String::new(),
+                    ingredient: LenPrefixed::new(vec![stack(1)]),
+                    result: stack(44),
+                },
+            });
+        }
+        if version >= 735 {
+            recipes.push(Recipe {
+                id: "minecraft:netherite_pickaxe_smithing".to_owned(),
+                ty: "minecraft:smithing".to_owned(),
+                data: RecipeData::Smithing {
+                    base: LenPrefixed::new(vec![stack(278)]),
+                    addition: LenPrefixed::new(vec![stack(600)]),
+                    result: stack(601),
+                },
+            });
+        }
+
+        let mut metadata = types::Metadata::new();
+        metadata.put(&types::MetadataKey::<i8>::new(0), 0x02);
+        metadata.put(&types::MetadataKey::<f32>::new(7), 20.0);
+        if version >= 404 {
+            metadata.put(
+                &types::MetadataKey::new(8),
+                ParticleData::Dust {
+                    red: 1.0,
+                    green: 0.5,
+                    blue: 0.0,
+                    scale: 1.0,
+                },
+            );
+            metadata.put(
+                &types::MetadataKey::new(9),
+                ParticleData::Item { item: stack(1) },
+            );
+        }
+        if version >= 477 {
+            metadata.put(&types::MetadataKey::new(6), PoseData::Sneaking);
+            metadata.put(
+                &types::MetadataKey::new(17),
+                VillagerData {
+                    villager_type: VarInt(2),
+                    profession: VarInt(5),
+                    level: VarInt(3),
+                },
+            );
+        }
+
+        let players = vec![
+            PlayerDetail::Add {
+                uuid: UUID::from_name("Notch"),
+                name: "Notch".to_owned(),
+                properties: vec![
+                    PlayerProperty {
+                        name: "textures".to_owned(),
+                        value: "e30=".to_owned(),
+                        signature: Some("c2lnbmF0dXJl".to_owned()),
+                    },
+                    PlayerProperty {
+                        name: "cape".to_owned(),
+                        value: "e30=".to_owned(),
+                        signature: None,
+                    },
+                ],
+                gamemode: VarInt(1),
+                ping: VarInt(42),
+                display: Some(format::Component::Text(format::TextComponent::new(
+                    "The Notch",
+                ))),
+            },
+            PlayerDetail::Add {
+                uuid: UUID::from_name("jeb_"),
+                name: "jeb_".to_owned(),
+                properties: vec![],
+                gamemode: VarInt(0),
+                ping: VarInt(0),
+                display: None,
+            },
+        ];
+
+        vec![
+            Packet::DeclareCommands(DeclareCommands {
+                nodes: LenPrefixed::new(nodes),
+                root_index: VarInt(0),
+            }),
+            Packet::DeclareRecipes(DeclareRecipes {
+                recipes: LenPrefixed::new(recipes),
+            }),
+            Packet::EntityMetadata(EntityMetadata {
+                entity_id: VarInt(1),
+                metadata: metadata.clone(),
+            }),
+            Packet::EntityMetadata_i32(EntityMetadata_i32 {
+                entity_id: 1,
+                metadata,
+            }),
+            Packet::PlayerInfo(PlayerInfo {
+                inner: packet::PlayerInfoData {
+                    action: VarInt(0),
+                    players,
+                },
+            }),
+        ]
+    }
+
+    #[test]
+    fn packets_round_trip() {
+        for &version in SUPPORTED_PROTOCOLS.iter() {
+            let defaults = packet::Packet::all_defaults().into_iter().map(complete);
+            for packet in defaults.chain(populated(version)) {
+                let id = match packet.id(version) {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let mut buf = vec![];
+                packet.write_to(version, &mut buf).unwrap();
+                let mut cursor = io::Cursor::new(&buf);
+                let read = packet::packet_by_id(
+                    version,
+                    packet.state(),
+                    packet.direction(),
+                    id,
+                    &mut cursor,
+                )
+                .unwrap_or_else(|err| panic!("{:?} in {}: {}", packet, version, err))
+                .unwrap();
+                assert_eq!(
+                    cursor.position() as usize,
+                    buf.len(),
+                    "{:?} in {}",
+                    packet,
+                    version
+                );
+                assert_eq!(read, packet, "in {}", version);
+                let mut again = vec![];
+                read.write_to(version, &mut again).unwrap();
+                assert_eq!(again, buf, "{:?} in {}", packet, version);
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_packet_ids() {
+        let mut buf = io::Cursor::new(vec![]);
+        let read = packet::packet_by_id(758, State::Play, Direction::Clientbound, 0x7f, &mut buf);
+        assert!(matches!(read, Ok(None)));
+        let read = packet::packet_by_id(1, State::Status, Direction::Clientbound, 0x00, &mut buf);
+        assert!(matches!(read, Ok(None)));
+    }
+
+    #[test]
+    fn unknown_nested_types() {
+        let ctx = Context::new(758);
+        // A node type of 3 isn't used
+        let node = [0x03, 0x00];
+        let read = packet::CommandNode::read_from_ctx(&mut io::Cursor::new(&node), &ctx);
+        assert!(matches!(read, Err(Error::Err(_))));
+
+        let mut recipe = vec![];
+        "minecraft:unknown"
+            .to_owned()
+            .write_to_ctx(&mut recipe, &ctx)
+            .unwrap();
+        "minecraft:test"
+            .to_owned()
+            .write_to_ctx(&mut recipe, &ctx)
+            .unwrap();
+        let read = packet::Recipe::read_from_ctx(&mut io::Cursor::new(&recipe), &ctx);
+        assert!(matches!(read, Err(Error::Err(_))));
+    }
+
+    #[test]
+    fn uuid_string() {
+        let text = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+        let uuid: UUID = text.parse().unwrap();
+        assert_eq!(uuid.to_string(), text);
+        assert_eq!(
+            UUID::from_name("Notch")
+                .to_string()
+                .parse::<UUID>()
+                .unwrap(),
+            UUID::from_name("Notch")
+        );
+    }
 }
@@ -76,6 +76,12 @@ state_packets!(
             packet TabComplete_NoAssume_NoTarget {
                 field text: String =,
             }
+            /// TabComplete_Transaction replaced the target of the request with
+            /// an id which the reply echoes back in 1.13.
+            packet TabComplete_Transaction {
+                field transaction_id: VarInt =,
+                field text: String =,
+            }
             /// ChatMessage is sent by the client when it sends a chat message or
             /// executes a command (prefixed by '/').
             packet ChatMessage {
@@ -189,7 +195,8 @@ state_packets!(
             packet EditBook_Pages {
                 field hand: VarInt =,
                 field pages: LenPrefixed<VarInt, String> =,
-                field title: Option<String> =,
+                field has_title: bool =,
+                field title: Option<String> = when(|p: &EditBook_Pages| p.has_title),
             }
             packet EditBook_Item {
                 field new_book: Option<item::Stack> =,
@@ -907,6 +914,12 @@ state_packets!(
             packet TabCompleteReply {
                 field matches: LenPrefixed<VarInt, String> =,
             }
+            packet TabCompleteReply_Transaction {
+                field transaction_id: VarInt =,
+                field start: VarInt =,
+                field length: VarInt =,
+                field matches: LenPrefixed<VarInt, packet::TabCompleteMatch> =,
+            }
             packet DeclareCommands {
                 field nodes: LenPrefixed<VarInt, packet::CommandNode> =,
                 field root_index: VarInt =,
@@ -928,10 +941,10 @@ state_packets!(
             packet ServerMessage_NoPosition {
                 field message: format::Component =,
             }
+            /// ClearTitles hides the current title, resetting the subtitle and
+            /// timings as well if `reset` is set.
             packet ClearTitles {
-                field json_data: String =,
-                field position: u8 =,
-                field sender: UUID =,
+                field reset: bool =,
             }
             /// MultiBlockChange is used to update a batch of blocks in a single packet.
             packet MultiBlockChange_Packed {
@@ -1879,7 +1892,7 @@ state_packets!(
                 field tab_id: String = when(|p: &SelectAdvancementTab| p.has_id),
             }
             packet ActionBar {
-                field text: String =,
+                field text: format::Component =,
             }
             /// WorldBorder configures the world's border.
             packet WorldBorder {
@@ -2019,6 +2032,12 @@ state_packets!(
                 field value: String = when(|p: &ScoreboardObjective| p.mode == 0 || p.mode == 2),
                 field ty: String = when(|p: &ScoreboardObjective| p.mode == 0 || p.mode == 2),
             }
+            packet ScoreboardObjective_VarInt {
+                field name: String =,
+                field mode: u8 =,
+                field value: String = when(|p: &ScoreboardObjective_VarInt| p.mode == 0 || p.mode == 2),
+                field ty: VarInt = when(|p: &ScoreboardObjective_VarInt| p.mode == 0 || p.mode == 2),
+            }
             packet ScoreboardObjective_NoMode {
                 field name: String =,
                 field value: String =,
@@ -2045,7 +2064,6 @@ state_packets!(
             packet Teams_u8 {
                 field name: String =,
                 field mode: u8 =,
-                field data: Vec<u8> =,
                 field display_name: Option<String> = when(|p: &Teams_u8| p.mode == 0 || p.mode == 2),
                 field prefix: Option<String> = when(|p: &Teams_u8| p.mode == 0 || p.mode == 2),
                 field suffix: Option<String> = when(|p: &Teams_u8| p.mode == 0 || p.mode == 2),
@@ -2055,6 +2073,17 @@ state_packets!(
                 field color: Option<i8> = when(|p: &Teams_u8| p.mode == 0 || p.mode == 2),
                 field players: Option<LenPrefixed<VarInt, String>> = when(|p: &Teams_u8| p.mode == 0 || p.mode == 3 || p.mode == 4),
             }
+            packet Teams_NoCollision {
+                field name: String =,
+                field mode: u8 =,
+                field display_name: Option<String> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 2),
+                field prefix: Option<String> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 2),
+                field suffix: Option<String> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 2),
+                field flags: Option<u8> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 2),
+                field name_tag_visibility: Option<String> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 2),
+                field color: Option<i8> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 2),
+                field players: Option<LenPrefixed<VarInt, String>> = when(|p: &Teams_NoCollision| p.mode == 0 || p.mode == 3 || p.mode == 4),
+            }
             packet Teams_NoVisColor {
                 field name: String =,
                 field mode: u8 =,
@@ -2120,7 +2149,7 @@ state_packets!(
                 field action: VarInt =,
                 field title: Option<format::Component> = when(|p: &Title| p.action.0 == 0),
                 field sub_title: Option<format::Component> = when(|p: &Title| p.action.0 == 1),
-                field action_bar_text: Option<String> = when(|p: &Title| p.action.0 == 2),
+                field action_bar_text: Option<format::Component> = when(|p: &Title| p.action.0 == 2),
                 field fade_in: Option<i32> = when(|p: &Title| p.action.0 == 3),
                 field fade_stay: Option<i32> = when(|p: &Title| p.action.0 == 3),
                 field fade_out: Option<i32> = when(|p: &Title| p.action.0 == 3),
@@ -2133,16 +2162,11 @@ state_packets!(
                 field fade_stay: Option<i32> = when(|p: &Title_notext| p.action.0 == 2),
                 field fade_out: Option<i32> = when(|p: &Title_notext| p.action.0 == 2),
             }
-            packet Title_notext_component {
-                field action: VarInt =,
-                field title: Option<format::Component> = when(|p: &Title_notext_component| p.action.0 == 0),
-                field sub_title: Option<format::Component> = when(|p: &Title_notext_component| p.action.0 == 1),
-                field fade_in: Option<format::Component> = when(|p: &Title_notext_component| p.action.0 == 2),
-                field fade_stay: Option<format::Component> = when(|p: &Title_notext_component| p.action.0 == 2),
-                field fade_out: Option<format::Component> = when(|p: &Title_notext_component| p.action.0 == 2),
+            packet TitleText {
+                field title: format::Component =,
             }
             packet TitleSubtitle {
-                field subtitle_text: String =,
+                field subtitle: format::Component =,
             }
             packet TitleTimes {
                 field fade_in: i32 =,
@@ -2499,18 +2523,18 @@ pub struct SpawnProperty {
 }
 
 impl Serializable for SpawnProperty {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(SpawnProperty {
-            name: Serializable::read_from(buf)?,
-            value: Serializable::read_from(buf)?,
-            signature: Serializable::read_from(buf)?,
+            name: Serializable::read_from_ctx(buf, ctx)?,
+            value: Serializable::read_from_ctx(buf, ctx)?,
+            signature: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.name.write_to(buf)?;
-        self.value.write_to(buf)?;
-        self.signature.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.name.write_to_ctx(buf, ctx)?;
+        self.value.write_to_ctx(buf, ctx)?;
+        self.signature.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2521,16 +2545,16 @@ pub struct Statistic {
 }
 
 impl Serializable for Statistic {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(Statistic {
-            name: Serializable::read_from(buf)?,
-            value: Serializable::read_from(buf)?,
+            name: Serializable::read_from_ctx(buf, ctx)?,
+            value: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.name.write_to(buf)?;
-        self.value.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.name.write_to_ctx(buf, ctx)?;
+        self.value.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2542,18 +2566,18 @@ pub struct BlockChangeRecord {
 }
 
 impl Serializable for BlockChangeRecord {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(BlockChangeRecord {
-            xz: Serializable::read_from(buf)?,
-            y: Serializable::read_from(buf)?,
-            block_id: Serializable::read_from(buf)?,
+            xz: Serializable::read_from_ctx(buf, ctx)?,
+            y: Serializable::read_from_ctx(buf, ctx)?,
+            block_id: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.xz.write_to(buf)?;
-        self.y.write_to(buf)?;
-        self.block_id.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.xz.write_to_ctx(buf, ctx)?;
+        self.y.write_to_ctx(buf, ctx)?;
+        self.block_id.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2565,18 +2589,18 @@ pub struct ChunkMeta {
 }
 
 impl Serializable for ChunkMeta {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(ChunkMeta {
-            x: Serializable::read_from(buf)?,
-            z: Serializable::read_from(buf)?,
-            bitmask: Serializable::read_from(buf)?,
+            x: Serializable::read_from_ctx(buf, ctx)?,
+            z: Serializable::read_from_ctx(buf, ctx)?,
+            bitmask: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.x.write_to(buf)?;
-        self.z.write_to(buf)?;
-        self.bitmask.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.x.write_to_ctx(buf, ctx)?;
+        self.z.write_to_ctx(buf, ctx)?;
+        self.bitmask.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2588,18 +2612,18 @@ pub struct ExplosionRecord {
 }
 
 impl Serializable for ExplosionRecord {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(ExplosionRecord {
-            x: Serializable::read_from(buf)?,
-            y: Serializable::read_from(buf)?,
-            z: Serializable::read_from(buf)?,
+            x: Serializable::read_from_ctx(buf, ctx)?,
+            y: Serializable::read_from_ctx(buf, ctx)?,
+            z: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.x.write_to(buf)?;
-        self.y.write_to(buf)?;
-        self.z.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.x.write_to_ctx(buf, ctx)?;
+        self.y.write_to_ctx(buf, ctx)?;
+        self.z.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2611,18 +2635,18 @@ pub struct MapIcon {
 }
 
 impl Serializable for MapIcon {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(MapIcon {
-            direction_type: Serializable::read_from(buf)?,
-            x: Serializable::read_from(buf)?,
-            z: Serializable::read_from(buf)?,
+            direction_type: Serializable::read_from_ctx(buf, ctx)?,
+            x: Serializable::read_from_ctx(buf, ctx)?,
+            z: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.direction_type.write_to(buf)?;
-        self.x.write_to(buf)?;
-        self.z.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.direction_type.write_to_ctx(buf, ctx)?;
+        self.x.write_to_ctx(buf, ctx)?;
+        self.z.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2646,31 +2670,31 @@ pub struct Advancement {
 }
 
 impl Serializable for Advancement {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let id: String = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let id: String = Serializable::read_from_ctx(buf, ctx)?;
         let parent_id = {
-            let has_parent: u8 = Serializable::read_from(buf)?;
+            let has_parent: u8 = Serializable::read_from_ctx(buf, ctx)?;
             if has_parent != 0 {
-                let parent_id: String = Serializable::read_from(buf)?;
+                let parent_id: String = Serializable::read_from_ctx(buf, ctx)?;
                 Some(parent_id)
             } else {
                 None
             }
         };
 
-        let has_display: u8 = Serializable::read_from(buf)?;
+        let has_display: u8 = Serializable::read_from_ctx(buf, ctx)?;
         let display_data = {
             if has_display != 0 {
-                let display_data: AdvancementDisplay = Serializable::read_from(buf)?;
+                let display_data: AdvancementDisplay = Serializable::read_from_ctx(buf, ctx)?;
                 Some(display_data)
             } else {
                 None
             }
         };
 
-        let criteria: LenPrefixed<VarInt, String> = Serializable::read_from(buf)?;
+        let criteria: LenPrefixed<VarInt, String> = Serializable::read_from_ctx(buf, ctx)?;
         let requirements: LenPrefixed<VarInt, LenPrefixed<VarInt, String>> =
-            Serializable::read_from(buf)?;
+            Serializable::read_from_ctx(buf, ctx)?;
         Ok(Advancement {
             id,
             parent_id,
@@ -2680,12 +2704,12 @@ impl Serializable for Advancement {
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.id.write_to(buf)?;
-        self.parent_id.write_to(buf)?;
-        self.display_data.write_to(buf)?;
-        self.criteria.write_to(buf)?;
-        self.requirements.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.id.write_to_ctx(buf, ctx)?;
+        self.parent_id.write_to_ctx(buf, ctx)?;
+        self.display_data.write_to_ctx(buf, ctx)?;
+        self.criteria.write_to_ctx(buf, ctx)?;
+        self.requirements.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2702,19 +2726,19 @@ pub struct AdvancementDisplay {
 }
 
 impl Serializable for AdvancementDisplay {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let title: String = Serializable::read_from(buf)?;
-        let description: String = Serializable::read_from(buf)?;
-        let icon: Option<crate::item::Stack> = Serializable::read_from(buf)?;
-        let frame_type: VarInt = Serializable::read_from(buf)?;
-        let flags: i32 = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let title: String = Serializable::read_from_ctx(buf, ctx)?;
+        let description: String = Serializable::read_from_ctx(buf, ctx)?;
+        let icon: Option<crate::item::Stack> = Serializable::read_from_ctx(buf, ctx)?;
+        let frame_type: VarInt = Serializable::read_from_ctx(buf, ctx)?;
+        let flags: i32 = Serializable::read_from_ctx(buf, ctx)?;
         let background_texture: Option<String> = if flags & 1 != 0 {
-            Serializable::read_from(buf)?
+            Serializable::read_from_ctx(buf, ctx)?
         } else {
             None
         };
-        let x_coord: f32 = Serializable::read_from(buf)?;
-        let y_coord: f32 = Serializable::read_from(buf)?;
+        let x_coord: f32 = Serializable::read_from_ctx(buf, ctx)?;
+        let y_coord: f32 = Serializable::read_from_ctx(buf, ctx)?;
 
         Ok(AdvancementDisplay {
             title,
@@ -2728,17 +2752,17 @@ impl Serializable for AdvancementDisplay {
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.title.write_to(buf)?;
-        self.description.write_to(buf)?;
-        self.icon.write_to(buf)?;
-        self.frame_type.write_to(buf)?;
-        self.flags.write_to(buf)?;
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.title.write_to_ctx(buf, ctx)?;
+        self.description.write_to_ctx(buf, ctx)?;
+        self.icon.write_to_ctx(buf, ctx)?;
+        self.frame_type.write_to_ctx(buf, ctx)?;
+        self.flags.write_to_ctx(buf, ctx)?;
         if self.flags & 1 != 0 {
-            self.background_texture.write_to(buf)?;
+            self.background_texture.write_to_ctx(buf, ctx)?;
         }
-        self.x_coord.write_to(buf)?;
-        self.y_coord.write_to(buf)
+        self.x_coord.write_to_ctx(buf, ctx)?;
+        self.y_coord.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2749,16 +2773,16 @@ pub struct AdvancementProgress {
 }
 
 impl Serializable for AdvancementProgress {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(AdvancementProgress {
-            id: Serializable::read_from(buf)?,
-            criteria: Serializable::read_from(buf)?,
+            id: Serializable::read_from_ctx(buf, ctx)?,
+            criteria: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.id.write_to(buf)?;
-        self.criteria.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.id.write_to_ctx(buf, ctx)?;
+        self.criteria.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2769,11 +2793,11 @@ pub struct CriterionProgress {
 }
 
 impl Serializable for CriterionProgress {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let id = Serializable::read_from(buf)?;
-        let achieved: u8 = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let id = Serializable::read_from_ctx(buf, ctx)?;
+        let achieved: u8 = Serializable::read_from_ctx(buf, ctx)?;
         let date_of_achieving: Option<i64> = if achieved != 0 {
-            Serializable::read_from(buf)?
+            Serializable::read_from_ctx(buf, ctx)?
         } else {
             None
         };
@@ -2784,9 +2808,9 @@ impl Serializable for CriterionProgress {
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.id.write_to(buf)?;
-        self.date_of_achieving.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.id.write_to_ctx(buf, ctx)?;
+        self.date_of_achieving.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2801,20 +2825,20 @@ pub struct BlockEntityAtPackedLocation {
 }
 
 impl Serializable for BlockEntityAtPackedLocation {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(BlockEntityAtPackedLocation {
-            packed_xz: Serializable::read_from(buf)?,
-            y: Serializable::read_from(buf)?,
-            ty: Serializable::read_from(buf)?,
-            data: Serializable::read_from(buf)?,
+            packed_xz: Serializable::read_from_ctx(buf, ctx)?,
+            y: Serializable::read_from_ctx(buf, ctx)?,
+            ty: Serializable::read_from_ctx(buf, ctx)?,
+            data: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.packed_xz.write_to(buf)?;
-        self.y.write_to(buf)?;
-        self.ty.write_to(buf)?;
-        self.data.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.packed_xz.write_to_ctx(buf, ctx)?;
+        self.y.write_to_ctx(buf, ctx)?;
+        self.ty.write_to_ctx(buf, ctx)?;
+        self.data.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2825,16 +2849,16 @@ pub struct EntityEquipment {
 }
 
 impl Serializable for EntityEquipment {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(EntityEquipment {
-            slot: Serializable::read_from(buf)?,
-            item: Serializable::read_from(buf)?,
+            slot: Serializable::read_from_ctx(buf, ctx)?,
+            item: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.slot.write_to(buf)?;
-        self.item.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.slot.write_to_ctx(buf, ctx)?;
+        self.item.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2845,11 +2869,11 @@ pub struct EntityEquipments {
 }
 
 impl Serializable for EntityEquipments {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         let mut equipments: Vec<EntityEquipment> = vec![];
 
         loop {
-            let e: EntityEquipment = Serializable::read_from(buf)?;
+            let e: EntityEquipment = Serializable::read_from_ctx(buf, ctx)?;
             equipments.push(EntityEquipment {
                 slot: e.slot & 0x7f,
                 item: e.item,
@@ -2864,8 +2888,14 @@ impl Serializable for EntityEquipments {
         Ok(EntityEquipments { equipments })
     }
 
-    fn write_to<W: io::Write>(&self, _buf: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        let last = self.equipments.len().saturating_sub(1);
+        for (i, e) in self.equipments.iter().enumerate() {
+            let more = if i < last { 0x80 } else { 0 };
+            (e.slot | more).write_to_ctx(buf, ctx)?;
+            e.item.write_to_ctx(buf, ctx)?;
+        }
+        Ok(())
     }
 }
 
@@ -2877,18 +2907,18 @@ pub struct EntityProperty {
 }
 
 impl Serializable for EntityProperty {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(EntityProperty {
-            key: Serializable::read_from(buf)?,
-            value: Serializable::read_from(buf)?,
-            modifiers: Serializable::read_from(buf)?,
+            key: Serializable::read_from_ctx(buf, ctx)?,
+            value: Serializable::read_from_ctx(buf, ctx)?,
+            modifiers: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.key.write_to(buf)?;
-        self.value.write_to(buf)?;
-        self.modifiers.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.key.write_to_ctx(buf, ctx)?;
+        self.value.write_to_ctx(buf, ctx)?;
+        self.modifiers.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2900,18 +2930,18 @@ pub struct EntityProperty_i16 {
 }
 
 impl Serializable for EntityProperty_i16 {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(EntityProperty_i16 {
-            key: Serializable::read_from(buf)?,
-            value: Serializable::read_from(buf)?,
-            modifiers: Serializable::read_from(buf)?,
+            key: Serializable::read_from_ctx(buf, ctx)?,
+            value: Serializable::read_from_ctx(buf, ctx)?,
+            modifiers: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.key.write_to(buf)?;
-        self.value.write_to(buf)?;
-        self.modifiers.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.key.write_to_ctx(buf, ctx)?;
+        self.value.write_to_ctx(buf, ctx)?;
+        self.modifiers.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2923,18 +2953,18 @@ pub struct PropertyModifier {
 }
 
 impl Serializable for PropertyModifier {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(PropertyModifier {
-            uuid: Serializable::read_from(buf)?,
-            amount: Serializable::read_from(buf)?,
-            operation: Serializable::read_from(buf)?,
+            uuid: Serializable::read_from_ctx(buf, ctx)?,
+            amount: Serializable::read_from_ctx(buf, ctx)?,
+            operation: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.uuid.write_to(buf)?;
-        self.amount.write_to(buf)?;
-        self.operation.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.uuid.write_to_ctx(buf, ctx)?;
+        self.amount.write_to_ctx(buf, ctx)?;
+        self.operation.write_to_ctx(buf, ctx)
     }
 }
 
@@ -2945,27 +2975,27 @@ pub struct PlayerInfoData {
 }
 
 impl Serializable for PlayerInfoData {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         let mut m = PlayerInfoData {
-            action: Serializable::read_from(buf)?,
+            action: Serializable::read_from_ctx(buf, ctx)?,
             players: Vec::new(),
         };
-        let len = VarInt::read_from(buf)?;
+        let len = VarInt::read_from_ctx(buf, ctx)?;
         for _ in 0..len.0 {
-            let uuid = UUID::read_from(buf)?;
+            let uuid = UUID::read_from_ctx(buf, ctx)?;
             match m.action.0 {
                 0 => {
-                    let name = String::read_from(buf)?;
+                    let name = String::read_from_ctx(buf, ctx)?;
                     let mut props = Vec::new();
-                    let plen = VarInt::read_from(buf)?.0;
+                    let plen = VarInt::read_from_ctx(buf, ctx)?.0;
                     for _ in 0..plen {
                         let mut prop = PlayerProperty {
-                            name: String::read_from(buf)?,
-                            value: String::read_from(buf)?,
+                            name: String::read_from_ctx(buf, ctx)?,
+                            value: String::read_from_ctx(buf, ctx)?,
                             signature: Default::default(),
                         };
-                        if bool::read_from(buf)? {
-                            prop.signature = Some(String::read_from(buf)?);
+                        if bool::read_from_ctx(buf, ctx)? {
+                            prop.signature = Some(String::read_from_ctx(buf, ctx)?);
                         }
                         props.push(prop);
                     }
@@ -2973,11 +3003,11 @@ impl Serializable for PlayerInfoData {
                         uuid,
                         name,
                         properties: props,
-                        gamemode: Serializable::read_from(buf)?,
-                        ping: Serializable::read_from(buf)?,
+                        gamemode: Serializable::read_from_ctx(buf, ctx)?,
+                        ping: Serializable::read_from_ctx(buf, ctx)?,
                         display: {
-                            if bool::read_from(buf)? {
-                                Some(Serializable::read_from(buf)?)
+                            if bool::read_from_ctx(buf, ctx)? {
+                                Some(Serializable::read_from_ctx(buf, ctx)?)
                             } else {
                                 None
                             }
@@ -2987,17 +3017,17 @@ impl Serializable for PlayerInfoData {
                 }
                 1 => m.players.push(PlayerDetail::UpdateGamemode {
                     uuid,
-                    gamemode: Serializable::read_from(buf)?,
+                    gamemode: Serializable::read_from_ctx(buf, ctx)?,
                 }),
                 2 => m.players.push(PlayerDetail::UpdateLatency {
                     uuid,
-                    ping: Serializable::read_from(buf)?,
+                    ping: Serializable::read_from_ctx(buf, ctx)?,
                 }),
                 3 => m.players.push(PlayerDetail::UpdateDisplayName {
                     uuid,
                     display: {
-                        if bool::read_from(buf)? {
-                            Some(Serializable::read_from(buf)?)
+                        if bool::read_from_ctx(buf, ctx)? {
+                            Some(Serializable::read_from_ctx(buf, ctx)?)
                         } else {
                             None
                         }
@@ -3010,8 +3040,53 @@ impl Serializable for PlayerInfoData {
         Ok(m)
     }
 
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
-        unimplemented!() // I'm lazy
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.action.write_to_ctx(buf, ctx)?;
+        VarInt(self.players.len() as i32).write_to_ctx(buf, ctx)?;
+        for player in &self.players {
+            match *player {
+                PlayerDetail::Add {
+                    ref uuid,
+                    ref name,
+                    ref properties,
+                    gamemode,
+                    ping,
+                    ref display,
+                } => {
+                    uuid.write_to_ctx(buf, ctx)?;
+                    name.write_to_ctx(buf, ctx)?;
+                    VarInt(properties.len() as i32).write_to_ctx(buf, ctx)?;
+                    for prop in properties {
+                        prop.name.write_to_ctx(buf, ctx)?;
+                        prop.value.write_to_ctx(buf, ctx)?;
+                        prop.signature.is_some().write_to_ctx(buf, ctx)?;
+                        prop.signature.write_to_ctx(buf, ctx)?;
+                    }
+                    gamemode.write_to_ctx(buf, ctx)?;
+                    ping.write_to_ctx(buf, ctx)?;
+                    display.is_some().write_to_ctx(buf, ctx)?;
+                    display.write_to_ctx(buf, ctx)?;
+                }
+                PlayerDetail::UpdateGamemode { ref uuid, gamemode } => {
+                    uuid.write_to_ctx(buf, ctx)?;
+                    gamemode.write_to_ctx(buf, ctx)?;
+                }
+                PlayerDetail::UpdateLatency { ref uuid, ping } => {
+                    uuid.write_to_ctx(buf, ctx)?;
+                    ping.write_to_ctx(buf, ctx)?;
+                }
+                PlayerDetail::UpdateDisplayName {
+                    ref uuid,
+                    ref display,
+                } => {
+                    uuid.write_to_ctx(buf, ctx)?;
+                    display.is_some().write_to_ctx(buf, ctx)?;
+                    display.write_to_ctx(buf, ctx)?;
+                }
+                PlayerDetail::Remove { ref uuid } => uuid.write_to_ctx(buf, ctx)?,
+            }
+        }
+        Ok(())
     }
 }
 
@@ -3143,12 +3218,12 @@ pub struct Recipe {
 }
 
 impl Serializable for Recipe {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         let (id, ty) = {
-            let a = String::read_from(buf)?;
-            let b = String::read_from(buf)?;
+            let a = String::read_from_ctx(buf, ctx)?;
+            let b = String::read_from_ctx(buf, ctx)?;
 
-            let protocol_version = super::current_protocol_version();
+            let protocol_version = ctx.protocol_version;
 
             // 1.14+ swaps recipe identifier and type, and adds namespace to type
             if protocol_version >= 477 {
@@ -3169,22 +3244,22 @@ impl Serializable for Recipe {
 
         let data = match ty.as_ref() {
             "minecraft:crafting_shapeless" => RecipeData::Shapeless {
-                group: Serializable::read_from(buf)?,
-                ingredients: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
+                group: Serializable::read_from_ctx(buf, ctx)?,
+                ingredients: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
             },
             "minecraft:crafting_shaped" => {
-                let width: VarInt = Serializable::read_from(buf)?;
-                let height: VarInt = Serializable::read_from(buf)?;
-                let group: String = Serializable::read_from(buf)?;
+                let width: VarInt = Serializable::read_from_ctx(buf, ctx)?;
+                let height: VarInt = Serializable::read_from_ctx(buf, ctx)?;
+                let group: String = Serializable::read_from_ctx(buf, ctx)?;
 
                 let capacity = width.0 as usize * height.0 as usize;
 
                 let mut ingredients = Vec::with_capacity(capacity);
                 for _ in 0..capacity {
-                    ingredients.push(Serializable::read_from(buf)?);
+                    ingredients.push(Serializable::read_from_ctx(buf, ctx)?);
                 }
-                let result: Option<item::Stack> = Serializable::read_from(buf)?;
+                let result: Option<item::Stack> = Serializable::read_from_ctx(buf, ctx)?;
 
                 RecipeData::Shaped {
                     width,
@@ -3209,51 +3284,141 @@ impl Serializable for Recipe {
             "minecraft:crafting_special_shulkerboxcoloring" => RecipeData::ShulkerBoxColoring,
             "minecraft:crafting_special_suspiciousstew" => RecipeData::SuspiciousStew,
             "minecraft:smelting" => RecipeData::Smelting {
-                group: Serializable::read_from(buf)?,
-                ingredient: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
-                experience: Serializable::read_from(buf)?,
-                cooking_time: Serializable::read_from(buf)?,
+                group: Serializable::read_from_ctx(buf, ctx)?,
+                ingredient: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
+                experience: Serializable::read_from_ctx(buf, ctx)?,
+                cooking_time: Serializable::read_from_ctx(buf, ctx)?,
             },
             "minecraft:blasting" => RecipeData::Blasting {
-                group: Serializable::read_from(buf)?,
-                ingredient: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
-                experience: Serializable::read_from(buf)?,
-                cooking_time: Serializable::read_from(buf)?,
+                group: Serializable::read_from_ctx(buf, ctx)?,
+                ingredient: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
+                experience: Serializable::read_from_ctx(buf, ctx)?,
+                cooking_time: Serializable::read_from_ctx(buf, ctx)?,
             },
             "minecraft:smoking" => RecipeData::Smoking {
-                group: Serializable::read_from(buf)?,
-                ingredient: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
-                experience: Serializable::read_from(buf)?,
-                cooking_time: Serializable::read_from(buf)?,
+                group: Serializable::read_from_ctx(buf, ctx)?,
+                ingredient: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
+                experience: Serializable::read_from_ctx(buf, ctx)?,
+                cooking_time: Serializable::read_from_ctx(buf, ctx)?,
             },
             "minecraft:campfire" | "minecraft:campfire_cooking" => RecipeData::Campfire {
-                group: Serializable::read_from(buf)?,
-                ingredient: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
-                experience: Serializable::read_from(buf)?,
-                cooking_time: Serializable::read_from(buf)?,
+                group: Serializable::read_from_ctx(buf, ctx)?,
+                ingredient: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
+                experience: Serializable::read_from_ctx(buf, ctx)?,
+                cooking_time: Serializable::read_from_ctx(buf, ctx)?,
             },
             "minecraft:stonecutting" => RecipeData::Stonecutting {
-                group: Serializable::read_from(buf)?,
-                ingredient: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
+                group: Serializable::read_from_ctx(buf, ctx)?,
+                ingredient: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
             },
             "minecraft:smithing" => RecipeData::Smithing {
-                base: Serializable::read_from(buf)?,
-                addition: Serializable::read_from(buf)?,
-                result: Serializable::read_from(buf)?,
+                base: Serializable::read_from_ctx(buf, ctx)?,
+                addition: Serializable::read_from_ctx(buf, ctx)?,
+                result: Serializable::read_from_ctx(buf, ctx)?,
             },
-            _ => panic!("unrecognized recipe type: {}", ty),
+            _ => {
+                return Err(Error::Err(format!("unrecognized recipe type: {}", ty)));
+            }
         };
 
         Ok(Recipe { id, ty, data })
     }
 
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        if ctx.protocol_version >= 477 {
+            self.ty.write_to_ctx(buf, ctx)?;
+            self.id.write_to_ctx(buf, ctx)?;
+        } else {
+            self.id.write_to_ctx(buf, ctx)?;
+            let ty = self.ty.strip_prefix("minecraft:").unwrap_or(&self.ty);
+            ty.to_owned().write_to_ctx(buf, ctx)?;
+        }
+
+        match &self.data {
+            RecipeData::Shapeless {
+                group,
+                ingredients,
+                result,
+            } => {
+                group.write_to_ctx(buf, ctx)?;
+                ingredients.write_to_ctx(buf, ctx)?;
+                result.write_to_ctx(buf, ctx)
+            }
+            RecipeData::Shaped {
+                width,
+                height,
+                group,
+                ingredients,
+                result,
+            } => {
+                width.write_to_ctx(buf, ctx)?;
+                height.write_to_ctx(buf, ctx)?;
+                group.write_to_ctx(buf, ctx)?;
+                for ingredient in ingredients {
+                    ingredient.write_to_ctx(buf, ctx)?;
+                }
+                result.write_to_ctx(buf, ctx)
+            }
+            RecipeData::Smelting {
+                group,
+                ingredient,
+                result,
+                experience,
+                cooking_time,
+            }
+            | RecipeData::Blasting {
+                group,
+                ingredient,
+                result,
+                experience,
+                cooking_time,
+            }
+            | RecipeData::Smoking {
+                group,
+                ingredient,
+                result,
+                experience,
+                cooking_time,
+            }
+            | RecipeData::Campfire {
+                group,
+                ingredient,
+                result,
+                experience,
+                cooking_time,
+            } => {
+                group.write_to_ctx(buf, ctx)?;
+                ingredient.write_to_ctx(buf, ctx)?;
+                result.write_to_ctx(buf, ctx)?;
+                experience.write_to_ctx(buf, ctx)?;
+                cooking_time.write_to_ctx(buf, ctx)
+            }
+            RecipeData::Stonecutting {
+                group,
+                ingredient,
+                result,
+            } => {
+                group.write_to_ctx(buf, ctx)?;
+                ingredient.write_to_ctx(buf, ctx)?;
+                result.write_to_ctx(buf, ctx)
+            }
+            RecipeData::Smithing {
+                base,
+                addition,
+                result,
+            } => {
+                base.write_to_ctx(buf, ctx)?;
+                addition.write_to_ctx(buf, ctx)?;
+                result.write_to_ctx(buf, ctx)
+            }
+            // Special recipes are only their type
+            _ => Ok(()),
+        }
     }
 }
 
@@ -3264,15 +3429,16 @@ pub struct Tags {
 }
 
 impl Serializable for Tags {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(Tags {
-            tag_name: Serializable::read_from(buf)?,
-            entries: Serializable::read_from(buf)?,
+            tag_name: Serializable::read_from_ctx(buf, ctx)?,
+            entries: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.tag_name.write_to_ctx(buf, ctx)?;
+        self.entries.write_to_ctx(buf, ctx)
     }
 }
 
@@ -3283,15 +3449,16 @@ pub struct TagsGroup {
 }
 
 impl Serializable for TagsGroup {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(TagsGroup {
-            tag_type: Serializable::read_from(buf)?,
-            tags: Serializable::read_from(buf)?,
+            tag_type: Serializable::read_from_ctx(buf, ctx)?,
+            tags: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.tag_type.write_to_ctx(buf, ctx)?;
+        self.tags.write_to_ctx(buf, ctx)
     }
 }
 
@@ -3311,30 +3478,43 @@ pub struct Trade {
 }
 
 impl Serializable for Trade {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let protocol_version = super::current_protocol_version();
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let protocol_version = ctx.protocol_version;
 
         Ok(Trade {
-            input_item_1: Serializable::read_from(buf)?,
-            output_item: Serializable::read_from(buf)?,
-            has_second_item: Serializable::read_from(buf)?,
-            input_item_2: Serializable::read_from(buf)?,
-            trades_disabled: Serializable::read_from(buf)?,
-            tool_uses: Serializable::read_from(buf)?,
-            max_trade_uses: Serializable::read_from(buf)?,
-            xp: Serializable::read_from(buf)?,
-            special_price: Serializable::read_from(buf)?,
-            price_multiplier: Serializable::read_from(buf)?,
+            input_item_1: Serializable::read_from_ctx(buf, ctx)?,
+            output_item: Serializable::read_from_ctx(buf, ctx)?,
+            has_second_item: Serializable::read_from_ctx(buf, ctx)?,
+            input_item_2: Serializable::read_from_ctx(buf, ctx)?,
+            trades_disabled: Serializable::read_from_ctx(buf, ctx)?,
+            tool_uses: Serializable::read_from_ctx(buf, ctx)?,
+            max_trade_uses: Serializable::read_from_ctx(buf, ctx)?,
+            xp: Serializable::read_from_ctx(buf, ctx)?,
+            special_price: Serializable::read_from_ctx(buf, ctx)?,
+            price_multiplier: Serializable::read_from_ctx(buf, ctx)?,
             demand: if protocol_version >= 498 {
-                Some(Serializable::read_from(buf)?)
+                Some(Serializable::read_from_ctx(buf, ctx)?)
             } else {
                 None
             },
         })
     }
 
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.input_item_1.write_to_ctx(buf, ctx)?;
+        self.output_item.write_to_ctx(buf, ctx)?;
+        self.has_second_item.write_to_ctx(buf, ctx)?;
+        self.input_item_2.write_to_ctx(buf, ctx)?;
+        self.trades_disabled.write_to_ctx(buf, ctx)?;
+        self.tool_uses.write_to_ctx(buf, ctx)?;
+        self.max_trade_uses.write_to_ctx(buf, ctx)?;
+        self.xp.write_to_ctx(buf, ctx)?;
+        self.special_price.write_to_ctx(buf, ctx)?;
+        self.price_multiplier.write_to_ctx(buf, ctx)?;
+        if ctx.protocol_version >= 498 {
+            self.demand.unwrap_or_default().write_to_ctx(buf, ctx)?;
+        }
+        Ok(())
     }
 }
 
@@ -3430,34 +3610,36 @@ pub enum CommandProperty {
 }
 
 impl Serializable for CommandNode {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
-        let flags: u8 = Serializable::read_from(buf)?;
-        let children: LenPrefixed<VarInt, VarInt> = Serializable::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let flags: u8 = Serializable::read_from_ctx(buf, ctx)?;
+        let children: LenPrefixed<VarInt, VarInt> = Serializable::read_from_ctx(buf, ctx)?;
 
         let node_type = match flags & 0x03 {
             0 => CommandNodeType::Root,
             1 => CommandNodeType::Literal,
             2 => CommandNodeType::Argument,
-            _ => panic!("unrecognized command node type {}", flags & 0x03),
+            ty => {
+                return Err(Error::Err(format!("unrecognized command node type {}", ty)));
+            }
         };
         let _is_executable = flags & 0x04 != 0;
         let has_redirect = flags & 0x08 != 0;
         let has_suggestions_type = flags & 0x10 != 0;
 
         let redirect_node: Option<VarInt> = if has_redirect {
-            Some(Serializable::read_from(buf)?)
+            Some(Serializable::read_from_ctx(buf, ctx)?)
         } else {
             None
         };
 
         let name: Option<String> =
             if node_type == CommandNodeType::Argument || node_type == CommandNodeType::Literal {
-                Serializable::read_from(buf)?
+                Serializable::read_from_ctx(buf, ctx)?
             } else {
                 None
             };
         let parser: Option<String> = if node_type == CommandNodeType::Argument {
-            Serializable::read_from(buf)?
+            Serializable::read_from_ctx(buf, ctx)?
         } else {
             None
         };
@@ -3466,52 +3648,52 @@ impl Serializable for CommandNode {
             Some(match parse.as_ref() {
                 "brigadier:bool" => CommandProperty::Bool,
                 "brigadier:double" => {
-                    let flags = Serializable::read_from(buf)?;
+                    let flags = Serializable::read_from_ctx(buf, ctx)?;
                     let min = if flags & 0x01 != 0 {
-                        Some(Serializable::read_from(buf)?)
+                        Some(Serializable::read_from_ctx(buf, ctx)?)
                     } else {
                         None
                     };
                     let max = if flags & 0x02 != 0 {
-                        Some(Serializable::read_from(buf)?)
+                        Some(Serializable::read_from_ctx(buf, ctx)?)
                     } else {
                         None
                     };
                     CommandProperty::Double { flags, min, max }
                 }
                 "brigadier:float" => {
-                    let flags = Serializable::read_from(buf)?;
+                    let flags = Serializable::read_from_ctx(buf, ctx)?;
                     let min = if flags & 0x01 != 0 {
-                        Some(Serializable::read_from(buf)?)
+                        Some(Serializable::read_from_ctx(buf, ctx)?)
                     } else {
                         None
                     };
                     let max = if flags & 0x02 != 0 {
-                        Some(Serializable::read_from(buf)?)
+                        Some(Serializable::read_from_ctx(buf, ctx)?)
                     } else {
                         None
                     };
                     CommandProperty::Float { flags, min, max }
                 }
                 "brigadier:integer" => {
-                    let flags = Serializable::read_from(buf)?;
+                    let flags = Serializable::read_from_ctx(buf, ctx)?;
                     let min = if flags & 0x01 != 0 {
-                        Some(Serializable::read_from(buf)?)
+                        Some(Serializable::read_from_ctx(buf, ctx)?)
                     } else {
                         None
                     };
                     let max = if flags & 0x02 != 0 {
-                        Some(Serializable::read_from(buf)?)
+                        Some(Serializable::read_from_ctx(buf, ctx)?)
                     } else {
                         None
                     };
                     CommandProperty::Integer { flags, min, max }
                 }
                 "brigadier:string" => CommandProperty::String {
-                    token_type: Serializable::read_from(buf)?,
+                    token_type: Serializable::read_from_ctx(buf, ctx)?,
                 },
                 "minecraft:entity" => CommandProperty::Entity {
-                    flags: Serializable::read_from(buf)?,
+                    flags: Serializable::read_from_ctx(buf, ctx)?,
                 },
                 "minecraft:angle" => CommandProperty::Angle,
                 "minecraft:game_profile" => CommandProperty::GameProfile,
@@ -3538,7 +3720,7 @@ impl Serializable for CommandNode {
                 "minecraft:rotation" => CommandProperty::Rotation,
                 "minecraft:scoreboard_slot" => CommandProperty::ScoreboardSlot,
                 "minecraft:score_holder" => CommandProperty::ScoreHolder {
-                    flags: Serializable::read_from(buf)?,
+                    flags: Serializable::read_from_ctx(buf, ctx)?,
                 },
                 "minecraft:swizzle" => CommandProperty::Swizzle,
                 "minecraft:team" => CommandProperty::Team,
@@ -3548,7 +3730,7 @@ impl Serializable for CommandNode {
                 "minecraft:function" => CommandProperty::Function,
                 "minecraft:entity_anchor" => CommandProperty::EntityAnchor,
                 "minecraft:range" => CommandProperty::Range {
-                    decimals: Serializable::read_from(buf)?,
+                    decimals: Serializable::read_from_ctx(buf, ctx)?,
                 },
                 "minecraft:int_range" => CommandProperty::IntRange,
                 "minecraft:float_range" => CommandProperty::FloatRange,
@@ -3558,16 +3740,21 @@ impl Serializable for CommandNode {
                 "minecraft:uuid" => CommandProperty::UUID,
                 "forge:modid" => CommandProperty::ForgeModId,
                 "forge:enum" => CommandProperty::ForgeEnum {
-                    cls: Serializable::read_from(buf)?,
+                    cls: Serializable::read_from_ctx(buf, ctx)?,
                 },
-                _ => panic!("unsupported command node parser {}", parse),
+                _ => {
+                    return Err(Error::Err(format!(
+                        "unsupported command node parser {}",
+                        parse
+                    )));
+                }
             })
         } else {
             None
         };
 
         let suggestions_type: Option<String> = if has_suggestions_type {
-            Serializable::read_from(buf)?
+            Serializable::read_from_ctx(buf, ctx)?
         } else {
             None
         };
@@ -3583,8 +3770,77 @@ impl Serializable for CommandNode {
         })
     }
 
-    fn write_to<W: io::Write>(&self, _: &mut W) -> Result<(), Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.flags.write_to_ctx(buf, ctx)?;
+        self.children.write_to_ctx(buf, ctx)?;
+        // Which of these are present is given by the flags and node type,
+        // so missing ones are left out just like the reader expects
+        self.redirect_node.write_to_ctx(buf, ctx)?;
+        self.name.write_to_ctx(buf, ctx)?;
+        self.parser.write_to_ctx(buf, ctx)?;
+        if let Some(properties) = &self.properties {
+            properties.write_to_ctx(buf, ctx)?;
+        }
+        self.suggestions_type.write_to_ctx(buf, ctx)
+    }
+}
+
+impl CommandProperty {
+    /// Writes the parser's properties, which follow the parser name in a
+    /// command node. Most parsers have none.
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        match self {
+            CommandProperty::Double { flags, min, max } => {
+                flags.write_to_ctx(buf, ctx)?;
+                min.write_to_ctx(buf, ctx)?;
+                max.write_to_ctx(buf, ctx)
+            }
+            CommandProperty::Float { flags, min, max } => {
+                flags.write_to_ctx(buf, ctx)?;
+                min.write_to_ctx(buf, ctx)?;
+                max.write_to_ctx(buf, ctx)
+            }
+            CommandProperty::Integer { flags, min, max } => {
+                flags.write_to_ctx(buf, ctx)?;
+                min.write_to_ctx(buf, ctx)?;
+                max.write_to_ctx(buf, ctx)
+            }
+            CommandProperty::String { token_type } => token_type.write_to_ctx(buf, ctx),
+            CommandProperty::Entity { flags } | CommandProperty::ScoreHolder { flags } => {
+                flags.write_to_ctx(buf, ctx)
+            }
+            CommandProperty::Range { decimals } => decimals.write_to_ctx(buf, ctx),
+            CommandProperty::ForgeEnum { cls } => cls.write_to_ctx(buf, ctx),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TabCompleteMatch {
+    pub text: String,
+    pub tooltip: Option<format::Component>,
+}
+
+impl Serializable for TabCompleteMatch {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
+        let text = Serializable::read_from_ctx(buf, ctx)?;
+        let has_tooltip: bool = Serializable::read_from_ctx(buf, ctx)?;
+        let tooltip = if has_tooltip {
+            Some(Serializable::read_from_ctx(buf, ctx)?)
+        } else {
+            None
+        };
+        Ok(TabCompleteMatch { text, tooltip })
+    }
+
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.text.write_to_ctx(buf, ctx)?;
+        self.tooltip.is_some().write_to_ctx(buf, ctx)?;
+        if let Some(tooltip) = &self.tooltip {
+            tooltip.write_to_ctx(buf, ctx)?;
+        }
+        Ok(())
     }
 }
 
@@ -3595,15 +3851,15 @@ pub struct NumberedSlot {
 }
 
 impl Serializable for NumberedSlot {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, Error> {
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, Error> {
         Ok(NumberedSlot {
-            slot_number: Serializable::read_from(buf)?,
-            slot_data: Serializable::read_from(buf)?,
+            slot_number: Serializable::read_from_ctx(buf, ctx)?,
+            slot_data: Serializable::read_from_ctx(buf, ctx)?,
         })
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), Error> {
-        self.slot_number.write_to(buf)?;
-        self.slot_data.write_to(buf)
+    fn write_to_ctx<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), Error> {
+        self.slot_number.write_to_ctx(buf, ctx)?;
+        self.slot_data.write_to_ctx(buf, ctx)
     }
 }
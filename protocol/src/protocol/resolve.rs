@@ -0,0 +1,520 @@
+//! Finds and connects to the server behind an address typed in by a player,
+//! which may leave out the port, be an IPv6 literal or be pointed elsewhere
+//! by an SRV record.
+
+use super::Error;
+use log::{debug, warn};
+use std::io;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The port servers are on when the address doesn't say otherwise.
+pub const DEFAULT_PORT: u16 = 25565;
+// Where the SRV records for servers are found, in front of their host
+const SRV_PREFIX: &str = "_minecraft._tcp.";
+
+// DNS record types and classes
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+const RCODE_NAME_ERROR: u8 = 3;
+
+/// A server's address split into its host and port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Address {
+    pub host: String,
+    /// The port given with the host, if one was
+    pub port: Option<u16>,
+}
+
+impl Address {
+    /// Parses an address in one of the forms `host`, `host:port`, `ipv6`,
+    /// `[ipv6]` or `[ipv6]:port`.
+    pub fn parse(target: &str) -> Result<Address, Error> {
+        let target = target.trim();
+        let (host, port) = if let Some(rest) = target.strip_prefix('[') {
+            let end = rest
+                .find(']')
+                .ok_or_else(|| Error::Err(format!("missing ']' in address {:?}", target)))?;
+            let port = match &rest[end + 1..] {
+                "" => None,
+                port => Some(port.strip_prefix(':').ok_or_else(|| {
+                    Error::Err(format!("unexpected text after ']' in address {:?}", target))
+                })?),
+            };
+            (&rest[..end], port)
+        } else if target.matches(':').count() > 1 {
+            // An IPv6 literal without brackets, which can't have a port
+            (target, None)
+        } else {
+            match target.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (target, None),
+            }
+        };
+        if host.is_empty() {
+            return Err(Error::Err(format!("missing host in address {:?}", target)));
+        }
+        let port = match port {
+            Some(port) => Some(
+                port.parse()
+                    .map_err(|_| Error::Err(format!("invalid port in address {:?}", target)))?,
+            ),
+            None => None,
+        };
+        Ok(Address {
+            host: host.to_owned(),
+            port,
+        })
+    }
+
+    /// Returns whether the host is an IP address rather than a name.
+    pub fn is_ip(&self) -> bool {
+        self.host.parse::<IpAddr>().is_ok()
+    }
+}
+
+/// A record naming the host and port a service can be found on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SrvRecord {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+}
+
+/// Looks up the records needed to find servers. Replaceable so that
+/// resolving can be done without a real network.
+pub trait Dns: Send + Sync {
+    /// Returns the SRV records with the name, if it has any.
+    fn lookup_srv(&self, name: &str) -> io::Result<Vec<SrvRecord>>;
+
+    /// Returns the addresses of the host, with the port.
+    fn lookup_host(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>>;
+}
+
+/// Looks up hosts through the operating system, and SRV records by asking
+/// its name servers directly.
+pub struct SystemDns {
+    nameservers: Vec<SocketAddr>,
+    timeout: Duration,
+}
+
+impl Default for SystemDns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemDns {
+    /// Uses the name servers listed in `/etc/resolv.conf`.
+    ///
+    /// Windows keeps its name servers in the registry instead, which isn't
+    /// read. There, and anywhere else without the file, no SRV records are
+    /// ever found and servers are connected to on the port in their address.
+    pub fn new() -> SystemDns {
+        let nameservers = match std::fs::read_to_string("/etc/resolv.conf") {
+            Ok(conf) => parse_resolv_conf(&conf),
+            Err(err) => {
+                warn!(
+                    "Can't read name servers from /etc/resolv.conf, SRV records won't be looked up: {}",
+                    err
+                );
+                vec![]
+            }
+        };
+        SystemDns::with_nameservers(nameservers)
+    }
+
+    pub fn with_nameservers(nameservers: Vec<SocketAddr>) -> SystemDns {
+        SystemDns {
+            nameservers,
+            timeout: Duration::from_secs(3),
+        }
+    }
+
+    /// Sets how long to wait for each name server to answer.
+    pub fn timeout(mut self, timeout: Duration) -> SystemDns {
+        self.timeout = timeout;
+        self
+    }
+
+    fn query(&self, nameserver: SocketAddr, name: &str) -> io::Result<Vec<SrvRecord>> {
+        let bind: SocketAddr = if nameserver.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_read_timeout(Some(self.timeout))?;
+        socket.connect(nameserver)?;
+
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |v| v.subsec_nanos() as u16);
+        socket.send(&build_srv_query(id, name)?)?;
+
+        let mut buf = [0; 1500];
+        loop {
+            let len = socket.recv(&mut buf)?;
+            // Late answers to an earlier query are skipped
+            if len >= 2 && u16::from_be_bytes([buf[0], buf[1]]) == id {
+                return parse_srv_response(&buf[..len]);
+            }
+        }
+    }
+}
+
+impl Dns for SystemDns {
+    fn lookup_srv(&self, name: &str) -> io::Result<Vec<SrvRecord>> {
+        let mut last_err = None;
+        for &nameserver in &self.nameservers {
+            match self.query(nameserver, name) {
+                Ok(records) => return Ok(records),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        match last_err {
+            Some(err) => Err(err),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn lookup_host(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        Ok((host, port).to_socket_addrs()?.collect())
+    }
+}
+
+/// Resolves addresses and connects to them.
+pub struct Resolver {
+    dns: Box<dyn Dns>,
+    /// How long to try each of a server's addresses for
+    pub connect_timeout: Duration,
+    /// How long to wait for a server to send something before giving up on
+    /// the connection. `None` waits forever.
+    pub read_timeout: Option<Duration>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver::with_dns(Box::new(SystemDns::new()))
+    }
+
+    pub fn with_dns(dns: Box<dyn Dns>) -> Resolver {
+        Resolver {
+            dns,
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Some(Duration::from_secs(30)),
+        }
+    }
+
+    /// Returns the host and port the server at the address is really on,
+    /// following its SRV record if it has one. Only names without a port
+    /// are looked up this way.
+    pub fn resolve(&self, target: &str) -> Result<(String, u16), Error> {
+        let address = Address::parse(target)?;
+        if let Some(port) = address.port {
+            return Ok((address.host, port));
+        }
+        if address.is_ip() {
+            return Ok((address.host, DEFAULT_PORT));
+        }
+        // Servers without a record, or a failed lookup, fall back to the
+        // default port
+        let name = format!("{}{}", SRV_PREFIX, address.host);
+        match self.dns.lookup_srv(&name) {
+            Ok(mut records) => {
+                records.sort_by_key(|v| (v.priority, std::cmp::Reverse(v.weight)));
+                if let Some(record) = records.into_iter().next() {
+                    debug!("Found SRV record for {}: {:?}", address.host, record);
+                    let target = record.target.trim_end_matches('.').to_owned();
+                    return Ok((target, record.port));
+                }
+            }
+            Err(err) => debug!("Failed to look up SRV record {}: {}", name, err),
+        }
+        Ok((address.host, DEFAULT_PORT))
+    }
+
+    /// Connects to the server at the address, trying each of its addresses
+    /// in turn. Returns the connection with the host and port that were
+    /// connected to.
+    pub fn connect(&self, target: &str) -> Result<(TcpStream, String, u16), Error> {
+        let (host, port) = self.resolve(target)?;
+        let addrs = self.dns.lookup_host(&host, port)?;
+        let mut last_err = None;
+        for addr in addrs {
+            match TcpStream::connect_timeout(&addr, self.connect_timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(self.read_timeout)?;
+                    return Ok((stream, host, port));
+                }
+                Err(err) => {
+                    debug!("Failed to connect to {}: {}", addr, err);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(match last_err {
+            Some(err) => Error::IOError(err),
+            None => Error::Err(format!("no addresses found for {}", host)),
+        })
+    }
+}
+
+/// Returns the name servers listed in a `resolv.conf` file.
+fn parse_resolv_conf(conf: &str) -> Vec<SocketAddr> {
+    conf.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("nameserver"), Some(ip)) => {
+                    // Scoped IPv6 addresses aren't supported
+                    ip.parse::<IpAddr>().ok().map(|ip| SocketAddr::new(ip, 53))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn build_srv_query(id: u16, name: &str) -> io::Result<Vec<u8>> {
+    let mut query = Vec::with_capacity(name.len() + 18);
+    query.extend_from_slice(&id.to_be_bytes());
+    // A standard query, recursion desired
+    query.extend_from_slice(&[0x01, 0x00]);
+    // One question, no other records
+    query.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid name {:?}", name),
+            ));
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_SRV.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(query)
+}
+
+fn invalid_response() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid DNS response")
+}
+
+fn read_u16(msg: &[u8], pos: usize) -> io::Result<u16> {
+    msg.get(pos..pos + 2)
+        .map(|v| u16::from_be_bytes([v[0], v[1]]))
+        .ok_or_else(invalid_response)
+}
+
+/// Reads the possibly compressed name at the position, returning it and
+/// the position after it.
+fn read_name(msg: &[u8], mut pos: usize) -> io::Result<(String, usize)> {
+    let mut name = String::new();
+    let mut end = None;
+    // Bounds the number of pointers followed, so loops can't hang
+    for _ in 0..msg.len() {
+        let len = *msg.get(pos).ok_or_else(invalid_response)? as usize;
+        if len == 0 {
+            return Ok((name, end.unwrap_or(pos + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let pointer = read_u16(msg, pos)? as usize & 0x3FFF;
+            end.get_or_insert(pos + 2);
+            pos = pointer;
+            continue;
+        }
+        let label = msg
+            .get(pos + 1..pos + 1 + len)
+            .ok_or_else(invalid_response)?;
+        if !name.is_empty() {
+            name.push('.');
+        }
+        name.push_str(&String::from_utf8_lossy(label));
+        pos += 1 + len;
+    }
+    Err(invalid_response())
+}
+
+fn parse_srv_response(msg: &[u8]) -> io::Result<Vec<SrvRecord>> {
+    if msg.len() < 12 {
+        return Err(invalid_response());
+    }
+    match msg[3] & 0x0F {
+        0 => {}
+        RCODE_NAME_ERROR => return Ok(vec![]),
+        code => {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("DNS server returned error {}", code),
+            ))
+        }
+    }
+    let questions = read_u16(msg, 4)?;
+    let answers = read_u16(msg, 6)?;
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = read_name(msg, pos)?.1 + 4;
+    }
+    let mut records = vec![];
+    for _ in 0..answers {
+        pos = read_name(msg, pos)?.1;
+        let ty = read_u16(msg, pos)?;
+        let class = read_u16(msg, pos + 2)?;
+        let len = read_u16(msg, pos + 8)? as usize;
+        let data = pos + 10;
+        if data + len > msg.len() {
+            return Err(invalid_response());
+        }
+        // Other answers, like the CNAMEs leading to the record, are skipped
+        if ty == TYPE_SRV && class == CLASS_IN {
+            records.push(SrvRecord {
+                priority: read_u16(msg, data)?,
+                weight: read_u16(msg, data + 2)?,
+                port: read_u16(msg, data + 4)?,
+                target: read_name(msg, data + 6)?.0,
+            });
+        }
+        pos = data + len;
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn address(host: &str, port: Option<u16>) -> Address {
+        Address {
+            host: host.to_owned(),
+            port,
+        }
+    }
+
+    #[test]
+    fn parse_addresses() {
+        let parse = |v| Address::parse(v).unwrap();
+        assert_eq!(parse("example.com"), address("example.com", None));
+        assert_eq!(
+            parse("example.com:25566"),
+            address("example.com", Some(25566))
+        );
+        assert_eq!(parse("127.0.0.1:1"), address("127.0.0.1", Some(1)));
+        assert_eq!(parse("::1"), address("::1", None));
+        assert_eq!(parse("[::1]"), address("::1", None));
+        assert_eq!(parse("[::1]:25565"), address("::1", Some(25565)));
+        assert_eq!(parse("[2001:db8::1]:1"), address("2001:db8::1", Some(1)));
+        assert!(Address::parse("example.com:port").is_err());
+        assert!(Address::parse("[::1").is_err());
+        assert!(Address::parse("[::1]25565").is_err());
+        assert!(Address::parse(":25565").is_err());
+    }
+
+    struct StubDns(Vec<SrvRecord>);
+
+    impl Dns for StubDns {
+        fn lookup_srv(&self, name: &str) -> io::Result<Vec<SrvRecord>> {
+            assert_eq!(name, "_minecraft._tcp.example.com");
+            Ok(self.0.clone())
+        }
+
+        fn lookup_host(&self, _host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+            Ok(vec![SocketAddr::from(([127, 0, 0, 1], port))])
+        }
+    }
+
+    fn srv(priority: u16, weight: u16, port: u16, target: &str) -> SrvRecord {
+        SrvRecord {
+            priority,
+            weight,
+            port,
+            target: target.to_owned(),
+        }
+    }
+
+    #[test]
+    fn resolve_srv() {
+        let resolver = Resolver::with_dns(Box::new(StubDns(vec![
+            srv(10, 0, 1, "backup.example.com."),
+            srv(5, 1, 2, "light.example.com."),
+            srv(5, 10, 3, "heavy.example.com."),
+        ])));
+        let resolve = |v| resolver.resolve(v).unwrap();
+        assert_eq!(resolve("example.com"), ("heavy.example.com".to_owned(), 3));
+        // Ports and IPs skip the lookup
+        assert_eq!(resolve("example.com:4"), ("example.com".to_owned(), 4));
+        assert_eq!(resolve("[::1]"), ("::1".to_owned(), DEFAULT_PORT));
+
+        let resolver = Resolver::with_dns(Box::new(StubDns(vec![])));
+        assert_eq!(
+            resolver.resolve("example.com").unwrap(),
+            ("example.com".to_owned(), DEFAULT_PORT)
+        );
+    }
+
+    #[test]
+    fn resolv_conf() {
+        let conf = "# comment\nnameserver 10.0.0.1\nsearch lan\nnameserver ::1\n";
+        assert_eq!(
+            parse_resolv_conf(conf),
+            vec![
+                SocketAddr::from(([10, 0, 0, 1], 53)),
+                SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], 53)),
+            ]
+        );
+    }
+
+    #[test]
+    fn srv_lookup() {
+        // A name server that answers every query with one record, its
+        // target compressed by pointing back into the question
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let nameserver = server.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0; 512];
+            let (len, from) = server.recv_from(&mut buf).unwrap();
+            let mut response = buf[..len].to_vec();
+            // A response with one answer
+            response[2] = 0x81;
+            response[3] = 0x80;
+            response[7] = 1;
+            // The name, by a pointer to the question
+            response.extend_from_slice(&[0xC0, 12]);
+            response.extend_from_slice(&TYPE_SRV.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&[0, 0, 0, 60]);
+            response.extend_from_slice(&[0, 11]);
+            response.extend_from_slice(&[0, 1, 0, 2, 0x63, 0xDD]);
+            // "mc." then a pointer to "example.com" in the question
+            response.extend_from_slice(&[2, b'm', b'c', 0xC0, 12 + 16]);
+            server.send_to(&response, from).unwrap();
+        });
+
+        let dns = SystemDns::with_nameservers(vec![nameserver]);
+        assert_eq!(
+            dns.lookup_srv("_minecraft._tcp.example.com").unwrap(),
+            vec![srv(1, 2, 25565, "mc.example.com")]
+        );
+    }
+
+    #[test]
+    fn srv_lookup_errors() {
+        let mut response = build_srv_query(1, "_minecraft._tcp.example.com").unwrap();
+        response[3] = RCODE_NAME_ERROR;
+        assert_eq!(parse_srv_response(&response).unwrap(), vec![]);
+        response[3] = 2;
+        assert!(parse_srv_response(&response).is_err());
+        assert!(parse_srv_response(&response[..8]).is_err());
+    }
+}
@@ -74,32 +74,53 @@ pub fn translate_internal_packet_id_for_version(
     id: i32,
     to_internal: bool,
 ) -> i32 {
+    if !SUPPORTED_PROTOCOLS.contains(&version) {
+        panic!("unsupported protocol version: {}", version);
+    }
+    match lookup_internal_packet_id_for_version(version, state, dir, id, to_internal) {
+        Some(id) => id,
+        None => panic!(
+            "bad packet id 0x{:x} in {:?} {:?} for protocol version {}",
+            id, dir, state, version
+        ),
+    }
+}
+
+/// Like `translate_internal_packet_id_for_version`, but returns `None` for
+/// unsupported versions and ids that aren't part of the version.
+pub fn lookup_internal_packet_id_for_version(
+    version: i32,
+    state: State,
+    dir: Direction,
+    id: i32,
+    to_internal: bool,
+) -> Option<i32> {
     match version {
-        758 => v1_18_2::translate_internal_packet_id(state, dir, id, to_internal),
-        757 => v1_18_1::translate_internal_packet_id(state, dir, id, to_internal),
-        756 => v1_17_1::translate_internal_packet_id(state, dir, id, to_internal),
-        754 | 753 | 751 => v1_16_4::translate_internal_packet_id(state, dir, id, to_internal),
-        736 => v1_16_1::translate_internal_packet_id(state, dir, id, to_internal),
-        735 => v1_16_1::translate_internal_packet_id(state, dir, id, to_internal),
-        578 => v1_15::translate_internal_packet_id(state, dir, id, to_internal),
-        575 => v1_15::translate_internal_packet_id(state, dir, id, to_internal),
-        498 => v1_14_4::translate_internal_packet_id(state, dir, id, to_internal),
-        490 => v1_14_3::translate_internal_packet_id(state, dir, id, to_internal),
-        485 => v1_14_2::translate_internal_packet_id(state, dir, id, to_internal),
-        480 => v1_14_1::translate_internal_packet_id(state, dir, id, to_internal),
-        477 => v1_14::translate_internal_packet_id(state, dir, id, to_internal),
-        452 => v19w02a::translate_internal_packet_id(state, dir, id, to_internal),
-        451 => v18w50a::translate_internal_packet_id(state, dir, id, to_internal),
-        404 => v1_13_2::translate_internal_packet_id(state, dir, id, to_internal),
-        340 => v1_12_2::translate_internal_packet_id(state, dir, id, to_internal),
-        316 => v1_11_2::translate_internal_packet_id(state, dir, id, to_internal),
-        315 => v1_11_2::translate_internal_packet_id(state, dir, id, to_internal),
-        210 => v1_10_2::translate_internal_packet_id(state, dir, id, to_internal),
-        109 => v1_9_2::translate_internal_packet_id(state, dir, id, to_internal),
-        107 => v1_9::translate_internal_packet_id(state, dir, id, to_internal),
-        74 => v15w39c::translate_internal_packet_id(state, dir, id, to_internal),
-        47 => v1_8_9::translate_internal_packet_id(state, dir, id, to_internal),
-        5 => v1_7_10::translate_internal_packet_id(state, dir, id, to_internal),
-        _ => panic!("unsupported protocol version: {}", version),
+        758 => v1_18_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        757 => v1_18_1::lookup_internal_packet_id(state, dir, id, to_internal),
+        756 => v1_17_1::lookup_internal_packet_id(state, dir, id, to_internal),
+        754 | 753 | 751 => v1_16_4::lookup_internal_packet_id(state, dir, id, to_internal),
+        736 => v1_16_1::lookup_internal_packet_id(state, dir, id, to_internal),
+        735 => v1_16_1::lookup_internal_packet_id(state, dir, id, to_internal),
+        578 => v1_15::lookup_internal_packet_id(state, dir, id, to_internal),
+        575 => v1_15::lookup_internal_packet_id(state, dir, id, to_internal),
+        498 => v1_14_4::lookup_internal_packet_id(state, dir, id, to_internal),
+        490 => v1_14_3::lookup_internal_packet_id(state, dir, id, to_internal),
+        485 => v1_14_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        480 => v1_14_1::lookup_internal_packet_id(state, dir, id, to_internal),
+        477 => v1_14::lookup_internal_packet_id(state, dir, id, to_internal),
+        452 => v19w02a::lookup_internal_packet_id(state, dir, id, to_internal),
+        451 => v18w50a::lookup_internal_packet_id(state, dir, id, to_internal),
+        404 => v1_13_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        340 => v1_12_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        316 => v1_11_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        315 => v1_11_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        210 => v1_10_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        109 => v1_9_2::lookup_internal_packet_id(state, dir, id, to_internal),
+        107 => v1_9::lookup_internal_packet_id(state, dir, id, to_internal),
+        74 => v15w39c::lookup_internal_packet_id(state, dir, id, to_internal),
+        47 => v1_8_9::lookup_internal_packet_id(state, dir, id, to_internal),
+        5 => v1_7_10::lookup_internal_packet_id(state, dir, id, to_internal),
+        _ => None,
     }
 }
@@ -105,7 +105,7 @@ protocol_packet_ids!(
             0x41 => UpdateScore_u8
             0x42 => SpawnPosition_NoAngle
             0x43 => TimeUpdate
-            0x44 => Title_notext_component
+            0x44 => Title_notext
             0x45 => UpdateSign
             0x46 => PlayerListHeaderFooter
             0x47 => CollectItem_nocount
@@ -14,7 +14,7 @@ protocol_packet_ids!(
             0x03 => ChatMessage
             0x04 => ClientStatus
             0x05 => ClientSettings_Filtering
-            0x06 => TabComplete
+            0x06 => TabComplete_Transaction
             0x07 => ClickWindowButton
             0x08 => ClickWindow_State
             0x09 => CloseWindow
@@ -75,7 +75,7 @@ protocol_packet_ids!(
             0x0e => ServerDifficulty_Locked
             0x0f => ServerMessage_Sender
             0x10 => ClearTitles
-            0x11 => TabCompleteReply
+            0x11 => TabCompleteReply_Transaction
             0x12 => DeclareCommands
             0x13 => WindowClose
             0x14 => WindowItems_StateCarry
@@ -141,14 +141,14 @@ protocol_packet_ids!(
             0x50 => EntityEquipment_Array
             0x51 => SetExperience
             0x52 => UpdateHealth
-            0x53 => ScoreboardObjective
+            0x53 => ScoreboardObjective_VarInt
             0x54 => SetPassengers
             0x55 => Teams_VarInt
             0x56 => UpdateScore_VarInt
             0x57 => UpdateSimulationDistance
             0x58 => TitleSubtitle
             0x59 => TimeUpdate
-            0x5a => Title
+            0x5a => TitleText
             0x5b => TitleTimes
             0x5c => EntitySoundEffect
             0x5d => SoundEffect
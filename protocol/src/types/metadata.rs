@@ -17,7 +17,7 @@ use crate::item;
 use crate::nbt;
 use crate::protocol;
 use crate::protocol::LenPrefixed;
-use crate::protocol::Serializable;
+use crate::protocol::{Context, Serializable};
 use crate::shared::Position;
 use std::collections::HashMap;
 use std::fmt;
@@ -31,8 +31,7 @@ pub struct MetadataKey<T: MetaValue> {
 }
 
 impl<T: MetaValue> MetadataKey<T> {
-    #[allow(dead_code)]
-    fn new(index: i32) -> MetadataKey<T> {
+    pub const fn new(index: i32) -> MetadataKey<T> {
         MetadataKey {
             index,
             ty: PhantomData,
@@ -56,18 +55,39 @@ impl Metadata {
         self.map.get(&key.index).map(T::unwrap)
     }
 
+    /// Returns the value at the index whatever its type, for values that
+    /// moved between versions and might not be the type expected.
+    pub fn get_value(&self, index: i32) -> Option<&Value> {
+        self.map.get(&index)
+    }
+
     pub fn put<T: MetaValue>(&mut self, key: &MetadataKey<T>, val: T) {
         self.map.insert(key.index, val.wrap());
     }
 
+    /// Returns the values ordered by index, so the same metadata is always
+    /// written out the same way.
+    fn entries(&self) -> Vec<(&i32, &Value)> {
+        let mut entries: Vec<_> = self.map.iter().collect();
+        entries.sort_by_key(|&(index, _)| *index);
+        entries
+    }
+
     fn put_raw<T: MetaValue>(&mut self, index: i32, val: T) {
         self.map.insert(index, val.wrap());
     }
 
-    fn read_from18<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
+    /// Copies the values from `other` over this metadata. Servers only
+    /// send the values which changed so updates have to be merged into
+    /// the existing state.
+    pub fn merge(&mut self, other: Metadata) {
+        self.map.extend(other.map);
+    }
+
+    fn read_from18<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
         let mut m = Self::new();
         loop {
-            let ty_index = u8::read_from(buf)? as i32;
+            let ty_index = u8::read_from_ctx(buf, ctx)? as i32;
             if ty_index == 0x7f {
                 break;
             }
@@ -75,26 +95,26 @@ impl Metadata {
             let ty = ty_index >> 5;
 
             match ty {
-                0 => m.put_raw(index, i8::read_from(buf)?),
-                1 => m.put_raw(index, i16::read_from(buf)?),
-                2 => m.put_raw(index, i32::read_from(buf)?),
-                3 => m.put_raw(index, f32::read_from(buf)?),
-                4 => m.put_raw(index, String::read_from(buf)?),
-                5 => m.put_raw(index, Option::<item::Stack>::read_from(buf)?),
+                0 => m.put_raw(index, i8::read_from_ctx(buf, ctx)?),
+                1 => m.put_raw(index, i16::read_from_ctx(buf, ctx)?),
+                2 => m.put_raw(index, i32::read_from_ctx(buf, ctx)?),
+                3 => m.put_raw(index, f32::read_from_ctx(buf, ctx)?),
+                4 => m.put_raw(index, String::read_from_ctx(buf, ctx)?),
+                5 => m.put_raw(index, Option::<item::Stack>::read_from_ctx(buf, ctx)?),
                 6 => m.put_raw(
                     index,
                     [
-                        i32::read_from(buf)?,
-                        i32::read_from(buf)?,
-                        i32::read_from(buf)?,
+                        i32::read_from_ctx(buf, ctx)?,
+                        i32::read_from_ctx(buf, ctx)?,
+                        i32::read_from_ctx(buf, ctx)?,
                     ],
                 ),
                 7 => m.put_raw(
                     index,
                     [
-                        f32::read_from(buf)?,
-                        f32::read_from(buf)?,
-                        f32::read_from(buf)?,
+                        f32::read_from_ctx(buf, ctx)?,
+                        f32::read_from_ctx(buf, ctx)?,
+                        f32::read_from_ctx(buf, ctx)?,
                     ],
                 ),
                 _ => return Err(protocol::Error::Err("unknown metadata type".to_owned())),
@@ -103,8 +123,8 @@ impl Metadata {
         Ok(m)
     }
 
-    fn write_to18<W: io::Write>(&self, buf: &mut W) -> Result<(), protocol::Error> {
-        for (k, v) in &self.map {
+    fn write_to18<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), protocol::Error> {
+        for (k, v) in self.entries() {
             if (*k as u8) > 0x1f {
                 panic!("write metadata index {:x} > 0x1f", *k as u8);
             }
@@ -114,41 +134,41 @@ impl Metadata {
 
             match *v {
                 Value::Byte(ref val) => {
-                    u8::write_to(&(ty_index | (0 << TYPE_SHIFT)), buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (0 << TYPE_SHIFT)), buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Short(ref val) => {
-                    u8::write_to(&(ty_index | (1 << TYPE_SHIFT)), buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (1 << TYPE_SHIFT)), buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
 
                 Value::Int(ref val) => {
-                    u8::write_to(&(ty_index | (2 << TYPE_SHIFT)), buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (2 << TYPE_SHIFT)), buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Float(ref val) => {
-                    u8::write_to(&(ty_index | (3 << TYPE_SHIFT)), buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (3 << TYPE_SHIFT)), buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::String(ref val) => {
-                    u8::write_to(&(ty_index | (4 << TYPE_SHIFT)), buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (4 << TYPE_SHIFT)), buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalItemStack(ref val) => {
-                    u8::write_to(&(ty_index | (5 << TYPE_SHIFT)), buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (5 << TYPE_SHIFT)), buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Vector(ref val) => {
-                    u8::write_to(&(ty_index | (6 << TYPE_SHIFT)), buf)?;
-                    val[0].write_to(buf)?;
-                    val[1].write_to(buf)?;
-                    val[2].write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (6 << TYPE_SHIFT)), buf, ctx)?;
+                    val[0].write_to_ctx(buf, ctx)?;
+                    val[1].write_to_ctx(buf, ctx)?;
+                    val[2].write_to_ctx(buf, ctx)?;
                 }
                 Value::Rotation(ref val) => {
-                    u8::write_to(&(ty_index | (7 << TYPE_SHIFT)), buf)?;
-                    val[0].write_to(buf)?;
-                    val[1].write_to(buf)?;
-                    val[2].write_to(buf)?;
+                    u8::write_to_ctx(&(ty_index | (7 << TYPE_SHIFT)), buf, ctx)?;
+                    val[0].write_to_ctx(buf, ctx)?;
+                    val[1].write_to_ctx(buf, ctx)?;
+                    val[2].write_to_ctx(buf, ctx)?;
                 }
 
                 _ => {
@@ -156,56 +176,56 @@ impl Metadata {
                 }
             }
         }
-        u8::write_to(&0x7f, buf)?;
+        u8::write_to_ctx(&0x7f, buf, ctx)?;
         Ok(())
     }
 
-    fn read_from19<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
+    fn read_from19<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
         let mut m = Self::new();
         loop {
-            let index = u8::read_from(buf)? as i32;
+            let index = u8::read_from_ctx(buf, ctx)? as i32;
             if index == 0xFF {
                 break;
             }
-            let ty = protocol::VarInt::read_from(buf)?.0;
+            let ty = protocol::VarInt::read_from_ctx(buf, ctx)?.0;
             match ty {
-                0 => m.put_raw(index, i8::read_from(buf)?),
-                1 => m.put_raw(index, protocol::VarInt::read_from(buf)?.0),
-                2 => m.put_raw(index, f32::read_from(buf)?),
-                3 => m.put_raw(index, String::read_from(buf)?),
-                4 => m.put_raw(index, format::Component::read_from(buf)?),
-                5 => m.put_raw(index, Option::<item::Stack>::read_from(buf)?),
-                6 => m.put_raw(index, bool::read_from(buf)?),
+                0 => m.put_raw(index, i8::read_from_ctx(buf, ctx)?),
+                1 => m.put_raw(index, protocol::VarInt::read_from_ctx(buf, ctx)?.0),
+                2 => m.put_raw(index, f32::read_from_ctx(buf, ctx)?),
+                3 => m.put_raw(index, String::read_from_ctx(buf, ctx)?),
+                4 => m.put_raw(index, format::Component::read_from_ctx(buf, ctx)?),
+                5 => m.put_raw(index, Option::<item::Stack>::read_from_ctx(buf, ctx)?),
+                6 => m.put_raw(index, bool::read_from_ctx(buf, ctx)?),
                 7 => m.put_raw(
                     index,
                     [
-                        f32::read_from(buf)?,
-                        f32::read_from(buf)?,
-                        f32::read_from(buf)?,
+                        f32::read_from_ctx(buf, ctx)?,
+                        f32::read_from_ctx(buf, ctx)?,
+                        f32::read_from_ctx(buf, ctx)?,
                     ],
                 ),
-                8 => m.put_raw(index, Position::read_from(buf)?),
+                8 => m.put_raw(index, Position::read_from_ctx(buf, ctx)?),
                 9 => {
-                    if bool::read_from(buf)? {
-                        m.put_raw(index, Option::<Position>::read_from(buf)?);
+                    if bool::read_from_ctx(buf, ctx)? {
+                        m.put_raw(index, Option::<Position>::read_from_ctx(buf, ctx)?);
                     } else {
                         m.put_raw::<Option<Position>>(index, None);
                     }
                 }
-                10 => m.put_raw(index, protocol::VarInt::read_from(buf)?),
+                10 => m.put_raw(index, protocol::VarInt::read_from_ctx(buf, ctx)?),
                 11 => {
-                    if bool::read_from(buf)? {
-                        m.put_raw(index, Option::<protocol::UUID>::read_from(buf)?);
+                    if bool::read_from_ctx(buf, ctx)? {
+                        m.put_raw(index, Option::<protocol::UUID>::read_from_ctx(buf, ctx)?);
                     } else {
                         m.put_raw::<Option<protocol::UUID>>(index, None);
                     }
                 }
-                12 => m.put_raw(index, protocol::VarInt::read_from(buf)?.0 as u16),
+                12 => m.put_raw(index, protocol::VarInt::read_from_ctx(buf, ctx)?.0 as u16),
                 13 => {
-                    let ty = u8::read_from(buf)?;
+                    let ty = u8::read_from_ctx(buf, ctx)?;
                     if ty != 0 {
                         let name = nbt::read_string(buf)?;
-                        let tag = nbt::Tag::read_from(buf)?;
+                        let tag = nbt::Tag::read_from_ctx(buf, ctx)?;
 
                         m.put_raw(index, nbt::NamedTag(name, tag));
                     }
@@ -216,263 +236,267 @@ impl Metadata {
         Ok(m)
     }
 
-    fn write_to19<W: io::Write>(&self, buf: &mut W) -> Result<(), protocol::Error> {
-        for (k, v) in &self.map {
-            (*k as u8).write_to(buf)?;
+    fn write_to19<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), protocol::Error> {
+        for (k, v) in self.entries() {
+            (*k as u8).write_to_ctx(buf, ctx)?;
             match *v {
                 Value::Byte(ref val) => {
-                    u8::write_to(&0, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&0, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Int(ref val) => {
-                    u8::write_to(&1, buf)?;
-                    protocol::VarInt(*val).write_to(buf)?;
+                    u8::write_to_ctx(&1, buf, ctx)?;
+                    protocol::VarInt(*val).write_to_ctx(buf, ctx)?;
                 }
                 Value::Float(ref val) => {
-                    u8::write_to(&2, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&2, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::String(ref val) => {
-                    u8::write_to(&3, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&3, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::FormatComponent(ref val) => {
-                    u8::write_to(&4, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&4, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalItemStack(ref val) => {
-                    u8::write_to(&5, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&5, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Bool(ref val) => {
-                    u8::write_to(&6, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&6, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Vector(ref val) => {
-                    u8::write_to(&7, buf)?;
-                    val[0].write_to(buf)?;
-                    val[1].write_to(buf)?;
-                    val[2].write_to(buf)?;
+                    u8::write_to_ctx(&7, buf, ctx)?;
+                    val[0].write_to_ctx(buf, ctx)?;
+                    val[1].write_to_ctx(buf, ctx)?;
+                    val[2].write_to_ctx(buf, ctx)?;
                 }
                 Value::Position(ref val) => {
-                    u8::write_to(&8, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&8, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalPosition(ref val) => {
-                    u8::write_to(&9, buf)?;
-                    val.is_some().write_to(buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&9, buf, ctx)?;
+                    val.is_some().write_to_ctx(buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Direction(ref val) => {
-                    u8::write_to(&10, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&10, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalUUID(ref val) => {
-                    u8::write_to(&11, buf)?;
-                    val.is_some().write_to(buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&11, buf, ctx)?;
+                    val.is_some().write_to_ctx(buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Block(ref val) => {
-                    u8::write_to(&11, buf)?;
-                    protocol::VarInt(*val as i32).write_to(buf)?;
+                    u8::write_to_ctx(&11, buf, ctx)?;
+                    protocol::VarInt(*val as i32).write_to_ctx(buf, ctx)?;
                 }
                 Value::NBTTag(ref _val) => {
-                    u8::write_to(&13, buf)?;
+                    u8::write_to_ctx(&13, buf, ctx)?;
                     // TODO: write NBT tags metadata
-                    //nbt::Tag(*val).write_to(buf)?;
+                    //nbt::Tag(*val).write_to_ctx(buf, ctx)?;
                 }
                 _ => panic!("unexpected metadata"),
             }
         }
-        u8::write_to(&0xFF, buf)?;
+        u8::write_to_ctx(&0xFF, buf, ctx)?;
         Ok(())
     }
 
-    fn read_from113<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
+    fn read_from113<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
         let mut m = Self::new();
         loop {
-            let index = u8::read_from(buf)? as i32;
+            let index = u8::read_from_ctx(buf, ctx)? as i32;
             if index == 0xFF {
                 break;
             }
-            let ty = protocol::VarInt::read_from(buf)?.0;
+            let ty = protocol::VarInt::read_from_ctx(buf, ctx)?.0;
             match ty {
-                0 => m.put_raw(index, i8::read_from(buf)?),
-                1 => m.put_raw(index, protocol::VarInt::read_from(buf)?.0),
-                2 => m.put_raw(index, f32::read_from(buf)?),
-                3 => m.put_raw(index, String::read_from(buf)?),
-                4 => m.put_raw(index, format::Component::read_from(buf)?),
+                0 => m.put_raw(index, i8::read_from_ctx(buf, ctx)?),
+                1 => m.put_raw(index, protocol::VarInt::read_from_ctx(buf, ctx)?.0),
+                2 => m.put_raw(index, f32::read_from_ctx(buf, ctx)?),
+                3 => m.put_raw(index, String::read_from_ctx(buf, ctx)?),
+                4 => m.put_raw(index, format::Component::read_from_ctx(buf, ctx)?),
                 5 => m.put_raw(
                     index,
-                    LenPrefixed::<bool, format::Component>::read_from(buf)?,
+                    LenPrefixed::<bool, format::Component>::read_from_ctx(buf, ctx)?,
                 ),
-                6 => m.put_raw(index, Option::<item::Stack>::read_from(buf)?),
-                7 => m.put_raw(index, bool::read_from(buf)?),
+                6 => m.put_raw(index, Option::<item::Stack>::read_from_ctx(buf, ctx)?),
+                7 => m.put_raw(index, bool::read_from_ctx(buf, ctx)?),
                 8 => m.put_raw(
                     index,
                     [
-                        f32::read_from(buf)?,
-                        f32::read_from(buf)?,
-                        f32::read_from(buf)?,
+                        f32::read_from_ctx(buf, ctx)?,
+                        f32::read_from_ctx(buf, ctx)?,
+                        f32::read_from_ctx(buf, ctx)?,
                     ],
                 ),
-                9 => m.put_raw(index, Position::read_from(buf)?),
+                9 => m.put_raw(index, Position::read_from_ctx(buf, ctx)?),
                 10 => {
-                    if bool::read_from(buf)? {
-                        m.put_raw(index, Option::<Position>::read_from(buf)?);
+                    if bool::read_from_ctx(buf, ctx)? {
+                        m.put_raw(index, Option::<Position>::read_from_ctx(buf, ctx)?);
                     } else {
                         m.put_raw::<Option<Position>>(index, None);
                     }
                 }
-                11 => m.put_raw(index, protocol::VarInt::read_from(buf)?),
+                11 => m.put_raw(index, protocol::VarInt::read_from_ctx(buf, ctx)?),
                 12 => {
-                    if bool::read_from(buf)? {
-                        m.put_raw(index, Option::<protocol::UUID>::read_from(buf)?);
+                    if bool::read_from_ctx(buf, ctx)? {
+                        m.put_raw(index, Option::<protocol::UUID>::read_from_ctx(buf, ctx)?);
                     } else {
                         m.put_raw::<Option<protocol::UUID>>(index, None);
                     }
                 }
-                13 => m.put_raw(index, protocol::VarInt::read_from(buf)?.0 as u16),
+                13 => m.put_raw(index, protocol::VarInt::read_from_ctx(buf, ctx)?.0 as u16),
                 14 => {
-                    let ty = u8::read_from(buf)?;
+                    let ty = u8::read_from_ctx(buf, ctx)?;
                     if ty != 0 {
                         let name = nbt::read_string(buf)?;
-                        let tag = nbt::Tag::read_from(buf)?;
+                        let tag = nbt::Tag::read_from_ctx(buf, ctx)?;
 
                         m.put_raw(index, nbt::NamedTag(name, tag));
                     }
                 }
-                15 => panic!("TODO: particle"),
-                16 => m.put_raw(index, VillagerData::read_from(buf)?),
+                15 => m.put_raw(index, ParticleData::read_from_ctx(buf, ctx)?),
+                16 => m.put_raw(index, VillagerData::read_from_ctx(buf, ctx)?),
                 17 => {
-                    if bool::read_from(buf)? {
-                        m.put_raw(index, Option::<protocol::VarInt>::read_from(buf)?);
+                    if bool::read_from_ctx(buf, ctx)? {
+                        m.put_raw(index, Option::<protocol::VarInt>::read_from_ctx(buf, ctx)?);
                     } else {
                         m.put_raw::<Option<protocol::VarInt>>(index, None);
                     }
                 }
-                18 => m.put_raw(index, PoseData::read_from(buf)?),
+                18 => m.put_raw(index, PoseData::read_from_ctx(buf, ctx)?),
                 _ => return Err(protocol::Error::Err("unknown metadata type".to_owned())),
             }
         }
         Ok(m)
     }
 
-    fn write_to113<W: io::Write>(&self, buf: &mut W) -> Result<(), protocol::Error> {
-        for (k, v) in &self.map {
-            (*k as u8).write_to(buf)?;
+    fn write_to113<W: io::Write>(&self, buf: &mut W, ctx: &Context) -> Result<(), protocol::Error> {
+        for (k, v) in self.entries() {
+            (*k as u8).write_to_ctx(buf, ctx)?;
             match *v {
                 Value::Byte(ref val) => {
-                    u8::write_to(&0, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&0, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Int(ref val) => {
-                    u8::write_to(&1, buf)?;
-                    protocol::VarInt(*val).write_to(buf)?;
+                    u8::write_to_ctx(&1, buf, ctx)?;
+                    protocol::VarInt(*val).write_to_ctx(buf, ctx)?;
                 }
                 Value::Float(ref val) => {
-                    u8::write_to(&2, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&2, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::String(ref val) => {
-                    u8::write_to(&3, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&3, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::FormatComponent(ref val) => {
-                    u8::write_to(&4, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&4, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalFormatComponent(ref val) => {
-                    u8::write_to(&5, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&5, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalItemStack(ref val) => {
-                    u8::write_to(&6, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&6, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Bool(ref val) => {
-                    u8::write_to(&7, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&7, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Vector(ref val) => {
-                    u8::write_to(&8, buf)?;
-                    val[0].write_to(buf)?;
-                    val[1].write_to(buf)?;
-                    val[2].write_to(buf)?;
+                    u8::write_to_ctx(&8, buf, ctx)?;
+                    val[0].write_to_ctx(buf, ctx)?;
+                    val[1].write_to_ctx(buf, ctx)?;
+                    val[2].write_to_ctx(buf, ctx)?;
                 }
                 Value::Position(ref val) => {
-                    u8::write_to(&9, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&9, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalPosition(ref val) => {
-                    u8::write_to(&10, buf)?;
-                    val.is_some().write_to(buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&10, buf, ctx)?;
+                    val.is_some().write_to_ctx(buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Direction(ref val) => {
-                    u8::write_to(&11, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&11, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalUUID(ref val) => {
-                    u8::write_to(&12, buf)?;
-                    val.is_some().write_to(buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&12, buf, ctx)?;
+                    val.is_some().write_to_ctx(buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Block(ref val) => {
-                    u8::write_to(&13, buf)?;
-                    protocol::VarInt(*val as i32).write_to(buf)?;
+                    u8::write_to_ctx(&13, buf, ctx)?;
+                    protocol::VarInt(*val as i32).write_to_ctx(buf, ctx)?;
                 }
                 Value::NBTTag(ref _val) => {
-                    u8::write_to(&14, buf)?;
+                    u8::write_to_ctx(&14, buf, ctx)?;
                     // TODO: write NBT tags metadata
-                    //nbt::Tag(*val).write_to(buf)?;
+                    //nbt::Tag(*val).write_to_ctx(buf, ctx)?;
                 }
                 Value::Particle(ref val) => {
-                    u8::write_to(&15, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&15, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Villager(ref val) => {
-                    u8::write_to(&16, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&16, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::OptionalVarInt(ref val) => {
-                    u8::write_to(&17, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&17, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 Value::Pose(ref val) => {
-                    u8::write_to(&18, buf)?;
-                    val.write_to(buf)?;
+                    u8::write_to_ctx(&18, buf, ctx)?;
+                    val.write_to_ctx(buf, ctx)?;
                 }
                 _ => panic!("unexpected metadata"),
             }
         }
-        u8::write_to(&0xFF, buf)?;
+        u8::write_to_ctx(&0xFF, buf, ctx)?;
         Ok(())
     }
 }
 
 impl Serializable for Metadata {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
-        let protocol_version = protocol::current_protocol_version();
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
+        let protocol_version = ctx.protocol_version;
 
         if protocol_version >= 404 {
-            Metadata::read_from113(buf)
+            Metadata::read_from113(buf, ctx)
         } else if protocol_version >= 74 {
-            Metadata::read_from19(buf)
+            Metadata::read_from19(buf, ctx)
         } else {
-            Metadata::read_from18(buf)
+            Metadata::read_from18(buf, ctx)
         }
     }
 
-    fn write_to<W: io::Write>(&self, buf: &mut W) -> Result<(), protocol::Error> {
-        let protocol_version = protocol::current_protocol_version();
+    fn write_to_ctx<W: io::Write>(
+        &self,
+        buf: &mut W,
+        ctx: &Context,
+    ) -> Result<(), protocol::Error> {
+        let protocol_version = ctx.protocol_version;
 
         if protocol_version >= 404 {
-            self.write_to113(buf)
+            self.write_to113(buf, ctx)
         } else if protocol_version >= 74 {
-            self.write_to19(buf)
+            self.write_to19(buf, ctx)
         } else {
-            self.write_to18(buf)
+            self.write_to18(buf, ctx)
         }
     }
 }
@@ -584,14 +608,14 @@ pub enum ParticleData {
 }
 
 impl Serializable for ParticleData {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
-        let id = protocol::VarInt::read_from(buf)?.0;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
+        let id = protocol::VarInt::read_from_ctx(buf, ctx)?.0;
         Ok(match id {
             0 => ParticleData::AmbientEntityEffect,
             1 => ParticleData::AngryVillager,
             2 => ParticleData::Barrier,
             3 => ParticleData::Block {
-                block_state: Serializable::read_from(buf)?,
+                block_state: Serializable::read_from_ctx(buf, ctx)?,
             },
             4 => ParticleData::Bubble,
             5 => ParticleData::Cloud,
@@ -601,10 +625,10 @@ impl Serializable for ParticleData {
             9 => ParticleData::DrippingLava,
             10 => ParticleData::DrippingWater,
             11 => ParticleData::Dust {
-                red: Serializable::read_from(buf)?,
-                green: Serializable::read_from(buf)?,
-                blue: Serializable::read_from(buf)?,
-                scale: Serializable::read_from(buf)?,
+                red: Serializable::read_from_ctx(buf, ctx)?,
+                green: Serializable::read_from_ctx(buf, ctx)?,
+                blue: Serializable::read_from_ctx(buf, ctx)?,
+                scale: Serializable::read_from_ctx(buf, ctx)?,
             },
             12 => ParticleData::Effect,
             13 => ParticleData::ElderGuardian,
@@ -615,7 +639,7 @@ impl Serializable for ParticleData {
             18 => ParticleData::ExplosionEmitter,
             19 => ParticleData::Explosion,
             20 => ParticleData::FallingDust {
-                block_state: Serializable::read_from(buf)?,
+                block_state: Serializable::read_from_ctx(buf, ctx)?,
             },
             21 => ParticleData::Firework,
             22 => ParticleData::Fishing,
@@ -624,7 +648,7 @@ impl Serializable for ParticleData {
             25 => ParticleData::Heart,
             26 => ParticleData::InstantEffect,
             27 => ParticleData::Item {
-                item: Serializable::read_from(buf)?,
+                item: Serializable::read_from_ctx(buf, ctx)?,
             },
             28 => ParticleData::ItemSlime,
             29 => ParticleData::ItemSnowball,
@@ -648,28 +672,108 @@ impl Serializable for ParticleData {
             47 => ParticleData::BubbleColumnUp,
             48 => ParticleData::Nautilus,
             49 => ParticleData::Dolphin,
-            _ => panic!("unrecognized particle data id {}", id),
+            _ => {
+                return Err(protocol::Error::Err(format!(
+                    "unrecognized particle data id {}",
+                    id
+                )))
+            }
         })
     }
 
-    fn write_to<W: io::Write>(&self, _buf: &mut W) -> Result<(), protocol::Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(
+        &self,
+        buf: &mut W,
+        ctx: &Context,
+    ) -> Result<(), protocol::Error> {
+        match self {
+            ParticleData::AmbientEntityEffect => protocol::VarInt(0).write_to_ctx(buf, ctx)?,
+            ParticleData::AngryVillager => protocol::VarInt(1).write_to_ctx(buf, ctx)?,
+            ParticleData::Barrier => protocol::VarInt(2).write_to_ctx(buf, ctx)?,
+            ParticleData::Block { block_state } => {
+                protocol::VarInt(3).write_to_ctx(buf, ctx)?;
+                block_state.write_to_ctx(buf, ctx)?;
+            }
+            ParticleData::Bubble => protocol::VarInt(4).write_to_ctx(buf, ctx)?,
+            ParticleData::Cloud => protocol::VarInt(5).write_to_ctx(buf, ctx)?,
+            ParticleData::Crit => protocol::VarInt(6).write_to_ctx(buf, ctx)?,
+            ParticleData::DamageIndicator => protocol::VarInt(7).write_to_ctx(buf, ctx)?,
+            ParticleData::DragonBreath => protocol::VarInt(8).write_to_ctx(buf, ctx)?,
+            ParticleData::DrippingLava => protocol::VarInt(9).write_to_ctx(buf, ctx)?,
+            ParticleData::DrippingWater => protocol::VarInt(10).write_to_ctx(buf, ctx)?,
+            ParticleData::Dust {
+                red,
+                green,
+                blue,
+                scale,
+            } => {
+                protocol::VarInt(11).write_to_ctx(buf, ctx)?;
+                red.write_to_ctx(buf, ctx)?;
+                green.write_to_ctx(buf, ctx)?;
+                blue.write_to_ctx(buf, ctx)?;
+                scale.write_to_ctx(buf, ctx)?;
+            }
+            ParticleData::Effect => protocol::VarInt(12).write_to_ctx(buf, ctx)?,
+            ParticleData::ElderGuardian => protocol::VarInt(13).write_to_ctx(buf, ctx)?,
+            ParticleData::EnchantedHit => protocol::VarInt(14).write_to_ctx(buf, ctx)?,
+            ParticleData::Enchant => protocol::VarInt(15).write_to_ctx(buf, ctx)?,
+            ParticleData::EndRod => protocol::VarInt(16).write_to_ctx(buf, ctx)?,
+            ParticleData::EntityEffect => protocol::VarInt(17).write_to_ctx(buf, ctx)?,
+            ParticleData::ExplosionEmitter => protocol::VarInt(18).write_to_ctx(buf, ctx)?,
+            ParticleData::Explosion => protocol::VarInt(19).write_to_ctx(buf, ctx)?,
+            ParticleData::FallingDust { block_state } => {
+                protocol::VarInt(20).write_to_ctx(buf, ctx)?;
+                block_state.write_to_ctx(buf, ctx)?;
+            }
+            ParticleData::Firework => protocol::VarInt(21).write_to_ctx(buf, ctx)?,
+            ParticleData::Fishing => protocol::VarInt(22).write_to_ctx(buf, ctx)?,
+            ParticleData::Flame => protocol::VarInt(23).write_to_ctx(buf, ctx)?,
+            ParticleData::HappyVillager => protocol::VarInt(24).write_to_ctx(buf, ctx)?,
+            ParticleData::Heart => protocol::VarInt(25).write_to_ctx(buf, ctx)?,
+            ParticleData::InstantEffect => protocol::VarInt(26).write_to_ctx(buf, ctx)?,
+            ParticleData::Item { item } => {
+                protocol::VarInt(27).write_to_ctx(buf, ctx)?;
+                item.write_to_ctx(buf, ctx)?;
+            }
+            ParticleData::ItemSlime => protocol::VarInt(28).write_to_ctx(buf, ctx)?,
+            ParticleData::ItemSnowball => protocol::VarInt(29).write_to_ctx(buf, ctx)?,
+            ParticleData::LargeSmoke => protocol::VarInt(30).write_to_ctx(buf, ctx)?,
+            ParticleData::Lava => protocol::VarInt(31).write_to_ctx(buf, ctx)?,
+            ParticleData::Mycelium => protocol::VarInt(32).write_to_ctx(buf, ctx)?,
+            ParticleData::Note => protocol::VarInt(33).write_to_ctx(buf, ctx)?,
+            ParticleData::Poof => protocol::VarInt(34).write_to_ctx(buf, ctx)?,
+            ParticleData::Portal => protocol::VarInt(35).write_to_ctx(buf, ctx)?,
+            ParticleData::Rain => protocol::VarInt(36).write_to_ctx(buf, ctx)?,
+            ParticleData::Smoke => protocol::VarInt(37).write_to_ctx(buf, ctx)?,
+            ParticleData::Spit => protocol::VarInt(38).write_to_ctx(buf, ctx)?,
+            ParticleData::SquidInk => protocol::VarInt(39).write_to_ctx(buf, ctx)?,
+            ParticleData::SweepAttack => protocol::VarInt(40).write_to_ctx(buf, ctx)?,
+            ParticleData::TotemOfUndying => protocol::VarInt(41).write_to_ctx(buf, ctx)?,
+            ParticleData::Underwater => protocol::VarInt(42).write_to_ctx(buf, ctx)?,
+            ParticleData::Splash => protocol::VarInt(43).write_to_ctx(buf, ctx)?,
+            ParticleData::Witch => protocol::VarInt(44).write_to_ctx(buf, ctx)?,
+            ParticleData::BubblePop => protocol::VarInt(45).write_to_ctx(buf, ctx)?,
+            ParticleData::CurrentDown => protocol::VarInt(46).write_to_ctx(buf, ctx)?,
+            ParticleData::BubbleColumnUp => protocol::VarInt(47).write_to_ctx(buf, ctx)?,
+            ParticleData::Nautilus => protocol::VarInt(48).write_to_ctx(buf, ctx)?,
+            ParticleData::Dolphin => protocol::VarInt(49).write_to_ctx(buf, ctx)?,
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[allow(dead_code)]
 pub struct VillagerData {
-    villager_type: protocol::VarInt,
-    profession: protocol::VarInt,
-    level: protocol::VarInt,
+    pub villager_type: protocol::VarInt,
+    pub profession: protocol::VarInt,
+    pub level: protocol::VarInt,
 }
 
 impl Serializable for VillagerData {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
-        let villager_type = protocol::VarInt::read_from(buf)?;
-        let profession = protocol::VarInt::read_from(buf)?;
-        let level = protocol::VarInt::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
+        let villager_type = protocol::VarInt::read_from_ctx(buf, ctx)?;
+        let profession = protocol::VarInt::read_from_ctx(buf, ctx)?;
+        let level = protocol::VarInt::read_from_ctx(buf, ctx)?;
         Ok(VillagerData {
             villager_type,
             profession,
@@ -677,8 +781,14 @@ impl Serializable for VillagerData {
         })
     }
 
-    fn write_to<W: io::Write>(&self, _buf: &mut W) -> Result<(), protocol::Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(
+        &self,
+        buf: &mut W,
+        ctx: &Context,
+    ) -> Result<(), protocol::Error> {
+        self.villager_type.write_to_ctx(buf, ctx)?;
+        self.profession.write_to_ctx(buf, ctx)?;
+        self.level.write_to_ctx(buf, ctx)
     }
 }
 
@@ -695,8 +805,8 @@ pub enum PoseData {
 }
 
 impl Serializable for PoseData {
-    fn read_from<R: io::Read>(buf: &mut R) -> Result<Self, protocol::Error> {
-        let n = protocol::VarInt::read_from(buf)?;
+    fn read_from_ctx<R: io::Read>(buf: &mut R, ctx: &Context) -> Result<Self, protocol::Error> {
+        let n = protocol::VarInt::read_from_ctx(buf, ctx)?;
         Ok(match n.0 {
             0 => PoseData::Standing,
             1 => PoseData::FallFlying,
@@ -710,8 +820,22 @@ impl Serializable for PoseData {
         })
     }
 
-    fn write_to<W: io::Write>(&self, _buf: &mut W) -> Result<(), protocol::Error> {
-        unimplemented!()
+    fn write_to_ctx<W: io::Write>(
+        &self,
+        buf: &mut W,
+        ctx: &Context,
+    ) -> Result<(), protocol::Error> {
+        let n = match *self {
+            PoseData::Standing => 0,
+            PoseData::FallFlying => 1,
+            PoseData::Sleeping => 2,
+            PoseData::Swimming => 3,
+            PoseData::SpinAttack => 4,
+            PoseData::Sneaking => 5,
+            PoseData::Dying => 6,
+            PoseData::LongJumping => 7,
+        };
+        protocol::VarInt(n).write_to_ctx(buf, ctx)
     }
 }
 
@@ -924,6 +1048,18 @@ impl MetaValue for nbt::NamedTag {
     }
 }
 
+impl MetaValue for ParticleData {
+    fn unwrap(value: &Value) -> &Self {
+        match *value {
+            Value::Particle(ref val) => val,
+            _ => panic!("incorrect key"),
+        }
+    }
+    fn wrap(self) -> Value {
+        Value::Particle(self)
+    }
+}
+
 impl MetaValue for VillagerData {
     fn unwrap(value: &Value) -> &Self {
         match *value {
@@ -983,4 +1119,19 @@ mod test {
             None => panic!("failed"),
         }
     }
+
+    #[test]
+    fn merge() {
+        const FLAGS: MetadataKey<i8> = MetadataKey::new(1);
+        let mut m = Metadata::new();
+        m.put(&TEST, "Hello world".to_owned());
+        m.put(&FLAGS, 0x02);
+
+        let mut update = Metadata::new();
+        update.put(&FLAGS, 0x20);
+        m.merge(update);
+
+        assert_eq!(m.get(&TEST).map(String::as_str), Some("Hello world"));
+        assert_eq!(m.get(&FLAGS), Some(&0x20));
+    }
 }
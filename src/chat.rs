@@ -0,0 +1,170 @@
+//! Chat history and the overlay which displays it on top of the game.
+
+use crate::format::Component;
+use crate::render;
+use crate::ui;
+use instant::Instant;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+const MAX_HISTORY: usize = 100;
+const MAX_SENT_HISTORY: usize = 100;
+const VISIBLE_LINES: usize = 10;
+const CHAT_WIDTH: f64 = 330.0;
+// Seconds a message stays on screen before it starts to fade out
+const FADE_START: f64 = 8.0;
+const FADE_TIME: f64 = 2.0;
+
+struct ChatLine {
+    message: Component,
+    received_at: Instant,
+}
+
+/// Messages received from the server and lines sent by the player.
+#[derive(Default)]
+pub struct Chat {
+    lines: VecDeque<ChatLine>,
+    sent: Vec<String>,
+    generation: usize,
+}
+
+impl Chat {
+    pub fn new() -> Chat {
+        Default::default()
+    }
+
+    pub fn add_message(&mut self, message: Component) {
+        if self.lines.len() >= MAX_HISTORY {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(ChatLine {
+            message,
+            received_at: Instant::now(),
+        });
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Remembers a line typed by the player so it can be recalled later.
+    pub fn add_sent(&mut self, line: &str) {
+        if self.sent.last().map_or(false, |v| v == line) {
+            return;
+        }
+        if self.sent.len() >= MAX_SENT_HISTORY {
+            self.sent.remove(0);
+        }
+        self.sent.push(line.to_owned());
+    }
+
+    /// Lines sent by the player, oldest first.
+    pub fn sent_history(&self) -> &[String] {
+        &self.sent
+    }
+}
+
+/// The scroll position of the chat while the chat screen is open,
+/// `None` when it is closed. Shared between the overlay and the screen.
+pub type ChatScroll = Rc<Cell<Option<usize>>>;
+
+pub struct ChatOverlay {
+    scroll: ChatScroll,
+    elements: Vec<(ui::ImageRef, ui::FormattedRef)>,
+    last_generation: Option<usize>,
+    last_scroll: Option<usize>,
+}
+
+impl Default for ChatOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChatOverlay {
+    pub fn new() -> ChatOverlay {
+        ChatOverlay {
+            scroll: Rc::new(Cell::new(None)),
+            elements: vec![],
+            last_generation: None,
+            last_scroll: None,
+        }
+    }
+
+    pub fn scroll_handle(&self) -> ChatScroll {
+        self.scroll.clone()
+    }
+
+    pub fn tick(
+        &mut self,
+        chat: &Chat,
+        ui_container: &mut ui::Container,
+        renderer: &render::Renderer,
+    ) {
+        let scroll = self.scroll.get().map(|v| {
+            let max = chat.lines.len().saturating_sub(VISIBLE_LINES);
+            let v = v.min(max);
+            self.scroll.set(Some(v));
+            v
+        });
+        let now = Instant::now();
+        let alpha_of = |line: &ChatLine| {
+            if scroll.is_some() {
+                return 1.0;
+            }
+            let age = now.duration_since(line.received_at).as_secs_f64();
+            (1.0 - (age - FADE_START) / FADE_TIME).clamp(0.0, 1.0)
+        };
+
+        if self.last_generation != Some(chat.generation) || self.last_scroll != scroll {
+            self.last_generation = Some(chat.generation);
+            self.last_scroll = scroll;
+            self.elements.clear();
+
+            let mut offset = 0.0;
+            for line in chat
+                .lines
+                .iter()
+                .rev()
+                .skip(scroll.unwrap_or(0))
+                .take(VISIBLE_LINES)
+            {
+                if alpha_of(line) <= 0.0 {
+                    break;
+                }
+                let (_, height) =
+                    ui::Formatted::compute_size(renderer, &line.message, CHAT_WIDTH - 10.0);
+                let background = ui::ImageBuilder::new()
+                    .texture("steven:solid")
+                    .position(2.0, 60.0 + offset)
+                    .size(CHAT_WIDTH, height)
+                    .colour((0, 0, 0, 100))
+                    .alignment(ui::VAttach::Bottom, ui::HAttach::Left)
+                    .draw_index(400)
+                    .create(ui_container);
+                let text = ui::FormattedBuilder::new()
+                    .text(line.message.clone())
+                    .position(4.0, 0.0)
+                    .max_width(CHAT_WIDTH - 10.0)
+                    .alignment(ui::VAttach::Top, ui::HAttach::Left)
+                    .attach(&mut *background.borrow_mut());
+                self.elements.push((background, text));
+                offset += height;
+            }
+        }
+
+        // Fade out old messages, dropping them once they are invisible
+        let mut expired = false;
+        for ((background, text), line) in self
+            .elements
+            .iter()
+            .zip(chat.lines.iter().rev().skip(scroll.unwrap_or(0)))
+        {
+            let alpha = alpha_of(line);
+            expired |= alpha <= 0.0;
+            background.borrow_mut().colour.3 = (100.0 * alpha) as u8;
+            text.borrow_mut().alpha = (255.0 * alpha) as u8;
+        }
+        if expired {
+            self.last_generation = None;
+        }
+    }
+}
@@ -56,6 +56,11 @@ impl ChunkBuilder {
         }
     }
 
+    /// Returns the block models used to build chunks.
+    pub fn get_models(&self) -> Arc<RwLock<model::Factory>> {
+        self.models.clone()
+    }
+
     pub fn tick(
         &mut self,
         world: &mut world::World,
@@ -0,0 +1,206 @@
+//! Tab completion shared by the chat box and the console.
+//!
+//! Matches either come straight from a local source (the command tree
+//! sent by 1.13+ servers or the console's own commands) or from a
+//! `TabCompleteReply` some time after the request was sent. Pressing tab
+//! again without changing the input cycles through the matches.
+
+use crate::protocol::packet;
+
+/// Remembers the matches of the last completion so repeated tab presses
+/// can cycle through them.
+#[derive(Default)]
+pub struct TabCompleter {
+    /// The input the matches were found for
+    input: String,
+    /// The byte offset into the input of the text replaced by a match
+    start: usize,
+    matches: Vec<String>,
+    index: usize,
+    /// The input after the last completion was applied
+    completed: Option<String>,
+}
+
+impl TabCompleter {
+    pub fn new() -> TabCompleter {
+        Default::default()
+    }
+
+    /// Returns the input with the next match applied if the input hasn't
+    /// changed since the last completion.
+    pub fn cycle(&mut self, input: &str) -> Option<String> {
+        if self.completed.as_deref() != Some(input) || self.matches.is_empty() {
+            return None;
+        }
+        self.index = (self.index + 1) % self.matches.len();
+        Some(self.apply())
+    }
+
+    /// Replaces the current matches, returning the input with the first
+    /// match applied.
+    pub fn set_matches(
+        &mut self,
+        input: &str,
+        start: usize,
+        matches: Vec<String>,
+    ) -> Option<String> {
+        self.input = input.to_owned();
+        self.start = start.min(input.len());
+        self.matches = matches;
+        self.index = 0;
+        self.completed = None;
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(self.apply())
+    }
+
+    pub fn matches(&self) -> &[String] {
+        &self.matches
+    }
+
+    fn apply(&mut self) -> String {
+        let completed = format!("{}{}", &self.input[..self.start], self.matches[self.index]);
+        self.completed = Some(completed.clone());
+        completed
+    }
+}
+
+/// Returns the byte offset of the word at the end of the input, the part
+/// that gets replaced when completing.
+pub fn last_word_start(input: &str) -> usize {
+    input.rfind(' ').map_or(0, |v| v + 1)
+}
+
+const NODE_TYPE_MASK: u8 = 0x03;
+const NODE_LITERAL: u8 = 1;
+const NODE_ARGUMENT: u8 = 2;
+
+/// The command graph sent by 1.13+ servers in `DeclareCommands`.
+pub struct CommandTree {
+    nodes: Vec<packet::CommandNode>,
+    root: usize,
+}
+
+impl CommandTree {
+    pub fn new(nodes: Vec<packet::CommandNode>, root: usize) -> CommandTree {
+        CommandTree { nodes, root }
+    }
+
+    /// Completes the literal at the end of a command, the input shouldn't
+    /// include the leading '/'. Returns the byte offset of the completed
+    /// word and the matching literals.
+    ///
+    /// Arguments are assumed to be a single word, commands which don't
+    /// match anything locally should be completed by the server instead.
+    pub fn complete(&self, input: &str) -> (usize, Vec<String>) {
+        let start = last_word_start(input);
+        let mut current = vec![self.root];
+        if start > 0 {
+            for word in input[..start - 1].split(' ') {
+                let mut next = vec![];
+                for child in current.iter().flat_map(|v| self.children(*v)) {
+                    let node = &self.nodes[child];
+                    let matches = match node.flags & NODE_TYPE_MASK {
+                        NODE_LITERAL => node.name.as_deref() == Some(word),
+                        NODE_ARGUMENT => !word.is_empty(),
+                        _ => false,
+                    };
+                    if matches && !next.contains(&child) {
+                        next.push(child);
+                    }
+                }
+                if next.is_empty() {
+                    return (start, vec![]);
+                }
+                current = next;
+            }
+        }
+
+        let partial = &input[start..];
+        let mut matches: Vec<String> = current
+            .iter()
+            .flat_map(|v| self.children(*v))
+            .map(|v| &self.nodes[v])
+            .filter(|v| v.flags & NODE_TYPE_MASK == NODE_LITERAL)
+            .filter_map(|v| v.name.clone())
+            .filter(|v| v.starts_with(partial))
+            .collect();
+        matches.sort();
+        matches.dedup();
+        (start, matches)
+    }
+
+    /// Returns the children of a node, following redirects.
+    fn children(&self, node: usize) -> Vec<usize> {
+        let node = match self.nodes.get(node) {
+            Some(node) => node,
+            None => return vec![],
+        };
+        let node = match node.redirect_node {
+            Some(redirect) => match self.nodes.get(redirect.0 as usize) {
+                Some(node) => node,
+                None => return vec![],
+            },
+            None => node,
+        };
+        node.children
+            .data
+            .iter()
+            .map(|v| v.0 as usize)
+            .filter(|v| *v < self.nodes.len())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{LenPrefixed, VarInt};
+
+    fn node(flags: u8, name: Option<&str>, children: &[i32]) -> packet::CommandNode {
+        packet::CommandNode {
+            flags,
+            children: LenPrefixed::new(children.iter().map(|v| VarInt(*v)).collect()),
+            name: name.map(|v| v.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn command_tree() {
+        // /gamemode <survival|creative> [<player>], /give <player>
+        let tree = CommandTree::new(
+            vec![
+                node(0, None, &[1, 5]),
+                node(NODE_LITERAL, Some("gamemode"), &[2, 3]),
+                node(NODE_LITERAL, Some("survival"), &[4]),
+                node(NODE_LITERAL, Some("creative"), &[4]),
+                node(NODE_ARGUMENT, Some("target"), &[]),
+                node(NODE_LITERAL, Some("give"), &[4]),
+            ],
+            0,
+        );
+        assert_eq!(
+            tree.complete("g"),
+            (0, vec!["gamemode".to_owned(), "give".to_owned()])
+        );
+        assert_eq!(
+            tree.complete("gamemode c"),
+            (9, vec!["creative".to_owned()])
+        );
+        assert_eq!(tree.complete("gamemode creative "), (18, vec![]));
+        assert_eq!(tree.complete("kill "), (5, vec![]));
+    }
+
+    #[test]
+    fn cycle_matches() {
+        let mut completer = TabCompleter::new();
+        assert_eq!(completer.cycle("/g"), None);
+        let first = completer.set_matches("/g", 1, vec!["gamemode".to_owned(), "give".to_owned()]);
+        assert_eq!(first.as_deref(), Some("/gamemode"));
+        assert_eq!(completer.cycle("/gamemode").as_deref(), Some("/give"));
+        assert_eq!(completer.cycle("/give").as_deref(), Some("/gamemode"));
+        assert_eq!(completer.cycle("/gamemode x"), None);
+    }
+}
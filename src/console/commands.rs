@@ -0,0 +1,228 @@
+use super::Vars;
+use crate::completion;
+use crate::format::{Color, Component, TextComponent};
+use crate::screen;
+use crate::Game;
+use std::collections::BTreeMap;
+
+/// Runs a command with the arguments typed after its name, returning the
+/// text to print to the console or an error message.
+pub type CommandFunc = fn(&mut Game, &[&str]) -> Result<String, String>;
+/// Returns the possible values of the next argument given the arguments
+/// typed before it.
+pub type CompleteFunc = fn(&Vars, &[&str]) -> Vec<String>;
+
+pub struct Command {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub func: CommandFunc,
+    pub complete: Option<CompleteFunc>,
+}
+
+#[derive(Default)]
+pub struct Commands {
+    commands: BTreeMap<&'static str, Command>,
+}
+
+impl Commands {
+    pub fn new() -> Commands {
+        Default::default()
+    }
+
+    pub fn register(&mut self, command: Command) {
+        if self.commands.contains_key(command.name) {
+            panic!("Key registered twice {}", command.name);
+        }
+        self.commands.insert(command.name, command);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Command> {
+        self.commands.values()
+    }
+
+    /// Completes the command name or argument at the end of the input,
+    /// returning the byte offset of the word and the matches.
+    pub fn complete(&self, vars: &Vars, input: &str) -> (usize, Vec<String>) {
+        let start = completion::last_word_start(input);
+        let words: Vec<&str> = input[..start].split_whitespace().collect();
+        let candidates = match words.split_first() {
+            None => self.commands.keys().map(|v| v.to_string()).collect(),
+            Some((name, args)) => self
+                .get(name)
+                .and_then(|v| v.complete)
+                .map_or_else(Vec::new, |complete| complete(vars, args)),
+        };
+        let partial = &input[start..];
+        let matches = candidates
+            .into_iter()
+            .filter(|v| v.starts_with(partial))
+            .collect();
+        (start, matches)
+    }
+
+    /// Runs a line typed into the console, printing the result.
+    pub fn execute(&self, game: &mut Game, line: &str) {
+        print(game, format!("> {}", line), None);
+        let mut parts = line.split_whitespace();
+        let name = match parts.next() {
+            Some(name) => name,
+            None => return,
+        };
+        let args: Vec<&str> = parts.collect();
+        let result = match self.get(name) {
+            Some(command) => (command.func)(game, &args),
+            None => Err(format!("Unknown command {}, try 'help'", name)),
+        };
+        match result {
+            Ok(output) => {
+                for line in output.lines() {
+                    print(game, line.to_owned(), None);
+                }
+            }
+            Err(err) => print(game, err, Some(Color::Red)),
+        }
+    }
+}
+
+fn print(game: &mut Game, text: String, color: Option<Color>) {
+    let mut msg = TextComponent::new(&text);
+    msg.modifier.color = color;
+    game.console.lock().unwrap().print(Component::Text(msg));
+}
+
+pub fn register_commands(commands: &mut Commands) {
+    commands.register(Command {
+        name: "help",
+        usage: "help",
+        description: "Lists the available commands",
+        func: cmd_help,
+        complete: None,
+    });
+    commands.register(Command {
+        name: "get",
+        usage: "get <name>",
+        description: "Prints the value of a variable",
+        func: cmd_get,
+        complete: Some(complete_var_name),
+    });
+    commands.register(Command {
+        name: "set",
+        usage: "set <name> <value>",
+        description: "Changes the value of a variable",
+        func: cmd_set,
+        complete: Some(complete_var_name),
+    });
+    commands.register(Command {
+        name: "reset",
+        usage: "reset <name>",
+        description: "Resets a variable to its default value",
+        func: cmd_reset,
+        complete: Some(complete_var_name),
+    });
+    commands.register(Command {
+        name: "list",
+        usage: "list [prefix]",
+        description: "Lists variables, optionally only those starting with the prefix",
+        func: cmd_list,
+        complete: Some(complete_var_name),
+    });
+    commands.register(Command {
+        name: "save",
+        usage: "save",
+        description: "Saves the variables to the config file",
+        func: cmd_save,
+        complete: None,
+    });
+    commands.register(Command {
+        name: "connect",
+        usage: "connect <address>",
+        description: "Connects to a server",
+        func: cmd_connect,
+        complete: None,
+    });
+}
+
+fn complete_var_name(vars: &Vars, args: &[&str]) -> Vec<String> {
+    if args.is_empty() {
+        vars.names().into_iter().map(|v| v.to_owned()).collect()
+    } else {
+        vec![]
+    }
+}
+
+fn cmd_help(game: &mut Game, _args: &[&str]) -> Result<String, String> {
+    Ok(game
+        .commands
+        .iter()
+        .map(|v| format!("{} - {}", v.usage, v.description))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn cmd_get(game: &mut Game, args: &[&str]) -> Result<String, String> {
+    match args {
+        [name] => Ok(format!("{} = {}", name, game.vars.get_string(name)?)),
+        _ => Err("Usage: get <name>".to_owned()),
+    }
+}
+
+fn cmd_set(game: &mut Game, args: &[&str]) -> Result<String, String> {
+    match args {
+        [name, value @ ..] if !value.is_empty() => {
+            game.vars.set_string(name, &value.join(" "))?;
+            Ok(format!("{} = {}", name, game.vars.get_string(name)?))
+        }
+        _ => Err("Usage: set <name> <value>".to_owned()),
+    }
+}
+
+fn cmd_reset(game: &mut Game, args: &[&str]) -> Result<String, String> {
+    match args {
+        [name] => {
+            game.vars.reset(name)?;
+            Ok(format!("{} = {}", name, game.vars.get_string(name)?))
+        }
+        _ => Err("Usage: reset <name>".to_owned()),
+    }
+}
+
+fn cmd_list(game: &mut Game, args: &[&str]) -> Result<String, String> {
+    let prefix = args.first().copied().unwrap_or("");
+    let mut out = vec![];
+    for name in game.vars.names() {
+        if !name.starts_with(prefix) {
+            continue;
+        }
+        out.push(format!("{} = {}", name, game.vars.get_string(name)?));
+        if let Some(description) = game.vars.description(name) {
+            out.push(format!("    {}", description));
+        }
+    }
+    if out.is_empty() {
+        return Err(format!("No variables starting with '{}'", prefix));
+    }
+    Ok(out.join("\n"))
+}
+
+fn cmd_save(game: &mut Game, _args: &[&str]) -> Result<String, String> {
+    game.vars.save_config();
+    Ok("Saved config".to_owned())
+}
+
+fn cmd_connect(game: &mut Game, args: &[&str]) -> Result<String, String> {
+    let address = match args {
+        [address] => *address,
+        _ => return Err("Usage: connect <address>".to_owned()),
+    };
+    game.console.lock().unwrap().toggle();
+    game.focused = false;
+    game.screen_sys
+        .replace_screen(Box::new(screen::connecting::Connecting::new(address)));
+    game.connect_to(address);
+    Ok(format!("Connecting to {}", address))
+}
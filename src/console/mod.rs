@@ -20,9 +20,14 @@ use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 use std_or_web::fs;
 
+use crate::completion;
 use crate::format::{Color, Component, TextComponent};
 use crate::render;
 use crate::ui;
+use winit::event::VirtualKeyCode;
+
+mod commands;
+pub use self::commands::{register_commands, Command, Commands};
 
 #[cfg(target_arch = "wasm32")]
 use web_sys;
@@ -102,6 +107,13 @@ impl Var for CVar<i64> {
         Box::new(input.parse::<i64>().unwrap())
     }
 
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, String> {
+        input
+            .parse::<i64>()
+            .map(|v| Box::new(v) as Box<dyn Any>)
+            .map_err(|_| format!("expected an integer, got '{}'", input))
+    }
+
     fn description(&self) -> &'static str {
         self.description
     }
@@ -109,6 +121,14 @@ impl Var for CVar<i64> {
     fn can_serialize(&self) -> bool {
         self.serializable
     }
+
+    fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    fn default_value(&self) -> Box<dyn Any> {
+        Box::new((self.default)())
+    }
 }
 
 impl Var for CVar<bool> {
@@ -120,6 +140,13 @@ impl Var for CVar<bool> {
         Box::new(input.parse::<bool>().unwrap())
     }
 
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, String> {
+        input
+            .parse::<bool>()
+            .map(|v| Box::new(v) as Box<dyn Any>)
+            .map_err(|_| format!("expected true or false, got '{}'", input))
+    }
+
     fn description(&self) -> &'static str {
         self.description
     }
@@ -127,6 +154,14 @@ impl Var for CVar<bool> {
     fn can_serialize(&self) -> bool {
         self.serializable
     }
+
+    fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    fn default_value(&self) -> Box<dyn Any> {
+        Box::new((self.default)())
+    }
 }
 
 impl Var for CVar<String> {
@@ -138,19 +173,41 @@ impl Var for CVar<String> {
         Box::new(input[1..input.len() - 1].to_owned())
     }
 
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, String> {
+        // Quotes are optional when typed into the console
+        let input = input
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(input);
+        Ok(Box::new(input.to_owned()))
+    }
+
     fn description(&self) -> &'static str {
         self.description
     }
     fn can_serialize(&self) -> bool {
         self.serializable
     }
+
+    fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    fn default_value(&self) -> Box<dyn Any> {
+        Box::new((self.default)())
+    }
 }
 
 pub trait Var {
     fn serialize(&self, val: &Box<dyn Any>) -> String;
     fn deserialize(&self, input: &str) -> Box<dyn Any>;
+    /// Like `deserialize` but for values typed by the user, returning an
+    /// error instead of panicking on invalid input.
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, String>;
     fn description(&self) -> &'static str;
     fn can_serialize(&self) -> bool;
+    fn is_mutable(&self) -> bool;
+    fn default_value(&self) -> Box<dyn Any>;
 }
 
 #[derive(Default)]
@@ -195,6 +252,59 @@ impl Vars {
         self.save_config();
     }
 
+    /// Returns the names of every registered variable in sorted order.
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.vars.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn description(&self, name: &str) -> Option<&'static str> {
+        self.names
+            .get(name)
+            .map(|name| self.vars.get(name).unwrap().description())
+    }
+
+    /// Returns the value of the named variable formatted the same way
+    /// as in the config file.
+    pub fn get_string(&self, name: &str) -> Result<String, String> {
+        let name = self.lookup(name)?;
+        let var = self.vars.get(name).unwrap();
+        Ok(var.serialize(&self.var_values.get(name).unwrap().borrow()))
+    }
+
+    /// Parses and sets the value of the named variable, failing if the
+    /// value is invalid or the variable can't be changed at runtime.
+    pub fn set_string(&self, name: &str, value: &str) -> Result<(), String> {
+        let name = self.lookup(name)?;
+        let var = self.vars.get(name).unwrap();
+        if !var.is_mutable() {
+            return Err(format!("{} can't be changed", name));
+        }
+        let value = var.parse(value)?;
+        *self.var_values.get(name).unwrap().borrow_mut() = value;
+        self.save_config();
+        Ok(())
+    }
+
+    pub fn reset(&self, name: &str) -> Result<(), String> {
+        let name = self.lookup(name)?;
+        let var = self.vars.get(name).unwrap();
+        if !var.is_mutable() {
+            return Err(format!("{} can't be changed", name));
+        }
+        *self.var_values.get(name).unwrap().borrow_mut() = var.default_value();
+        self.save_config();
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> Result<&'static str, String> {
+        self.names
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("unknown variable {}", name))
+    }
+
     pub fn load_config(&mut self) {
         if let Ok(file) = fs::File::open("conf.cfg") {
             let reader = BufReader::new(file);
@@ -242,6 +352,10 @@ impl Vars {
 pub struct Console {
     history: Vec<Component>,
     dirty: bool,
+    input: String,
+    input_history: Vec<String>,
+    input_history_index: Option<usize>,
+    completer: completion::TabCompleter,
     logfile: fs::File,
     log_level_term: log::Level,
     log_level_file: log::Level,
@@ -253,6 +367,7 @@ pub struct Console {
 
 struct ConsoleElements {
     background: ui::ImageRef,
+    input: ui::TextRef,
     lines: Vec<ui::FormattedRef>,
 }
 
@@ -267,6 +382,10 @@ impl Console {
         Console {
             history: vec![Component::Text(TextComponent::new("")); 200],
             dirty: false,
+            input: String::new(),
+            input_history: vec![],
+            input_history_index: None,
+            completer: completion::TabCompleter::new(),
             logfile: fs::File::create("client.log").expect("failed to open log file"),
             log_level_term: log::Level::Info,
             log_level_file: log::Level::Trace,
@@ -329,8 +448,14 @@ impl Console {
                 .colour((0, 0, 0, 180))
                 .draw_index(500)
                 .create(ui_container);
+            let input = ui::TextBuilder::new()
+                .text("")
+                .position(5.0, 5.0)
+                .alignment(ui::VAttach::Bottom, ui::HAttach::Left)
+                .create(&mut *background.borrow_mut());
             self.elements = Some(ConsoleElements {
                 background,
+                input,
                 lines: vec![],
             });
             self.dirty = true;
@@ -339,12 +464,14 @@ impl Console {
         let mut background = elements.background.borrow_mut();
         background.y = self.position;
         background.width = w;
+        elements.input.borrow_mut().text = format!("> {}", self.input);
 
         if self.dirty {
             self.dirty = false;
             elements.lines.clear();
 
-            let mut offset = 0.0;
+            // Leave space for the input line
+            let mut offset = 18.0;
             for line in self.history.iter().rev() {
                 if offset >= 210.0 {
                     break;
@@ -363,6 +490,79 @@ impl Console {
         }
     }
 
+    /// Handles a character typed while the console is active.
+    pub fn key_type(&mut self, c: char) {
+        match c {
+            // Backspace
+            '\x08' | '\x7f' => {
+                self.input.pop();
+            }
+            // The key which toggles the console
+            '`' => {}
+            c if c >= ' ' => self.input.push(c),
+            _ => {}
+        }
+    }
+
+    /// Handles a key press while the console is active, returning the
+    /// typed line once it is submitted.
+    pub fn key_press(&mut self, key: VirtualKeyCode) -> Option<String> {
+        match key {
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                self.input_history_index = None;
+                let line = std::mem::take(&mut self.input).trim().to_owned();
+                if line.is_empty() {
+                    return None;
+                }
+                if self.input_history.last() != Some(&line) {
+                    self.input_history.push(line.clone());
+                }
+                Some(line)
+            }
+            VirtualKeyCode::Up | VirtualKeyCode::Down if !self.input_history.is_empty() => {
+                let len = self.input_history.len();
+                self.input_history_index = match (key, self.input_history_index) {
+                    (VirtualKeyCode::Up, None) => Some(len - 1),
+                    (VirtualKeyCode::Up, Some(index)) => Some(index.saturating_sub(1)),
+                    (_, Some(index)) if index + 1 < len => Some(index + 1),
+                    _ => None,
+                };
+                self.input = self
+                    .input_history_index
+                    .map_or_else(String::new, |index| self.input_history[index].clone());
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Completes the word at the end of the input. `complete` returns the
+    /// byte offset of the word and its possible completions.
+    pub fn complete<F>(&mut self, complete: F)
+    where
+        F: FnOnce(&str) -> (usize, Vec<String>),
+    {
+        if let Some(completed) = self.completer.cycle(&self.input) {
+            self.input = completed;
+            return;
+        }
+        let (start, matches) = complete(&self.input);
+        if let Some(completed) = self.completer.set_matches(&self.input, start, matches) {
+            if self.completer.matches().len() > 1 {
+                let matches = self.completer.matches().join(" ");
+                self.print(Component::Text(TextComponent::new(&matches)));
+            }
+            self.input = completed;
+        }
+    }
+
+    /// Adds a line to the console without logging it.
+    pub fn print(&mut self, line: Component) {
+        self.history.remove(0);
+        self.history.push(line);
+        self.dirty = true;
+    }
+
     fn log(&mut self, record: &log::Record) {
         for filtered in FILTERED_CRATES {
             if record.module_path().unwrap_or("").starts_with(filtered) {
@@ -0,0 +1,277 @@
+use super::{
+    append_model_box, block_matrix, color_from_id, create_model, facing_rotation,
+    standing_rotation, update_light,
+};
+use crate::ecs;
+use crate::nbt;
+use crate::render;
+use crate::render::model;
+use crate::shared::Position;
+use crate::world;
+use crate::world::block::{Block, ColoredVariant};
+use cgmath::{Matrix4, Vector3};
+
+// Banners are drawn at two thirds of the size of their model
+const SCALE: f32 = 2.0 / 3.0;
+// Space between the layers of patterns, to stop them flickering
+const LAYER_SPACING: f32 = 0.01;
+
+pub fn add_systems(m: &mut ecs::Manager) {
+    let sys = BannerRenderer::new(m);
+    m.add_render_system(sys);
+}
+
+pub fn init_entity(m: &mut ecs::Manager, e: ecs::Entity) {
+    m.add_component_direct(
+        e,
+        BannerInfo {
+            model: None,
+            base: None,
+            patterns: vec![],
+            dirty: false,
+        },
+    );
+}
+
+/// Reads the patterns on the banner, along with its color before 1.13.
+pub fn update_data(m: &mut ecs::Manager, e: ecs::Entity, data: &nbt::Tag, protocol_version: i32) {
+    let banner_info: ecs::Key<BannerInfo> = m.get_key();
+    let info = match m.get_component_mut(e, banner_info) {
+        Some(info) => info,
+        None => return,
+    };
+    // Colors used to be numbered like dyes, starting from black
+    let color = |id: i32| color_from_id(if protocol_version < 404 { 15 - id } else { id });
+    if let Some(base) = data.get("Base").and_then(|v| v.as_int()) {
+        info.base = Some(color(base));
+    }
+    info.patterns = data
+        .get("Patterns")
+        .and_then(|v| v.as_list())
+        .unwrap_or_default()
+        .iter()
+        .filter(|v| v.is_compound())
+        .filter_map(|v| {
+            let pattern = v.get("Pattern")?.as_str()?;
+            Some((pattern.to_owned(), color(v.get("Color")?.as_int()?)))
+        })
+        .collect();
+    info.dirty = true;
+}
+
+pub struct BannerInfo {
+    model: Option<model::ModelKey>,
+    /// Replaces the color of the block when set
+    base: Option<ColoredVariant>,
+    /// The short names of patterns on top of the base, with their colors
+    patterns: Vec<(String, ColoredVariant)>,
+    dirty: bool,
+}
+
+/// Returns the texture of the pattern with the short name used in the
+/// banner's data.
+fn pattern_texture(pattern: &str) -> Option<&'static str> {
+    Some(match pattern {
+        "b" => "base",
+        "bl" => "square_bottom_left",
+        "br" => "square_bottom_right",
+        "tl" => "square_top_left",
+        "tr" => "square_top_right",
+        "bs" => "stripe_bottom",
+        "ts" => "stripe_top",
+        "ls" => "stripe_left",
+        "rs" => "stripe_right",
+        "cs" => "stripe_center",
+        "ms" => "stripe_middle",
+        "drs" => "stripe_downright",
+        "dls" => "stripe_downleft",
+        "ss" => "small_stripes",
+        "cr" => "cross",
+        "sc" => "straight_cross",
+        "bt" => "triangle_bottom",
+        "tt" => "triangle_top",
+        "bts" => "triangles_bottom",
+        "tts" => "triangles_top",
+        "ld" => "diagonal_left",
+        "rd" => "diagonal_up_right",
+        "lud" => "diagonal_up_left",
+        "rud" => "diagonal_right",
+        "mc" => "circle",
+        "mr" => "rhombus",
+        "vh" => "half_vertical",
+        "hh" => "half_horizontal",
+        "vhr" => "half_vertical_right",
+        "hhb" => "half_horizontal_bottom",
+        "bo" => "border",
+        "cbo" => "curly_border",
+        "cre" => "creeper",
+        "gra" => "gradient",
+        "gru" => "gradient_up",
+        "bri" => "bricks",
+        "sku" => "skull",
+        "flo" => "flower",
+        "moj" => "mojang",
+        _ => return None,
+    })
+}
+
+/// The color patterns are tinted with.
+fn dye_color(color: ColoredVariant) -> (u8, u8, u8) {
+    match color {
+        ColoredVariant::White => (249, 255, 254),
+        ColoredVariant::Orange => (249, 128, 29),
+        ColoredVariant::Magenta => (199, 78, 189),
+        ColoredVariant::LightBlue => (58, 179, 218),
+        ColoredVariant::Yellow => (254, 216, 61),
+        ColoredVariant::Lime => (128, 199, 31),
+        ColoredVariant::Pink => (243, 139, 170),
+        ColoredVariant::Gray => (71, 79, 82),
+        ColoredVariant::Silver => (157, 157, 151),
+        ColoredVariant::Cyan => (22, 156, 156),
+        ColoredVariant::Purple => (137, 50, 184),
+        ColoredVariant::Blue => (60, 68, 170),
+        ColoredVariant::Brown => (131, 84, 50),
+        ColoredVariant::Green => (94, 124, 22),
+        ColoredVariant::Red => (176, 46, 38),
+        ColoredVariant::Black => (29, 29, 33),
+    }
+}
+
+struct BannerRenderer {
+    filter: ecs::Filter,
+    position: ecs::Key<Position>,
+    banner_info: ecs::Key<BannerInfo>,
+}
+
+impl BannerRenderer {
+    fn new(m: &mut ecs::Manager) -> BannerRenderer {
+        let banner_info = m.get_key();
+        let position = m.get_key();
+        BannerRenderer {
+            filter: ecs::Filter::new().with(position).with(banner_info),
+            position,
+            banner_info,
+        }
+    }
+}
+
+impl ecs::System for BannerRenderer {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(
+        &mut self,
+        m: &mut ecs::Manager,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        for e in m.find(&self.filter) {
+            let position = *m.get_component(e, self.position).unwrap();
+            let info = m.get_component_mut(e, self.banner_info).unwrap();
+            if info.dirty {
+                self.entity_removed(m, e, world, renderer);
+                self.entity_added(m, e, world, renderer);
+            }
+            if let Some(model) = info.model {
+                update_light(renderer, world, model, position);
+            }
+        }
+    }
+
+    fn entity_added(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let position = *m.get_component(e, self.position).unwrap();
+        let info = m.get_component_mut(e, self.banner_info).unwrap();
+        info.dirty = false;
+        let (matrix, color, standing) = match world.get_block(position) {
+            Block::StandingBanner { rotation, color } => (
+                block_matrix(position, standing_rotation(rotation.data()))
+                    * Matrix4::from_translation(Vector3::new(0.0, -0.5, 0.0)),
+                color,
+                true,
+            ),
+            // Wall banners hang down from the top of the block
+            Block::WallBanner { facing, color } => (
+                block_matrix(position, facing_rotation(facing))
+                    * Matrix4::from_translation(Vector3::new(0.0, 1.0 / 6.0 + 0.3125, 0.4375)),
+                color,
+                false,
+            ),
+            _ => return,
+        };
+        let tex = render::Renderer::get_texture(renderer.get_textures_ref(), "entity/banner_base");
+
+        let mut verts = vec![];
+        if standing {
+            append_model_box(
+                &mut verts,
+                &tex,
+                (64.0, 64.0),
+                (44.0, 0.0),
+                (-1.0, -12.0, -1.0),
+                (2.0, 42.0, 2.0),
+            );
+        }
+        append_model_box(
+            &mut verts,
+            &tex,
+            (64.0, 64.0),
+            (0.0, 42.0),
+            (-10.0, 30.0, -1.0),
+            (20.0, 2.0, 2.0),
+        );
+
+        let base = info.base.unwrap_or(color);
+        let layers = std::iter::once(("base", base)).chain(
+            info.patterns
+                .iter()
+                .filter_map(|(pattern, color)| Some((pattern_texture(pattern)?, *color))),
+        );
+        for (i, (pattern, color)) in layers.enumerate() {
+            let tex = render::Renderer::get_texture(
+                renderer.get_textures_ref(),
+                &format!("entity/banner/{}", pattern),
+            );
+            let grow = i as f32 * LAYER_SPACING;
+            let start = verts.len();
+            append_model_box(
+                &mut verts,
+                &tex,
+                (64.0, 64.0),
+                (0.0, 0.0),
+                (-10.0 - grow, -8.0 - grow, -2.0 - grow),
+                (20.0 + grow * 2.0, 40.0 + grow * 2.0, 1.0 + grow * 2.0),
+            );
+            let (r, g, b) = dye_color(color);
+            for vert in &mut verts[start..] {
+                vert.r = (vert.r as u32 * r as u32 / 255) as u8;
+                vert.g = (vert.g as u32 * g as u32 / 255) as u8;
+                vert.b = (vert.b as u32 * b as u32 / 255) as u8;
+            }
+        }
+
+        let model = create_model(renderer, position, vec![verts]);
+        renderer.model.get_model(model).unwrap().matrix[0] = matrix * Matrix4::from_scale(SCALE);
+        info.model = Some(model);
+    }
+
+    fn entity_removed(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let info = m.get_component_mut(e, self.banner_info).unwrap();
+        if let Some(model) = info.model {
+            renderer.model.remove_model(model);
+        }
+        info.model = None;
+    }
+}
@@ -0,0 +1,238 @@
+use super::{
+    append_model_box, block_matrix, color_from_id, create_model, facing_rotation, update_light,
+};
+use crate::ecs;
+use crate::nbt;
+use crate::render;
+use crate::render::model;
+use crate::shared::{Direction, Position};
+use crate::world;
+use crate::world::block::{BedPart, Block, ColoredVariant};
+
+pub fn add_systems(m: &mut ecs::Manager) {
+    let sys = BedRenderer::new(m);
+    m.add_render_system(sys);
+}
+
+pub fn init_entity(m: &mut ecs::Manager, e: ecs::Entity) {
+    m.add_component_direct(
+        e,
+        BedInfo {
+            model: None,
+            color: None,
+            dirty: false,
+        },
+    );
+}
+
+/// Reads the color of beds from before 1.13, which isn't part of the
+/// block.
+pub fn update_data(m: &mut ecs::Manager, e: ecs::Entity, data: &nbt::Tag) {
+    let bed_info: ecs::Key<BedInfo> = m.get_key();
+    if let Some(info) = m.get_component_mut(e, bed_info) {
+        if let Some(color) = data.get("color").and_then(|v| v.as_int()) {
+            info.color = Some(color_from_id(color));
+            info.dirty = true;
+        }
+    }
+}
+
+pub struct BedInfo {
+    model: Option<model::ModelKey>,
+    /// Replaces the color of the block when set
+    color: Option<ColoredVariant>,
+    dirty: bool,
+}
+
+struct BedRenderer {
+    filter: ecs::Filter,
+    position: ecs::Key<Position>,
+    bed_info: ecs::Key<BedInfo>,
+}
+
+impl BedRenderer {
+    fn new(m: &mut ecs::Manager) -> BedRenderer {
+        let bed_info = m.get_key();
+        let position = m.get_key();
+        BedRenderer {
+            filter: ecs::Filter::new().with(position).with(bed_info),
+            position,
+            bed_info,
+        }
+    }
+}
+
+/// Adds one face of the mattress, with the texture turned a quarter turn
+/// either way when `turn` is set, as the sides are stored upright.
+fn append_face(
+    verts: &mut Vec<model::Vertex>,
+    dir: Direction,
+    texture: render::Texture,
+    turn: Option<bool>,
+) {
+    let mut textures: [Option<render::Texture>; 6] = Default::default();
+    textures[dir.index()] = Some(texture);
+    model::append_box(
+        verts,
+        -0.5,
+        3.0 / 16.0,
+        -0.5,
+        1.0,
+        6.0 / 16.0,
+        1.0,
+        textures,
+    );
+    let start = verts.len() - 4;
+    for vert in &mut verts[start..] {
+        let (x, y) = (vert.texture_x, vert.texture_y);
+        match turn {
+            Some(true) => {
+                vert.texture_x = 1.0 - y;
+                vert.texture_y = x;
+            }
+            Some(false) => {
+                vert.texture_x = y;
+                vert.texture_y = 1.0 - x;
+            }
+            None => {}
+        }
+    }
+}
+
+impl ecs::System for BedRenderer {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(
+        &mut self,
+        m: &mut ecs::Manager,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        for e in m.find(&self.filter) {
+            let position = *m.get_component(e, self.position).unwrap();
+            let info = m.get_component_mut(e, self.bed_info).unwrap();
+            if info.dirty {
+                self.entity_removed(m, e, world, renderer);
+                self.entity_added(m, e, world, renderer);
+            }
+            if let Some(model) = info.model {
+                update_light(renderer, world, model, position);
+            }
+        }
+    }
+
+    fn entity_added(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let position = *m.get_component(e, self.position).unwrap();
+        let info = m.get_component_mut(e, self.bed_info).unwrap();
+        info.dirty = false;
+        let (color, facing, part) = match world.get_block(position) {
+            Block::Bed {
+                color,
+                facing,
+                part,
+                ..
+            } => (info.color.unwrap_or(color), facing, part),
+            _ => return,
+        };
+        let tex = render::Renderer::get_texture(
+            renderer.get_textures_ref(),
+            &format!("entity/bed/{}", color.as_string()),
+        );
+        macro_rules! rel {
+            ($x:expr, $y:expr, $w:expr, $h:expr) => {
+                tex.relative(($x) / 64.0, ($y) / 64.0, ($w) / 64.0, ($h) / 64.0)
+            };
+        }
+
+        // Both halves are built with the pillow end facing north
+        let mut verts = vec![];
+        let (v, legs_z, legs_v) = match part {
+            BedPart::Head => (0.0, -8.0, 0.0),
+            BedPart::Foot => (22.0, 5.0, 12.0),
+        };
+        append_face(
+            &mut verts,
+            Direction::Up,
+            rel!(6.0, v + 6.0, 16.0, 16.0),
+            None,
+        );
+        append_face(
+            &mut verts,
+            Direction::Down,
+            rel!(28.0, v + 6.0, 16.0, 16.0),
+            None,
+        );
+        append_face(
+            &mut verts,
+            Direction::West,
+            rel!(0.0, v + 6.0, 6.0, 16.0),
+            Some(true),
+        );
+        append_face(
+            &mut verts,
+            Direction::East,
+            rel!(22.0, v + 6.0, 6.0, 16.0),
+            Some(false),
+        );
+        match part {
+            BedPart::Head => {
+                append_face(
+                    &mut verts,
+                    Direction::North,
+                    rel!(6.0, 0.0, 16.0, 6.0),
+                    None,
+                );
+                // The end is stored upside down
+                let start = verts.len() - 4;
+                for vert in &mut verts[start..] {
+                    vert.texture_y = 1.0 - vert.texture_y;
+                }
+            }
+            BedPart::Foot => {
+                append_face(
+                    &mut verts,
+                    Direction::South,
+                    rel!(22.0, 22.0, 16.0, 6.0),
+                    None,
+                );
+            }
+        }
+        for (i, x) in [-8.0, 5.0].iter().enumerate() {
+            append_model_box(
+                &mut verts,
+                &tex,
+                (64.0, 64.0),
+                (50.0, legs_v + i as f32 * 6.0),
+                (*x, 0.0, legs_z),
+                (3.0, 3.0, 3.0),
+            );
+        }
+
+        let model = create_model(renderer, position, vec![verts]);
+        renderer.model.get_model(model).unwrap().matrix[0] =
+            block_matrix(position, facing_rotation(facing));
+        info.model = Some(model);
+    }
+
+    fn entity_removed(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let info = m.get_component_mut(e, self.bed_info).unwrap();
+        if let Some(model) = info.model {
+            renderer.model.remove_model(model);
+        }
+        info.model = None;
+    }
+}
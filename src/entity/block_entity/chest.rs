@@ -0,0 +1,237 @@
+use super::{append_model_box, block_matrix, create_model, facing_rotation, update_light};
+use crate::ecs;
+use crate::entity::GameInfo;
+use crate::render;
+use crate::render::model;
+use crate::shared::{Direction, Position};
+use crate::world;
+use crate::world::block::{Block, ChestType};
+use cgmath::{Matrix4, Quaternion, Rad, Rotation3, Vector3};
+use std::f64::consts::PI;
+
+pub fn add_systems(m: &mut ecs::Manager) {
+    let sys = ChestRenderer::new(m);
+    m.add_render_system(sys);
+}
+
+pub fn init_entity(m: &mut ecs::Manager, e: ecs::Entity) {
+    m.add_component_direct(
+        e,
+        ChestInfo {
+            model: None,
+            look: None,
+            viewers: 0,
+            openness: 0.0,
+        },
+    );
+}
+
+/// Opens the lid while anyone is looking inside the chest.
+pub fn set_viewers(m: &mut ecs::Manager, e: ecs::Entity, viewers: u8) {
+    let chest_info: ecs::Key<ChestInfo> = m.get_key();
+    if let Some(info) = m.get_component_mut(e, chest_info) {
+        info.viewers = viewers;
+    }
+}
+
+pub struct ChestInfo {
+    model: Option<model::ModelKey>,
+    /// What the model was built to look like
+    look: Option<Look>,
+    /// The number of players with the chest open
+    viewers: u8,
+    /// How far the lid is open, from 0 to 1
+    openness: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Single,
+    /// Draws both halves of a double chest
+    Double,
+    /// The half of a double chest drawn by the other half
+    Hidden,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Look {
+    texture: &'static str,
+    facing: Direction,
+    shape: Shape,
+}
+
+impl Look {
+    fn of(world: &world::World, position: Position) -> Option<Look> {
+        let block = world.get_block(position);
+        let (facing, type_, texture) = match block {
+            Block::Chest { facing, type_, .. } => (facing, type_, "entity/chest/normal"),
+            Block::TrappedChest { facing, type_, .. } => (facing, type_, "entity/chest/trapped"),
+            Block::EnderChest { facing, .. } => {
+                return Some(Look {
+                    texture: "entity/chest/ender",
+                    facing,
+                    shape: Shape::Single,
+                })
+            }
+            _ => return None,
+        };
+        let joins = |dir: Direction| match (block, world.get_block(position.shift(dir))) {
+            (Block::Chest { .. }, Block::Chest { facing: other, .. })
+            | (Block::TrappedChest { .. }, Block::TrappedChest { facing: other, .. }) => {
+                other == facing
+            }
+            _ => false,
+        };
+        let shape = match type_ {
+            ChestType::Left => Shape::Double,
+            ChestType::Right => Shape::Hidden,
+            // Chests before 1.13 don't know which chest they are joined to
+            ChestType::Single if joins(facing.clockwise()) => Shape::Double,
+            ChestType::Single if joins(facing.counter_clockwise()) => Shape::Hidden,
+            ChestType::Single => Shape::Single,
+        };
+        Some(Look {
+            texture,
+            facing,
+            shape,
+        })
+    }
+}
+
+struct ChestRenderer {
+    filter: ecs::Filter,
+    position: ecs::Key<Position>,
+    chest_info: ecs::Key<ChestInfo>,
+    game_info: ecs::Key<GameInfo>,
+}
+
+impl ChestRenderer {
+    fn new(m: &mut ecs::Manager) -> ChestRenderer {
+        let chest_info = m.get_key();
+        let position = m.get_key();
+        ChestRenderer {
+            filter: ecs::Filter::new().with(position).with(chest_info),
+            position,
+            chest_info,
+            game_info: m.get_key(),
+        }
+    }
+}
+
+impl ecs::System for ChestRenderer {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(
+        &mut self,
+        m: &mut ecs::Manager,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let world_entity = m.get_world();
+        let delta = m
+            .get_component(world_entity, self.game_info)
+            .unwrap()
+            .delta
+            .min(5.0);
+        for e in m.find(&self.filter) {
+            let position = *m.get_component(e, self.position).unwrap();
+            let info = m.get_component_mut(e, self.chest_info).unwrap();
+            // Chests join up with ones placed next to them
+            if info.look != Look::of(world, position) {
+                self.entity_removed(m, e, world, renderer);
+                self.entity_added(m, e, world, renderer);
+            }
+            let model = match info.model {
+                Some(model) => model,
+                None => continue,
+            };
+            // The lid takes half a second to open or close, like vanilla
+            let step = delta / 30.0;
+            if info.viewers > 0 {
+                info.openness = (info.openness + step).min(1.0);
+            } else {
+                info.openness = (info.openness - step).max(0.0);
+            }
+            let angle = (1.0 - (1.0 - info.openness).powi(3)) * PI / 2.0;
+            let look = info.look.unwrap();
+            update_light(renderer, world, model, position);
+            let mdl = renderer.model.get_model(model).unwrap();
+            let base = block_matrix(position, facing_rotation(look.facing));
+            mdl.matrix[0] = base;
+            // The lid swings around its hinge at the back of the chest
+            mdl.matrix[1] = base
+                * Matrix4::from_translation(Vector3::new(0.0, -9.0 / 16.0, 7.0 / 16.0))
+                * Matrix4::from(Quaternion::from_angle_x(Rad(-angle as f32)));
+        }
+    }
+
+    fn entity_added(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let position = *m.get_component(e, self.position).unwrap();
+        let info = m.get_component_mut(e, self.chest_info).unwrap();
+        info.look = Look::of(world, position);
+        let look = match info.look {
+            Some(look) if look.shape != Shape::Hidden => look,
+            _ => return,
+        };
+
+        let double = look.shape == Shape::Double;
+        let (texture, size, width) = if double {
+            (format!("{}_double", look.texture), (128.0, 64.0), 30.0)
+        } else {
+            (look.texture.to_owned(), (64.0, 64.0), 14.0)
+        };
+        let tex = render::Renderer::get_texture(renderer.get_textures_ref(), &texture);
+
+        let mut base = vec![];
+        append_model_box(
+            &mut base,
+            &tex,
+            size,
+            (0.0, 19.0),
+            (-7.0, 0.0, -7.0),
+            (width, 10.0, 14.0),
+        );
+        // The lid and the latch are placed relative to the hinge
+        let mut lid = vec![];
+        append_model_box(
+            &mut lid,
+            &tex,
+            size,
+            (0.0, 0.0),
+            (-7.0, 0.0, -14.0),
+            (width, 5.0, 14.0),
+        );
+        append_model_box(
+            &mut lid,
+            &tex,
+            size,
+            (0.0, 0.0),
+            (width / 2.0 - 8.0, -2.0, -15.0),
+            (2.0, 4.0, 1.0),
+        );
+
+        info.model = Some(create_model(renderer, position, vec![base, lid]));
+    }
+
+    fn entity_removed(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let info = m.get_component_mut(e, self.chest_info).unwrap();
+        if let Some(model) = info.model {
+            renderer.model.remove_model(model);
+        }
+        info.model = None;
+    }
+}
@@ -1,21 +1,78 @@
+pub mod banner;
+pub mod bed;
+pub mod chest;
+pub mod shulker_box;
 pub mod sign;
+pub mod skull;
 
 use crate::ecs;
-use crate::shared::Position;
-use crate::world::block::Block;
+use crate::nbt;
+use crate::render;
+use crate::render::model;
+use crate::shared::{Direction, Position};
+use crate::world;
+use crate::world::block::{Block, ColoredVariant};
+use cgmath::{Decomposed, Matrix4, Quaternion, Rad, Rotation3, Vector3};
+use std::f64::consts::PI;
 
 pub fn add_systems(m: &mut ecs::Manager) {
     sign::add_systems(m);
+    chest::add_systems(m);
+    bed::add_systems(m);
+    banner::add_systems(m);
+    skull::add_systems(m);
+    shulker_box::add_systems(m);
 }
 
 pub enum BlockEntityType {
     Sign,
+    Chest,
+    Bed,
+    Banner,
+    Skull,
+    ShulkerBox,
 }
 
 impl BlockEntityType {
     pub fn get_block_entity(bl: Block) -> Option<BlockEntityType> {
         match bl {
             Block::StandingSign { .. } | Block::WallSign { .. } => Some(BlockEntityType::Sign),
+            Block::Chest { .. } | Block::TrappedChest { .. } | Block::EnderChest { .. } => {
+                Some(BlockEntityType::Chest)
+            }
+            Block::Bed { .. } => Some(BlockEntityType::Bed),
+            Block::StandingBanner { .. } | Block::WallBanner { .. } => {
+                Some(BlockEntityType::Banner)
+            }
+            Block::SkullSkeletonWall { .. }
+            | Block::SkullSkeleton { .. }
+            | Block::SkullWitherSkeletonWall { .. }
+            | Block::SkullWitherSkeleton { .. }
+            | Block::ZombieWallHead { .. }
+            | Block::ZombieHead { .. }
+            | Block::PlayerWallHead { .. }
+            | Block::PlayerHead { .. }
+            | Block::CreeperWallHead { .. }
+            | Block::CreeperHead { .. }
+            | Block::DragonWallHead { .. }
+            | Block::DragonHead { .. } => Some(BlockEntityType::Skull),
+            Block::ShulkerBox { .. }
+            | Block::WhiteShulkerBox { .. }
+            | Block::OrangeShulkerBox { .. }
+            | Block::MagentaShulkerBox { .. }
+            | Block::LightBlueShulkerBox { .. }
+            | Block::YellowShulkerBox { .. }
+            | Block::LimeShulkerBox { .. }
+            | Block::PinkShulkerBox { .. }
+            | Block::GrayShulkerBox { .. }
+            | Block::LightGrayShulkerBox { .. }
+            | Block::CyanShulkerBox { .. }
+            | Block::PurpleShulkerBox { .. }
+            | Block::BlueShulkerBox { .. }
+            | Block::BrownShulkerBox { .. }
+            | Block::GreenShulkerBox { .. }
+            | Block::RedShulkerBox { .. }
+            | Block::BlackShulkerBox { .. } => Some(BlockEntityType::ShulkerBox),
             _ => None,
         }
     }
@@ -25,7 +82,151 @@ impl BlockEntityType {
         m.add_component_direct(e, pos);
         match *self {
             BlockEntityType::Sign => sign::init_entity(m, e),
+            BlockEntityType::Chest => chest::init_entity(m, e),
+            BlockEntityType::Bed => bed::init_entity(m, e),
+            BlockEntityType::Banner => banner::init_entity(m, e),
+            BlockEntityType::Skull => skull::init_entity(m, e),
+            BlockEntityType::ShulkerBox => shulker_box::init_entity(m, e),
         }
         e
     }
 }
+
+/// Applies the data sent by the server for a block entity. Each kind of
+/// block entity picks out the parts that change how it looks.
+pub fn update_data(m: &mut ecs::Manager, e: ecs::Entity, data: &nbt::Tag, protocol_version: i32) {
+    if !data.is_compound() {
+        return;
+    }
+    sign::update_data(m, e, data);
+    bed::update_data(m, e, data);
+    banner::update_data(m, e, data, protocol_version);
+    skull::update_data(m, e, data);
+}
+
+/// Sets the number of players looking inside a container, which opens
+/// the lids of chests and shulker boxes.
+pub fn set_viewers(m: &mut ecs::Manager, e: ecs::Entity, viewers: u8) {
+    chest::set_viewers(m, e, viewers);
+    shulker_box::set_viewers(m, e, viewers);
+}
+
+/// Returns the dye color with the id used by wool and beds, white first.
+pub fn color_from_id(id: i32) -> ColoredVariant {
+    match id {
+        1 => ColoredVariant::Orange,
+        2 => ColoredVariant::Magenta,
+        3 => ColoredVariant::LightBlue,
+        4 => ColoredVariant::Yellow,
+        5 => ColoredVariant::Lime,
+        6 => ColoredVariant::Pink,
+        7 => ColoredVariant::Gray,
+        8 => ColoredVariant::Silver,
+        9 => ColoredVariant::Cyan,
+        10 => ColoredVariant::Purple,
+        11 => ColoredVariant::Blue,
+        12 => ColoredVariant::Brown,
+        13 => ColoredVariant::Green,
+        14 => ColoredVariant::Red,
+        15 => ColoredVariant::Black,
+        _ => ColoredVariant::White,
+    }
+}
+
+/// The rotation that turns a model built facing north to face `facing`.
+fn facing_rotation(facing: Direction) -> f64 {
+    match facing {
+        Direction::South => PI,
+        Direction::West => PI / 2.0,
+        Direction::East => -PI / 2.0,
+        _ => 0.0,
+    }
+}
+
+/// The rotation of a model placed in one of the 16 directions of standing
+/// signs, banners and skulls, where 0 faces south.
+fn standing_rotation(rotation: usize) -> f64 {
+    -(rotation as f64 / 16.0) * PI * 2.0 + PI
+}
+
+/// Places a model on the bottom of the block, in the middle, turned by
+/// `rotation`.
+fn block_matrix(position: Position, rotation: f64) -> Matrix4<f32> {
+    Matrix4::from(Decomposed {
+        scale: 1.0,
+        rot: Quaternion::from_angle_y(Rad(rotation as f32)),
+        disp: Vector3::new(
+            position.x as f32 + 0.5,
+            -position.y as f32,
+            position.z as f32 + 0.5,
+        ),
+    })
+}
+
+/// Creates a model out of `parts` centered on the block.
+fn create_model(
+    renderer: &mut render::Renderer,
+    position: Position,
+    parts: Vec<Vec<model::Vertex>>,
+) -> model::ModelKey {
+    let model = renderer.model.create_model(model::DEFAULT, parts);
+    let mdl = renderer.model.get_model(model).unwrap();
+    mdl.radius = 2.0;
+    mdl.x = position.x as f32 + 0.5;
+    mdl.y = position.y as f32 + 0.5;
+    mdl.z = position.z as f32 + 0.5;
+    model
+}
+
+/// Lights the model with the light of the block it is in.
+fn update_light(
+    renderer: &mut render::Renderer,
+    world: &world::World,
+    model: model::ModelKey,
+    position: Position,
+) {
+    if let Some(mdl) = renderer.model.get_model(model) {
+        mdl.block_light = world.get_block_light(position) as f32;
+        mdl.sky_light = world.get_sky_light(position) as f32;
+    }
+}
+
+/// Adds a box of an entity model to `verts`, textured using the layout of
+/// vanilla's entity textures for a box starting at `uv`. Everything is
+/// measured in pixels and the front of the box faces north.
+fn append_model_box(
+    verts: &mut Vec<model::Vertex>,
+    texture: &render::Texture,
+    texture_size: (f32, f32),
+    uv: (f32, f32),
+    from: (f32, f32, f32),
+    size: (f32, f32, f32),
+) {
+    let (u, v) = uv;
+    let (w, h, d) = size;
+    let rel = |x: f32, y: f32, width: f32, height: f32| {
+        Some(texture.relative(
+            x / texture_size.0,
+            y / texture_size.1,
+            width / texture_size.0,
+            height / texture_size.1,
+        ))
+    };
+    model::append_box(
+        verts,
+        from.0 / 16.0,
+        from.1 / 16.0,
+        from.2 / 16.0,
+        w / 16.0,
+        h / 16.0,
+        d / 16.0,
+        [
+            rel(u + d + w, v, w, d),         // Down
+            rel(u + d, v, w, d),             // Up
+            rel(u + d, v + d, w, h),         // North
+            rel(u + d + w + d, v + d, w, h), // South
+            rel(u + d + w, v + d, d, h),     // West
+            rel(u, v + d, d, h),             // East
+        ],
+    );
+}
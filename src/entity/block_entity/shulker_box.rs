@@ -0,0 +1,201 @@
+use super::{append_model_box, block_matrix, create_model, facing_rotation, update_light};
+use crate::ecs;
+use crate::entity::GameInfo;
+use crate::render;
+use crate::render::model;
+use crate::shared::{Direction, Position};
+use crate::world;
+use crate::world::block::{Block, ColoredVariant};
+use cgmath::{Matrix4, Quaternion, Rad, Rotation3, Vector3};
+use std::f64::consts::PI;
+
+pub fn add_systems(m: &mut ecs::Manager) {
+    let sys = ShulkerBoxRenderer::new(m);
+    m.add_render_system(sys);
+}
+
+pub fn init_entity(m: &mut ecs::Manager, e: ecs::Entity) {
+    m.add_component_direct(
+        e,
+        ShulkerBoxInfo {
+            model: None,
+            facing: Direction::Up,
+            viewers: 0,
+            openness: 0.0,
+        },
+    );
+}
+
+/// Opens the lid while anyone is looking inside the box.
+pub fn set_viewers(m: &mut ecs::Manager, e: ecs::Entity, viewers: u8) {
+    let shulker_box_info: ecs::Key<ShulkerBoxInfo> = m.get_key();
+    if let Some(info) = m.get_component_mut(e, shulker_box_info) {
+        info.viewers = viewers;
+    }
+}
+
+pub struct ShulkerBoxInfo {
+    model: Option<model::ModelKey>,
+    /// The side the lid is on
+    facing: Direction,
+    /// The number of players with the box open
+    viewers: u8,
+    /// How far the lid is open, from 0 to 1
+    openness: f64,
+}
+
+/// Returns the color and facing of a shulker box block.
+fn shulker_box(block: Block) -> Option<(ColoredVariant, Direction)> {
+    Some(match block {
+        // The uncolored box looks like a purple one
+        Block::ShulkerBox { facing } => (ColoredVariant::Purple, facing),
+        Block::WhiteShulkerBox { facing } => (ColoredVariant::White, facing),
+        Block::OrangeShulkerBox { facing } => (ColoredVariant::Orange, facing),
+        Block::MagentaShulkerBox { facing } => (ColoredVariant::Magenta, facing),
+        Block::LightBlueShulkerBox { facing } => (ColoredVariant::LightBlue, facing),
+        Block::YellowShulkerBox { facing } => (ColoredVariant::Yellow, facing),
+        Block::LimeShulkerBox { facing } => (ColoredVariant::Lime, facing),
+        Block::PinkShulkerBox { facing } => (ColoredVariant::Pink, facing),
+        Block::GrayShulkerBox { facing } => (ColoredVariant::Gray, facing),
+        Block::LightGrayShulkerBox { facing } => (ColoredVariant::Silver, facing),
+        Block::CyanShulkerBox { facing } => (ColoredVariant::Cyan, facing),
+        Block::PurpleShulkerBox { facing } => (ColoredVariant::Purple, facing),
+        Block::BlueShulkerBox { facing } => (ColoredVariant::Blue, facing),
+        Block::BrownShulkerBox { facing } => (ColoredVariant::Brown, facing),
+        Block::GreenShulkerBox { facing } => (ColoredVariant::Green, facing),
+        Block::RedShulkerBox { facing } => (ColoredVariant::Red, facing),
+        Block::BlackShulkerBox { facing } => (ColoredVariant::Black, facing),
+        _ => return None,
+    })
+}
+
+struct ShulkerBoxRenderer {
+    filter: ecs::Filter,
+    position: ecs::Key<Position>,
+    shulker_box_info: ecs::Key<ShulkerBoxInfo>,
+    game_info: ecs::Key<GameInfo>,
+}
+
+impl ShulkerBoxRenderer {
+    fn new(m: &mut ecs::Manager) -> ShulkerBoxRenderer {
+        let shulker_box_info = m.get_key();
+        let position = m.get_key();
+        ShulkerBoxRenderer {
+            filter: ecs::Filter::new().with(position).with(shulker_box_info),
+            position,
+            shulker_box_info,
+            game_info: m.get_key(),
+        }
+    }
+}
+
+impl ecs::System for ShulkerBoxRenderer {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(
+        &mut self,
+        m: &mut ecs::Manager,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let world_entity = m.get_world();
+        let delta = m
+            .get_component(world_entity, self.game_info)
+            .unwrap()
+            .delta
+            .min(5.0);
+        for e in m.find(&self.filter) {
+            let position = *m.get_component(e, self.position).unwrap();
+            let info = m.get_component_mut(e, self.shulker_box_info).unwrap();
+            let model = match info.model {
+                Some(model) => model,
+                None => continue,
+            };
+            // Opens or closes over half a second, like vanilla
+            let step = delta / 30.0;
+            if info.viewers > 0 {
+                info.openness = (info.openness + step).min(1.0);
+            } else {
+                info.openness = (info.openness - step).max(0.0);
+            }
+            update_light(renderer, world, model, position);
+
+            // Turned around the middle of the block so the lid is on the
+            // side it faces
+            let tilt = match info.facing {
+                Direction::Up => 0.0,
+                Direction::Down => PI,
+                _ => PI / 2.0,
+            };
+            let base = block_matrix(position, facing_rotation(info.facing))
+                * Matrix4::from_translation(Vector3::new(0.0, -0.5, 0.0))
+                * Matrix4::from(Quaternion::from_angle_x(Rad(tilt as f32)))
+                * Matrix4::from_translation(Vector3::new(0.0, 0.5, 0.0));
+            let mdl = renderer.model.get_model(model).unwrap();
+            mdl.matrix[0] = base;
+            // The lid twists as it rises out of the box
+            mdl.matrix[1] = base
+                * Matrix4::from_translation(Vector3::new(0.0, -(info.openness * 0.5) as f32, 0.0))
+                * Matrix4::from(Quaternion::from_angle_y(Rad(
+                    (info.openness * PI * 1.5) as f32
+                )));
+        }
+    }
+
+    fn entity_added(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let position = *m.get_component(e, self.position).unwrap();
+        let info = m.get_component_mut(e, self.shulker_box_info).unwrap();
+        let (color, facing) = match shulker_box(world.get_block(position)) {
+            Some(val) => val,
+            None => return,
+        };
+        info.facing = facing;
+        let tex = render::Renderer::get_texture(
+            renderer.get_textures_ref(),
+            &format!("entity/shulker/shulker_{}", color.as_string()),
+        );
+
+        let mut base = vec![];
+        append_model_box(
+            &mut base,
+            &tex,
+            (64.0, 64.0),
+            (0.0, 28.0),
+            (-8.0, 0.0, -8.0),
+            (16.0, 8.0, 16.0),
+        );
+        let mut lid = vec![];
+        append_model_box(
+            &mut lid,
+            &tex,
+            (64.0, 64.0),
+            (0.0, 0.0),
+            (-8.0, 4.0, -8.0),
+            (16.0, 12.0, 16.0),
+        );
+
+        info.model = Some(create_model(renderer, position, vec![base, lid]));
+    }
+
+    fn entity_removed(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let info = m.get_component_mut(e, self.shulker_box_info).unwrap();
+        if let Some(model) = info.model {
+            renderer.model.remove_model(model);
+        }
+        info.model = None;
+    }
+}
@@ -1,5 +1,6 @@
 use crate::ecs;
 use crate::format::{self, Component};
+use crate::nbt;
 use crate::render;
 use crate::render::model::{self, FormatState};
 use crate::shared::{Direction, Position};
@@ -32,6 +33,26 @@ pub fn init_entity(m: &mut ecs::Manager, e: ecs::Entity) {
     );
 }
 
+/// Reads the lines of the sign.
+pub fn update_data(m: &mut ecs::Manager, e: ecs::Entity, data: &nbt::Tag) {
+    let sign_info: ecs::Key<SignInfo> = m.get_key();
+    let info = match m.get_component_mut(e, sign_info) {
+        Some(info) => info,
+        None => return,
+    };
+    let line = |name| {
+        data.get(name)
+            .and_then(|v| v.as_str())
+            .map(format::Component::from_string)
+    };
+    if let (Some(line1), Some(line2), Some(line3), Some(line4)) =
+        (line("Text1"), line("Text2"), line("Text3"), line("Text4"))
+    {
+        info.lines = [line1, line2, line3, line4];
+        info.dirty = true;
+    }
+}
+
 pub struct SignInfo {
     model: Option<model::ModelKey>,
 
@@ -0,0 +1,256 @@
+use super::{
+    append_model_box, block_matrix, create_model, facing_rotation, standing_rotation, update_light,
+};
+use crate::ecs;
+use crate::entity::player;
+use crate::nbt;
+use crate::render;
+use crate::render::model;
+use crate::shared::{Direction, Position};
+use crate::world;
+use crate::world::block::Block;
+use cgmath::{Matrix4, Vector3};
+
+pub fn add_systems(m: &mut ecs::Manager) {
+    let sys = SkullRenderer::new(m);
+    m.add_render_system(sys);
+}
+
+pub fn init_entity(m: &mut ecs::Manager, e: ecs::Entity) {
+    m.add_component_direct(
+        e,
+        SkullInfo {
+            model: None,
+            kind: None,
+            rotation: None,
+            skin_url: None,
+            loaded_skin: None,
+            dirty: false,
+        },
+    );
+}
+
+/// Reads the owner of player heads, along with the type and rotation of
+/// skulls before 1.13 which aren't part of the block.
+pub fn update_data(m: &mut ecs::Manager, e: ecs::Entity, data: &nbt::Tag) {
+    let skull_info: ecs::Key<SkullInfo> = m.get_key();
+    let info = match m.get_component_mut(e, skull_info) {
+        Some(info) => info,
+        None => return,
+    };
+    if let Some(kind) = data.get("SkullType").and_then(|v| v.as_byte()) {
+        info.kind = Some(Kind::from_id(kind));
+    }
+    if let Some(rotation) = data.get("Rot").and_then(|v| v.as_byte()) {
+        info.rotation = Some((rotation & 0xF) as usize);
+    }
+    // Renamed from Owner in 1.12
+    let owner = data.get("SkullOwner").or_else(|| data.get("Owner"));
+    info.skin_url = owner
+        .and_then(|v| child(v, "Properties"))
+        .and_then(|v| child(v, "textures"))
+        .and_then(|v| v.as_list())
+        .and_then(|v| v.first())
+        .and_then(|v| child(v, "Value"))
+        .and_then(|v| v.as_str())
+        .and_then(player::skin_url);
+    info.dirty = true;
+}
+
+/// Returns the named tag inside `tag` if it is a compound.
+fn child<'a>(tag: &'a nbt::Tag, name: &str) -> Option<&'a nbt::Tag> {
+    tag.as_compound()?.get(name)
+}
+
+pub struct SkullInfo {
+    model: Option<model::ModelKey>,
+    /// Replaces the type of the block when set
+    kind: Option<Kind>,
+    /// Replaces the rotation of the block when set
+    rotation: Option<usize>,
+    skin_url: Option<String>,
+    /// The skin used by the model, released when it is removed
+    loaded_skin: Option<String>,
+    dirty: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Skeleton,
+    WitherSkeleton,
+    Zombie,
+    Player,
+    Creeper,
+    Dragon,
+}
+
+impl Kind {
+    fn from_id(id: i8) -> Kind {
+        match id {
+            1 => Kind::WitherSkeleton,
+            2 => Kind::Zombie,
+            3 => Kind::Player,
+            4 => Kind::Creeper,
+            5 => Kind::Dragon,
+            _ => Kind::Skeleton,
+        }
+    }
+}
+
+enum Placement {
+    /// On top of a block, turned to one of 16 directions
+    Floor(usize),
+    /// Against the side of a block, facing away from it
+    Wall(Direction),
+}
+
+struct SkullRenderer {
+    filter: ecs::Filter,
+    position: ecs::Key<Position>,
+    skull_info: ecs::Key<SkullInfo>,
+}
+
+impl SkullRenderer {
+    fn new(m: &mut ecs::Manager) -> SkullRenderer {
+        let skull_info = m.get_key();
+        let position = m.get_key();
+        SkullRenderer {
+            filter: ecs::Filter::new().with(position).with(skull_info),
+            position,
+            skull_info,
+        }
+    }
+}
+
+impl ecs::System for SkullRenderer {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(
+        &mut self,
+        m: &mut ecs::Manager,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        for e in m.find(&self.filter) {
+            let position = *m.get_component(e, self.position).unwrap();
+            let info = m.get_component_mut(e, self.skull_info).unwrap();
+            if info.dirty {
+                self.entity_removed(m, e, world, renderer);
+                self.entity_added(m, e, world, renderer);
+            }
+            if let Some(model) = info.model {
+                update_light(renderer, world, model, position);
+            }
+        }
+    }
+
+    fn entity_added(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        world: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let position = *m.get_component(e, self.position).unwrap();
+        let info = m.get_component_mut(e, self.skull_info).unwrap();
+        info.dirty = false;
+        let floor = Placement::Floor(info.rotation.unwrap_or(0));
+        let (kind, placement) = match world.get_block(position) {
+            Block::SkullSkeletonWall { facing, .. } => (
+                info.kind.unwrap_or(Kind::Skeleton),
+                if facing == Direction::Up {
+                    floor
+                } else {
+                    Placement::Wall(facing)
+                },
+            ),
+            Block::SkullSkeleton { rotation } => {
+                (Kind::Skeleton, Placement::Floor(rotation as usize))
+            }
+            Block::SkullWitherSkeletonWall { facing } => {
+                (Kind::WitherSkeleton, Placement::Wall(facing))
+            }
+            Block::SkullWitherSkeleton { rotation } => {
+                (Kind::WitherSkeleton, Placement::Floor(rotation as usize))
+            }
+            Block::ZombieWallHead { facing } => (Kind::Zombie, Placement::Wall(facing)),
+            Block::ZombieHead { rotation } => (Kind::Zombie, Placement::Floor(rotation as usize)),
+            Block::PlayerWallHead { facing } => (Kind::Player, Placement::Wall(facing)),
+            Block::PlayerHead { rotation } => (Kind::Player, Placement::Floor(rotation as usize)),
+            Block::CreeperWallHead { facing } => (Kind::Creeper, Placement::Wall(facing)),
+            Block::CreeperHead { rotation } => (Kind::Creeper, Placement::Floor(rotation as usize)),
+            _ => return,
+        };
+        // TODO: Dragon heads have a model of their own
+        let (tex, height, has_hat) = match kind {
+            Kind::Skeleton => ("entity/skeleton/skeleton", 32.0, false),
+            Kind::WitherSkeleton => ("entity/skeleton/wither_skeleton", 32.0, false),
+            Kind::Zombie => ("entity/zombie/zombie", 64.0, true),
+            Kind::Creeper => ("entity/creeper/creeper", 32.0, false),
+            Kind::Player => ("entity/steve", 64.0, true),
+            Kind::Dragon => return,
+        };
+        let tex = match (kind, info.skin_url.as_ref()) {
+            (Kind::Player, Some(url)) => {
+                info.loaded_skin = Some(url.clone());
+                renderer.get_skin(renderer.get_textures_ref(), url)
+            }
+            _ => render::Renderer::get_texture(renderer.get_textures_ref(), tex),
+        };
+
+        let mut verts = vec![];
+        append_model_box(
+            &mut verts,
+            &tex,
+            (64.0, height),
+            (0.0, 0.0),
+            (-4.0, 0.0, -4.0),
+            (8.0, 8.0, 8.0),
+        );
+        if has_hat {
+            append_model_box(
+                &mut verts,
+                &tex,
+                (64.0, height),
+                (32.0, 0.0),
+                (-4.25, -0.25, -4.25),
+                (8.5, 8.5, 8.5),
+            );
+        }
+
+        let matrix = match placement {
+            Placement::Floor(rotation) => block_matrix(position, standing_rotation(rotation)),
+            // Pushed back against the wall, halfway up it
+            Placement::Wall(facing) => {
+                block_matrix(position, facing_rotation(facing))
+                    * Matrix4::from_translation(Vector3::new(0.0, -0.25, 0.24))
+            }
+        };
+        let model = create_model(renderer, position, vec![verts]);
+        renderer.model.get_model(model).unwrap().matrix[0] = matrix;
+        info.model = Some(model);
+    }
+
+    fn entity_removed(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let info = m.get_component_mut(e, self.skull_info).unwrap();
+        if let Some(model) = info.model {
+            renderer.model.remove_model(model);
+        }
+        info.model = None;
+        if let Some(url) = info.loaded_skin.take() {
+            renderer
+                .get_textures_ref()
+                .read()
+                .unwrap()
+                .release_skin(&url);
+        }
+    }
+}
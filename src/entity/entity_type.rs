@@ -0,0 +1,1206 @@
+use super::{Bounds, Light, Position, Rotation, TargetPosition, TargetRotation, Velocity};
+use crate::ecs;
+use crate::render;
+use crate::render::model;
+use crate::world;
+use cgmath::{Decomposed, Matrix4, Point3, Quaternion, Rad, Rotation3, Vector3};
+use collision::Aabb3;
+
+macro_rules! define_entity_types {
+    (
+        $(
+            $name:ident {
+                name $resource_name:expr,
+                $(legacy $legacy:expr,)?
+                $(object $object:expr,)?
+                size ($width:expr, $height:expr),
+            }
+        )+
+    ) => (
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum EntityType {
+            $($name,)+
+        }
+
+        impl EntityType {
+            /// Returns the registry name of the entity type, without namespace.
+            pub fn resource_name(&self) -> &'static str {
+                match *self {
+                    $(EntityType::$name => $resource_name,)+
+                }
+            }
+
+            /// Returns the width and height of the entity's bounding box.
+            pub fn size(&self) -> (f64, f64) {
+                match *self {
+                    $(EntityType::$name => ($width, $height),)+
+                }
+            }
+
+            /// The id used by `SpawnMob` before the 1.13 flattening.
+            fn legacy_id(&self) -> Option<usize> {
+                match *self {
+                    $(
+                        EntityType::$name => {
+                            $(return Some($legacy);)?
+                            #[allow(unreachable_code)]
+                            None
+                        }
+                    )+
+                }
+            }
+
+            /// The id used by `SpawnObject` before 1.14.
+            fn object_id(&self) -> Option<usize> {
+                match *self {
+                    $(
+                        EntityType::$name => {
+                            $(return Some($object);)?
+                            #[allow(unreachable_code)]
+                            None
+                        }
+                    )+
+                }
+            }
+
+            fn all() -> &'static [EntityType] {
+                &[$(EntityType::$name,)+]
+            }
+        }
+    );
+}
+
+define_entity_types! {
+    AreaEffectCloud { name "area_effect_cloud", legacy 3, object 3, size (6.0, 0.5), }
+    ArmorStand { name "armor_stand", legacy 30, object 78, size (0.5, 1.975), }
+    Arrow { name "arrow", legacy 10, object 60, size (0.5, 0.5), }
+    Axolotl { name "axolotl", size (0.75, 0.42), }
+    Bat { name "bat", legacy 65, size (0.5, 0.9), }
+    Bee { name "bee", size (0.7, 0.6), }
+    Blaze { name "blaze", legacy 61, size (0.6, 1.8), }
+    Boat { name "boat", legacy 41, object 1, size (1.375, 0.5625), }
+    Cat { name "cat", size (0.6, 0.7), }
+    CaveSpider { name "cave_spider", legacy 59, size (0.7, 0.5), }
+    Chicken { name "chicken", legacy 93, size (0.4, 0.7), }
+    Cod { name "cod", size (0.5, 0.3), }
+    Cow { name "cow", legacy 92, size (0.9, 1.4), }
+    Creeper { name "creeper", legacy 50, size (0.6, 1.7), }
+    Dolphin { name "dolphin", size (0.9, 0.6), }
+    Donkey { name "donkey", legacy 31, size (1.3964844, 1.5), }
+    DragonFireball { name "dragon_fireball", legacy 26, object 93, size (1.0, 1.0), }
+    Drowned { name "drowned", size (0.6, 1.95), }
+    ElderGuardian { name "elder_guardian", legacy 4, size (1.9975, 1.9975), }
+    EndCrystal { name "end_crystal", legacy 200, object 51, size (2.0, 2.0), }
+    EnderDragon { name "ender_dragon", legacy 63, size (16.0, 8.0), }
+    Enderman { name "enderman", legacy 58, size (0.6, 2.9), }
+    Endermite { name "endermite", legacy 67, size (0.4, 0.3), }
+    Evoker { name "evoker", legacy 34, size (0.6, 1.95), }
+    EvokerFangs { name "evoker_fangs", legacy 33, object 79, size (0.5, 0.8), }
+    ExperienceOrb { name "experience_orb", legacy 2, size (0.5, 0.5), }
+    EyeOfEnder { name "eye_of_ender", legacy 15, object 72, size (0.25, 0.25), }
+    FallingBlock { name "falling_block", legacy 21, object 70, size (0.98, 0.98), }
+    FireworkRocket { name "firework_rocket", legacy 22, object 76, size (0.25, 0.25), }
+    Fox { name "fox", size (0.6, 0.7), }
+    Ghast { name "ghast", legacy 56, size (4.0, 4.0), }
+    Giant { name "giant", legacy 53, size (3.6, 12.0), }
+    GlowItemFrame { name "glow_item_frame", size (0.5, 0.5), }
+    GlowSquid { name "glow_squid", size (0.8, 0.8), }
+    Goat { name "goat", size (0.9, 1.3), }
+    Guardian { name "guardian", legacy 68, size (0.85, 0.85), }
+    Hoglin { name "hoglin", size (1.3964844, 1.4), }
+    Horse { name "horse", legacy 100, size (1.3964844, 1.6), }
+    Husk { name "husk", legacy 23, size (0.6, 1.95), }
+    Illusioner { name "illusioner", legacy 37, size (0.6, 1.95), }
+    IronGolem { name "iron_golem", legacy 99, size (1.4, 2.7), }
+    Item { name "item", legacy 1, object 2, size (0.25, 0.25), }
+    ItemFrame { name "item_frame", legacy 18, object 71, size (0.5, 0.5), }
+    Fireball { name "fireball", legacy 12, object 63, size (1.0, 1.0), }
+    LeashKnot { name "leash_knot", legacy 8, object 77, size (0.375, 0.5), }
+    LightningBolt { name "lightning_bolt", size (0.0, 0.0), }
+    Llama { name "llama", legacy 103, size (0.9, 1.87), }
+    LlamaSpit { name "llama_spit", legacy 104, object 68, size (0.25, 0.25), }
+    MagmaCube { name "magma_cube", legacy 62, size (2.04, 2.04), }
+    Marker { name "marker", size (0.0, 0.0), }
+    Minecart { name "minecart", legacy 42, object 10, size (0.98, 0.7), }
+    ChestMinecart { name "chest_minecart", legacy 43, size (0.98, 0.7), }
+    CommandBlockMinecart { name "command_block_minecart", legacy 40, size (0.98, 0.7), }
+    FurnaceMinecart { name "furnace_minecart", legacy 44, size (0.98, 0.7), }
+    HopperMinecart { name "hopper_minecart", legacy 46, size (0.98, 0.7), }
+    SpawnerMinecart { name "spawner_minecart", legacy 47, size (0.98, 0.7), }
+    TntMinecart { name "tnt_minecart", legacy 45, size (0.98, 0.7), }
+    Mule { name "mule", legacy 32, size (1.3964844, 1.6), }
+    Mooshroom { name "mooshroom", legacy 96, size (0.9, 1.4), }
+    Ocelot { name "ocelot", legacy 98, size (0.6, 0.7), }
+    Painting { name "painting", legacy 9, size (0.5, 0.5), }
+    Panda { name "panda", size (1.3, 1.25), }
+    Parrot { name "parrot", legacy 105, size (0.5, 0.9), }
+    Phantom { name "phantom", size (0.9, 0.5), }
+    Pig { name "pig", legacy 90, size (0.9, 0.9), }
+    Piglin { name "piglin", size (0.6, 1.95), }
+    PiglinBrute { name "piglin_brute", size (0.6, 1.95), }
+    Pillager { name "pillager", size (0.6, 1.95), }
+    PolarBear { name "polar_bear", legacy 102, size (1.4, 1.4), }
+    Tnt { name "tnt", legacy 20, object 50, size (0.98, 0.98), }
+    Pufferfish { name "pufferfish", size (0.7, 0.7), }
+    Rabbit { name "rabbit", legacy 101, size (0.4, 0.5), }
+    Ravager { name "ravager", size (1.95, 2.2), }
+    Salmon { name "salmon", size (0.7, 0.4), }
+    Sheep { name "sheep", legacy 91, size (0.9, 1.3), }
+    Shulker { name "shulker", legacy 69, size (1.0, 1.0), }
+    ShulkerBullet { name "shulker_bullet", legacy 25, object 67, size (0.3125, 0.3125), }
+    Silverfish { name "silverfish", legacy 60, size (0.4, 0.3), }
+    Skeleton { name "skeleton", legacy 51, size (0.6, 1.99), }
+    SkeletonHorse { name "skeleton_horse", legacy 28, size (1.3964844, 1.6), }
+    Slime { name "slime", legacy 55, size (2.04, 2.04), }
+    SmallFireball { name "small_fireball", legacy 13, object 64, size (0.3125, 0.3125), }
+    SnowGolem { name "snow_golem", legacy 97, size (0.7, 1.9), }
+    Snowball { name "snowball", legacy 11, object 61, size (0.25, 0.25), }
+    SpectralArrow { name "spectral_arrow", legacy 24, object 91, size (0.5, 0.5), }
+    Spider { name "spider", legacy 52, size (1.4, 0.9), }
+    Squid { name "squid", legacy 94, size (0.8, 0.8), }
+    Stray { name "stray", legacy 6, size (0.6, 1.99), }
+    Strider { name "strider", size (0.9, 1.7), }
+    Egg { name "egg", legacy 7, object 62, size (0.25, 0.25), }
+    EnderPearl { name "ender_pearl", legacy 14, object 65, size (0.25, 0.25), }
+    ExperienceBottle { name "experience_bottle", legacy 17, object 75, size (0.25, 0.25), }
+    Potion { name "potion", legacy 16, object 73, size (0.25, 0.25), }
+    Trident { name "trident", object 94, size (0.5, 0.5), }
+    TraderLlama { name "trader_llama", size (0.9, 1.87), }
+    TropicalFish { name "tropical_fish", size (0.5, 0.4), }
+    Turtle { name "turtle", size (1.2, 0.4), }
+    Vex { name "vex", legacy 35, size (0.4, 0.8), }
+    Villager { name "villager", legacy 120, size (0.6, 1.95), }
+    Vindicator { name "vindicator", legacy 36, size (0.6, 1.95), }
+    WanderingTrader { name "wandering_trader", size (0.6, 1.95), }
+    Witch { name "witch", legacy 66, size (0.6, 1.95), }
+    Wither { name "wither", legacy 64, size (0.9, 3.5), }
+    WitherSkeleton { name "wither_skeleton", legacy 5, size (0.7, 2.4), }
+    WitherSkull { name "wither_skull", legacy 19, object 66, size (0.3125, 0.3125), }
+    Wolf { name "wolf", legacy 95, size (0.6, 0.85), }
+    Zoglin { name "zoglin", size (1.3964844, 1.4), }
+    Zombie { name "zombie", legacy 54, size (0.6, 1.95), }
+    ZombieHorse { name "zombie_horse", legacy 29, size (1.3964844, 1.6), }
+    ZombieVillager { name "zombie_villager", legacy 27, size (0.6, 1.95), }
+    ZombifiedPiglin { name "zombified_piglin", legacy 57, size (0.6, 1.95), }
+    Player { name "player", size (0.6, 1.8), }
+    FishingBobber { name "fishing_bobber", object 90, size (0.25, 0.25), }
+}
+
+/// Maps the numeric entity type ids sent by the server to `EntityType`s.
+///
+/// Before 1.13 mobs used their own id space and objects (vehicles,
+/// projectiles, etc) used another. From 1.13 onwards mobs use the
+/// entity type registry, which objects joined in 1.14. The registry
+/// order changes between versions so a table is kept per version.
+pub struct EntityIDMap {
+    flat: Vec<Option<EntityType>>,
+    legacy: Vec<Option<EntityType>>,
+    objects: Vec<Option<EntityType>>,
+
+    protocol_version: i32,
+}
+
+impl EntityIDMap {
+    pub fn new(protocol_version: i32) -> EntityIDMap {
+        let mut legacy = vec![];
+        let mut objects = vec![];
+        for ty in EntityType::all() {
+            if let Some(id) = ty.legacy_id() {
+                insert_id(&mut legacy, id, *ty);
+            }
+            if let Some(id) = ty.object_id() {
+                insert_id(&mut objects, id, *ty);
+            }
+        }
+        let flat = registry_order(protocol_version)
+            .iter()
+            .map(|ty| Some(*ty))
+            .collect();
+
+        EntityIDMap {
+            flat,
+            legacy,
+            objects,
+            protocol_version,
+        }
+    }
+
+    /// Looks up the type of a mob spawned with `SpawnMob`.
+    pub fn by_vanilla_id(&self, id: usize) -> Option<EntityType> {
+        if self.protocol_version >= 404 {
+            self.flat.get(id).and_then(|v| *v)
+        } else {
+            self.legacy.get(id).and_then(|v| *v)
+        }
+    }
+
+    /// Looks up the type of an object spawned with `SpawnObject`. Before
+    /// 1.14 minecarts share a single object id and use the data field to
+    /// select their variant.
+    pub fn object_by_vanilla_id(&self, id: usize, data: i32) -> Option<EntityType> {
+        if self.protocol_version >= 477 {
+            return self.by_vanilla_id(id);
+        }
+        match self.objects.get(id).and_then(|v| *v) {
+            Some(EntityType::Minecart) => Some(match data {
+                1 => EntityType::ChestMinecart,
+                2 => EntityType::FurnaceMinecart,
+                3 => EntityType::TntMinecart,
+                4 => EntityType::SpawnerMinecart,
+                5 => EntityType::HopperMinecart,
+                6 => EntityType::CommandBlockMinecart,
+                _ => EntityType::Minecart,
+            }),
+            ty => ty,
+        }
+    }
+
+    /// Looks up the type of an entity spawned with `SpawnGlobalEntity`.
+    pub fn global_by_vanilla_id(&self, id: usize) -> Option<EntityType> {
+        match id {
+            1 => Some(EntityType::LightningBolt),
+            _ => None,
+        }
+    }
+}
+
+fn insert_id(ids: &mut Vec<Option<EntityType>>, id: usize, ty: EntityType) {
+    if ids.len() <= id {
+        ids.resize(id + 1, None);
+    }
+    if let Some(existing) = ids[id] {
+        panic!(
+            "Tried to register {:?} to {} but {:?} was already registered",
+            ty, id, existing
+        );
+    }
+    ids[id] = Some(ty);
+}
+
+fn registry_order(protocol_version: i32) -> &'static [EntityType] {
+    use self::EntityType::*;
+    match protocol_version {
+        755..=i32::MAX => &[
+            AreaEffectCloud,
+            ArmorStand,
+            Arrow,
+            Axolotl,
+            Bat,
+            Bee,
+            Blaze,
+            Boat,
+            Cat,
+            CaveSpider,
+            Chicken,
+            Cod,
+            Cow,
+            Creeper,
+            Dolphin,
+            Donkey,
+            DragonFireball,
+            Drowned,
+            ElderGuardian,
+            EndCrystal,
+            EnderDragon,
+            Enderman,
+            Endermite,
+            Evoker,
+            EvokerFangs,
+            ExperienceOrb,
+            EyeOfEnder,
+            FallingBlock,
+            FireworkRocket,
+            Fox,
+            Ghast,
+            Giant,
+            GlowItemFrame,
+            GlowSquid,
+            Goat,
+            Guardian,
+            Hoglin,
+            Horse,
+            Husk,
+            Illusioner,
+            IronGolem,
+            Item,
+            ItemFrame,
+            Fireball,
+            LeashKnot,
+            LightningBolt,
+            Llama,
+            LlamaSpit,
+            MagmaCube,
+            Marker,
+            Minecart,
+            ChestMinecart,
+            CommandBlockMinecart,
+            FurnaceMinecart,
+            HopperMinecart,
+            SpawnerMinecart,
+            TntMinecart,
+            Mule,
+            Mooshroom,
+            Ocelot,
+            Painting,
+            Panda,
+            Parrot,
+            Phantom,
+            Pig,
+            Piglin,
+            PiglinBrute,
+            Pillager,
+            PolarBear,
+            Tnt,
+            Pufferfish,
+            Rabbit,
+            Ravager,
+            Salmon,
+            Sheep,
+            Shulker,
+            ShulkerBullet,
+            Silverfish,
+            Skeleton,
+            SkeletonHorse,
+            Slime,
+            SmallFireball,
+            SnowGolem,
+            Snowball,
+            SpectralArrow,
+            Spider,
+            Squid,
+            Stray,
+            Strider,
+            Egg,
+            EnderPearl,
+            ExperienceBottle,
+            Potion,
+            Trident,
+            TraderLlama,
+            TropicalFish,
+            Turtle,
+            Vex,
+            Villager,
+            Vindicator,
+            WanderingTrader,
+            Witch,
+            Wither,
+            WitherSkeleton,
+            WitherSkull,
+            Wolf,
+            Zoglin,
+            Zombie,
+            ZombieHorse,
+            ZombieVillager,
+            ZombifiedPiglin,
+            Player,
+            FishingBobber,
+        ],
+        751..=754 => &[
+            AreaEffectCloud,
+            ArmorStand,
+            Arrow,
+            Bat,
+            Bee,
+            Blaze,
+            Boat,
+            Cat,
+            CaveSpider,
+            Chicken,
+            Cod,
+            Cow,
+            Creeper,
+            Dolphin,
+            Donkey,
+            DragonFireball,
+            Drowned,
+            ElderGuardian,
+            EndCrystal,
+            EnderDragon,
+            Enderman,
+            Endermite,
+            Evoker,
+            EvokerFangs,
+            ExperienceOrb,
+            EyeOfEnder,
+            FallingBlock,
+            FireworkRocket,
+            Fox,
+            Ghast,
+            Giant,
+            Guardian,
+            Hoglin,
+            Horse,
+            Husk,
+            Illusioner,
+            IronGolem,
+            Item,
+            ItemFrame,
+            Fireball,
+            LeashKnot,
+            LightningBolt,
+            Llama,
+            LlamaSpit,
+            MagmaCube,
+            Minecart,
+            ChestMinecart,
+            CommandBlockMinecart,
+            FurnaceMinecart,
+            HopperMinecart,
+            SpawnerMinecart,
+            TntMinecart,
+            Mule,
+            Mooshroom,
+            Ocelot,
+            Painting,
+            Panda,
+            Parrot,
+            Phantom,
+            Pig,
+            Piglin,
+            PiglinBrute,
+            Pillager,
+            PolarBear,
+            Tnt,
+            Pufferfish,
+            Rabbit,
+            Ravager,
+            Salmon,
+            Sheep,
+            Shulker,
+            ShulkerBullet,
+            Silverfish,
+            Skeleton,
+            SkeletonHorse,
+            Slime,
+            SmallFireball,
+            SnowGolem,
+            Snowball,
+            SpectralArrow,
+            Spider,
+            Squid,
+            Stray,
+            Strider,
+            Egg,
+            EnderPearl,
+            ExperienceBottle,
+            Potion,
+            Trident,
+            TraderLlama,
+            TropicalFish,
+            Turtle,
+            Vex,
+            Villager,
+            Vindicator,
+            WanderingTrader,
+            Witch,
+            Wither,
+            WitherSkeleton,
+            WitherSkull,
+            Wolf,
+            Zoglin,
+            Zombie,
+            ZombieHorse,
+            ZombieVillager,
+            ZombifiedPiglin,
+            Player,
+            FishingBobber,
+        ],
+        735..=750 => &[
+            AreaEffectCloud,
+            ArmorStand,
+            Arrow,
+            Bat,
+            Bee,
+            Blaze,
+            Boat,
+            Cat,
+            CaveSpider,
+            Chicken,
+            Cod,
+            Cow,
+            Creeper,
+            Dolphin,
+            Donkey,
+            DragonFireball,
+            Drowned,
+            ElderGuardian,
+            EndCrystal,
+            EnderDragon,
+            Enderman,
+            Endermite,
+            Evoker,
+            EvokerFangs,
+            ExperienceOrb,
+            EyeOfEnder,
+            FallingBlock,
+            FireworkRocket,
+            Fox,
+            Ghast,
+            Giant,
+            Guardian,
+            Hoglin,
+            Horse,
+            Husk,
+            Illusioner,
+            IronGolem,
+            Item,
+            ItemFrame,
+            Fireball,
+            LeashKnot,
+            LightningBolt,
+            Llama,
+            LlamaSpit,
+            MagmaCube,
+            Minecart,
+            ChestMinecart,
+            CommandBlockMinecart,
+            FurnaceMinecart,
+            HopperMinecart,
+            SpawnerMinecart,
+            TntMinecart,
+            Mule,
+            Mooshroom,
+            Ocelot,
+            Painting,
+            Panda,
+            Parrot,
+            Phantom,
+            Pig,
+            Piglin,
+            Pillager,
+            PolarBear,
+            Tnt,
+            Pufferfish,
+            Rabbit,
+            Ravager,
+            Salmon,
+            Sheep,
+            Shulker,
+            ShulkerBullet,
+            Silverfish,
+            Skeleton,
+            SkeletonHorse,
+            Slime,
+            SmallFireball,
+            SnowGolem,
+            Snowball,
+            SpectralArrow,
+            Spider,
+            Squid,
+            Stray,
+            Strider,
+            Egg,
+            EnderPearl,
+            ExperienceBottle,
+            Potion,
+            Trident,
+            TraderLlama,
+            TropicalFish,
+            Turtle,
+            Vex,
+            Villager,
+            Vindicator,
+            WanderingTrader,
+            Witch,
+            Wither,
+            WitherSkeleton,
+            WitherSkull,
+            Wolf,
+            Zoglin,
+            Zombie,
+            ZombieHorse,
+            ZombieVillager,
+            ZombifiedPiglin,
+            Player,
+            FishingBobber,
+        ],
+        573..=734 => &[
+            AreaEffectCloud,
+            ArmorStand,
+            Arrow,
+            Bat,
+            Bee,
+            Blaze,
+            Boat,
+            Cat,
+            CaveSpider,
+            Chicken,
+            Cod,
+            Cow,
+            Creeper,
+            Donkey,
+            Dolphin,
+            DragonFireball,
+            Drowned,
+            ElderGuardian,
+            EndCrystal,
+            EnderDragon,
+            Enderman,
+            Endermite,
+            EvokerFangs,
+            Evoker,
+            ExperienceOrb,
+            EyeOfEnder,
+            FallingBlock,
+            FireworkRocket,
+            Fox,
+            Ghast,
+            Giant,
+            Guardian,
+            Horse,
+            Husk,
+            Illusioner,
+            Item,
+            ItemFrame,
+            Fireball,
+            LeashKnot,
+            Llama,
+            LlamaSpit,
+            MagmaCube,
+            Minecart,
+            ChestMinecart,
+            CommandBlockMinecart,
+            FurnaceMinecart,
+            HopperMinecart,
+            SpawnerMinecart,
+            TntMinecart,
+            Mule,
+            Mooshroom,
+            Ocelot,
+            Painting,
+            Panda,
+            Parrot,
+            Pig,
+            Pufferfish,
+            ZombifiedPiglin,
+            PolarBear,
+            Tnt,
+            Rabbit,
+            Salmon,
+            Sheep,
+            Shulker,
+            ShulkerBullet,
+            Silverfish,
+            Skeleton,
+            SkeletonHorse,
+            Slime,
+            SmallFireball,
+            SnowGolem,
+            Snowball,
+            SpectralArrow,
+            Spider,
+            Squid,
+            Stray,
+            TraderLlama,
+            TropicalFish,
+            Turtle,
+            Egg,
+            EnderPearl,
+            ExperienceBottle,
+            Potion,
+            Vex,
+            Villager,
+            IronGolem,
+            Vindicator,
+            Pillager,
+            WanderingTrader,
+            Witch,
+            Wither,
+            WitherSkeleton,
+            WitherSkull,
+            Wolf,
+            Zombie,
+            ZombieHorse,
+            ZombieVillager,
+            Phantom,
+            Ravager,
+            LightningBolt,
+            Player,
+            FishingBobber,
+            Trident,
+        ],
+        451..=572 => &[
+            AreaEffectCloud,
+            ArmorStand,
+            Arrow,
+            Bat,
+            Blaze,
+            Boat,
+            Cat,
+            CaveSpider,
+            Chicken,
+            Cod,
+            Cow,
+            Creeper,
+            Donkey,
+            Dolphin,
+            DragonFireball,
+            Drowned,
+            ElderGuardian,
+            EndCrystal,
+            EnderDragon,
+            Enderman,
+            Endermite,
+            EvokerFangs,
+            Evoker,
+            ExperienceOrb,
+            EyeOfEnder,
+            FallingBlock,
+            FireworkRocket,
+            Fox,
+            Ghast,
+            Giant,
+            Guardian,
+            Horse,
+            Husk,
+            Illusioner,
+            Item,
+            ItemFrame,
+            Fireball,
+            LeashKnot,
+            Llama,
+            LlamaSpit,
+            MagmaCube,
+            Minecart,
+            ChestMinecart,
+            CommandBlockMinecart,
+            FurnaceMinecart,
+            HopperMinecart,
+            SpawnerMinecart,
+            TntMinecart,
+            Mule,
+            Mooshroom,
+            Ocelot,
+            Painting,
+            Panda,
+            Parrot,
+            Pig,
+            Pufferfish,
+            ZombifiedPiglin,
+            PolarBear,
+            Tnt,
+            Rabbit,
+            Salmon,
+            Sheep,
+            Shulker,
+            ShulkerBullet,
+            Silverfish,
+            Skeleton,
+            SkeletonHorse,
+            Slime,
+            SmallFireball,
+            SnowGolem,
+            Snowball,
+            SpectralArrow,
+            Spider,
+            Squid,
+            Stray,
+            TraderLlama,
+            TropicalFish,
+            Turtle,
+            Egg,
+            EnderPearl,
+            ExperienceBottle,
+            Potion,
+            Vex,
+            Villager,
+            IronGolem,
+            Vindicator,
+            Pillager,
+            WanderingTrader,
+            Witch,
+            Wither,
+            WitherSkeleton,
+            WitherSkull,
+            Wolf,
+            Zombie,
+            ZombieHorse,
+            ZombieVillager,
+            Phantom,
+            Ravager,
+            LightningBolt,
+            Player,
+            FishingBobber,
+            Trident,
+        ],
+        404..=450 => &[
+            AreaEffectCloud,
+            ArmorStand,
+            Arrow,
+            Bat,
+            Blaze,
+            Boat,
+            CaveSpider,
+            Chicken,
+            Cod,
+            Cow,
+            Creeper,
+            Donkey,
+            Dolphin,
+            DragonFireball,
+            Drowned,
+            ElderGuardian,
+            EndCrystal,
+            EnderDragon,
+            Enderman,
+            Endermite,
+            EvokerFangs,
+            Evoker,
+            ExperienceOrb,
+            EyeOfEnder,
+            FallingBlock,
+            FireworkRocket,
+            Ghast,
+            Giant,
+            Guardian,
+            Horse,
+            Husk,
+            Illusioner,
+            Item,
+            ItemFrame,
+            Fireball,
+            LeashKnot,
+            Llama,
+            LlamaSpit,
+            MagmaCube,
+            Minecart,
+            ChestMinecart,
+            CommandBlockMinecart,
+            FurnaceMinecart,
+            HopperMinecart,
+            SpawnerMinecart,
+            TntMinecart,
+            Mule,
+            Mooshroom,
+            Ocelot,
+            Painting,
+            Parrot,
+            Pig,
+            Pufferfish,
+            ZombifiedPiglin,
+            PolarBear,
+            Tnt,
+            Rabbit,
+            Salmon,
+            Sheep,
+            Shulker,
+            ShulkerBullet,
+            Silverfish,
+            Skeleton,
+            SkeletonHorse,
+            Slime,
+            SmallFireball,
+            SnowGolem,
+            Snowball,
+            SpectralArrow,
+            Spider,
+            Squid,
+            Stray,
+            TropicalFish,
+            Turtle,
+            Egg,
+            EnderPearl,
+            ExperienceBottle,
+            Potion,
+            Vex,
+            Villager,
+            IronGolem,
+            Vindicator,
+            Witch,
+            Wither,
+            WitherSkeleton,
+            WitherSkull,
+            Wolf,
+            Zombie,
+            ZombieHorse,
+            ZombieVillager,
+            Phantom,
+            LightningBolt,
+            Player,
+            FishingBobber,
+            Trident,
+        ],
+        _ => &[],
+    }
+}
+
+impl EntityType {
+    /// Returns whether the player can hit or interact with the entity, which
+    /// excludes things like dropped items and most projectiles.
+    pub fn is_targetable(&self) -> bool {
+        !matches!(
+            self,
+            EntityType::AreaEffectCloud
+                | EntityType::Arrow
+                | EntityType::Egg
+                | EntityType::EnderPearl
+                | EntityType::EvokerFangs
+                | EntityType::ExperienceBottle
+                | EntityType::ExperienceOrb
+                | EntityType::EyeOfEnder
+                | EntityType::FallingBlock
+                | EntityType::FireworkRocket
+                | EntityType::FishingBobber
+                | EntityType::Item
+                | EntityType::LightningBolt
+                | EntityType::LlamaSpit
+                | EntityType::Marker
+                | EntityType::Potion
+                | EntityType::Snowball
+                | EntityType::SpectralArrow
+                | EntityType::Trident
+        )
+    }
+
+    /// Returns how far above the entity its passengers sit, like vanilla.
+    pub fn mounted_offset(&self) -> f64 {
+        match self {
+            EntityType::Boat => -0.1,
+            EntityType::Minecart
+            | EntityType::ChestMinecart
+            | EntityType::CommandBlockMinecart
+            | EntityType::FurnaceMinecart
+            | EntityType::HopperMinecart
+            | EntityType::SpawnerMinecart
+            | EntityType::TntMinecart => 0.0,
+            _ => self.size().1 * 0.75,
+        }
+    }
+
+    pub fn create_entity(&self, m: &mut ecs::Manager) -> ecs::Entity {
+        let entity = m.create_entity();
+        let (width, height) = self.size();
+        m.add_component_direct(entity, *self);
+        m.add_component_direct(entity, Position::new(0.0, 0.0, 0.0));
+        m.add_component_direct(entity, TargetPosition::new(0.0, 0.0, 0.0));
+        m.add_component_direct(entity, Rotation::new(0.0, 0.0));
+        m.add_component_direct(entity, TargetRotation::new(0.0, 0.0));
+        m.add_component_direct(entity, Velocity::new(0.0, 0.0, 0.0));
+        m.add_component_direct(
+            entity,
+            Bounds::new(Aabb3::new(
+                Point3::new(-width / 2.0, 0.0, -width / 2.0),
+                Point3::new(width / 2.0, height, width / 2.0),
+            )),
+        );
+        m.add_component_direct(entity, EntityModel::new());
+        m.add_component_direct(entity, Light::new());
+        entity
+    }
+}
+
+pub fn add_systems(m: &mut ecs::Manager) {
+    let sys = EntityRenderer::new(m);
+    m.add_render_system(sys);
+}
+
+/// A placeholder model showing the bounding box of an entity
+/// until it has a proper model of its own.
+#[derive(Default)]
+pub struct EntityModel {
+    model: Option<model::ModelKey>,
+}
+
+impl EntityModel {
+    pub fn new() -> EntityModel {
+        Default::default()
+    }
+}
+
+struct EntityRenderer {
+    filter: ecs::Filter,
+    entity_type: ecs::Key<EntityType>,
+    entity_model: ecs::Key<EntityModel>,
+    position: ecs::Key<Position>,
+    rotation: ecs::Key<Rotation>,
+    light: ecs::Key<Light>,
+}
+
+impl EntityRenderer {
+    fn new(m: &mut ecs::Manager) -> EntityRenderer {
+        let entity_type = m.get_key();
+        let entity_model = m.get_key();
+        let position = m.get_key();
+        let rotation = m.get_key();
+        let light = m.get_key();
+        EntityRenderer {
+            filter: ecs::Filter::new()
+                .with(entity_type)
+                .with(entity_model)
+                .with(position)
+                .with(rotation)
+                .with(light),
+            entity_type,
+            entity_model,
+            position,
+            rotation,
+            light,
+        }
+    }
+}
+
+impl ecs::System for EntityRenderer {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(
+        &mut self,
+        m: &mut ecs::Manager,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        use std::f32::consts::PI;
+        for e in m.find(&self.filter) {
+            let entity_model = m.get_component(e, self.entity_model).unwrap();
+            let position = m.get_component(e, self.position).unwrap();
+            let rotation = m.get_component(e, self.rotation).unwrap();
+            let light = m.get_component(e, self.light).unwrap();
+
+            if let Some(emodel) = entity_model.model {
+                let mdl = renderer.model.get_model(emodel).unwrap();
+
+                mdl.block_light = light.block_light;
+                mdl.sky_light = light.sky_light;
+
+                mdl.matrix[0] = Matrix4::from(Decomposed {
+                    scale: 1.0,
+                    rot: Quaternion::from_angle_y(Rad(PI + rotation.yaw as f32)),
+                    disp: Vector3::new(
+                        position.position.x as f32,
+                        -position.position.y as f32,
+                        position.position.z as f32,
+                    ),
+                });
+            }
+        }
+    }
+
+    fn entity_added(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let entity_type = *m.get_component(e, self.entity_type).unwrap();
+        let entity_model = m.get_component_mut(e, self.entity_model).unwrap();
+
+        let (width, height) = entity_type.size();
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+
+        let tex = render::Renderer::get_texture(renderer.get_textures_ref(), "steven:solid");
+        let mut verts = vec![];
+        model::append_box(
+            &mut verts,
+            -(width / 2.0) as f32,
+            0.0,
+            -(width / 2.0) as f32,
+            width as f32,
+            height as f32,
+            width as f32,
+            [
+                Some(tex.clone()),
+                Some(tex.clone()),
+                Some(tex.clone()),
+                Some(tex.clone()),
+                Some(tex.clone()),
+                Some(tex),
+            ],
+        );
+        // Give each type a stable tint so different entities can be
+        // told apart.
+        let hash = entity_type
+            .resource_name()
+            .bytes()
+            .fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32));
+        for vert in &mut verts {
+            vert.r = 96 + (hash & 0x7F) as u8;
+            vert.g = 96 + ((hash >> 8) & 0x7F) as u8;
+            vert.b = 96 + ((hash >> 16) & 0x7F) as u8;
+        }
+
+        entity_model.model = Some(renderer.model.create_model(model::DEFAULT, vec![verts]));
+    }
+
+    fn entity_removed(
+        &mut self,
+        m: &mut ecs::Manager,
+        e: ecs::Entity,
+        _: &mut world::World,
+        renderer: &mut render::Renderer,
+    ) {
+        let entity_model = m.get_component_mut(e, self.entity_model).unwrap();
+        if let Some(model) = entity_model.model.take() {
+            renderer.model.remove_model(model);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol;
+
+    #[test]
+    fn mob_ids() {
+        let cases = [
+            (47, 50, EntityType::Creeper),
+            (47, 90, EntityType::Pig),
+            (340, 50, EntityType::Creeper),
+            (340, 54, EntityType::Zombie),
+            (404, 10, EntityType::Creeper),
+            (404, 92, EntityType::Player),
+            (477, 11, EntityType::Creeper),
+            (578, 12, EntityType::Creeper),
+            (754, 12, EntityType::Creeper),
+            (754, 106, EntityType::Player),
+            (758, 13, EntityType::Creeper),
+            (758, 111, EntityType::Player),
+        ];
+        for (version, id, ty) in cases {
+            let ids = EntityIDMap::new(version);
+            assert_eq!(ids.by_vanilla_id(id), Some(ty), "{} in {}", id, version);
+        }
+    }
+
+    #[test]
+    fn object_ids() {
+        let ids = EntityIDMap::new(340);
+        assert_eq!(ids.object_by_vanilla_id(1, 0), Some(EntityType::Boat));
+        assert_eq!(ids.object_by_vanilla_id(10, 0), Some(EntityType::Minecart));
+        assert_eq!(
+            ids.object_by_vanilla_id(10, 1),
+            Some(EntityType::ChestMinecart)
+        );
+        assert_eq!(
+            ids.object_by_vanilla_id(10, 6),
+            Some(EntityType::CommandBlockMinecart)
+        );
+        // Minecarts got their own ids in 1.14
+        let ids = EntityIDMap::new(758);
+        assert_eq!(ids.object_by_vanilla_id(50, 0), Some(EntityType::Minecart));
+        assert_eq!(
+            ids.object_by_vanilla_id(51, 0),
+            Some(EntityType::ChestMinecart)
+        );
+    }
+
+    #[test]
+    fn tables_for_every_version() {
+        for &version in protocol::SUPPORTED_PROTOCOLS.iter() {
+            // Registering an id twice panics
+            let ids = EntityIDMap::new(version);
+            if version >= 404 {
+                let order = registry_order(version);
+                assert!(!order.is_empty(), "no ids for {}", version);
+                for (index, ty) in order.iter().enumerate() {
+                    assert_eq!(order.iter().position(|v| v == ty), Some(index));
+                }
+                assert_eq!(ids.by_vanilla_id(order.len()), None);
+            }
+        }
+    }
+}
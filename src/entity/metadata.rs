@@ -0,0 +1,154 @@
+use super::EntityType;
+use crate::format;
+use crate::protocol::LenPrefixed;
+use crate::types::{self, MetadataKey, PoseData, Value};
+
+// Flags shared by every entity, stored as a bitfield in the first
+// metadata entry on all protocol versions.
+const FLAG_ON_FIRE: i8 = 0x01;
+const FLAG_SNEAKING: i8 = 0x02;
+const FLAG_SPRINTING: i8 = 0x08;
+const FLAG_INVISIBLE: i8 = 0x20;
+
+const FLAGS: MetadataKey<i8> = MetadataKey::new(0);
+
+const CUSTOM_NAME_18: MetadataKey<String> = MetadataKey::new(2);
+const CUSTOM_NAME_VISIBLE_18: MetadataKey<i8> = MetadataKey::new(3);
+const CUSTOM_NAME_113: MetadataKey<LenPrefixed<bool, format::Component>> = MetadataKey::new(2);
+const CUSTOM_NAME_VISIBLE_19: MetadataKey<bool> = MetadataKey::new(3);
+const POSE_114: MetadataKey<PoseData> = MetadataKey::new(6);
+
+// Horses and llamas keep whether they are saddled in a bitfield
+const HORSE_SADDLED: i8 = 0x04;
+
+/// The latest metadata the server has sent for an entity.
+///
+/// The layout of the metadata changes between protocol versions, the
+/// accessors hide this and return sensible defaults when a value
+/// hasn't been sent.
+pub struct EntityMetadata {
+    metadata: types::Metadata,
+    protocol_version: i32,
+}
+
+impl EntityMetadata {
+    pub fn new(protocol_version: i32) -> EntityMetadata {
+        EntityMetadata {
+            metadata: types::Metadata::new(),
+            protocol_version,
+        }
+    }
+
+    /// Applies the values from an `EntityMetadata` or spawn packet.
+    pub fn update(&mut self, metadata: types::Metadata) {
+        self.metadata.merge(metadata);
+    }
+
+    pub fn get(&self) -> &types::Metadata {
+        &self.metadata
+    }
+
+    fn flag(&self, flag: i8) -> bool {
+        self.metadata.get(&FLAGS).map_or(false, |v| v & flag != 0)
+    }
+
+    pub fn is_on_fire(&self) -> bool {
+        self.flag(FLAG_ON_FIRE)
+    }
+
+    pub fn is_sneaking(&self) -> bool {
+        self.flag(FLAG_SNEAKING) || self.pose() == PoseData::Sneaking
+    }
+
+    pub fn is_sprinting(&self) -> bool {
+        self.flag(FLAG_SPRINTING)
+    }
+
+    pub fn is_invisible(&self) -> bool {
+        self.flag(FLAG_INVISIBLE)
+    }
+
+    pub fn custom_name(&self) -> Option<format::Component> {
+        let name = if self.protocol_version >= 404 {
+            return self
+                .metadata
+                .get(&CUSTOM_NAME_113)
+                .and_then(|v| v.data.first().cloned());
+        } else if self.protocol_version >= 47 {
+            self.metadata.get(&CUSTOM_NAME_18)
+        } else {
+            // 1.7 reuses the index of the name for other values on
+            // non-living entities so it can't be read safely here.
+            None
+        };
+        name.filter(|v| !v.is_empty()).map(|v| {
+            let mut name = format::Component::Text(format::TextComponent::new(v));
+            format::convert_legacy(&mut name);
+            name
+        })
+    }
+
+    pub fn is_custom_name_visible(&self) -> bool {
+        if self.protocol_version >= 74 {
+            self.metadata
+                .get(&CUSTOM_NAME_VISIBLE_19)
+                .map_or(false, |v| *v)
+        } else if self.protocol_version >= 47 {
+            self.metadata
+                .get(&CUSTOM_NAME_VISIBLE_18)
+                .map_or(false, |v| *v != 0)
+        } else {
+            false
+        }
+    }
+
+    /// Returns the pose of the entity. Before 1.14 this is derived from
+    /// the entity flags as poses didn't exist.
+    pub fn pose(&self) -> PoseData {
+        if self.protocol_version >= 477 {
+            if let Some(pose) = self.metadata.get(&POSE_114) {
+                return pose.clone();
+            }
+            PoseData::Standing
+        } else if self.flag(FLAG_SNEAKING) {
+            PoseData::Sneaking
+        } else {
+            PoseData::Standing
+        }
+    }
+
+    /// Returns whether a horse, llama, pig or strider wears a saddle. This
+    /// is only known from 1.9, when players started steering them.
+    pub fn is_saddled(&self, ty: EntityType) -> bool {
+        if self.protocol_version < 107 {
+            return false;
+        }
+        // The first value of animals comes after those every living
+        // entity has, which were added to over the versions
+        let first = match self.protocol_version {
+            v if v >= 755 => 17,
+            v if v >= 573 => 16,
+            v if v >= 477 => 15,
+            v if v >= 210 => 13,
+            _ => 12,
+        };
+        let value = match ty {
+            EntityType::Horse
+            | EntityType::Donkey
+            | EntityType::Mule
+            | EntityType::SkeletonHorse
+            | EntityType::ZombieHorse
+            | EntityType::Llama
+            | EntityType::TraderLlama
+            | EntityType::Pig => self.metadata.get_value(first),
+            // After its boost time and whether it is shaking
+            EntityType::Strider => self.metadata.get_value(first + 2),
+            _ => None,
+        };
+        match value {
+            Some(Value::Byte(flags)) => flags & HORSE_SADDLED != 0,
+            Some(Value::Bool(saddled)) => *saddled,
+            _ => false,
+        }
+    }
+}
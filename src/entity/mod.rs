@@ -1,12 +1,17 @@
 use steven_blocks as block;
 use steven_protocol::protocol::packet;
 pub mod block_entity;
+pub mod entity_type;
+pub mod metadata;
 pub mod player;
 
+pub use self::entity_type::{EntityIDMap, EntityType};
+
 use crate::ecs;
 use cgmath::Vector3;
 use collision::Aabb3;
 
+pub mod physics;
 mod systems;
 
 pub fn add_systems(m: &mut ecs::Manager) {
@@ -14,6 +19,7 @@ pub fn add_systems(m: &mut ecs::Manager) {
     m.add_system(sys);
 
     player::add_systems(m);
+    entity_type::add_systems(m);
 
     let sys = systems::ApplyVelocity::new(m);
     m.add_system(sys);
@@ -23,6 +29,8 @@ pub fn add_systems(m: &mut ecs::Manager) {
     m.add_render_system(sys);
     let sys = systems::LerpRotation::new(m);
     m.add_render_system(sys);
+    let sys = systems::FollowVehicle::new(m);
+    m.add_render_system(sys);
     let sys = systems::LightEntity::new(m);
     m.add_render_system(sys);
     let sys = systems::ApplyDigging::new(m);
@@ -57,6 +65,9 @@ impl Position {
 pub struct TargetPosition {
     pub position: Vector3<f64>,
     pub lerp_amount: f64,
+    /// How far the entity's velocity moved it since the server last said
+    /// where it is, which the server's next move already includes
+    pub predicted: Vector3<f64>,
 }
 
 impl TargetPosition {
@@ -64,6 +75,7 @@ impl TargetPosition {
         TargetPosition {
             position: Vector3::new(x, y, z),
             lerp_amount: 0.2,
+            predicted: Vector3::new(0.0, 0.0, 0.0),
         }
     }
 
@@ -133,6 +145,16 @@ impl Gravity {
     }
 }
 
+/// The vehicle an entity is riding, which carries it along.
+pub struct Riding {
+    pub vehicle: ecs::Entity,
+    /// Whether the entity is the first passenger, who controls the vehicle
+    pub controlling: bool,
+    /// Whether the local player moves the vehicle instead of the server,
+    /// like the player steering a boat or a saddled horse
+    pub steering: bool,
+}
+
 pub struct Bounds {
     pub bounds: Aabb3<f64>,
 }
@@ -179,6 +201,8 @@ pub struct DiggingState {
 pub struct Digging {
     pub last: Option<DiggingState>,
     pub current: Option<DiggingState>,
+    /// The tool in the player's hand
+    pub tool: Option<block::Tool>,
 }
 
 impl Digging {
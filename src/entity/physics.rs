@@ -0,0 +1,430 @@
+//! Movement of entities through the world, following the rules of vanilla
+//! closely enough that servers accept where we end up.
+
+use crate::shared::Position as BPosition;
+use crate::world;
+use cgmath::Vector3;
+use collision::{Aabb, Aabb3};
+use steven_blocks as block;
+use steven_blocks::material::Liquid;
+
+/// How high entities walk up without having to jump
+pub const STEP_HEIGHT: f64 = 0.6;
+// Distance moved back at a time when sneaking off an edge
+const EDGE_STEP: f64 = 0.05;
+
+/// The result of moving an entity.
+#[derive(Debug, PartialEq)]
+pub struct Movement {
+    /// How far the entity actually moved
+    pub offset: Vector3<f64>,
+    pub collided_x: bool,
+    pub collided_y: bool,
+    pub collided_z: bool,
+    /// Whether the entity landed on something while moving down
+    pub on_ground: bool,
+}
+
+impl Movement {
+    /// Moving without anything in the way, like spectators do.
+    pub fn unobstructed(offset: Vector3<f64>) -> Movement {
+        Movement {
+            offset,
+            collided_x: false,
+            collided_y: false,
+            collided_z: false,
+            on_ground: false,
+        }
+    }
+}
+
+/// Returns the collision boxes of the blocks overlapping `bounds`, placed
+/// in the world.
+pub fn collision_boxes(world: &world::World, bounds: &Aabb3<f64>) -> Vec<Aabb3<f64>> {
+    let mut boxes = vec![];
+    // Fences and walls stick out of the top of their block
+    for y in (bounds.min.y.floor() as i32 - 1)..=(bounds.max.y.floor() as i32) {
+        for z in (bounds.min.z.floor() as i32)..=(bounds.max.z.floor() as i32) {
+            for x in (bounds.min.x.floor() as i32)..=(bounds.max.x.floor() as i32) {
+                let block = world.get_block(BPosition::new(x, y, z));
+                if !block.get_material().collidable {
+                    continue;
+                }
+                for bb in block.get_collision_boxes() {
+                    let bb = bb.add_v(Vector3::new(x as f64, y as f64, z as f64));
+                    if overlaps(&bb, bounds) {
+                        boxes.push(bb);
+                    }
+                }
+            }
+        }
+    }
+    boxes
+}
+
+/// Moves `bounds` by up to `offset`, stopping at blocks. Entities on the
+/// ground walk up steps and sneaking ones won't fall off edges.
+pub fn move_bounds(
+    world: &world::World,
+    bounds: Aabb3<f64>,
+    offset: Vector3<f64>,
+    on_ground: bool,
+    sneaking: bool,
+) -> Movement {
+    move_bounds_stepping(world, bounds, offset, on_ground, sneaking, STEP_HEIGHT)
+}
+
+/// Moves `bounds` like `move_bounds`, walking up steps as high as
+/// `step_height` instead.
+pub fn move_bounds_stepping(
+    world: &world::World,
+    bounds: Aabb3<f64>,
+    offset: Vector3<f64>,
+    on_ground: bool,
+    sneaking: bool,
+    step_height: f64,
+) -> Movement {
+    let mut offset = offset;
+    if on_ground && sneaking {
+        offset = back_off_edges(world, &bounds, offset);
+    }
+
+    let boxes = collision_boxes(world, &stretch(&bounds, offset));
+    let mut moved = sweep(&boxes, bounds, offset);
+    let landed = moved.y != offset.y && offset.y < 0.0;
+
+    // Try again from a step higher and keep whichever goes further
+    if (on_ground || landed) && (moved.x != offset.x || moved.z != offset.z) {
+        let up = Vector3::new(offset.x, step_height, offset.z);
+        let reach = stretch(&stretch(&bounds, up), Vector3::new(0.0, -step_height, 0.0));
+        let boxes = collision_boxes(world, &reach);
+        let mut stepped = sweep(&boxes, bounds, up);
+        // Then back down on to the step
+        let raised = bounds.add_v(stepped);
+        stepped.y += boxes
+            .iter()
+            .fold(-step_height, |down, other| clip(&raised, other, 1, down));
+        if horizontal_distance(stepped) > horizontal_distance(moved) {
+            moved = stepped;
+        }
+    }
+
+    Movement {
+        offset: moved,
+        collided_x: moved.x != offset.x,
+        collided_y: moved.y != offset.y,
+        collided_z: moved.z != offset.z,
+        on_ground: moved.y != offset.y && offset.y < 0.0,
+    }
+}
+
+/// Moves `bounds` by up to `offset`, stopping at blocks without trying to
+/// step up them, and returns how far it moved.
+pub fn slide_bounds(
+    world: &world::World,
+    bounds: Aabb3<f64>,
+    offset: Vector3<f64>,
+) -> Vector3<f64> {
+    let boxes = collision_boxes(world, &stretch(&bounds, offset));
+    sweep(&boxes, bounds, offset)
+}
+
+/// Returns whether `bounds` overlaps anything solid.
+pub fn is_colliding(world: &world::World, bounds: &Aabb3<f64>) -> bool {
+    !collision_boxes(world, bounds).is_empty()
+}
+
+/// Returns whether `bounds` reaches below the surface of the liquid.
+pub fn is_in_liquid(world: &world::World, bounds: &Aabb3<f64>, liquid: Liquid) -> bool {
+    for y in (bounds.min.y.floor() as i32)..=(bounds.max.y.floor() as i32) {
+        for z in (bounds.min.z.floor() as i32)..=(bounds.max.z.floor() as i32) {
+            for x in (bounds.min.x.floor() as i32)..=(bounds.max.x.floor() as i32) {
+                let block = world.get_block(BPosition::new(x, y, z));
+                if block.get_material().liquid != Some(liquid) {
+                    continue;
+                }
+                let surface = (y + 1) as f64 - liquid_height(block);
+                if bounds.max.y >= surface {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Returns the height of the highest surface of the liquid that `bounds`
+/// overlaps.
+pub fn liquid_surface(world: &world::World, bounds: &Aabb3<f64>, liquid: Liquid) -> Option<f64> {
+    let mut surface = None;
+    for y in (bounds.min.y.floor() as i32)..=(bounds.max.y.floor() as i32) {
+        for z in (bounds.min.z.floor() as i32)..=(bounds.max.z.floor() as i32) {
+            for x in (bounds.min.x.floor() as i32)..=(bounds.max.x.floor() as i32) {
+                let block = world.get_block(BPosition::new(x, y, z));
+                if block.get_material().liquid != Some(liquid) {
+                    continue;
+                }
+                let height = (y + 1) as f64 - liquid_height(block);
+                if bounds.min.y <= height && surface.map_or(true, |v| height > v) {
+                    surface = Some(height);
+                }
+            }
+        }
+    }
+    surface
+}
+
+/// The part of the top of a liquid block that is empty, as a fraction of
+/// the block.
+fn liquid_height(block: block::Block) -> f64 {
+    match block {
+        block::Block::Water { level }
+        | block::Block::FlowingWater { level }
+        | block::Block::Lava { level }
+        | block::Block::FlowingLava { level } => {
+            // Falling liquids fill the whole block
+            let level = if level >= 8 { 0 } else { level };
+            (level + 1) as f64 / 9.0
+        }
+        _ => 0.0,
+    }
+}
+
+/// Returns the block under an entity at `position` that changes how it
+/// moves, such as ice or slime.
+pub fn block_below(world: &world::World, position: Vector3<f64>) -> block::Block {
+    world.get_block(BPosition::new(
+        position.x.floor() as i32,
+        (position.y - 0.5).floor() as i32,
+        position.z.floor() as i32,
+    ))
+}
+
+/// Returns the block at the feet of an entity at `position`.
+pub fn block_at(world: &world::World, position: Vector3<f64>) -> block::Block {
+    world.get_block(BPosition::new(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    ))
+}
+
+/// Returns how much an entity inside a block like a cobweb is slowed, as
+/// fractions of its horizontal and vertical speed.
+pub fn stuck_speed(world: &world::World, bounds: &Aabb3<f64>) -> Option<(f64, f64)> {
+    let bounds = shrink(bounds, Vector3::new(0.001, 0.001, 0.001));
+    for y in (bounds.min.y.floor() as i32)..=(bounds.max.y.floor() as i32) {
+        for z in (bounds.min.z.floor() as i32)..=(bounds.max.z.floor() as i32) {
+            for x in (bounds.min.x.floor() as i32)..=(bounds.max.x.floor() as i32) {
+                let material = world.get_block(BPosition::new(x, y, z)).get_material();
+                if material.stuck_speed.is_some() {
+                    return material.stuck_speed;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Shrinks the horizontal part of `offset` until moving by it leaves
+/// something under `bounds` to stand on.
+fn back_off_edges(world: &world::World, bounds: &Aabb3<f64>, offset: Vector3<f64>) -> Vector3<f64> {
+    let has_floor = |x: f64, z: f64| is_colliding(world, &bounds.add_v(Vector3::new(x, -1.0, z)));
+    let back_off = |v: f64| {
+        if v.abs() < EDGE_STEP {
+            0.0
+        } else {
+            v - EDGE_STEP * v.signum()
+        }
+    };
+    let mut offset = offset;
+    while offset.x != 0.0 && !has_floor(offset.x, 0.0) {
+        offset.x = back_off(offset.x);
+    }
+    while offset.z != 0.0 && !has_floor(0.0, offset.z) {
+        offset.z = back_off(offset.z);
+    }
+    while offset.x != 0.0 && offset.z != 0.0 && !has_floor(offset.x, offset.z) {
+        offset.x = back_off(offset.x);
+        offset.z = back_off(offset.z);
+    }
+    offset
+}
+
+/// Moves `bounds` along each axis in turn, vertically first, stopping at
+/// the first of `boxes` in the way, and returns how far it moved.
+fn sweep(boxes: &[Aabb3<f64>], bounds: Aabb3<f64>, offset: Vector3<f64>) -> Vector3<f64> {
+    let mut bounds = bounds;
+    let mut moved = Vector3::new(0.0, 0.0, 0.0);
+    for axis in [1, 0, 2] {
+        let distance = boxes.iter().fold(offset[axis], |distance, other| {
+            clip(&bounds, other, axis, distance)
+        });
+        let mut step = Vector3::new(0.0, 0.0, 0.0);
+        step[axis] = distance;
+        bounds = bounds.add_v(step);
+        moved[axis] = distance;
+    }
+    moved
+}
+
+/// Shortens `distance` along `axis` so that `bounds` stops when it reaches
+/// `other`.
+fn clip(bounds: &Aabb3<f64>, other: &Aabb3<f64>, axis: usize, distance: f64) -> f64 {
+    let in_line = (0..3)
+        .filter(|v| *v != axis)
+        .all(|v| other.max[v] > bounds.min[v] && other.min[v] < bounds.max[v]);
+    if !in_line {
+        distance
+    } else if distance > 0.0 && other.min[axis] >= bounds.max[axis] {
+        distance.min(other.min[axis] - bounds.max[axis])
+    } else if distance < 0.0 && other.max[axis] <= bounds.min[axis] {
+        distance.max(other.max[axis] - bounds.min[axis])
+    } else {
+        distance
+    }
+}
+
+fn overlaps(a: &Aabb3<f64>, b: &Aabb3<f64>) -> bool {
+    (0..3).all(|v| a.max[v] > b.min[v] && a.min[v] < b.max[v])
+}
+
+/// Grows `bounds` to cover everything it passes through when moved by
+/// `offset`.
+fn stretch(bounds: &Aabb3<f64>, offset: Vector3<f64>) -> Aabb3<f64> {
+    let mut stretched = *bounds;
+    for axis in 0..3 {
+        if offset[axis] < 0.0 {
+            stretched.min[axis] += offset[axis];
+        } else {
+            stretched.max[axis] += offset[axis];
+        }
+    }
+    stretched
+}
+
+/// Moves every side of `bounds` inwards by `amount`.
+pub fn shrink(bounds: &Aabb3<f64>, amount: Vector3<f64>) -> Aabb3<f64> {
+    Aabb3::new(bounds.min + amount, bounds.max - amount)
+}
+
+fn horizontal_distance(offset: Vector3<f64>) -> f64 {
+    offset.x * offset.x + offset.z * offset.z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Point3;
+
+    fn player_at(x: f64, y: f64, z: f64) -> Aabb3<f64> {
+        Aabb3::new(
+            Point3::new(x - 0.3, y, z - 0.3),
+            Point3::new(x + 0.3, y + 1.8, z + 0.3),
+        )
+    }
+
+    fn cube(x: f64, y: f64, z: f64) -> Aabb3<f64> {
+        Aabb3::new(Point3::new(x, y, z), Point3::new(x + 1.0, y + 1.0, z + 1.0))
+    }
+
+    #[test]
+    fn sweep_lands_on_blocks() {
+        let floor = [cube(0.0, 0.0, 0.0)];
+        let moved = sweep(
+            &floor,
+            player_at(0.5, 1.2, 0.5),
+            Vector3::new(0.0, -0.5, 0.0),
+        );
+        assert!((moved.y + 0.2).abs() < 1e-9);
+
+        // Walls stop horizontal movement but not falling past them
+        let wall = [cube(1.0, 0.0, 0.0)];
+        let moved = sweep(
+            &wall,
+            player_at(0.5, 0.0, 0.5),
+            Vector3::new(0.5, -0.1, 0.0),
+        );
+        assert!((moved.x - 0.2).abs() < 1e-9);
+        assert!((moved.y + 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sweep_stops_in_corners() {
+        // Two walls meeting stop both directions of a diagonal move
+        let walls = [cube(1.0, 0.0, 0.0), cube(0.0, 0.0, 1.0)];
+        let moved = sweep(
+            &walls,
+            player_at(0.5, 0.0, 0.5),
+            Vector3::new(0.5, 0.0, 0.5),
+        );
+        assert!((moved.x - 0.2).abs() < 1e-9);
+        assert!((moved.z - 0.2).abs() < 1e-9);
+
+        // A lone corner block is only hit by the axis moved last
+        let corner = [cube(1.0, 0.0, 1.0)];
+        let moved = sweep(
+            &corner,
+            player_at(0.5, 0.0, 0.5),
+            Vector3::new(0.5, 0.0, 0.5),
+        );
+        assert!((moved.x - 0.5).abs() < 1e-9);
+        assert!((moved.z - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sweep_hits_ceilings() {
+        let ceiling = [cube(0.0, 2.0, 0.0)];
+        let moved = sweep(
+            &ceiling,
+            player_at(0.5, 0.0, 0.5),
+            Vector3::new(0.1, 0.42, 0.0),
+        );
+        assert!((moved.y - 0.2).abs() < 1e-9);
+        assert!((moved.x - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn move_steps_up_slabs() {
+        let mut world = world::World::new(340);
+        let stone = block::Block::Stone {
+            variant: block::StoneVariant::Normal,
+        };
+        for x in 0..2 {
+            for z in 0..2 {
+                world.set_block(BPosition::new(x, 0, z), stone);
+            }
+        }
+        world.set_block(
+            BPosition::new(1, 1, 0),
+            block::Block::StoneSlab {
+                half: block::BlockHalf::Bottom,
+                variant: block::StoneSlabVariant::Stone,
+            },
+        );
+        world.set_block(BPosition::new(1, 1, 1), stone);
+
+        let moved = move_bounds(
+            &world,
+            player_at(0.5, 1.0, 0.5),
+            Vector3::new(0.5, -0.08, 0.0),
+            true,
+            false,
+        );
+        assert!((moved.offset.x - 0.5).abs() < 1e-9);
+        assert!((moved.offset.y - 0.5).abs() < 1e-9);
+        assert!(!moved.collided_x);
+
+        // Full blocks are too high to walk up
+        let moved = move_bounds(
+            &world,
+            player_at(0.5, 1.0, 1.5),
+            Vector3::new(0.5, -0.08, 0.0),
+            true,
+            false,
+        );
+        assert!((moved.offset.x - 0.2).abs() < 1e-9);
+        assert!(moved.collided_x);
+        assert!(moved.on_ground);
+    }
+}
@@ -1,21 +1,24 @@
+use super::metadata::EntityMetadata;
+use super::physics;
 use super::{
-    Bounds, Digging, GameInfo, Gravity, Light, MouseButtons, Position, Rotation, TargetPosition,
-    TargetRotation, Velocity,
+    Bounds, Digging, EntityType, GameInfo, Gravity, Light, MouseButtons, Position, Riding,
+    Rotation, TargetPosition, TargetRotation, Velocity,
 };
 use crate::ecs;
 use crate::format;
 use crate::render;
 use crate::render::model::{self, FormatState};
 use crate::settings::Stevenkey;
-use crate::shared::Position as BPosition;
 use crate::types::hash::FNVHash;
 use crate::types::Gamemode;
 use crate::world;
 use cgmath::{self, Decomposed, Matrix4, Point3, Quaternion, Rad, Rotation3, Vector3};
 use collision::{Aabb, Aabb3};
 use instant::Instant;
+use log::error;
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
+use steven_blocks::material::Liquid;
 
 pub fn add_systems(m: &mut ecs::Manager) {
     let sys = MovementHandler::new(m);
@@ -35,6 +38,7 @@ pub fn create_local(m: &mut ecs::Manager) -> ecs::Entity {
     m.add_component_direct(entity, Gamemode::Survival);
     m.add_component_direct(entity, Gravity::new());
     m.add_component_direct(entity, PlayerMovement::new());
+    m.add_component_direct(entity, PlayerActions::new());
     m.add_component_direct(
         entity,
         Bounds::new(Aabb3::new(
@@ -73,6 +77,8 @@ pub struct PlayerModel {
     skin_url: Option<String>,
     dirty: bool,
     name: String,
+    /// The text shown above the player, `None` hides it
+    name_tag: Option<format::Component>,
 
     has_head: bool,
     has_name_tag: bool,
@@ -87,11 +93,14 @@ pub struct PlayerModel {
 
 impl PlayerModel {
     pub fn new(name: &str, has_head: bool, has_name_tag: bool, first_person: bool) -> PlayerModel {
+        let mut name_tag = format::Component::Text(format::TextComponent::new(name));
+        format::convert_legacy(&mut name_tag);
         PlayerModel {
             model: None,
             skin_url: None,
             dirty: false,
             name: name.to_owned(),
+            name_tag: Some(name_tag),
 
             has_head,
             has_name_tag,
@@ -109,6 +118,43 @@ impl PlayerModel {
         self.skin_url = skin;
         self.dirty = true;
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Changes the text shown above the player, e.g. to add the prefix
+    /// of their team.
+    pub fn set_name_tag(&mut self, name_tag: Option<format::Component>) {
+        if self.name_tag != name_tag {
+            self.name_tag = name_tag;
+            self.dirty = true;
+        }
+    }
+}
+
+/// Returns the url of the skin in a base64 encoded `textures` property,
+/// as sent with players and stored in player heads. Only skins on
+/// Mojang's texture server are returned.
+pub fn skin_url(textures: &str) -> Option<String> {
+    let blob = match base64::decode(textures) {
+        Ok(val) => val,
+        Err(err) => {
+            error!("Failed to decode skin blob, {:?}", err);
+            return None;
+        }
+    };
+    let blob: serde_json::Value = match serde_json::from_slice(&blob) {
+        Ok(val) => val,
+        Err(err) => {
+            error!("Failed to parse skin blob, {:?}", err);
+            return None;
+        }
+    };
+    blob.pointer("/textures/SKIN/url")
+        .and_then(|v| v.as_str())
+        .filter(|v| v.starts_with("http://textures.minecraft.net/texture/"))
+        .map(|v| v.to_owned())
 }
 
 struct PlayerRenderer {
@@ -118,6 +164,7 @@ struct PlayerRenderer {
     rotation: ecs::Key<Rotation>,
     game_info: ecs::Key<GameInfo>,
     light: ecs::Key<Light>,
+    entity_metadata: ecs::Key<EntityMetadata>,
 }
 
 impl PlayerRenderer {
@@ -137,6 +184,7 @@ impl PlayerRenderer {
             rotation,
             game_info: m.get_key(),
             light,
+            entity_metadata: m.get_key(),
         }
     }
 }
@@ -176,6 +224,10 @@ impl ecs::System for PlayerRenderer {
             let position = m.get_component_mut(e, self.position).unwrap();
             let rotation = m.get_component_mut(e, self.rotation).unwrap();
             let light = m.get_component(e, self.light).unwrap();
+            // Metadata is only attached once the server has told us about the entity
+            let (sneaking, invisible) = m
+                .get_component(e, self.entity_metadata)
+                .map_or((false, false), |v| (v.is_sneaking(), v.is_invisible()));
 
             if player_model.dirty {
                 self.entity_removed(m, e, world, renderer);
@@ -188,6 +240,21 @@ impl ecs::System for PlayerRenderer {
                 mdl.block_light = light.block_light;
                 mdl.sky_light = light.sky_light;
 
+                // Invisible players are hidden completely, including their name tag
+                let alpha = if invisible { 0.0 } else { 1.0 };
+                for color in &mut mdl.colors {
+                    color[3] = alpha;
+                }
+                // Sneaking players have a faded name tag like vanilla
+                if sneaking && !invisible {
+                    mdl.colors[PlayerModelPart::NameTag as usize][3] = 0.5;
+                }
+                let (sneak_offset, sneak_lean) = if sneaking {
+                    (3.0 / 16.0, 0.5)
+                } else {
+                    (0.0, 0.0)
+                };
+
                 let offset = if player_model.first_person {
                     let ox = (rotation.yaw - PI64 / 2.0).cos() * 0.25;
                     let oz = -(rotation.yaw - PI64 / 2.0).sin() * 0.25;
@@ -214,7 +281,7 @@ impl ecs::System for PlayerRenderer {
                     mdl.matrix[PlayerModelPart::NameTag as usize] = Matrix4::from(Decomposed {
                         scale: 1.0,
                         rot: Quaternion::from_angle_y(Rad(renderer.camera.yaw as f32)),
-                        disp: offset + Vector3::new(0.0, (-24.0 / 16.0) - 0.6, 0.0),
+                        disp: offset + Vector3::new(0.0, (-24.0 / 16.0) - 0.6 + sneak_offset, 0.0),
                     });
                 }
 
@@ -222,13 +289,13 @@ impl ecs::System for PlayerRenderer {
                     * Matrix4::from(Decomposed {
                         scale: 1.0,
                         rot: Quaternion::from_angle_x(Rad(-rotation.pitch as f32)),
-                        disp: Vector3::new(0.0, -12.0 / 16.0 - 12.0 / 16.0, 0.0),
+                        disp: Vector3::new(0.0, -12.0 / 16.0 - 12.0 / 16.0 + sneak_offset, 0.0),
                     });
                 mdl.matrix[PlayerModelPart::Body as usize] = offset_matrix
                     * Matrix4::from(Decomposed {
                         scale: 1.0,
-                        rot: Quaternion::from_angle_x(Rad(0.0)),
-                        disp: Vector3::new(0.0, -12.0 / 16.0 - 6.0 / 16.0, 0.0),
+                        rot: Quaternion::from_angle_x(Rad(sneak_lean)),
+                        disp: Vector3::new(0.0, -12.0 / 16.0 - 6.0 / 16.0 + sneak_offset, 0.0),
                     });
 
                 let mut time = player_model.time;
@@ -268,7 +335,7 @@ impl ecs::System for PlayerRenderer {
                 mdl.matrix[PlayerModelPart::ArmRight as usize] = offset_matrix
                     * Matrix4::from_translation(Vector3::new(
                         6.0 / 16.0,
-                        -12.0 / 16.0 - 12.0 / 16.0,
+                        -12.0 / 16.0 - 12.0 / 16.0 + sneak_offset,
                         0.0,
                     ))
                     * Matrix4::from(Quaternion::from_angle_x(Rad(-(ang * 0.75) as f32)))
@@ -282,7 +349,7 @@ impl ecs::System for PlayerRenderer {
                 mdl.matrix[PlayerModelPart::ArmLeft as usize] = offset_matrix
                     * Matrix4::from_translation(Vector3::new(
                         -6.0 / 16.0,
-                        -12.0 / 16.0 - 12.0 / 16.0,
+                        -12.0 / 16.0 - 12.0 / 16.0 + sneak_offset,
                         0.0,
                     ))
                     * Matrix4::from(Quaternion::from_angle_x(Rad((ang * 0.75) as f32)))
@@ -468,7 +535,7 @@ impl ecs::System for PlayerRenderer {
         }
 
         let mut name_verts = vec![];
-        if player_model.has_name_tag {
+        if let (true, Some(name_tag)) = (player_model.has_name_tag, &player_model.name_tag) {
             let mut state = FormatState {
                 width: 0.0,
                 offset: 0.0,
@@ -477,25 +544,23 @@ impl ecs::System for PlayerRenderer {
                 y_scale: 0.16,
                 x_scale: 0.01,
             };
-            let mut name = format::Component::Text(format::TextComponent::new(&player_model.name));
-            format::convert_legacy(&mut name);
-            state.build(&name, format::Color::Black);
+            state.build(name_tag, format::Color::White);
             let width = state.width;
             // Center align text
             for vert in &mut state.text {
                 vert.x += width * 0.5;
-                vert.r = 64;
-                vert.g = 64;
-                vert.b = 64;
             }
-            name_verts.extend_from_slice(&state.text);
+            // The shadow is a darker copy of the text drawn behind it
+            name_verts.extend(state.text.iter().cloned().map(|mut vert| {
+                vert.r /= 4;
+                vert.g /= 4;
+                vert.b /= 4;
+                vert
+            }));
             for vert in &mut state.text {
                 vert.x -= 0.01;
                 vert.y -= 0.01;
                 vert.z -= 0.05;
-                vert.r = 255;
-                vert.g = 255;
-                vert.b = 255;
             }
             name_verts.extend_from_slice(&state.text);
         }
@@ -550,54 +615,95 @@ impl PlayerMovement {
         Default::default()
     }
 
-    fn calculate_movement(&self, player_yaw: f64) -> (f64, f64) {
-        use std::f64::consts::PI;
-        let mut forward = 0.0f64;
-        let mut yaw = player_yaw - (PI / 2.0);
-        if self.is_key_pressed(Stevenkey::Forward) || self.is_key_pressed(Stevenkey::Backward) {
-            forward = 1.0;
-            if self.is_key_pressed(Stevenkey::Backward) {
-                yaw += PI;
-            }
-        }
-        let change = if self.is_key_pressed(Stevenkey::Left) {
-            (PI / 2.0) / (forward.abs() + 1.0)
-        } else if self.is_key_pressed(Stevenkey::Right) {
-            -(PI / 2.0) / (forward.abs() + 1.0)
-        } else {
-            0.0
+    /// Returns how much the player is trying to move left and forward,
+    /// from -1.0 to 1.0.
+    fn input(&self) -> (f64, f64) {
+        let axis = |positive, negative| match (
+            self.is_key_pressed(positive),
+            self.is_key_pressed(negative),
+        ) {
+            (true, false) => 1.0,
+            (false, true) => -1.0,
+            _ => 0.0,
         };
-        if self.is_key_pressed(Stevenkey::Left) || self.is_key_pressed(Stevenkey::Right) {
-            forward = 1.0;
-        }
-        if self.is_key_pressed(Stevenkey::Backward) {
-            yaw -= change;
-        } else {
-            yaw += change;
-        }
+        (
+            axis(Stevenkey::Left, Stevenkey::Right),
+            axis(Stevenkey::Forward, Stevenkey::Backward),
+        )
+    }
 
-        (forward, yaw)
+    /// Returns the sideways and forward input sent to the server while
+    /// riding, scaled like vanilla.
+    pub fn steering(&self) -> (f32, f32) {
+        let (strafe, forward) = self.input();
+        ((strafe * 0.98) as f32, (forward * 0.98) as f32)
     }
 
-    fn is_key_pressed(&self, key: Stevenkey) -> bool {
+    pub fn is_key_pressed(&self, key: Stevenkey) -> bool {
         self.pressed_keys.get(&key).map_or(false, |v| *v)
     }
 }
 
+/// The flying speed given to players by default.
+pub const DEFAULT_FLY_SPEED: f32 = 0.05;
+/// The walking speed given to players by default.
+pub const DEFAULT_WALK_SPEED: f32 = 0.1;
+
+/// The actions and abilities of the local player that are shared with the
+/// server.
+pub struct PlayerActions {
+    pub sneaking: bool,
+    pub sprinting: bool,
+    /// Whether the server allows flying, which can be outside of creative
+    pub can_fly: bool,
+    pub fly_speed: f32,
+    pub walk_speed: f32,
+    // The state last sent to the server, to only send changes
+    pub sent_sneaking: bool,
+    pub sent_sprinting: bool,
+    pub sent_flying: bool,
+}
+
+impl Default for PlayerActions {
+    fn default() -> Self {
+        PlayerActions {
+            sneaking: false,
+            sprinting: false,
+            can_fly: false,
+            fly_speed: DEFAULT_FLY_SPEED,
+            walk_speed: DEFAULT_WALK_SPEED,
+            sent_sneaking: false,
+            sent_sprinting: false,
+            sent_flying: false,
+        }
+    }
+}
+
+impl PlayerActions {
+    pub fn new() -> PlayerActions {
+        Default::default()
+    }
+}
+
 struct MovementHandler {
     filter: ecs::Filter,
     movement: ecs::Key<PlayerMovement>,
+    actions: ecs::Key<PlayerActions>,
     gravity: ecs::Key<Gravity>,
     gamemode: ecs::Key<Gamemode>,
     position: ecs::Key<TargetPosition>,
     velocity: ecs::Key<Velocity>,
     bounds: ecs::Key<Bounds>,
     rotation: ecs::Key<Rotation>,
+    target_rotation: ecs::Key<TargetRotation>,
+    riding: ecs::Key<Riding>,
+    entity_type: ecs::Key<EntityType>,
 }
 
 impl MovementHandler {
     pub fn new(m: &mut ecs::Manager) -> MovementHandler {
         let movement = m.get_key();
+        let actions = m.get_key();
         let position = m.get_key();
         let velocity = m.get_key();
         let bounds = m.get_key();
@@ -605,19 +711,188 @@ impl MovementHandler {
         MovementHandler {
             filter: ecs::Filter::new()
                 .with(movement)
+                .with(actions)
                 .with(position)
                 .with(velocity)
                 .with(bounds)
                 .with(rotation),
             movement,
+            actions,
             gravity: m.get_key(),
             gamemode: m.get_key(),
             position,
             velocity,
             bounds,
             rotation,
+            target_rotation: m.get_key(),
+            riding: m.get_key(),
+            entity_type: m.get_key(),
         }
     }
+
+    /// Moves the boat the player is steering, which the client controlling
+    /// it simulates instead of the server. The boat's speed is kept in the
+    /// player's velocity.
+    fn steer_boat(
+        &self,
+        m: &mut ecs::Manager,
+        world: &world::World,
+        player: ecs::Entity,
+        boat: ecs::Entity,
+    ) {
+        // Boats turn about as fast as vanilla ones do once they get going
+        const TURN_SPEED: f64 = 9.0;
+        let movement = m.get_component(player, self.movement).unwrap();
+        let velocity = &mut m.get_component_mut(player, self.velocity).unwrap().velocity;
+        let (position, bounds) = match (
+            m.get_component_mut(boat, self.position),
+            m.get_component(boat, self.bounds),
+        ) {
+            (Some(position), Some(bounds)) => (position, bounds.bounds),
+            _ => return,
+        };
+        // The server's idea of where the boat goes would fight ours
+        if let Some(boat_velocity) = m.get_component_mut(boat, self.velocity) {
+            boat_velocity.velocity = Vector3::new(0.0, 0.0, 0.0);
+        }
+
+        let (left, forward) = movement.input();
+        let turn = (left * TURN_SPEED).to_radians();
+        if let Some(rotation) = m.get_component_mut(player, self.rotation) {
+            rotation.yaw += turn;
+        }
+        let mut yaw = 0.0;
+        if let Some(rotation) = m.get_component_mut(boat, self.target_rotation) {
+            rotation.yaw += turn;
+            yaw = rotation.yaw;
+        }
+        let thrust = if forward > 0.0 {
+            0.04
+        } else if forward < 0.0 {
+            -0.005
+        } else if left != 0.0 {
+            0.005
+        } else {
+            0.0
+        };
+        velocity.x += yaw.sin() * thrust;
+        velocity.z += yaw.cos() * thrust;
+
+        let bounds = bounds.add_v(position.position);
+        // Look a little under the boat so it stays on top of the water
+        let reach = Aabb3::new(bounds.min - Vector3::new(0.0, 0.1, 0.0), bounds.max);
+        let momentum = match physics::liquid_surface(world, &reach, Liquid::Water) {
+            Some(surface) => {
+                velocity.y = (surface - bounds.min.y).min(0.1);
+                0.9
+            }
+            None => {
+                velocity.y -= 0.04;
+                physics::block_below(world, position.position)
+                    .get_material()
+                    .slipperiness
+            }
+        };
+        velocity.x *= momentum;
+        velocity.z *= momentum;
+
+        let moved = physics::move_bounds(world, bounds, *velocity, false, false);
+        position.position += moved.offset;
+        if moved.collided_x {
+            velocity.x = 0.0;
+        }
+        if moved.collided_y {
+            velocity.y = 0.0;
+        }
+        if moved.collided_z {
+            velocity.z = 0.0;
+        }
+    }
+
+    /// Moves the saddled animal the player is riding, which walks where the
+    /// player looks. Like boats its speed is kept in the player's velocity.
+    fn steer_mount(
+        &self,
+        m: &mut ecs::Manager,
+        world: &world::World,
+        player: ecs::Entity,
+        mount: ecs::Entity,
+        ty: EntityType,
+    ) {
+        let movement = m.get_component(player, self.movement).unwrap();
+        let yaw = m.get_component(player, self.rotation).unwrap().yaw;
+        let velocity = &mut m.get_component_mut(player, self.velocity).unwrap().velocity;
+        let (position, bounds) = match (
+            m.get_component_mut(mount, self.position),
+            m.get_component(mount, self.bounds),
+        ) {
+            (Some(position), Some(bounds)) => (position, bounds.bounds),
+            _ => return,
+        };
+        if let Some(mount_velocity) = m.get_component_mut(mount, self.velocity) {
+            mount_velocity.velocity = Vector3::new(0.0, 0.0, 0.0);
+        }
+        if let Some(rotation) = m.get_component_mut(mount, self.target_rotation) {
+            rotation.yaw = yaw;
+        }
+
+        // Horses and llamas are ridden like walking, while pigs and striders
+        // just follow the item on the stick
+        let (strafe, forward, speed) = match ty {
+            EntityType::Pig => (0.0, 1.0, 0.25 * 0.225),
+            EntityType::Strider => (0.0, 1.0, 0.175 * 0.55),
+            _ => {
+                let (strafe, forward) = movement.input();
+                let forward = if forward < 0.0 {
+                    forward * 0.25
+                } else {
+                    forward
+                };
+                let speed = match ty {
+                    EntityType::Llama | EntityType::TraderLlama => 0.175,
+                    _ => 0.225,
+                };
+                (strafe * 0.5, forward, speed)
+            }
+        };
+
+        let bounds = bounds.add_v(position.position);
+        let below = Aabb3::new(
+            bounds.min - Vector3::new(0.0, 0.01, 0.0),
+            Point3::new(bounds.max.x, bounds.min.y, bounds.max.z),
+        );
+        let on_ground = physics::is_colliding(world, &below);
+        let slipperiness = if on_ground {
+            physics::block_below(world, position.position)
+                .get_material()
+                .slipperiness
+                * 0.91
+        } else {
+            0.91
+        };
+        let acceleration = if on_ground {
+            speed * 0.16277136 / (slipperiness * slipperiness * slipperiness)
+        } else {
+            0.02
+        };
+        accelerate(velocity, strafe, forward, yaw, acceleration);
+
+        // Mounts walk up whole blocks without jumping
+        let moved = physics::move_bounds_stepping(world, bounds, *velocity, on_ground, false, 1.0);
+        position.position += moved.offset;
+        if moved.collided_x {
+            velocity.x = 0.0;
+        }
+        if moved.collided_y {
+            velocity.y = 0.0;
+        }
+        if moved.collided_z {
+            velocity.z = 0.0;
+        }
+        velocity.y = (velocity.y - 0.08) * 0.98;
+        velocity.x *= slipperiness;
+        velocity.z *= slipperiness;
+    }
 }
 
 impl ecs::System for MovementHandler {
@@ -628,6 +903,7 @@ impl ecs::System for MovementHandler {
     fn update(&mut self, m: &mut ecs::Manager, world: &mut world::World, _: &mut render::Renderer) {
         for e in m.find(&self.filter) {
             let movement = m.get_component_mut(e, self.movement).unwrap();
+            let actions = m.get_component_mut(e, self.actions).unwrap();
             if movement.flying && m.get_component(e, self.gravity).is_some() {
                 m.remove_component(e, self.gravity);
             } else if !movement.flying && m.get_component(e, self.gravity).is_none() {
@@ -649,7 +925,7 @@ impl ecs::System for MovementHandler {
                             movement.want_to_fly = !movement.want_to_fly;
                             //info!("double jump! dt={:?} toggle want_to_fly = {}", dt, movement.want_to_fly);
 
-                            if gamemode.can_fly() && !gamemode.always_fly() {
+                            if (gamemode.can_fly() || actions.can_fly) && !gamemode.always_fly() {
                                 movement.flying = movement.want_to_fly;
                             }
                         }
@@ -660,6 +936,26 @@ impl ecs::System for MovementHandler {
                 movement.when_last_jump_pressed = None;
             }
 
+            // Sprinting only happens while moving forward and stops when sneaking
+            actions.sneaking = movement.is_key_pressed(Stevenkey::Sneak);
+            actions.sprinting = movement.is_key_pressed(Stevenkey::Sprint)
+                && movement.is_key_pressed(Stevenkey::Forward)
+                && !actions.sneaking;
+
+            // Passengers are carried along by their vehicle
+            if let Some(riding) = m.get_component(e, self.riding) {
+                let vehicle_type = m.get_component(riding.vehicle, self.entity_type).copied();
+                if riding.steering && vehicle_type == Some(EntityType::Boat) {
+                    self.steer_boat(m, world, e, riding.vehicle);
+                } else if let (true, Some(ty)) = (riding.steering, vehicle_type) {
+                    self.steer_mount(m, world, e, riding.vehicle, ty);
+                } else {
+                    m.get_component_mut(e, self.velocity).unwrap().velocity =
+                        Vector3::new(0.0, 0.0, 0.0);
+                }
+                continue;
+            }
+
             let position = m.get_component_mut(e, self.position).unwrap();
             let rotation = m.get_component(e, self.rotation).unwrap();
             let velocity = m.get_component_mut(e, self.velocity).unwrap();
@@ -667,203 +963,210 @@ impl ecs::System for MovementHandler {
 
             let player_bounds = m.get_component(e, self.bounds).unwrap().bounds;
 
-            let mut last_position = position.position;
-
-            if world.is_chunk_loaded(
+            if !world.is_chunk_loaded(
                 (position.position.x as i32) >> 4,
                 (position.position.z as i32) >> 4,
             ) {
-                let (forward, yaw) = movement.calculate_movement(rotation.yaw);
-                let mut speed = if movement.is_key_pressed(Stevenkey::Sprint) {
-                    0.2806
+                continue;
+            }
+
+            let on_ground = gravity.as_ref().map_or(false, |v| v.on_ground);
+            let bounds = player_bounds.add_v(position.position);
+            let in_water = !movement.flying
+                && physics::is_in_liquid(
+                    world,
+                    &physics::shrink(&bounds, Vector3::new(0.001, 0.4, 0.001)),
+                    Liquid::Water,
+                );
+            let in_lava = !movement.flying
+                && !in_water
+                && physics::is_in_liquid(
+                    world,
+                    &physics::shrink(&bounds, Vector3::new(0.1, 0.4, 0.1)),
+                    Liquid::Lava,
+                );
+            let climbing = !movement.flying
+                && physics::block_at(world, position.position)
+                    .get_material()
+                    .climbable;
+
+            // Sneaking slows walking down to a crawl, while flying it only descends
+            let input_scale = if actions.sneaking && !movement.flying {
+                0.3 * 0.98
+            } else {
+                0.98
+            };
+            let (strafe, forward) = movement.input();
+            let (strafe, forward) = (strafe * input_scale, forward * input_scale);
+            let yaw = rotation.yaw;
+            let jumping = movement.is_key_pressed(Stevenkey::Jump);
+
+            if movement.flying {
+                let vertical_speed = actions.fly_speed as f64 * 3.0;
+                if actions.sneaking {
+                    velocity.velocity.y -= vertical_speed;
+                }
+                if jumping {
+                    velocity.velocity.y += vertical_speed;
+                }
+            } else if in_water || in_lava {
+                if jumping {
+                    velocity.velocity.y += 0.04;
+                }
+            } else if on_ground && jumping {
+                velocity.velocity.y = 0.42;
+                if actions.sprinting {
+                    velocity.velocity.x += yaw.sin() * 0.2;
+                    velocity.velocity.z += yaw.cos() * 0.2;
+                }
+            }
+
+            let (acceleration, slipperiness) = if in_water {
+                (0.02, 0.8)
+            } else if in_lava {
+                (0.02, 0.5)
+            } else {
+                let slipperiness = if on_ground {
+                    let below = physics::block_below(world, position.position);
+                    below.get_material().slipperiness * 0.91
                 } else {
-                    0.21585
+                    0.91
                 };
-                if movement.flying {
-                    speed *= 2.5;
-
-                    if movement.is_key_pressed(Stevenkey::Jump) {
-                        position.position.y += speed;
-                    }
-                    if movement.is_key_pressed(Stevenkey::Sneak) {
-                        position.position.y -= speed;
-                    }
-                } else if gravity.as_ref().map_or(false, |v| v.on_ground) {
-                    if movement.is_key_pressed(Stevenkey::Jump) && velocity.velocity.y.abs() < 0.001
-                    {
-                        velocity.velocity.y = 0.42;
-                    }
+                let acceleration = if on_ground {
+                    let speed =
+                        actions.walk_speed as f64 * if actions.sprinting { 1.3 } else { 1.0 };
+                    speed * 0.16277136 / (slipperiness * slipperiness * slipperiness)
+                } else if movement.flying {
+                    actions.fly_speed as f64 * if actions.sprinting { 2.0 } else { 1.0 }
+                } else if actions.sprinting {
+                    0.026
                 } else {
-                    velocity.velocity.y -= 0.08;
-                    if velocity.velocity.y < -3.92 {
-                        velocity.velocity.y = -3.92;
-                    }
+                    0.02
+                };
+                (acceleration, slipperiness)
+            };
+            accelerate(&mut velocity.velocity, strafe, forward, yaw, acceleration);
+
+            let in_liquid = in_water || in_lava;
+            if climbing && !in_liquid {
+                velocity.velocity.x = velocity.velocity.x.clamp(-0.15, 0.15);
+                velocity.velocity.z = velocity.velocity.z.clamp(-0.15, 0.15);
+                velocity.velocity.y = velocity.velocity.y.max(-0.15);
+                // Sneaking holds on to the ladder
+                if actions.sneaking && velocity.velocity.y < 0.0 {
+                    velocity.velocity.y = 0.0;
                 }
-                velocity.velocity.y *= 0.98;
-                position.position.x += forward * yaw.cos() * speed;
-                position.position.z -= forward * yaw.sin() * speed;
-                position.position.y += velocity.velocity.y;
-
-                if !gamemode.noclip() {
-                    let mut target = position.position;
-                    position.position.y = last_position.y;
-                    position.position.z = last_position.z;
-
-                    // We handle each axis separately to allow for a sliding
-                    // effect when pushing up against walls.
-
-                    let (bounds, xhit) =
-                        check_collisions(world, position, &last_position, player_bounds);
-                    position.position.x = bounds.min.x + 0.3;
-                    last_position.x = position.position.x;
-
-                    position.position.z = target.z;
-                    let (bounds, zhit) =
-                        check_collisions(world, position, &last_position, player_bounds);
-                    position.position.z = bounds.min.z + 0.3;
-                    last_position.z = position.position.z;
-
-                    // Half block jumps
-                    // Minecraft lets you 'jump' up 0.5 blocks
-                    // for slabs and stairs (or smaller blocks).
-                    // Currently we implement this as a teleport to the
-                    // top of the block if we could move there
-                    // but this isn't smooth.
-                    if (xhit || zhit) && gravity.as_ref().map_or(false, |v| v.on_ground) {
-                        let mut ox = position.position.x;
-                        let mut oz = position.position.z;
-                        position.position.x = target.x;
-                        position.position.z = target.z;
-                        for offset in 1..9 {
-                            let mini = player_bounds.add_v(cgmath::Vector3::new(
-                                0.0,
-                                offset as f64 / 16.0,
-                                0.0,
-                            ));
-                            let (_, hit) = check_collisions(world, position, &last_position, mini);
-                            if !hit {
-                                target.y += offset as f64 / 16.0;
-                                ox = target.x;
-                                oz = target.z;
-                                break;
-                            }
-                        }
-                        position.position.x = ox;
-                        position.position.z = oz;
-                    }
-
-                    position.position.y = target.y;
-                    let (bounds, yhit) =
-                        check_collisions(world, position, &last_position, player_bounds);
-                    position.position.y = bounds.min.y;
-                    last_position.y = position.position.y;
-                    if yhit {
-                        velocity.velocity.y = 0.0;
-                    }
+            }
+            let flying_velocity = velocity.velocity.y;
+
+            let mut offset = velocity.velocity;
+            if let Some((horizontal, vertical)) = physics::stuck_speed(world, &bounds) {
+                offset.x *= horizontal;
+                offset.y *= vertical;
+                offset.z *= horizontal;
+                velocity.velocity = Vector3::new(0.0, 0.0, 0.0);
+            }
+            let moved = if gamemode.noclip() {
+                physics::Movement::unobstructed(offset)
+            } else {
+                physics::move_bounds(
+                    world,
+                    bounds,
+                    offset,
+                    on_ground,
+                    actions.sneaking && !movement.flying,
+                )
+            };
+            position.position += moved.offset;
 
-                    if let Some(gravity) = gravity {
-                        let ground =
-                            Aabb3::new(Point3::new(-0.3, -0.005, -0.3), Point3::new(0.3, 0.0, 0.3));
-                        let prev = gravity.on_ground;
-                        let (_, hit) = check_collisions(world, position, &last_position, ground);
-                        gravity.on_ground = hit;
-                        if !prev && gravity.on_ground {
-                            movement.did_touch_ground = true;
-                        }
-                    }
+            let below = physics::block_below(world, position.position).get_material();
+            if moved.collided_x {
+                velocity.velocity.x = 0.0;
+            }
+            if moved.collided_z {
+                velocity.velocity.z = 0.0;
+            }
+            if moved.collided_y {
+                if moved.on_ground && below.bouncy && !actions.sneaking {
+                    velocity.velocity.y = -velocity.velocity.y;
+                } else {
+                    velocity.velocity.y = 0.0;
                 }
             }
-        }
-    }
-}
-
-fn check_collisions(
-    world: &world::World,
-    position: &mut TargetPosition,
-    last_position: &Vector3<f64>,
-    bounds: Aabb3<f64>,
-) -> (Aabb3<f64>, bool) {
-    let mut bounds = bounds.add_v(position.position);
-
-    let dir = position.position - last_position;
-
-    let min_x = (bounds.min.x - 1.0) as i32;
-    let min_y = (bounds.min.y - 1.0) as i32;
-    let min_z = (bounds.min.z - 1.0) as i32;
-    let max_x = (bounds.max.x + 1.0) as i32;
-    let max_y = (bounds.max.y + 1.0) as i32;
-    let max_z = (bounds.max.z + 1.0) as i32;
-
-    let mut hit = false;
-    for y in min_y..max_y {
-        for z in min_z..max_z {
-            for x in min_x..max_x {
-                let block = world.get_block(BPosition::new(x, y, z));
-                if block.get_material().collidable {
-                    for bb in block.get_collision_boxes() {
-                        let bb = bb.add_v(cgmath::Vector3::new(x as f64, y as f64, z as f64));
-                        if bb.collides(&bounds) {
-                            bounds = bounds.move_out_of(bb, dir);
-                            hit = true;
-                        }
-                    }
+            if moved.on_ground && below.bouncy && !actions.sneaking {
+                let v = velocity.velocity.y.abs();
+                if v < 0.1 {
+                    velocity.velocity.x *= 0.4 + v * 0.2;
+                    velocity.velocity.z *= 0.4 + v * 0.2;
                 }
             }
-        }
-    }
-
-    (bounds, hit)
-}
-
-trait Collidable<T> {
-    fn collides(&self, t: &T) -> bool;
-    fn move_out_of(self, other: Self, dir: cgmath::Vector3<f64>) -> Self;
-}
-
-impl Collidable<Aabb3<f64>> for Aabb3<f64> {
-    fn collides(&self, t: &Aabb3<f64>) -> bool {
-        !(t.min.x >= self.max.x
-            || t.max.x <= self.min.x
-            || t.min.y >= self.max.y
-            || t.max.y <= self.min.y
-            || t.min.z >= self.max.z
-            || t.max.z <= self.min.z)
-    }
 
-    fn move_out_of(mut self, other: Self, dir: cgmath::Vector3<f64>) -> Self {
-        if dir.x != 0.0 {
-            if dir.x > 0.0 {
-                let ox = self.max.x;
-                self.max.x = other.min.x - 0.0001;
-                self.min.x += self.max.x - ox;
+            let collided_horizontally = moved.collided_x || moved.collided_z;
+            if in_liquid {
+                velocity.velocity *= slipperiness;
+                velocity.velocity.y -= 0.02;
+                // Climb out of the liquid when swimming into its edge
+                if collided_horizontally {
+                    let lifted = bounds.add_v(moved.offset).add_v(Vector3::new(
+                        velocity.velocity.x,
+                        velocity.velocity.y + 0.6 - moved.offset.y,
+                        velocity.velocity.z,
+                    ));
+                    if !physics::is_colliding(world, &lifted)
+                        && !physics::is_in_liquid(world, &lifted, Liquid::Water)
+                        && !physics::is_in_liquid(world, &lifted, Liquid::Lava)
+                    {
+                        velocity.velocity.y = 0.3;
+                    }
+                }
             } else {
-                let ox = self.min.x;
-                self.min.x = other.max.x + 0.0001;
-                self.max.x += self.min.x - ox;
+                if collided_horizontally && climbing {
+                    velocity.velocity.y = 0.2;
+                }
+                if movement.flying {
+                    velocity.velocity.y = flying_velocity * 0.6;
+                } else {
+                    velocity.velocity.y = (velocity.velocity.y - 0.08) * 0.98;
+                }
+                velocity.velocity.x *= slipperiness;
+                velocity.velocity.z *= slipperiness;
             }
-        }
-        if dir.y != 0.0 {
-            if dir.y > 0.0 {
-                let oy = self.max.y;
-                self.max.y = other.min.y - 0.0001;
-                self.min.y += self.max.y - oy;
+
+            // Soul sand slows down entities standing in it
+            let feet = physics::block_at(world, position.position).get_material();
+            let speed_factor = if feet.speed_factor != 1.0 {
+                feet.speed_factor
+            } else if moved.on_ground {
+                below.speed_factor
             } else {
-                let oy = self.min.y;
-                self.min.y = other.max.y + 0.0001;
-                self.max.y += self.min.y - oy;
+                1.0
+            };
+            velocity.velocity.x *= speed_factor;
+            velocity.velocity.z *= speed_factor;
+
+            if movement.flying && moved.on_ground && !gamemode.always_fly() {
+                movement.flying = false;
+                movement.want_to_fly = false;
             }
-        }
-        if dir.z != 0.0 {
-            if dir.z > 0.0 {
-                let oz = self.max.z;
-                self.max.z = other.min.z - 0.0001;
-                self.min.z += self.max.z - oz;
-            } else {
-                let oz = self.min.z;
-                self.min.z = other.max.z + 0.0001;
-                self.max.z += self.min.z - oz;
+            if let Some(gravity) = gravity {
+                if !gravity.on_ground && moved.on_ground {
+                    movement.did_touch_ground = true;
+                }
+                gravity.on_ground = moved.on_ground;
             }
         }
-        self
     }
 }
+
+/// Adds the player's input to their velocity, strafing to the left and
+/// moving forward relative to where they are facing.
+fn accelerate(velocity: &mut Vector3<f64>, strafe: f64, forward: f64, yaw: f64, acceleration: f64) {
+    let length = (strafe * strafe + forward * forward).sqrt();
+    if length < 0.01 {
+        return;
+    }
+    let scale = acceleration / length.max(1.0);
+    let (strafe, forward) = (strafe * scale, forward * scale);
+    velocity.x += forward * yaw.sin() + strafe * yaw.cos();
+    velocity.z += forward * yaw.cos() - strafe * yaw.sin();
+}
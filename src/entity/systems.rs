@@ -4,28 +4,32 @@ use std::sync::RwLock;
 use super::*;
 use crate::ecs;
 use crate::render;
+use crate::render::destroy::{Breaker, STAGES};
 use crate::shared::Position as BPos;
 use crate::world;
 use cgmath::InnerSpace;
-use log::debug;
+use collision::Aabb;
+use log::{debug, warn};
 use steven_protocol::protocol;
 use steven_protocol::protocol::Conn;
 
 pub struct ApplyVelocity {
     filter: ecs::Filter,
-    position: ecs::Key<Position>,
+    target_position: ecs::Key<TargetPosition>,
     velocity: ecs::Key<Velocity>,
+    bounds: ecs::Key<Bounds>,
     movement: ecs::Key<super::player::PlayerMovement>,
 }
 
 impl ApplyVelocity {
     pub fn new(m: &mut ecs::Manager) -> ApplyVelocity {
-        let position = m.get_key();
+        let target_position = m.get_key();
         let velocity = m.get_key();
         ApplyVelocity {
-            filter: ecs::Filter::new().with(position).with(velocity),
-            position,
+            filter: ecs::Filter::new().with(target_position).with(velocity),
+            target_position,
             velocity,
+            bounds: m.get_key(),
             movement: m.get_key(),
         }
     }
@@ -36,15 +40,42 @@ impl ecs::System for ApplyVelocity {
         &self.filter
     }
 
-    fn update(&mut self, m: &mut ecs::Manager, _: &mut world::World, _: &mut render::Renderer) {
+    fn update(&mut self, m: &mut ecs::Manager, world: &mut world::World, _: &mut render::Renderer) {
         for e in m.find(&self.filter) {
             if m.get_component(e, self.movement).is_some() {
                 // Player's handle their own physics
                 continue;
             }
-            let pos = m.get_component_mut(e, self.position).unwrap();
-            let vel = m.get_component(e, self.velocity).unwrap();
-            pos.position += vel.velocity;
+            let vel = m.get_component_mut(e, self.velocity).unwrap();
+            if vel.velocity.magnitude2() < 1e-8 {
+                continue;
+            }
+            // Only the target is moved, so the position eases towards it the
+            // same way it does for moves sent by the server
+            let target = m.get_component_mut(e, self.target_position).unwrap();
+            let offset = match m.get_component(e, self.bounds) {
+                Some(bounds) => {
+                    let bounds = bounds.bounds.add_v(target.position);
+                    let moved =
+                        super::physics::move_bounds(world, bounds, vel.velocity, false, false);
+                    if moved.collided_x {
+                        vel.velocity.x = 0.0;
+                    }
+                    if moved.collided_y {
+                        vel.velocity.y = 0.0;
+                    }
+                    if moved.collided_z {
+                        vel.velocity.z = 0.0;
+                    }
+                    moved.offset
+                }
+                None => vel.velocity,
+            };
+            target.position += offset;
+            target.predicted += offset;
+            // Slow down like entities moving through the air, so knockback
+            // sent by the server doesn't carry them away forever
+            vel.velocity *= 0.98;
         }
     }
 }
@@ -164,6 +195,68 @@ impl ecs::System for LerpPosition {
     }
 }
 
+/// Keeps passengers in their seat on the vehicle they are riding.
+pub struct FollowVehicle {
+    filter: ecs::Filter,
+    riding: ecs::Key<Riding>,
+    position: ecs::Key<Position>,
+    target_position: ecs::Key<TargetPosition>,
+    entity_type: ecs::Key<EntityType>,
+}
+
+impl FollowVehicle {
+    pub fn new(m: &mut ecs::Manager) -> FollowVehicle {
+        let riding = m.get_key();
+        let position = m.get_key();
+        FollowVehicle {
+            filter: ecs::Filter::new().with(riding).with(position),
+            riding,
+            position,
+            target_position: m.get_key(),
+            entity_type: m.get_key(),
+        }
+    }
+}
+
+impl ecs::System for FollowVehicle {
+    fn filter(&self) -> &ecs::Filter {
+        &self.filter
+    }
+
+    fn update(&mut self, m: &mut ecs::Manager, _: &mut world::World, _: &mut render::Renderer) {
+        // Players sit lower than other passengers
+        const PLAYER_OFFSET: f64 = -0.35;
+        for e in m.find(&self.filter) {
+            let vehicle = m.get_component(e, self.riding).unwrap().vehicle;
+            let vehicle_position = if m.is_entity_valid(vehicle) {
+                m.get_component(vehicle, self.position)
+            } else {
+                None
+            };
+            let vehicle_position = match vehicle_position {
+                Some(position) => position.position,
+                // The vehicle is gone, so the passenger is too
+                None => {
+                    m.remove_component(e, self.riding);
+                    continue;
+                }
+            };
+            let mut offset = m
+                .get_component(vehicle, self.entity_type)
+                .map_or(1.8 * 0.75, |v| v.mounted_offset());
+            // Only players lack an entity type
+            if m.get_component(e, self.entity_type).is_none() {
+                offset += PLAYER_OFFSET;
+            }
+            let seat = vehicle_position + Vector3::new(0.0, offset, 0.0);
+            m.get_component_mut(e, self.position).unwrap().position = seat;
+            if let Some(target_position) = m.get_component_mut(e, self.target_position) {
+                target_position.position = seat;
+            }
+        }
+    }
+}
+
 pub struct LerpRotation {
     filter: ecs::Filter,
     rotation: ecs::Key<Rotation>,
@@ -297,6 +390,9 @@ pub struct ApplyDigging {
     mouse_buttons: ecs::Key<MouseButtons>,
     digging: ecs::Key<Digging>,
     conn: ecs::Key<Arc<RwLock<Option<Conn>>>>,
+    game_info: ecs::Key<GameInfo>,
+    /// Time since a piece was last knocked off the block being dug
+    hit_timer: f64,
 }
 
 impl ApplyDigging {
@@ -308,6 +404,8 @@ impl ApplyDigging {
             mouse_buttons,
             digging,
             conn: m.get_key(),
+            game_info: m.get_key(),
+            hit_timer: 0.0,
         }
     }
 
@@ -319,33 +417,32 @@ impl ApplyDigging {
             n => panic!("Invalid dig state {}", n),
         }
 
-        match conn.protocol_version {
+        let result = match conn.protocol_version {
             // 1.7.10
-            5 => conn
-                .write_packet(packet::play::serverbound::PlayerDigging_u8_u8y {
-                    status: state as u8,
-                    x: target.position.x,
-                    y: target.position.y as u8,
-                    z: target.position.z,
-                    face: target.face.index() as u8,
-                })
-                .unwrap(),
+            5 => conn.write_packet(packet::play::serverbound::PlayerDigging_u8_u8y {
+                status: state as u8,
+                x: target.position.x,
+                y: target.position.y as u8,
+                z: target.position.z,
+                face: target.face.index() as u8,
+            }),
             // 1.8.9 or v15w39c
-            47 | 74 => conn
-                .write_packet(packet::play::serverbound::PlayerDigging_u8 {
-                    status: state as u8,
-                    location: target.position,
-                    face: target.face.index() as u8,
-                })
-                .unwrap(),
+            47 | 74 => conn.write_packet(packet::play::serverbound::PlayerDigging_u8 {
+                status: state as u8,
+                location: target.position,
+                face: target.face.index() as u8,
+            }),
             // 1.9+
-            _ => conn
-                .write_packet(packet::play::serverbound::PlayerDigging {
-                    status: protocol::VarInt(state),
-                    location: target.position,
-                    face: target.face.index() as u8,
-                })
-                .unwrap(),
+            _ => conn.write_packet(packet::play::serverbound::PlayerDigging {
+                status: protocol::VarInt(state),
+                location: target.position,
+                face: target.face.index() as u8,
+            }),
+        };
+        // Losing the connection is noticed, and reported, by the server
+        // when it next reads from it
+        if let Err(err) = result {
+            warn!("Failed to send dig packet: {}", err);
         }
     }
 
@@ -394,8 +491,20 @@ impl ApplyDigging {
         }
     }
 
-    fn is_finished(&self, state: &DiggingState) -> bool {
-        let mining_time = state.block.get_mining_time(&None);
+    /// Returns how far through breaking the block is, as one of the stages
+    /// its cracks are drawn with. Blocks that break instantly, or never
+    /// break, have no stages.
+    fn break_stage(&self, state: &DiggingState, tool: &Option<block::Tool>) -> Option<u8> {
+        let mining_time = state.block.get_mining_time(tool)?.as_secs_f64();
+        if mining_time <= 0.0 {
+            return None;
+        }
+        let progress = state.start.elapsed().as_secs_f64() / mining_time;
+        Some(((progress * f64::from(STAGES)) as u8).min(STAGES - 1))
+    }
+
+    fn is_finished(&self, state: &DiggingState, tool: &Option<block::Tool>) -> bool {
+        let mining_time = state.block.get_mining_time(tool);
         match mining_time {
             Some(mining_time) => {
                 let finish_time = state.start + mining_time;
@@ -421,6 +530,11 @@ impl ecs::System for ApplyDigging {
         use cgmath::EuclideanSpace;
 
         let world_entity = m.get_world();
+        let delta = m
+            .get_component(world_entity, self.game_info)
+            .unwrap()
+            .delta
+            .min(5.0);
         let mut conn = m
             .get_component(world_entity, self.conn)
             .unwrap()
@@ -450,6 +564,7 @@ impl ecs::System for ApplyDigging {
             digging.current = self.next_state(&digging.last, mouse_buttons, target);
 
             // Handle digging packets
+            let tool = digging.tool;
             match (&digging.last, &mut digging.current) {
                 // Start the new digging operation.
                 (None, Some(current)) => self.send_packet(conn, current, 0),
@@ -465,12 +580,53 @@ impl ecs::System for ApplyDigging {
                     self.send_packet(conn, current, 0);
                 }
                 // Finish the new digging operation.
-                (Some(_), Some(current)) if !self.is_finished(current) && !current.finished => {
+                (Some(_), Some(current))
+                    if !self.is_finished(current, &tool) && !current.finished =>
+                {
                     self.send_packet(conn, current, 2);
                     current.finished = true;
+                    // The server doesn't tell the player who broke the block
+                    renderer
+                        .particles
+                        .add(render::particles::Spawn::BreakBlock {
+                            block: current.block,
+                            position: current.position,
+                        });
                 }
                 _ => {}
             }
+
+            // Pieces fly off the block every tick while it is dug into
+            match &digging.current {
+                Some(current) if !current.finished => {
+                    self.hit_timer += delta;
+                    while self.hit_timer >= 3.0 {
+                        self.hit_timer -= 3.0;
+                        renderer.particles.add(render::particles::Spawn::HitBlock {
+                            block: current.block,
+                            position: current.position,
+                            face: current.face,
+                        });
+                    }
+                }
+                _ => self.hit_timer = 0.0,
+            }
+
+            // Cracks spread over the block as it gets closer to breaking
+            let stage = digging
+                .current
+                .as_ref()
+                .filter(|current| !current.finished)
+                .and_then(|current| Some((current, self.break_stage(current, &tool)?)));
+            match stage {
+                Some((current, stage)) => renderer.destroy.set(
+                    &mut renderer.model,
+                    Breaker::Local,
+                    current.position,
+                    stage,
+                ),
+                None => renderer.destroy.remove(&mut renderer.model, Breaker::Local),
+            }
         }
     }
 }
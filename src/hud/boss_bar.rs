@@ -0,0 +1,225 @@
+use crate::format::Component;
+use crate::protocol;
+use crate::ui;
+
+// Every pixel of gui/bars is drawn as 2x2 ui units
+const SCALE: f64 = 2.0;
+const BARS: &str = "gui/bars";
+const BAR_WIDTH: f64 = 182.0;
+const BAR_HEIGHT: f64 = 5.0;
+// Offsets from the top of the screen in pixels of the texture
+const FIRST_BAR_Y: f64 = 12.0;
+const TITLE_OFFSET: f64 = 10.0;
+const ROW_HEIGHT: f64 = 19.0;
+// Bars past this are not drawn so they don't cover the screen
+const MAX_VISIBLE: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossBarColor {
+    Pink,
+    Blue,
+    Red,
+    Green,
+    Yellow,
+    Purple,
+    White,
+}
+
+impl BossBarColor {
+    pub fn from_id(id: i32) -> BossBarColor {
+        match id {
+            1 => BossBarColor::Blue,
+            2 => BossBarColor::Red,
+            3 => BossBarColor::Green,
+            4 => BossBarColor::Yellow,
+            5 => BossBarColor::Purple,
+            6 => BossBarColor::White,
+            _ => BossBarColor::Pink,
+        }
+    }
+
+    /// The row of gui/bars the bar is drawn with
+    fn texture_v(self) -> f64 {
+        (self as i32 * 10) as f64
+    }
+}
+
+/// The number of notches drawn over the bar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossBarStyle {
+    Progress,
+    Notched6,
+    Notched10,
+    Notched12,
+    Notched20,
+}
+
+impl BossBarStyle {
+    pub fn from_id(id: i32) -> BossBarStyle {
+        match id {
+            1 => BossBarStyle::Notched6,
+            2 => BossBarStyle::Notched10,
+            3 => BossBarStyle::Notched12,
+            4 => BossBarStyle::Notched20,
+            _ => BossBarStyle::Progress,
+        }
+    }
+
+    /// The row of gui/bars holding the notches, if any.
+    fn texture_v(self) -> Option<f64> {
+        match self {
+            BossBarStyle::Progress => None,
+            style => Some(70.0 + (style as i32 * 10) as f64),
+        }
+    }
+}
+
+pub struct BossBar {
+    pub title: Component,
+    /// Between 0.0 and 1.0
+    pub health: f32,
+    pub color: BossBarColor,
+    pub style: BossBarStyle,
+    /// 0x1 - darken sky, 0x2 - dragon bar (plays end music), 0x4 - create fog
+    pub flags: u8,
+}
+
+/// The boss bars shown at the top of the screen, in the order they were
+/// added by the server.
+#[derive(Default)]
+pub struct BossBars {
+    bars: Vec<(protocol::UUID, BossBar)>,
+    generation: usize,
+}
+
+impl BossBars {
+    pub fn new() -> BossBars {
+        Default::default()
+    }
+
+    /// Adds a bar, replacing any existing bar with the same id in place.
+    pub fn add(&mut self, uuid: protocol::UUID, bar: BossBar) {
+        match self.bars.iter_mut().find(|v| v.0 == uuid) {
+            Some(existing) => existing.1 = bar,
+            None => self.bars.push((uuid, bar)),
+        }
+        self.changed();
+    }
+
+    pub fn remove(&mut self, uuid: &protocol::UUID) {
+        self.bars.retain(|v| v.0 != *uuid);
+        self.changed();
+    }
+
+    /// Changes an existing bar, updates for unknown bars are ignored.
+    pub fn update<F: FnOnce(&mut BossBar)>(&mut self, uuid: &protocol::UUID, f: F) {
+        if let Some((_, bar)) = self.bars.iter_mut().find(|v| v.0 == *uuid) {
+            f(bar);
+            self.changed();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bars.clear();
+        self.changed();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BossBar> {
+        self.bars.iter().map(|v| &v.1)
+    }
+
+    fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+pub(super) struct BossBarDisplay {
+    batch: Option<ui::BatchRef>,
+    elements: Vec<ui::ImageRef>,
+    titles: Vec<ui::FormattedRef>,
+    last_generation: Option<usize>,
+}
+
+impl BossBarDisplay {
+    pub fn new() -> BossBarDisplay {
+        BossBarDisplay {
+            batch: None,
+            elements: vec![],
+            titles: vec![],
+            last_generation: None,
+        }
+    }
+
+    pub fn tick(&mut self, bars: &BossBars, visible: bool, ui_container: &mut ui::Container) {
+        if !visible || bars.bars.is_empty() {
+            self.clear();
+            return;
+        }
+        if self.last_generation == Some(bars.generation) {
+            return;
+        }
+        self.clear();
+
+        let rows = bars.bars.len().min(MAX_VISIBLE);
+        let batch = ui::BatchBuilder::new()
+            .position(0.0, 0.0)
+            .size(
+                BAR_WIDTH * SCALE,
+                (FIRST_BAR_Y + rows as f64 * ROW_HEIGHT) * SCALE,
+            )
+            .alignment(ui::VAttach::Top, ui::HAttach::Center)
+            .create(ui_container);
+        {
+            let mut batch = batch.borrow_mut();
+            for (index, bar) in bars.iter().take(rows).enumerate() {
+                let y = FIRST_BAR_Y + index as f64 * ROW_HEIGHT;
+                let color_v = bar.color.texture_v();
+                let filled = (bar.health.clamp(0.0, 1.0) as f64 * (BAR_WIDTH + 1.0)).floor();
+                let filled = filled.min(BAR_WIDTH);
+
+                self.elements
+                    .push(bar_part(&mut batch, y, BAR_WIDTH, color_v));
+                if let Some(notch_v) = bar.style.texture_v() {
+                    self.elements
+                        .push(bar_part(&mut batch, y, BAR_WIDTH, notch_v));
+                }
+                if filled > 0.0 {
+                    self.elements
+                        .push(bar_part(&mut batch, y, filled, color_v + BAR_HEIGHT));
+                    if let Some(notch_v) = bar.style.texture_v() {
+                        self.elements
+                            .push(bar_part(&mut batch, y, filled, notch_v + BAR_HEIGHT));
+                    }
+                }
+
+                self.titles.push(
+                    ui::FormattedBuilder::new()
+                        .text(bar.title.clone())
+                        .position(0.0, (y - TITLE_OFFSET) * SCALE)
+                        .alignment(ui::VAttach::Top, ui::HAttach::Center)
+                        .attach(&mut *batch),
+                );
+            }
+        }
+        self.batch = Some(batch);
+        self.last_generation = Some(bars.generation);
+    }
+
+    fn clear(&mut self) {
+        self.batch = None;
+        self.elements.clear();
+        self.titles.clear();
+        self.last_generation = None;
+    }
+}
+
+/// Adds the left `width` pixels of the row of gui/bars at `v`.
+fn bar_part(batch: &mut ui::Batch, y: f64, width: f64, v: f64) -> ui::ImageRef {
+    ui::ImageBuilder::new()
+        .texture(BARS)
+        .position(0.0, y * SCALE)
+        .size(width * SCALE, BAR_HEIGHT * SCALE)
+        .texture_coords((0.0, v / 256.0, width / 256.0, BAR_HEIGHT / 256.0))
+        .alignment(ui::VAttach::Top, ui::HAttach::Left)
+        .attach(batch)
+}
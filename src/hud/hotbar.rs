@@ -0,0 +1,140 @@
+use crate::inventory::InventoryContext;
+use crate::model;
+use crate::render;
+use crate::ui;
+use std::collections::HashMap;
+
+// Every pixel of gui/widgets is drawn as 2x2 ui units
+const SCALE: f64 = 2.0;
+const WIDGETS: &str = "gui/widgets";
+const WIDTH: f64 = 182.0;
+const HEIGHT: f64 = 22.0;
+const SLOT_SPACING: f64 = 20.0;
+
+const ICON_SIZE: f64 = 16.0;
+
+/// The nine hotbar slots along the bottom of the screen, showing the icon
+/// and size of each stack.
+pub(super) struct Hotbar {
+    elements: Option<Elements>,
+    last_slots: Vec<Slot>,
+    // The textures of item models, each only looked up once
+    textures: HashMap<String, Option<String>>,
+}
+
+#[derive(Clone, PartialEq)]
+struct Slot {
+    texture: Option<String>,
+    count: isize,
+}
+
+struct Elements {
+    background: ui::ImageRef,
+    selection: ui::ImageRef,
+    icons: Vec<ui::ImageRef>,
+    counts: Vec<ui::TextRef>,
+}
+
+impl Hotbar {
+    pub fn new() -> Hotbar {
+        Hotbar {
+            elements: None,
+            last_slots: vec![],
+            textures: HashMap::new(),
+        }
+    }
+
+    pub fn tick(
+        &mut self,
+        inventory: &InventoryContext,
+        visible: bool,
+        ui_container: &mut ui::Container,
+        renderer: &render::Renderer,
+    ) {
+        if !visible {
+            self.elements = None;
+            self.last_slots.clear();
+            return;
+        }
+        let elements = self.elements.get_or_insert_with(|| {
+            let background = ui::ImageBuilder::new()
+                .texture(WIDGETS)
+                .position(0.0, 0.0)
+                .size(WIDTH * SCALE, HEIGHT * SCALE)
+                .texture_coords((0.0, 0.0, WIDTH / 256.0, HEIGHT / 256.0))
+                .alignment(ui::VAttach::Bottom, ui::HAttach::Center)
+                .create(ui_container);
+            // The selection is a pixel larger than a slot on every side
+            let selection = ui::ImageBuilder::new()
+                .texture(WIDGETS)
+                .position(0.0, -SCALE)
+                .size(24.0 * SCALE, 24.0 * SCALE)
+                .texture_coords((0.0, 22.0 / 256.0, 24.0 / 256.0, 24.0 / 256.0))
+                .alignment(ui::VAttach::Bottom, ui::HAttach::Left)
+                .draw_index(1)
+                .attach(&mut *background.borrow_mut());
+            Elements {
+                background,
+                selection,
+                icons: vec![],
+                counts: vec![],
+            }
+        });
+
+        let x = (inventory.hotbar_index as f64 * SLOT_SPACING - 1.0) * SCALE;
+        elements.selection.borrow_mut().x = x;
+
+        let textures = &mut self.textures;
+        let slots: Vec<Slot> = inventory
+            .hotbar()
+            .map(|item| Slot {
+                texture: item
+                    .and_then(|v| inventory.items.model_name(v))
+                    .and_then(|name| {
+                        textures
+                            .entry(name)
+                            .or_insert_with_key(|name| {
+                                model::item_texture(&renderer.resources.read().unwrap(), name)
+                            })
+                            .clone()
+                    }),
+                count: item.map_or(0, |v| v.count),
+            })
+            .collect();
+        if slots == self.last_slots {
+            return;
+        }
+        elements.icons.clear();
+        elements.counts.clear();
+        let mut background = elements.background.borrow_mut();
+        for (index, slot) in slots.iter().enumerate() {
+            // Icons are inset from the edges of the slot
+            if let Some(texture) = &slot.texture {
+                let left = 3.0 + index as f64 * SLOT_SPACING;
+                elements.icons.push(
+                    ui::ImageBuilder::new()
+                        .texture(texture)
+                        .position(left * SCALE, 3.0 * SCALE)
+                        .size(ICON_SIZE * SCALE, ICON_SIZE * SCALE)
+                        .alignment(ui::VAttach::Bottom, ui::HAttach::Left)
+                        .draw_index(2)
+                        .create(&mut *background),
+                );
+            }
+            if slot.count <= 1 {
+                continue;
+            }
+            // Counts sit in the bottom right corner of the slot
+            let right = WIDTH - (index as f64 + 1.0) * SLOT_SPACING + 1.0;
+            elements.counts.push(
+                ui::TextBuilder::new()
+                    .text(format!("{}", slot.count))
+                    .position(right * SCALE, 2.0 * SCALE)
+                    .alignment(ui::VAttach::Bottom, ui::HAttach::Right)
+                    .draw_index(3)
+                    .create(&mut *background),
+            );
+        }
+        self.last_slots = slots;
+    }
+}
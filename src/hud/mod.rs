@@ -0,0 +1,96 @@
+//! The overlay drawn on top of the world while playing.
+
+mod boss_bar;
+mod hotbar;
+mod player_list;
+mod sidebar;
+mod status;
+mod title;
+
+pub use self::boss_bar::{BossBar, BossBarColor, BossBarStyle, BossBars};
+pub use self::status::PlayerStats;
+pub use self::title::Titles;
+
+use crate::render;
+use crate::server;
+use crate::settings::Stevenkey;
+use crate::types::Gamemode;
+use crate::ui;
+
+pub struct Hud {
+    boss_bars: boss_bar::BossBarDisplay,
+    hotbar: hotbar::Hotbar,
+    player_list: player_list::PlayerList,
+    /// Whether the player list key is held
+    show_player_list: bool,
+    sidebar: sidebar::Sidebar,
+    status: status::StatusBars,
+    titles: title::TitleDisplay,
+}
+
+impl Default for Hud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hud {
+    pub fn new() -> Hud {
+        Hud {
+            boss_bars: boss_bar::BossBarDisplay::new(),
+            hotbar: hotbar::Hotbar::new(),
+            player_list: player_list::PlayerList::new(),
+            show_player_list: false,
+            sidebar: sidebar::Sidebar::new(),
+            status: status::StatusBars::new(),
+            titles: title::TitleDisplay::new(),
+        }
+    }
+
+    pub fn key_press(&mut self, down: bool, key: &Stevenkey) {
+        if *key == Stevenkey::PlayerList {
+            self.show_player_list = down;
+        }
+    }
+
+    pub fn tick(
+        &mut self,
+        server: &server::Server,
+        ui_container: &mut ui::Container,
+        renderer: &render::Renderer,
+    ) {
+        let gamemode = server.gamemode();
+        let survival = matches!(
+            gamemode,
+            Some(Gamemode::Survival) | Some(Gamemode::Adventure)
+        );
+        self.hotbar.tick(
+            &server.inventory_context,
+            server.is_connected() && gamemode.map_or(false, |v| v != Gamemode::Spectator),
+            ui_container,
+            renderer,
+        );
+        self.status.tick(
+            &server.player_stats,
+            server.is_connected() && survival,
+            ui_container,
+        );
+        self.boss_bars
+            .tick(&server.boss_bars, server.is_connected(), ui_container);
+        self.titles
+            .tick(&server.titles, server.is_connected(), ui_container);
+        self.sidebar.tick(
+            &server.scoreboard,
+            server.username(),
+            server.is_connected(),
+            ui_container,
+            renderer,
+        );
+        self.player_list.tick(
+            server,
+            server.is_connected() && self.show_player_list,
+            ui_container,
+            renderer,
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::format::{self, Component, TextComponent};
+use crate::render;
+use crate::server;
+use crate::types::Gamemode;
+use crate::ui;
+
+// Every pixel of gui/icons is drawn as 2x2 ui units
+const SCALE: f64 = 2.0;
+const ROW_HEIGHT: f64 = 9.0 * SCALE;
+const HEAD_SIZE: f64 = 8.0 * SCALE;
+const PING_WIDTH: f64 = 10.0 * SCALE;
+const PING_HEIGHT: f64 = 8.0 * SCALE;
+const COLUMN_SPACING: f64 = 5.0 * SCALE;
+const PADDING: f64 = SCALE;
+const TOP: f64 = 10.0 * SCALE;
+const MAX_ROWS: usize = 20;
+const MAX_ENTRIES: usize = 80;
+const HEADER_WIDTH: f64 = 600.0;
+
+#[derive(PartialEq)]
+struct Entry {
+    name: Component,
+    ping_level: u8,
+    skin_url: Option<String>,
+}
+
+/// Everything that changes what the list looks like, to only rebuild it
+/// when something changes.
+#[derive(PartialEq)]
+struct State {
+    header: Option<Component>,
+    footer: Option<Component>,
+    entries: Vec<Entry>,
+}
+
+impl State {
+    fn new(server: &server::Server) -> State {
+        let scoreboard = &server.scoreboard;
+        let mut players: Vec<&server::PlayerInfo> = server.players().collect();
+        // Spectators go last, then players are grouped by team
+        players.sort_by_cached_key(|v| {
+            (
+                v.gamemode == Gamemode::Spectator,
+                scoreboard.team_of(&v.name).map(|v| v.name.clone()),
+                v.name.clone(),
+            )
+        });
+        let entries = players
+            .into_iter()
+            .take(MAX_ENTRIES)
+            .map(|info| {
+                let name = info.display_name.clone().unwrap_or_else(|| {
+                    let mut name = Component::Text(TextComponent::new(&info.name));
+                    format::convert_legacy(&mut name);
+                    scoreboard.decorate_name(&info.name, name)
+                });
+                let name = if info.gamemode == Gamemode::Spectator {
+                    let mut spectator = TextComponent::new("");
+                    spectator.modifier.color = Some(format::Color::Gray);
+                    spectator.modifier.italic = Some(true);
+                    spectator.modifier.extra = Some(vec![name]);
+                    Component::Text(spectator)
+                } else {
+                    name
+                };
+                Entry {
+                    name,
+                    ping_level: ping_level(info.ping),
+                    skin_url: info.skin_url.clone(),
+                }
+            })
+            .collect();
+        State {
+            header: server.player_list_header.clone(),
+            footer: server.player_list_footer.clone(),
+            entries,
+        }
+    }
+}
+
+/// The row of the latency icons in gui/icons, like vanilla.
+fn ping_level(ping: i32) -> u8 {
+    match ping {
+        i32::MIN..=-1 => 5,
+        0..=149 => 0,
+        150..=299 => 1,
+        300..=599 => 2,
+        600..=999 => 3,
+        _ => 4,
+    }
+}
+
+/// The players on the server, shown while the player list key is held.
+pub(super) struct PlayerList {
+    batch: Option<ui::BatchRef>,
+    images: Vec<ui::ImageRef>,
+    texts: Vec<ui::FormattedRef>,
+    /// Skins loaded for the heads, released when the list is hidden
+    skins: Vec<String>,
+    last_state: Option<State>,
+}
+
+impl PlayerList {
+    pub fn new() -> PlayerList {
+        PlayerList {
+            batch: None,
+            images: vec![],
+            texts: vec![],
+            skins: vec![],
+            last_state: None,
+        }
+    }
+
+    pub fn tick(
+        &mut self,
+        server: &server::Server,
+        visible: bool,
+        ui_container: &mut ui::Container,
+        renderer: &render::Renderer,
+    ) {
+        if !visible {
+            self.clear(renderer);
+            return;
+        }
+        let state = State::new(server);
+        if self.last_state.as_ref() == Some(&state) {
+            return;
+        }
+        self.clear(renderer);
+
+        let size = |text: &Component, max_width: f64| {
+            ui::Formatted::compute_size(renderer, text, max_width)
+        };
+        let count = state.entries.len();
+        let columns = ((count + MAX_ROWS - 1) / MAX_ROWS).max(1);
+        let rows = (count + columns - 1) / columns;
+        let name_width = state
+            .entries
+            .iter()
+            .map(|v| size(&v.name, -1.0).0)
+            .fold(0.0, f64::max);
+        let entry_width = HEAD_SIZE + PADDING + name_width + PING_WIDTH + PADDING;
+        let list_width =
+            columns as f64 * entry_width + columns.saturating_sub(1) as f64 * COLUMN_SPACING;
+
+        let header_size = state.header.as_ref().map(|v| size(v, HEADER_WIDTH));
+        let footer_size = state.footer.as_ref().map(|v| size(v, HEADER_WIDTH));
+        let header_height = header_size.map_or(0.0, |v| v.1);
+        let footer_height = footer_size.map_or(0.0, |v| v.1);
+        let width = [header_size, footer_size]
+            .iter()
+            .flatten()
+            .map(|v| v.0)
+            .fold(list_width, f64::max)
+            + PADDING * 2.0;
+        let height = header_height + rows as f64 * ROW_HEIGHT + footer_height + PADDING * 2.0;
+
+        let batch = ui::BatchBuilder::new()
+            .position(0.0, TOP)
+            .size(width, height)
+            .alignment(ui::VAttach::Top, ui::HAttach::Center)
+            .create(ui_container);
+        {
+            let mut batch = batch.borrow_mut();
+            self.images.push(
+                ui::ImageBuilder::new()
+                    .texture("steven:solid")
+                    .position(0.0, 0.0)
+                    .size(width, height)
+                    .colour((0, 0, 0, 128))
+                    .attach(&mut *batch),
+            );
+            if let Some(header) = state.header.clone() {
+                self.texts.push(
+                    ui::FormattedBuilder::new()
+                        .text(header)
+                        .position(0.0, PADDING)
+                        .max_width(HEADER_WIDTH)
+                        .alignment(ui::VAttach::Top, ui::HAttach::Center)
+                        .attach(&mut *batch),
+                );
+            }
+            if let Some(footer) = state.footer.clone() {
+                self.texts.push(
+                    ui::FormattedBuilder::new()
+                        .text(footer)
+                        .position(0.0, PADDING)
+                        .max_width(HEADER_WIDTH)
+                        .alignment(ui::VAttach::Bottom, ui::HAttach::Center)
+                        .attach(&mut *batch),
+                );
+            }
+
+            let left = (width - list_width) / 2.0;
+            let top = PADDING + header_height;
+            for (index, entry) in state.entries.iter().enumerate() {
+                let x = left + (index / rows) as f64 * (entry_width + COLUMN_SPACING);
+                let y = top + (index % rows) as f64 * ROW_HEIGHT;
+                self.images.push(
+                    ui::ImageBuilder::new()
+                        .texture("steven:solid")
+                        .position(x, y)
+                        .size(entry_width, ROW_HEIGHT - SCALE)
+                        .colour((255, 255, 255, 32))
+                        .attach(&mut *batch),
+                );
+                self.add_head(&mut batch, entry.skin_url.as_deref(), x, y, renderer);
+                self.texts.push(
+                    ui::FormattedBuilder::new()
+                        .text(entry.name.clone())
+                        .position(x + HEAD_SIZE + PADDING, y - SCALE)
+                        .attach(&mut *batch),
+                );
+                let v = 176.0 + entry.ping_level as f64 * 8.0;
+                self.images.push(
+                    ui::ImageBuilder::new()
+                        .texture("gui/icons")
+                        .position(x + entry_width - PING_WIDTH - PADDING, y)
+                        .size(PING_WIDTH, PING_HEIGHT)
+                        .texture_coords((0.0, v / 256.0, 10.0 / 256.0, 8.0 / 256.0))
+                        .attach(&mut *batch),
+                );
+            }
+        }
+        self.batch = Some(batch);
+        self.last_state = Some(state);
+    }
+
+    /// Adds the face and hat layers of the player's skin.
+    fn add_head(
+        &mut self,
+        batch: &mut ui::Batch,
+        skin_url: Option<&str>,
+        x: f64,
+        y: f64,
+        renderer: &render::Renderer,
+    ) {
+        let texture = match skin_url {
+            Some(url) => {
+                self.skins.push(url.to_owned());
+                renderer.get_skin(renderer.get_textures_ref(), url).name
+            }
+            None => "entity/steve".to_owned(),
+        };
+        for u in &[8.0, 40.0] {
+            self.images.push(
+                ui::ImageBuilder::new()
+                    .texture(&texture)
+                    .position(x, y)
+                    .size(HEAD_SIZE, HEAD_SIZE)
+                    .texture_coords((u / 64.0, 8.0 / 64.0, 8.0 / 64.0, 8.0 / 64.0))
+                    .attach(batch),
+            );
+        }
+    }
+
+    fn clear(&mut self, renderer: &render::Renderer) {
+        self.batch = None;
+        self.images.clear();
+        self.texts.clear();
+        self.last_state = None;
+        if !self.skins.is_empty() {
+            let textures = renderer.get_textures_ref().read().unwrap();
+            for url in self.skins.drain(..) {
+                textures.release_skin(&url);
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::format::{self, Component, TextComponent};
+use crate::render;
+use crate::scoreboard::Scoreboard;
+use crate::ui;
+
+const LINE_HEIGHT: f64 = 18.0;
+const PADDING: f64 = 4.0;
+// Space between the longest name and its score
+const SCORE_SPACING: f64 = 12.0;
+
+/// The objective shown in the sidebar slot on the right of the screen.
+pub(super) struct Sidebar {
+    batch: Option<ui::BatchRef>,
+    backgrounds: Vec<ui::ImageRef>,
+    texts: Vec<ui::FormattedRef>,
+    last_generation: Option<usize>,
+}
+
+impl Sidebar {
+    pub fn new() -> Sidebar {
+        Sidebar {
+            batch: None,
+            backgrounds: vec![],
+            texts: vec![],
+            last_generation: None,
+        }
+    }
+
+    pub fn tick(
+        &mut self,
+        scoreboard: &Scoreboard,
+        player: &str,
+        visible: bool,
+        ui_container: &mut ui::Container,
+        renderer: &render::Renderer,
+    ) {
+        if !visible {
+            self.clear();
+            return;
+        }
+        if self.last_generation == Some(scoreboard.generation()) {
+            return;
+        }
+        self.clear();
+        self.last_generation = Some(scoreboard.generation());
+
+        let (objective, entries) = match scoreboard.sidebar(player) {
+            Some(sidebar) => sidebar,
+            None => return,
+        };
+        let lines: Vec<(Component, Component)> = entries
+            .into_iter()
+            .map(|(entry, score)| {
+                let mut name = Component::Text(TextComponent::new(entry));
+                format::convert_legacy(&mut name);
+                let mut score = TextComponent::new(&format!("{}", score));
+                score.modifier.color = Some(format::Color::Red);
+                (
+                    scoreboard.decorate_name(entry, name),
+                    Component::Text(score),
+                )
+            })
+            .collect();
+
+        let size = |text: &Component| ui::Formatted::compute_size(renderer, text, -1.0).0;
+        let width = lines
+            .iter()
+            .map(|(name, score)| size(name) + SCORE_SPACING + size(score))
+            .fold(size(&objective.display_name), f64::max)
+            + PADDING * 2.0;
+        let height = (lines.len() + 1) as f64 * LINE_HEIGHT;
+
+        let batch = ui::BatchBuilder::new()
+            .position(PADDING, 0.0)
+            .size(width, height)
+            .alignment(ui::VAttach::Middle, ui::HAttach::Right)
+            .create(ui_container);
+        {
+            let mut batch = batch.borrow_mut();
+            // The title has a darker background than the entries
+            self.backgrounds
+                .push(background(&mut batch, 0.0, width, LINE_HEIGHT, 102));
+            self.backgrounds.push(background(
+                &mut batch,
+                LINE_HEIGHT,
+                width,
+                height - LINE_HEIGHT,
+                76,
+            ));
+            self.texts.push(
+                ui::FormattedBuilder::new()
+                    .text(objective.display_name.clone())
+                    .position(0.0, 0.0)
+                    .alignment(ui::VAttach::Top, ui::HAttach::Center)
+                    .attach(&mut *batch),
+            );
+            for (index, (name, score)) in lines.into_iter().enumerate() {
+                let y = (index + 1) as f64 * LINE_HEIGHT;
+                self.texts.push(
+                    ui::FormattedBuilder::new()
+                        .text(name)
+                        .position(PADDING, y)
+                        .alignment(ui::VAttach::Top, ui::HAttach::Left)
+                        .attach(&mut *batch),
+                );
+                self.texts.push(
+                    ui::FormattedBuilder::new()
+                        .text(score)
+                        .position(PADDING, y)
+                        .alignment(ui::VAttach::Top, ui::HAttach::Right)
+                        .attach(&mut *batch),
+                );
+            }
+        }
+        self.batch = Some(batch);
+    }
+
+    fn clear(&mut self) {
+        self.batch = None;
+        self.backgrounds.clear();
+        self.texts.clear();
+        self.last_generation = None;
+    }
+}
+
+fn background(batch: &mut ui::Batch, y: f64, width: f64, height: f64, alpha: u8) -> ui::ImageRef {
+    ui::ImageBuilder::new()
+        .texture("steven:solid")
+        .position(0.0, y)
+        .size(width, height)
+        .colour((0, 0, 0, alpha))
+        .alignment(ui::VAttach::Top, ui::HAttach::Left)
+        .attach(batch)
+}
@@ -0,0 +1,262 @@
+use crate::format;
+use crate::ui;
+
+// Every pixel of gui/icons is drawn as 2x2 ui units
+const SCALE: f64 = 2.0;
+const ICONS: &str = "gui/icons";
+const BAR_WIDTH: f64 = 182.0;
+const ICON_SIZE: f64 = 9.0;
+const ICON_SPACING: f64 = 8.0;
+// Offsets from the bottom of the screen in pixels of the texture
+const EXPERIENCE_Y: f64 = 24.0;
+const HEARTS_Y: f64 = 30.0;
+
+/// The health, food and experience of the local player as last sent by
+/// the server.
+pub struct PlayerStats {
+    pub health: f32,
+    pub max_health: f32,
+    pub food: i32,
+    pub saturation: f32,
+    pub armor: i32,
+    pub experience_bar: f32,
+    pub level: i32,
+    pub total_experience: i32,
+    pub hardcore: bool,
+    pub dead: bool,
+    pub death_message: Option<format::Component>,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        PlayerStats {
+            health: 20.0,
+            max_health: 20.0,
+            food: 20,
+            saturation: 5.0,
+            armor: 0,
+            experience_bar: 0.0,
+            level: 0,
+            total_experience: 0,
+            hardcore: false,
+            dead: false,
+            death_message: None,
+        }
+    }
+}
+
+impl PlayerStats {
+    pub fn new() -> PlayerStats {
+        Default::default()
+    }
+
+    pub fn set_health(&mut self, health: f32, food: i32, saturation: f32) {
+        self.health = health;
+        self.food = food;
+        self.saturation = saturation;
+        if health <= 0.0 {
+            self.dead = true;
+        } else {
+            self.respawn();
+        }
+    }
+
+    pub fn set_experience(&mut self, experience_bar: f32, level: i32, total_experience: i32) {
+        self.experience_bar = experience_bar;
+        self.level = level;
+        self.total_experience = total_experience;
+    }
+
+    pub fn die(&mut self, message: Option<format::Component>) {
+        self.dead = true;
+        if message.is_some() {
+            self.death_message = message;
+        }
+    }
+
+    pub fn respawn(&mut self) {
+        self.dead = false;
+        self.death_message = None;
+    }
+}
+
+/// The parts of the stats which change what is drawn, to avoid
+/// rebuilding the bars every frame.
+#[derive(PartialEq)]
+struct State {
+    health: i32,
+    max_health: i32,
+    food: i32,
+    armor: i32,
+    experience: i32,
+    level: i32,
+    hardcore: bool,
+}
+
+impl State {
+    fn new(stats: &PlayerStats) -> State {
+        State {
+            health: (stats.health.max(0.0).ceil()) as i32,
+            max_health: (stats.max_health.max(1.0).ceil()) as i32,
+            food: stats.food.clamp(0, 20),
+            armor: stats.armor.clamp(0, 20),
+            experience: (stats.experience_bar.clamp(0.0, 1.0) as f64 * (BAR_WIDTH + 1.0)) as i32,
+            level: stats.level,
+            hardcore: stats.hardcore,
+        }
+    }
+}
+
+/// Hearts, hunger, armor and the experience bar above the hotbar.
+pub(super) struct StatusBars {
+    batch: Option<ui::BatchRef>,
+    elements: Vec<ui::ImageRef>,
+    level: Option<ui::TextRef>,
+    last_state: Option<State>,
+}
+
+impl Default for StatusBars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusBars {
+    pub fn new() -> StatusBars {
+        StatusBars {
+            batch: None,
+            elements: vec![],
+            level: None,
+            last_state: None,
+        }
+    }
+
+    /// Draws the bars, `visible` is false in creative and spectator mode
+    /// or when not in game.
+    pub fn tick(&mut self, stats: &PlayerStats, visible: bool, ui_container: &mut ui::Container) {
+        if !visible {
+            self.clear();
+            return;
+        }
+        let state = State::new(stats);
+        if self.last_state.as_ref() == Some(&state) {
+            return;
+        }
+        self.clear();
+
+        let batch = ui::BatchBuilder::new()
+            .position(0.0, 0.0)
+            .size(BAR_WIDTH * SCALE, 100.0 * SCALE)
+            .alignment(ui::VAttach::Bottom, ui::HAttach::Center)
+            .create(ui_container);
+        {
+            let mut batch = batch.borrow_mut();
+            let mut elements = vec![];
+
+            // Experience
+            elements.push(icon(
+                &mut batch,
+                0.0,
+                EXPERIENCE_Y,
+                (0.0, 64.0, BAR_WIDTH, 5.0),
+            ));
+            if state.experience > 0 {
+                let width = state.experience.min(BAR_WIDTH as i32) as f64;
+                elements.push(icon(&mut batch, 0.0, EXPERIENCE_Y, (0.0, 69.0, width, 5.0)));
+            }
+            if state.level > 0 {
+                self.level = Some(
+                    ui::TextBuilder::new()
+                        .text(format!("{}", state.level))
+                        .position(0.0, (EXPERIENCE_Y + 2.0) * SCALE)
+                        .colour((128, 255, 32, 255))
+                        .alignment(ui::VAttach::Bottom, ui::HAttach::Center)
+                        .attach(&mut *batch),
+                );
+            }
+
+            // Health, rows of hearts move closer together the more there are
+            let hearts = (state.max_health + 1) / 2;
+            let rows = (hearts + 9) / 10;
+            let row_height = (10 - (rows - 2)).max(3) as f64;
+            let heart_v = if state.hardcore { 45.0 } else { 0.0 };
+            for i in 0..hearts {
+                let x = (i % 10) as f64 * ICON_SPACING;
+                let y = HEARTS_Y + (i / 10) as f64 * row_height;
+                elements.push(icon(
+                    &mut batch,
+                    x,
+                    y,
+                    (16.0, heart_v, ICON_SIZE, ICON_SIZE),
+                ));
+                if let Some(u) = fill_u(state.health, i, 52.0, 61.0) {
+                    elements.push(icon(&mut batch, x, y, (u, heart_v, ICON_SIZE, ICON_SIZE)));
+                }
+            }
+
+            // Armor sits above the hearts
+            if state.armor > 0 {
+                let y = HEARTS_Y + (rows - 1) as f64 * row_height + 10.0;
+                for i in 0..10 {
+                    let u = fill_u(state.armor, i, 34.0, 25.0).unwrap_or(16.0);
+                    let x = i as f64 * ICON_SPACING;
+                    elements.push(icon(&mut batch, x, y, (u, 9.0, ICON_SIZE, ICON_SIZE)));
+                }
+            }
+
+            // Food, filled from the right
+            for i in 0..10 {
+                let x = BAR_WIDTH - ICON_SIZE - i as f64 * ICON_SPACING;
+                elements.push(icon(
+                    &mut batch,
+                    x,
+                    HEARTS_Y,
+                    (16.0, 27.0, ICON_SIZE, ICON_SIZE),
+                ));
+                if let Some(u) = fill_u(state.food, i, 52.0, 61.0) {
+                    elements.push(icon(
+                        &mut batch,
+                        x,
+                        HEARTS_Y,
+                        (u, 27.0, ICON_SIZE, ICON_SIZE),
+                    ));
+                }
+            }
+            self.elements = elements;
+        }
+        self.batch = Some(batch);
+        self.last_state = Some(state);
+    }
+
+    fn clear(&mut self) {
+        self.batch = None;
+        self.elements.clear();
+        self.level = None;
+        self.last_state = None;
+    }
+}
+
+/// Returns the texture offset for icon `index` of a bar where every icon
+/// holds two points, or `None` if the icon is empty.
+fn fill_u(value: i32, index: i32, full: f64, half: f64) -> Option<f64> {
+    if value >= index * 2 + 2 {
+        Some(full)
+    } else if value == index * 2 + 1 {
+        Some(half)
+    } else {
+        None
+    }
+}
+
+/// Adds part of gui/icons at the position, given in pixels of the
+/// texture from the bottom left of the bars.
+fn icon(batch: &mut ui::Batch, x: f64, y: f64, uv: (f64, f64, f64, f64)) -> ui::ImageRef {
+    let (u, v, w, h) = uv;
+    ui::ImageBuilder::new()
+        .texture(ICONS)
+        .position(x * SCALE, y * SCALE)
+        .size(w * SCALE, h * SCALE)
+        .texture_coords((u / 256.0, v / 256.0, w / 256.0, h / 256.0))
+        .alignment(ui::VAttach::Bottom, ui::HAttach::Left)
+        .attach(batch)
+}
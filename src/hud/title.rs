@@ -0,0 +1,211 @@
+use crate::format::Component;
+use crate::ui;
+use instant::Instant;
+
+// Timings are sent in ticks
+const TICKS_PER_SECOND: f64 = 20.0;
+const DEFAULT_FADE_IN: i32 = 10;
+const DEFAULT_STAY: i32 = 70;
+const DEFAULT_FADE_OUT: i32 = 20;
+// Seconds the action bar stays on screen, fading out over the last one
+const ACTION_BAR_TIME: f64 = 3.0;
+const ACTION_BAR_FADE: f64 = 1.0;
+
+/// The title, subtitle and action bar messages sent by the server.
+pub struct Titles {
+    title: Option<Component>,
+    subtitle: Option<Component>,
+    fade_in: i32,
+    stay: i32,
+    fade_out: i32,
+    shown_at: Option<Instant>,
+    action_bar: Option<(Component, Instant)>,
+    generation: usize,
+}
+
+impl Default for Titles {
+    fn default() -> Self {
+        Titles {
+            title: None,
+            subtitle: None,
+            fade_in: DEFAULT_FADE_IN,
+            stay: DEFAULT_STAY,
+            fade_out: DEFAULT_FADE_OUT,
+            shown_at: None,
+            action_bar: None,
+            generation: 0,
+        }
+    }
+}
+
+impl Titles {
+    pub fn new() -> Titles {
+        Default::default()
+    }
+
+    /// Shows a title, along with the last subtitle sent.
+    pub fn set_title(&mut self, title: Component) {
+        self.title = Some(title);
+        self.shown_at = Some(Instant::now());
+        self.changed();
+    }
+
+    /// Sets the subtitle, which is only shown once a title is.
+    pub fn set_subtitle(&mut self, subtitle: Component) {
+        self.subtitle = Some(subtitle);
+        self.changed();
+    }
+
+    /// Changes the timings of titles, restarting the current title if
+    /// there is one.
+    pub fn set_times(&mut self, fade_in: i32, stay: i32, fade_out: i32) {
+        self.fade_in = fade_in.max(0);
+        self.stay = stay.max(0);
+        self.fade_out = fade_out.max(0);
+        if self.shown_at.is_some() {
+            self.shown_at = Some(Instant::now());
+        }
+    }
+
+    /// Hides the current title and subtitle.
+    pub fn hide(&mut self) {
+        self.title = None;
+        self.subtitle = None;
+        self.shown_at = None;
+        self.changed();
+    }
+
+    /// Hides the current title and restores the default timings.
+    pub fn reset(&mut self) {
+        self.hide();
+        self.fade_in = DEFAULT_FADE_IN;
+        self.stay = DEFAULT_STAY;
+        self.fade_out = DEFAULT_FADE_OUT;
+    }
+
+    pub fn set_action_bar(&mut self, text: Component) {
+        self.action_bar = Some((text, Instant::now()));
+        self.changed();
+    }
+
+    /// Forgets titles that have finished fading out so a later subtitle
+    /// isn't shown with a new title.
+    pub fn tick(&mut self) {
+        if self.shown_at.is_some() && self.title_alpha().is_none() {
+            self.hide();
+        }
+        if self.action_bar.is_some() && self.action_bar_alpha().is_none() {
+            self.action_bar = None;
+            self.changed();
+        }
+    }
+
+    /// Returns the opacity of the title, or `None` if it isn't shown.
+    fn title_alpha(&self) -> Option<f64> {
+        let age = self.shown_at?.elapsed().as_secs_f64() * TICKS_PER_SECOND;
+        let fade_in = self.fade_in as f64;
+        let stay = self.stay as f64;
+        let fade_out = self.fade_out as f64;
+        if age < fade_in {
+            Some(age / fade_in)
+        } else if age < fade_in + stay {
+            Some(1.0)
+        } else if age < fade_in + stay + fade_out {
+            Some(1.0 - (age - fade_in - stay) / fade_out)
+        } else {
+            None
+        }
+    }
+
+    fn action_bar_alpha(&self) -> Option<f64> {
+        let age = self.action_bar.as_ref()?.1.elapsed().as_secs_f64();
+        if age < ACTION_BAR_TIME {
+            Some(((ACTION_BAR_TIME - age) / ACTION_BAR_FADE).min(1.0))
+        } else {
+            None
+        }
+    }
+
+    fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+struct Elements {
+    title: Option<ui::FormattedRef>,
+    subtitle: Option<ui::FormattedRef>,
+    action_bar: Option<ui::FormattedRef>,
+}
+
+/// Draws the title in the middle of the screen and the action bar above
+/// the hotbar.
+pub(super) struct TitleDisplay {
+    elements: Option<Elements>,
+    last_generation: Option<usize>,
+}
+
+impl TitleDisplay {
+    pub fn new() -> TitleDisplay {
+        TitleDisplay {
+            elements: None,
+            last_generation: None,
+        }
+    }
+
+    pub fn tick(&mut self, titles: &Titles, visible: bool, ui_container: &mut ui::Container) {
+        if !visible {
+            self.elements = None;
+            self.last_generation = None;
+            return;
+        }
+        if self.last_generation != Some(titles.generation) {
+            self.last_generation = Some(titles.generation);
+            let shown = titles.shown_at.is_some();
+            self.elements = Some(Elements {
+                title: titles
+                    .title
+                    .as_ref()
+                    .filter(|_| shown)
+                    .map(|v| text(v, -44.0, 4.0, ui::VAttach::Middle, ui_container)),
+                subtitle: titles
+                    .subtitle
+                    .as_ref()
+                    .filter(|_| shown)
+                    .map(|v| text(v, 38.0, 2.0, ui::VAttach::Middle, ui_container)),
+                action_bar: titles
+                    .action_bar
+                    .as_ref()
+                    .map(|v| text(&v.0, 126.0, 1.0, ui::VAttach::Bottom, ui_container)),
+            });
+        }
+
+        let elements = self.elements.as_mut().unwrap();
+        let title_alpha = alpha(titles.title_alpha());
+        for element in elements.title.iter().chain(elements.subtitle.iter()) {
+            element.borrow_mut().alpha = title_alpha;
+        }
+        if let Some(action_bar) = &elements.action_bar {
+            action_bar.borrow_mut().alpha = alpha(titles.action_bar_alpha());
+        }
+    }
+}
+
+fn alpha(alpha: Option<f64>) -> u8 {
+    (alpha.unwrap_or(0.0).clamp(0.0, 1.0) * 255.0) as u8
+}
+
+fn text(
+    text: &Component,
+    y: f64,
+    scale: f64,
+    v_attach: ui::VAttach,
+    ui_container: &mut ui::Container,
+) -> ui::FormattedRef {
+    ui::FormattedBuilder::new()
+        .text(text.clone())
+        .position(0.0, y)
+        .scale_x(scale)
+        .scale_y(scale)
+        .alignment(v_attach, ui::HAttach::Center)
+        .create(ui_container)
+}
@@ -0,0 +1,323 @@
+use super::{InventoryContext, Window, OUTSIDE_SLOT};
+use crate::item;
+
+// The client doesn't know the stack size of items so assume the common
+// case, the server corrects the slots if this was wrong.
+const MAX_STACK_SIZE: isize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Click {
+    Left,
+    Right,
+    ShiftLeft,
+    ShiftRight,
+    /// Swaps the slot with the given hotbar slot
+    NumberKey(u8),
+    Drop,
+    DropStack,
+    DragStart {
+        right: bool,
+    },
+    DragAdd,
+    DragEnd,
+}
+
+pub(super) struct Drag {
+    right: bool,
+    slots: Vec<i16>,
+}
+
+/// The information needed to build the click packet for the current
+/// protocol version.
+pub struct ClickAction {
+    pub window_id: u8,
+    pub slot: i16,
+    pub button: u8,
+    pub mode: u8,
+    pub action_number: i16,
+    /// The item in the clicked slot before the click, only used before
+    /// 1.17.
+    pub clicked_item: Option<item::Stack>,
+    pub state_id: i32,
+    /// The predicted contents of the slots changed by the click.
+    pub changed_slots: Vec<(i16, Option<item::Stack>)>,
+    /// The predicted item on the cursor after the click.
+    pub carried: Option<item::Stack>,
+}
+
+impl InventoryContext {
+    /// Applies a click to the current window and returns the action which
+    /// has to be sent to the server.
+    pub fn click(&mut self, slot: i16, click: Click) -> ClickAction {
+        let drag_right = self.drag.as_ref().map_or(false, |v| v.right);
+        let (mode, button) = match click {
+            Click::Left => (0, 0),
+            Click::Right => (0, 1),
+            Click::ShiftLeft => (1, 0),
+            Click::ShiftRight => (1, 1),
+            Click::NumberKey(index) => (2, index),
+            Click::Drop => (4, 0),
+            Click::DropStack => (4, 1),
+            Click::DragStart { right } => (5, if right { 4 } else { 0 }),
+            Click::DragAdd => (5, if drag_right { 5 } else { 1 }),
+            Click::DragEnd => (5, if drag_right { 6 } else { 2 }),
+        };
+        let clicked_item = match click {
+            Click::Left | Click::Right | Click::ShiftLeft | Click::ShiftRight => {
+                self.current_window().get(slot).cloned()
+            }
+            _ => None,
+        };
+        let before = self.current_window().slots.clone();
+
+        match click {
+            Click::Left | Click::Right => self.pickup(slot, click == Click::Right),
+            // Where the items end up depends on the window type, leave
+            // it to the server
+            Click::ShiftLeft | Click::ShiftRight => {}
+            Click::NumberKey(index) => {
+                let window = self.current_window_mut();
+                let hotbar = window.hotbar_slot(index);
+                if in_window(window, slot) && hotbar < window.slots.len() {
+                    window.slots.swap(slot as usize, hotbar);
+                }
+            }
+            Click::Drop | Click::DropStack => {
+                let window = self.current_window_mut();
+                if let Some(mut item) = window.get(slot).cloned() {
+                    item.count = if click == Click::DropStack {
+                        0
+                    } else {
+                        item.count - 1
+                    };
+                    window.set(slot, non_empty(item));
+                }
+            }
+            Click::DragStart { right } => {
+                self.drag = Some(Drag {
+                    right,
+                    slots: vec![],
+                });
+            }
+            Click::DragAdd => self.add_drag_slot(slot),
+            Click::DragEnd => self.finish_drag(),
+        }
+        self.sync_from_container();
+
+        let state_id = self.state_id;
+        let carried = self.cursor.clone();
+        let window = self.current_window_mut();
+        let changed_slots = window
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| before.get(*i) != Some(*v))
+            .map(|(i, v)| (i as i16, v.clone()))
+            .collect();
+        ClickAction {
+            window_id: window.id,
+            slot,
+            button,
+            mode,
+            action_number: window.next_action_number(),
+            clicked_item,
+            state_id,
+            changed_slots,
+            carried,
+        }
+    }
+
+    fn pickup(&mut self, slot: i16, right: bool) {
+        if slot == OUTSIDE_SLOT {
+            // Drops the cursor, or a single item of it
+            self.cursor = match self.cursor.take() {
+                Some(mut cursor) if right => {
+                    cursor.count -= 1;
+                    non_empty(cursor)
+                }
+                _ => None,
+            };
+            return;
+        }
+        let window = self
+            .open_window
+            .as_mut()
+            .unwrap_or(&mut self.player_inventory);
+        if !in_window(window, slot) {
+            return;
+        }
+        let (slot_item, cursor) = match (window.get(slot).cloned(), self.cursor.take()) {
+            (None, None) => (None, None),
+            (Some(mut item), None) => {
+                if right {
+                    let mut taken = item.clone();
+                    taken.count = (item.count + 1) / 2;
+                    item.count -= taken.count;
+                    (non_empty(item), Some(taken))
+                } else {
+                    (None, Some(item))
+                }
+            }
+            (None, Some(mut cursor)) => {
+                if right {
+                    let mut placed = cursor.clone();
+                    placed.count = 1;
+                    cursor.count -= 1;
+                    (Some(placed), non_empty(cursor))
+                } else {
+                    (Some(cursor), None)
+                }
+            }
+            (Some(mut item), Some(mut cursor)) => {
+                if can_stack(&item, &cursor) {
+                    let space = (MAX_STACK_SIZE - item.count).max(0);
+                    let moved = if right { 1 } else { cursor.count }.min(space);
+                    item.count += moved;
+                    cursor.count -= moved;
+                    (Some(item), non_empty(cursor))
+                } else {
+                    (Some(cursor), Some(item))
+                }
+            }
+        };
+        window.set(slot, slot_item);
+        self.cursor = cursor;
+    }
+
+    fn add_drag_slot(&mut self, slot: i16) {
+        let cursor = match self.cursor.as_ref() {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        let window = self.open_window.as_ref().unwrap_or(&self.player_inventory);
+        if !in_window(window, slot) {
+            return;
+        }
+        let fits = window.get(slot).map_or(true, |v| can_stack(v, cursor));
+        if let Some(drag) = self.drag.as_mut() {
+            if fits && !drag.slots.contains(&slot) && (drag.slots.len() as isize) < cursor.count {
+                drag.slots.push(slot);
+            }
+        }
+    }
+
+    fn finish_drag(&mut self) {
+        let (drag, mut cursor) = match (self.drag.take(), self.cursor.take()) {
+            (Some(drag), Some(cursor)) if !drag.slots.is_empty() => (drag, cursor),
+            (_, cursor) => {
+                self.cursor = cursor;
+                return;
+            }
+        };
+        let per_slot = if drag.right {
+            1
+        } else {
+            cursor.count / drag.slots.len() as isize
+        };
+        let window = self
+            .open_window
+            .as_mut()
+            .unwrap_or(&mut self.player_inventory);
+        for slot in drag.slots {
+            let mut item = window.get(slot).cloned().unwrap_or_else(|| {
+                let mut item = cursor.clone();
+                item.count = 0;
+                item
+            });
+            let moved = per_slot.min(MAX_STACK_SIZE - item.count).min(cursor.count);
+            if moved <= 0 {
+                continue;
+            }
+            item.count += moved;
+            cursor.count -= moved;
+            window.set(slot, Some(item));
+        }
+        self.cursor = non_empty(cursor);
+    }
+}
+
+fn in_window(window: &Window, slot: i16) -> bool {
+    slot >= 0 && (slot as usize) < window.slots.len()
+}
+
+fn can_stack(a: &item::Stack, b: &item::Stack) -> bool {
+    a.id == b.id && a.damage == b.damage && a.tag == b.tag
+}
+
+fn non_empty(item: item::Stack) -> Option<item::Stack> {
+    if item.count > 0 {
+        Some(item)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(id: isize, count: isize) -> Option<item::Stack> {
+        Some(item::Stack {
+            id,
+            count,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn pickup_and_place() {
+        let mut inv = InventoryContext::new(340);
+        inv.set_slot(0, 36, stack(1, 10), None);
+
+        let action = inv.click(36, Click::Right);
+        assert_eq!(
+            (action.mode, action.button, action.action_number),
+            (0, 1, 1)
+        );
+        assert_eq!(action.clicked_item, stack(1, 10));
+        assert_eq!(inv.cursor, stack(1, 5));
+        assert_eq!(inv.player_inventory.slots[36], stack(1, 5));
+
+        let action = inv.click(37, Click::Left);
+        assert_eq!(action.action_number, 2);
+        assert_eq!(action.changed_slots, vec![(37, stack(1, 5))]);
+        assert_eq!(inv.cursor, None);
+    }
+
+    #[test]
+    fn drag_split() {
+        let mut inv = InventoryContext::new(340);
+        inv.set_slot(255, -1, stack(1, 7), None);
+
+        inv.click(OUTSIDE_SLOT, Click::DragStart { right: false });
+        inv.click(9, Click::DragAdd);
+        inv.click(10, Click::DragAdd);
+        let action = inv.click(OUTSIDE_SLOT, Click::DragEnd);
+        assert_eq!((action.mode, action.button), (5, 2));
+        assert_eq!(inv.player_inventory.slots[9], stack(1, 3));
+        assert_eq!(inv.player_inventory.slots[10], stack(1, 3));
+        assert_eq!(inv.cursor, stack(1, 1));
+    }
+
+    #[test]
+    fn container_mirrors_player_inventory() {
+        let mut inv = InventoryContext::new(340);
+        inv.set_slot(0, 36, stack(1, 1), None);
+        inv.open(1, None, Some(27));
+        assert_eq!(inv.current_window().slots[27 + 27], stack(1, 1));
+
+        inv.click(27 + 27, Click::NumberKey(1));
+        assert_eq!(inv.player_inventory.slots[37], stack(1, 1));
+        assert_eq!(inv.player_inventory.slots[36], None);
+    }
+
+    #[test]
+    fn short_window_items() {
+        let mut inv = InventoryContext::new(340);
+        inv.set_items(0, vec![None, stack(1, 1)], None);
+        assert_eq!(inv.player_inventory.slots.len(), 46);
+        assert_eq!(inv.player_inventory.slots[1], stack(1, 1));
+        inv.open(1, None, Some(27));
+        assert_eq!(inv.current_window().slots.len(), 27 + 36);
+    }
+}
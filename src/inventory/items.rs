@@ -0,0 +1,909 @@
+//! Names of items, which the protocol only refers to by number.
+//!
+//! Before 1.13 every item kept its id between releases. Since then items
+//! are numbered in the order the game registers them, so like blocks the
+//! ids for a version come from walking the registration order and
+//! skipping the items that version doesn't have.
+
+use crate::item;
+
+pub struct ItemRegistry {
+    protocol_version: i32,
+    // Indexed by id, from 1.13
+    flat: Vec<&'static str>,
+}
+
+impl ItemRegistry {
+    pub fn new(protocol_version: i32) -> ItemRegistry {
+        ItemRegistry {
+            protocol_version,
+            flat: flat_names(protocol_version),
+        }
+    }
+
+    /// Returns the name of the item with the id, without namespace.
+    pub fn name(&self, id: isize) -> Option<&'static str> {
+        if self.protocol_version >= 404 {
+            usize::try_from(id)
+                .ok()
+                .and_then(|id| self.flat.get(id).copied())
+        } else {
+            legacy_name(id)
+        }
+    }
+
+    /// Returns the name of the item's model in the 1.12 resources used to
+    /// draw it, which tells apart the variants that were kept in the
+    /// damage value before 1.13.
+    pub fn model_name(&self, stack: &item::Stack) -> Option<String> {
+        let name = self.name(stack.id)?;
+        Some(if self.protocol_version >= 404 {
+            flattened_model_name(name)
+        } else {
+            legacy_model_name(name, stack.damage.unwrap_or(0))
+        })
+    }
+}
+
+const COLORS: [&str; 16] = [
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "silver",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+const WOODS: [&str; 6] = ["oak", "spruce", "birch", "jungle", "acacia", "dark_oak"];
+
+fn legacy_model_name(name: &str, damage: isize) -> String {
+    let index = |names: &[&'static str]| names.get(damage as usize).copied();
+    let variant = match name {
+        "wool"
+        | "carpet"
+        | "stained_glass"
+        | "stained_glass_pane"
+        | "stained_hardened_clay"
+        | "concrete"
+        | "concrete_powder" => index(&COLORS).map(|v| format!("{}_{}", v, name)),
+        "planks" | "sapling" => index(&WOODS).map(|v| format!("{}_{}", v, name)),
+        "log" | "leaves" => index(&WOODS[..4]).map(|v| format!("{}_{}", v, name)),
+        "log2" | "leaves2" => {
+            index(&WOODS[4..]).map(|v| format!("{}_{}", v, name.trim_end_matches('2')))
+        }
+        // Dyes are numbered the other way around to wool
+        "dye" => COLORS
+            .get(15usize.wrapping_sub(damage as usize))
+            .map(|v| format!("dye_{}", v)),
+        "stone" => index(&[
+            "stone",
+            "granite",
+            "granite_smooth",
+            "diorite",
+            "diorite_smooth",
+            "andesite",
+            "andesite_smooth",
+        ])
+        .map(String::from),
+        "dirt" => index(&["dirt", "coarse_dirt", "podzol"]).map(String::from),
+        "sand" => index(&["sand", "red_sand"]).map(String::from),
+        "coal" => index(&["coal", "charcoal"]).map(String::from),
+        "fish" => index(&["cod", "salmon", "clownfish", "pufferfish"]).map(String::from),
+        "cooked_fish" => index(&["cooked_cod", "cooked_salmon"]).map(String::from),
+        _ => None,
+    };
+    variant.unwrap_or_else(|| name.to_owned())
+}
+
+/// Returns the 1.12 name of an item renamed by the 1.13 flattening, for
+/// the more common items.
+fn flattened_model_name(name: &str) -> String {
+    let name = name.replace("light_gray", "silver");
+    if let Some(color) = name.strip_suffix("_dye") {
+        return format!("dye_{}", color);
+    }
+    if let Some(record) = name.strip_prefix("music_disc_") {
+        return format!("record_{}", record);
+    }
+    if let Some(color) = name.strip_suffix("_terracotta") {
+        if !color.ends_with("_glazed") {
+            return format!("{}_stained_hardened_clay", color);
+        }
+    }
+    match name.as_str() {
+        "bone_meal" => "dye_white",
+        "ink_sac" => "dye_black",
+        "lapis_lazuli" => "dye_blue",
+        "cocoa_beans" => "dye_brown",
+        "grass_block" => "grass",
+        "terracotta" => "hardened_clay",
+        "polished_granite" => "granite_smooth",
+        "polished_diorite" => "diorite_smooth",
+        "polished_andesite" => "andesite_smooth",
+        "stone_bricks" => "stonebrick",
+        "bricks" => "brick_block",
+        "nether_bricks" => "nether_brick",
+        "nether_brick" => "netherbrick",
+        "red_nether_bricks" => "red_nether_brick",
+        "end_stone_bricks" => "end_bricks",
+        "cobweb" => "web",
+        "dead_bush" => "deadbush",
+        "sugar_cane" => "reeds",
+        "lily_pad" => "waterlily",
+        "snow" => "snow_layer",
+        "snow_block" => "snow",
+        "slime_block" => "slime",
+        "magma_block" => "magma",
+        "spawner" => "mob_spawner",
+        "note_block" => "noteblock",
+        "jack_o_lantern" => "lit_pumpkin",
+        "melon_slice" => "melon",
+        "glistering_melon_slice" => "speckled_melon",
+        "popped_chorus_fruit" => "chorus_fruit_popped",
+        "firework_rocket" => "fireworks",
+        "firework_star" => "firework_charge",
+        "tropical_fish" => "clownfish",
+        "oak_boat" => "boat",
+        "oak_door" => "wooden_door",
+        "oak_sign" => "sign",
+        _ => return name,
+    }
+    .to_owned()
+}
+
+fn legacy_name(id: isize) -> Option<&'static str> {
+    let name = match id {
+        0..=255 => LEGACY_BLOCKS[id as usize],
+        256..=453 => LEGACY_ITEMS[id as usize - 256],
+        2256..=2267 => LEGACY_RECORDS[id as usize - 2256],
+        _ => "",
+    };
+    Some(name).filter(|v| !v.is_empty())
+}
+
+// The names of items before 1.13, by id. Unused ids are empty.
+#[rustfmt::skip]
+const LEGACY_BLOCKS: [&str; 256] = [
+    "air", "stone", "grass", "dirt", "cobblestone", "planks", "sapling", "bedrock",
+    "flowing_water", "water", "flowing_lava", "lava", "sand", "gravel", "gold_ore", "iron_ore",
+    "coal_ore", "log", "leaves", "sponge", "glass", "lapis_ore", "lapis_block", "dispenser",
+    "sandstone", "noteblock", "bed", "golden_rail", "detector_rail", "sticky_piston", "web",
+    "tallgrass", "deadbush", "piston", "piston_head", "wool", "piston_extension", "yellow_flower",
+    "red_flower", "brown_mushroom", "red_mushroom", "gold_block", "iron_block",
+    "double_stone_slab", "stone_slab", "brick_block", "tnt", "bookshelf", "mossy_cobblestone",
+    "obsidian", "torch", "fire", "mob_spawner", "oak_stairs", "chest", "redstone_wire",
+    "diamond_ore", "diamond_block", "crafting_table", "wheat", "farmland", "furnace",
+    "lit_furnace", "standing_sign", "wooden_door", "ladder", "rail", "stone_stairs", "wall_sign",
+    "lever", "stone_pressure_plate", "iron_door", "wooden_pressure_plate", "redstone_ore",
+    "lit_redstone_ore", "unlit_redstone_torch", "redstone_torch", "stone_button", "snow_layer",
+    "ice", "snow", "cactus", "clay", "reeds", "jukebox", "fence", "pumpkin", "netherrack",
+    "soul_sand", "glowstone", "portal", "lit_pumpkin", "cake", "unpowered_repeater",
+    "powered_repeater", "stained_glass", "trapdoor", "monster_egg", "stonebrick",
+    "brown_mushroom_block", "red_mushroom_block", "iron_bars", "glass_pane", "melon_block",
+    "pumpkin_stem", "melon_stem", "vine", "fence_gate", "brick_stairs", "stone_brick_stairs",
+    "mycelium", "waterlily", "nether_brick", "nether_brick_fence", "nether_brick_stairs",
+    "nether_wart", "enchanting_table", "brewing_stand", "cauldron", "end_portal",
+    "end_portal_frame", "end_stone", "dragon_egg", "redstone_lamp", "lit_redstone_lamp",
+    "double_wooden_slab", "wooden_slab", "cocoa", "sandstone_stairs", "emerald_ore",
+    "ender_chest", "tripwire_hook", "tripwire", "emerald_block", "spruce_stairs", "birch_stairs",
+    "jungle_stairs", "command_block", "beacon", "cobblestone_wall", "flower_pot", "carrots",
+    "potatoes", "wooden_button", "skull", "anvil", "trapped_chest",
+    "light_weighted_pressure_plate", "heavy_weighted_pressure_plate", "unpowered_comparator",
+    "powered_comparator", "daylight_detector", "redstone_block", "quartz_ore", "hopper",
+    "quartz_block", "quartz_stairs", "activator_rail", "dropper", "stained_hardened_clay",
+    "stained_glass_pane", "leaves2", "log2", "acacia_stairs", "dark_oak_stairs", "slime",
+    "barrier", "iron_trapdoor", "prismarine", "sea_lantern", "hay_block", "carpet",
+    "hardened_clay", "coal_block", "packed_ice", "double_plant", "standing_banner",
+    "wall_banner", "daylight_detector_inverted", "red_sandstone", "red_sandstone_stairs",
+    "double_stone_slab2", "stone_slab2", "spruce_fence_gate", "birch_fence_gate",
+    "jungle_fence_gate", "dark_oak_fence_gate", "acacia_fence_gate", "spruce_fence",
+    "birch_fence", "jungle_fence", "dark_oak_fence", "acacia_fence", "spruce_door", "birch_door",
+    "jungle_door", "acacia_door", "dark_oak_door", "end_rod", "chorus_plant", "chorus_flower",
+    "purpur_block", "purpur_pillar", "purpur_stairs", "purpur_double_slab", "purpur_slab",
+    "end_bricks", "beetroots", "grass_path", "end_gateway", "repeating_command_block",
+    "chain_command_block", "frosted_ice", "magma", "nether_wart_block", "red_nether_brick",
+    "bone_block", "structure_void", "observer", "white_shulker_box", "orange_shulker_box",
+    "magenta_shulker_box", "light_blue_shulker_box", "yellow_shulker_box", "lime_shulker_box",
+    "pink_shulker_box", "gray_shulker_box", "silver_shulker_box", "cyan_shulker_box",
+    "purple_shulker_box", "blue_shulker_box", "brown_shulker_box", "green_shulker_box",
+    "red_shulker_box", "black_shulker_box", "white_glazed_terracotta",
+    "orange_glazed_terracotta", "magenta_glazed_terracotta", "light_blue_glazed_terracotta",
+    "yellow_glazed_terracotta", "lime_glazed_terracotta", "pink_glazed_terracotta",
+    "gray_glazed_terracotta", "silver_glazed_terracotta", "cyan_glazed_terracotta",
+    "purple_glazed_terracotta", "blue_glazed_terracotta", "brown_glazed_terracotta",
+    "green_glazed_terracotta", "red_glazed_terracotta", "black_glazed_terracotta", "concrete",
+    "concrete_powder", "", "", "structure_block",
+];
+
+#[rustfmt::skip]
+const LEGACY_ITEMS: [&str; 198] = [
+    "iron_shovel", "iron_pickaxe", "iron_axe", "flint_and_steel", "apple", "bow", "arrow", "coal",
+    "diamond", "iron_ingot", "gold_ingot", "iron_sword", "wooden_sword", "wooden_shovel",
+    "wooden_pickaxe", "wooden_axe", "stone_sword", "stone_shovel", "stone_pickaxe", "stone_axe",
+    "diamond_sword", "diamond_shovel", "diamond_pickaxe", "diamond_axe", "stick", "bowl",
+    "mushroom_stew", "golden_sword", "golden_shovel", "golden_pickaxe", "golden_axe", "string",
+    "feather", "gunpowder", "wooden_hoe", "stone_hoe", "iron_hoe", "diamond_hoe", "golden_hoe",
+    "wheat_seeds", "wheat", "bread", "leather_helmet", "leather_chestplate", "leather_leggings",
+    "leather_boots", "chainmail_helmet", "chainmail_chestplate", "chainmail_leggings",
+    "chainmail_boots", "iron_helmet", "iron_chestplate", "iron_leggings", "iron_boots",
+    "diamond_helmet", "diamond_chestplate", "diamond_leggings", "diamond_boots",
+    "golden_helmet", "golden_chestplate", "golden_leggings", "golden_boots", "flint",
+    "porkchop", "cooked_porkchop", "painting", "golden_apple", "sign", "wooden_door", "bucket",
+    "water_bucket", "lava_bucket", "minecart", "saddle", "iron_door", "redstone", "snowball",
+    "boat", "leather", "milk_bucket", "brick", "clay_ball", "reeds", "paper", "book",
+    "slime_ball", "chest_minecart", "furnace_minecart", "egg", "compass", "fishing_rod", "clock",
+    "glowstone_dust", "fish", "cooked_fish", "dye", "bone", "sugar", "cake", "bed", "repeater",
+    "cookie", "filled_map", "shears", "melon", "pumpkin_seeds", "melon_seeds", "beef",
+    "cooked_beef", "chicken", "cooked_chicken", "rotten_flesh", "ender_pearl", "blaze_rod",
+    "ghast_tear", "gold_nugget", "nether_wart", "potion", "glass_bottle", "spider_eye",
+    "fermented_spider_eye", "blaze_powder", "magma_cream", "brewing_stand", "cauldron",
+    "ender_eye", "speckled_melon", "spawn_egg", "experience_bottle", "fire_charge",
+    "writable_book", "written_book", "emerald", "item_frame", "flower_pot", "carrot", "potato",
+    "baked_potato", "poisonous_potato", "map", "golden_carrot", "skull", "carrot_on_a_stick",
+    "nether_star", "pumpkin_pie", "fireworks", "firework_charge", "enchanted_book",
+    "comparator", "netherbrick", "quartz", "tnt_minecart", "hopper_minecart",
+    "prismarine_shard", "prismarine_crystals", "rabbit", "cooked_rabbit", "rabbit_stew",
+    "rabbit_foot", "rabbit_hide", "armor_stand", "iron_horse_armor", "golden_horse_armor",
+    "diamond_horse_armor", "lead", "name_tag", "command_block_minecart", "mutton",
+    "cooked_mutton", "banner", "end_crystal", "spruce_door", "birch_door", "jungle_door",
+    "acacia_door", "dark_oak_door", "chorus_fruit", "chorus_fruit_popped", "beetroot",
+    "beetroot_seeds", "beetroot_soup", "dragon_breath", "splash_potion", "spectral_arrow",
+    "tipped_arrow", "lingering_potion", "shield", "elytra", "spruce_boat", "birch_boat",
+    "jungle_boat", "acacia_boat", "dark_oak_boat", "totem_of_undying", "shulker_shell", "",
+    "iron_nugget", "knowledge_book",
+];
+
+#[rustfmt::skip]
+const LEGACY_RECORDS: [&str; 12] = [
+    "record_13", "record_cat", "record_blocks", "record_chirp", "record_far", "record_mall",
+    "record_mellohi", "record_stal", "record_strad", "record_ward", "record_11", "record_wait",
+];
+
+/// An item in the order the game registers them. It has an id in protocol
+/// versions from `since` up to, but not including, `until`.
+struct FlatItem {
+    name: &'static str,
+    since: i32,
+    until: i32,
+}
+
+macro_rules! first {
+    ($v:expr $(, $rest:expr)*) => {
+        $v
+    };
+}
+
+macro_rules! flat_items {
+    ($($name:literal $(since $since:literal)? $(until $until:literal)?,)*) => {
+        &[$(FlatItem {
+            name: $name,
+            since: first!($($since,)? 0),
+            until: first!($($until,)? i32::MAX),
+        },)*]
+    };
+}
+
+/// Returns the names of the items of a version from 1.13 on, by id.
+fn flat_names(protocol_version: i32) -> Vec<&'static str> {
+    let items = if protocol_version >= 755 {
+        ITEMS_1_17
+    } else if protocol_version >= 404 {
+        ITEMS_1_13
+    } else {
+        return vec![];
+    };
+    items
+        .iter()
+        .filter(|v| (v.since..v.until).contains(&protocol_version))
+        .map(|v| v.name)
+        .collect()
+}
+
+// The items of 1.13 to 1.16.5. The 1.14 snapshots are given the 1.14
+// items.
+#[rustfmt::skip]
+const ITEMS_1_13: &[FlatItem] = flat_items! {
+    "air", "stone", "granite", "polished_granite", "diorite", "polished_diorite", "andesite",
+    "polished_andesite", "grass_block", "dirt", "coarse_dirt", "podzol", "crimson_nylium" since 735,
+    "warped_nylium" since 735, "cobblestone", "oak_planks", "spruce_planks", "birch_planks",
+    "jungle_planks", "acacia_planks", "dark_oak_planks", "crimson_planks" since 735,
+    "warped_planks" since 735, "oak_sapling", "spruce_sapling", "birch_sapling", "jungle_sapling",
+    "acacia_sapling", "dark_oak_sapling", "bedrock", "sand", "red_sand", "gravel", "gold_ore",
+    "iron_ore", "coal_ore", "nether_gold_ore" since 735, "oak_log", "spruce_log", "birch_log",
+    "jungle_log", "acacia_log", "dark_oak_log", "crimson_stem" since 735, "warped_stem" since 735,
+    "stripped_oak_log", "stripped_spruce_log", "stripped_birch_log", "stripped_jungle_log",
+    "stripped_acacia_log", "stripped_dark_oak_log", "stripped_crimson_stem" since 735,
+    "stripped_warped_stem" since 735, "stripped_oak_wood", "stripped_spruce_wood",
+    "stripped_birch_wood", "stripped_jungle_wood", "stripped_acacia_wood", "stripped_dark_oak_wood",
+    "stripped_crimson_hyphae" since 735, "stripped_warped_hyphae" since 735, "oak_wood",
+    "spruce_wood", "birch_wood", "jungle_wood", "acacia_wood", "dark_oak_wood",
+    "crimson_hyphae" since 735, "warped_hyphae" since 735, "oak_leaves", "spruce_leaves",
+    "birch_leaves", "jungle_leaves", "acacia_leaves", "dark_oak_leaves", "sponge", "wet_sponge",
+    "glass", "lapis_ore", "lapis_block", "dispenser", "sandstone", "chiseled_sandstone",
+    "cut_sandstone", "note_block", "powered_rail", "detector_rail", "sticky_piston", "cobweb",
+    "grass", "fern", "dead_bush", "seagrass", "sea_pickle", "piston", "white_wool", "orange_wool",
+    "magenta_wool", "light_blue_wool", "yellow_wool", "lime_wool", "pink_wool", "gray_wool",
+    "light_gray_wool", "cyan_wool", "purple_wool", "blue_wool", "brown_wool", "green_wool",
+    "red_wool", "black_wool", "dandelion", "poppy", "blue_orchid", "allium", "azure_bluet",
+    "red_tulip", "orange_tulip", "white_tulip", "pink_tulip", "oxeye_daisy", "cornflower" since 451,
+    "lily_of_the_valley" since 451, "wither_rose" since 451, "brown_mushroom", "red_mushroom",
+    "crimson_fungus" since 735, "warped_fungus" since 735, "crimson_roots" since 735,
+    "warped_roots" since 735, "nether_sprouts" since 735, "weeping_vines" since 735,
+    "twisting_vines" since 735, "sugar_cane" since 735, "kelp" since 735, "bamboo" since 735,
+    "gold_block", "iron_block", "oak_slab", "spruce_slab", "birch_slab", "jungle_slab",
+    "acacia_slab", "dark_oak_slab", "crimson_slab" since 735, "warped_slab" since 735, "stone_slab",
+    "smooth_stone_slab" since 451, "sandstone_slab", "cut_sandstone_slab" since 451,
+    "petrified_oak_slab", "cobblestone_slab", "brick_slab", "stone_brick_slab", "nether_brick_slab",
+    "quartz_slab", "red_sandstone_slab", "cut_red_sandstone_slab" since 451, "purpur_slab",
+    "prismarine_slab", "prismarine_brick_slab", "dark_prismarine_slab", "smooth_quartz",
+    "smooth_red_sandstone", "smooth_sandstone", "smooth_stone", "bricks", "tnt", "bookshelf",
+    "mossy_cobblestone", "obsidian", "torch", "end_rod", "chorus_plant", "chorus_flower",
+    "purpur_block", "purpur_pillar", "purpur_stairs", "spawner", "oak_stairs", "chest",
+    "diamond_ore", "diamond_block", "crafting_table", "farmland", "furnace", "ladder", "rail",
+    "cobblestone_stairs", "lever", "stone_pressure_plate", "oak_pressure_plate",
+    "spruce_pressure_plate", "birch_pressure_plate", "jungle_pressure_plate",
+    "acacia_pressure_plate", "dark_oak_pressure_plate", "crimson_pressure_plate" since 735,
+    "warped_pressure_plate" since 735, "polished_blackstone_pressure_plate" since 735,
+    "redstone_ore", "redstone_torch", "stone_button" until 451, "snow", "ice", "snow_block",
+    "cactus", "clay", "jukebox", "oak_fence", "spruce_fence", "birch_fence", "jungle_fence",
+    "acacia_fence", "dark_oak_fence", "crimson_fence" since 735, "warped_fence" since 735,
+    "pumpkin", "carved_pumpkin", "netherrack", "soul_sand", "soul_soil" since 735,
+    "basalt" since 735, "polished_basalt" since 735, "soul_torch" since 735, "glowstone",
+    "jack_o_lantern", "oak_trapdoor", "spruce_trapdoor", "birch_trapdoor", "jungle_trapdoor",
+    "acacia_trapdoor", "dark_oak_trapdoor", "crimson_trapdoor" since 735,
+    "warped_trapdoor" since 735, "infested_stone", "infested_cobblestone", "infested_stone_bricks",
+    "infested_mossy_stone_bricks", "infested_cracked_stone_bricks",
+    "infested_chiseled_stone_bricks", "stone_bricks", "mossy_stone_bricks", "cracked_stone_bricks",
+    "chiseled_stone_bricks", "brown_mushroom_block", "red_mushroom_block", "mushroom_stem",
+    "iron_bars", "chain" since 735, "glass_pane", "melon", "vine", "oak_fence_gate",
+    "spruce_fence_gate", "birch_fence_gate", "jungle_fence_gate", "acacia_fence_gate",
+    "dark_oak_fence_gate", "crimson_fence_gate" since 735, "warped_fence_gate" since 735,
+    "brick_stairs", "stone_brick_stairs", "mycelium", "lily_pad", "nether_bricks",
+    "cracked_nether_bricks" since 735, "chiseled_nether_bricks" since 735, "nether_brick_fence",
+    "nether_brick_stairs", "enchanting_table", "end_portal_frame", "end_stone", "end_stone_bricks",
+    "dragon_egg", "redstone_lamp", "sandstone_stairs", "emerald_ore", "ender_chest",
+    "tripwire_hook", "emerald_block", "spruce_stairs", "birch_stairs", "jungle_stairs",
+    "crimson_stairs" since 735, "warped_stairs" since 735, "command_block", "beacon",
+    "cobblestone_wall", "mossy_cobblestone_wall", "brick_wall" since 451,
+    "prismarine_wall" since 451, "red_sandstone_wall" since 451, "mossy_stone_brick_wall" since 451,
+    "granite_wall" since 451, "stone_brick_wall" since 451, "nether_brick_wall" since 451,
+    "andesite_wall" since 451, "red_nether_brick_wall" since 451, "sandstone_wall" since 451,
+    "end_stone_brick_wall" since 451, "diorite_wall" since 451, "blackstone_wall" since 735,
+    "polished_blackstone_wall" since 735, "polished_blackstone_brick_wall" since 735,
+    "stone_button" since 451, "oak_button", "spruce_button", "birch_button", "jungle_button",
+    "acacia_button", "dark_oak_button", "crimson_button" since 735, "warped_button" since 735,
+    "polished_blackstone_button" since 735, "anvil", "chipped_anvil", "damaged_anvil",
+    "trapped_chest", "light_weighted_pressure_plate", "heavy_weighted_pressure_plate",
+    "daylight_detector", "redstone_block", "nether_quartz_ore", "hopper", "chiseled_quartz_block",
+    "quartz_block", "quartz_bricks" since 735, "quartz_pillar", "quartz_stairs", "activator_rail",
+    "dropper", "white_terracotta", "orange_terracotta", "magenta_terracotta",
+    "light_blue_terracotta", "yellow_terracotta", "lime_terracotta", "pink_terracotta",
+    "gray_terracotta", "light_gray_terracotta", "cyan_terracotta", "purple_terracotta",
+    "blue_terracotta", "brown_terracotta", "green_terracotta", "red_terracotta", "black_terracotta",
+    "barrier", "iron_trapdoor", "hay_block", "white_carpet", "orange_carpet", "magenta_carpet",
+    "light_blue_carpet", "yellow_carpet", "lime_carpet", "pink_carpet", "gray_carpet",
+    "light_gray_carpet", "cyan_carpet", "purple_carpet", "blue_carpet", "brown_carpet",
+    "green_carpet", "red_carpet", "black_carpet", "terracotta", "coal_block", "packed_ice",
+    "acacia_stairs", "dark_oak_stairs", "slime_block", "grass_path", "sunflower", "lilac",
+    "rose_bush", "peony", "tall_grass", "large_fern", "white_stained_glass", "orange_stained_glass",
+    "magenta_stained_glass", "light_blue_stained_glass", "yellow_stained_glass",
+    "lime_stained_glass", "pink_stained_glass", "gray_stained_glass", "light_gray_stained_glass",
+    "cyan_stained_glass", "purple_stained_glass", "blue_stained_glass", "brown_stained_glass",
+    "green_stained_glass", "red_stained_glass", "black_stained_glass", "white_stained_glass_pane",
+    "orange_stained_glass_pane", "magenta_stained_glass_pane", "light_blue_stained_glass_pane",
+    "yellow_stained_glass_pane", "lime_stained_glass_pane", "pink_stained_glass_pane",
+    "gray_stained_glass_pane", "light_gray_stained_glass_pane", "cyan_stained_glass_pane",
+    "purple_stained_glass_pane", "blue_stained_glass_pane", "brown_stained_glass_pane",
+    "green_stained_glass_pane", "red_stained_glass_pane", "black_stained_glass_pane", "prismarine",
+    "prismarine_bricks", "dark_prismarine", "prismarine_stairs", "prismarine_brick_stairs",
+    "dark_prismarine_stairs", "sea_lantern", "red_sandstone", "chiseled_red_sandstone",
+    "cut_red_sandstone", "red_sandstone_stairs", "repeating_command_block", "chain_command_block",
+    "magma_block", "nether_wart_block", "warped_wart_block" since 735, "red_nether_bricks",
+    "bone_block", "structure_void", "observer", "shulker_box", "white_shulker_box",
+    "orange_shulker_box", "magenta_shulker_box", "light_blue_shulker_box", "yellow_shulker_box",
+    "lime_shulker_box", "pink_shulker_box", "gray_shulker_box", "light_gray_shulker_box",
+    "cyan_shulker_box", "purple_shulker_box", "blue_shulker_box", "brown_shulker_box",
+    "green_shulker_box", "red_shulker_box", "black_shulker_box", "white_glazed_terracotta",
+    "orange_glazed_terracotta", "magenta_glazed_terracotta", "light_blue_glazed_terracotta",
+    "yellow_glazed_terracotta", "lime_glazed_terracotta", "pink_glazed_terracotta",
+    "gray_glazed_terracotta", "light_gray_glazed_terracotta", "cyan_glazed_terracotta",
+    "purple_glazed_terracotta", "blue_glazed_terracotta", "brown_glazed_terracotta",
+    "green_glazed_terracotta", "red_glazed_terracotta", "black_glazed_terracotta", "white_concrete",
+    "orange_concrete", "magenta_concrete", "light_blue_concrete", "yellow_concrete",
+    "lime_concrete", "pink_concrete", "gray_concrete", "light_gray_concrete", "cyan_concrete",
+    "purple_concrete", "blue_concrete", "brown_concrete", "green_concrete", "red_concrete",
+    "black_concrete", "white_concrete_powder", "orange_concrete_powder", "magenta_concrete_powder",
+    "light_blue_concrete_powder", "yellow_concrete_powder", "lime_concrete_powder",
+    "pink_concrete_powder", "gray_concrete_powder", "light_gray_concrete_powder",
+    "cyan_concrete_powder", "purple_concrete_powder", "blue_concrete_powder",
+    "brown_concrete_powder", "green_concrete_powder", "red_concrete_powder",
+    "black_concrete_powder", "turtle_egg", "dead_tube_coral_block", "dead_brain_coral_block",
+    "dead_bubble_coral_block", "dead_fire_coral_block", "dead_horn_coral_block", "tube_coral_block",
+    "brain_coral_block", "bubble_coral_block", "fire_coral_block", "horn_coral_block", "tube_coral",
+    "brain_coral", "bubble_coral", "fire_coral", "horn_coral", "dead_brain_coral",
+    "dead_bubble_coral", "dead_fire_coral", "dead_horn_coral", "dead_tube_coral", "tube_coral_fan",
+    "brain_coral_fan", "bubble_coral_fan", "fire_coral_fan", "horn_coral_fan",
+    "dead_tube_coral_fan", "dead_brain_coral_fan", "dead_bubble_coral_fan", "dead_fire_coral_fan",
+    "dead_horn_coral_fan", "blue_ice", "conduit", "polished_granite_stairs" since 451,
+    "smooth_red_sandstone_stairs" since 451, "mossy_stone_brick_stairs" since 451,
+    "polished_diorite_stairs" since 451, "mossy_cobblestone_stairs" since 451,
+    "end_stone_brick_stairs" since 451, "stone_stairs" since 451,
+    "smooth_sandstone_stairs" since 451, "smooth_quartz_stairs" since 451,
+    "granite_stairs" since 451, "andesite_stairs" since 451, "red_nether_brick_stairs" since 451,
+    "polished_andesite_stairs" since 451, "diorite_stairs" since 451,
+    "polished_granite_slab" since 451, "smooth_red_sandstone_slab" since 451,
+    "mossy_stone_brick_slab" since 451, "polished_diorite_slab" since 451,
+    "mossy_cobblestone_slab" since 451, "end_stone_brick_slab" since 451,
+    "smooth_sandstone_slab" since 451, "smooth_quartz_slab" since 451, "granite_slab" since 451,
+    "andesite_slab" since 451, "red_nether_brick_slab" since 451,
+    "polished_andesite_slab" since 451, "diorite_slab" since 451, "scaffolding" since 451,
+    "iron_door", "oak_door", "spruce_door", "birch_door", "jungle_door", "acacia_door",
+    "dark_oak_door", "crimson_door" since 735, "warped_door" since 735, "repeater", "comparator",
+    "structure_block", "jigsaw" since 451, "turtle_helmet", "scute", "iron_shovel", "iron_pickaxe",
+    "iron_axe", "flint_and_steel", "apple", "bow", "arrow", "coal", "charcoal", "diamond",
+    "iron_ingot", "gold_ingot", "netherite_ingot" since 735, "netherite_scrap" since 735,
+    "iron_sword", "wooden_sword", "wooden_shovel", "wooden_pickaxe", "wooden_axe", "stone_sword",
+    "stone_shovel", "stone_pickaxe", "stone_axe", "diamond_sword", "diamond_shovel",
+    "diamond_pickaxe", "diamond_axe", "stick", "bowl", "mushroom_stew", "golden_sword",
+    "golden_shovel", "golden_pickaxe", "golden_axe", "netherite_sword" since 735,
+    "netherite_shovel" since 735, "netherite_pickaxe" since 735, "netherite_axe" since 735,
+    "string", "feather", "gunpowder", "wooden_hoe", "stone_hoe", "iron_hoe", "diamond_hoe",
+    "golden_hoe", "netherite_hoe" since 735, "wheat_seeds", "wheat", "bread", "leather_helmet",
+    "leather_chestplate", "leather_leggings", "leather_boots", "chainmail_helmet",
+    "chainmail_chestplate", "chainmail_leggings", "chainmail_boots", "iron_helmet",
+    "iron_chestplate", "iron_leggings", "iron_boots", "diamond_helmet", "diamond_chestplate",
+    "diamond_leggings", "diamond_boots", "golden_helmet", "golden_chestplate", "golden_leggings",
+    "golden_boots", "netherite_helmet" since 735, "netherite_chestplate" since 735,
+    "netherite_leggings" since 735, "netherite_boots" since 735, "flint", "porkchop",
+    "cooked_porkchop", "painting", "golden_apple", "enchanted_golden_apple", "sign" until 451,
+    "oak_sign" since 451, "spruce_sign" since 451, "birch_sign" since 451, "jungle_sign" since 451,
+    "acacia_sign" since 451, "dark_oak_sign" since 451, "crimson_sign" since 735,
+    "warped_sign" since 735, "bucket", "water_bucket", "lava_bucket", "minecart", "saddle",
+    "redstone", "snowball", "oak_boat", "leather", "milk_bucket", "pufferfish_bucket",
+    "salmon_bucket", "cod_bucket", "tropical_fish_bucket", "brick", "clay_ball",
+    "sugar_cane" until 735, "kelp" until 735, "dried_kelp_block", "bamboo" since 451 until 735,
+    "paper", "book", "slime_ball", "chest_minecart", "furnace_minecart", "egg", "compass",
+    "fishing_rod", "clock", "glowstone_dust", "cod", "salmon", "tropical_fish", "pufferfish",
+    "cooked_cod", "cooked_salmon", "ink_sac", "rose_red" until 451, "cactus_green" until 451,
+    "cocoa_beans", "lapis_lazuli", "purple_dye" until 451, "cyan_dye" until 451,
+    "light_gray_dye" until 451, "gray_dye" until 451, "pink_dye" until 451, "lime_dye" until 451,
+    "dandelion_yellow" until 451, "light_blue_dye" until 451, "magenta_dye" until 451,
+    "orange_dye" until 451, "white_dye" since 451, "orange_dye" since 451, "magenta_dye" since 451,
+    "light_blue_dye" since 451, "yellow_dye" since 451, "lime_dye" since 451, "pink_dye" since 451,
+    "gray_dye" since 451, "light_gray_dye" since 451, "cyan_dye" since 451, "purple_dye" since 451,
+    "blue_dye" since 451, "brown_dye" since 451, "green_dye" since 451, "red_dye" since 451,
+    "black_dye" since 451, "bone_meal", "bone", "sugar", "cake", "white_bed", "orange_bed",
+    "magenta_bed", "light_blue_bed", "yellow_bed", "lime_bed", "pink_bed", "gray_bed",
+    "light_gray_bed", "cyan_bed", "purple_bed", "blue_bed", "brown_bed", "green_bed", "red_bed",
+    "black_bed", "cookie", "filled_map", "shears", "melon_slice", "dried_kelp", "pumpkin_seeds",
+    "melon_seeds", "beef", "cooked_beef", "chicken", "cooked_chicken", "rotten_flesh",
+    "ender_pearl", "blaze_rod", "ghast_tear", "gold_nugget", "nether_wart", "potion",
+    "glass_bottle", "spider_eye", "fermented_spider_eye", "blaze_powder", "magma_cream",
+    "brewing_stand", "cauldron", "ender_eye", "glistering_melon_slice", "bat_spawn_egg",
+    "bee_spawn_egg" since 573, "blaze_spawn_egg", "cat_spawn_egg" since 451,
+    "cave_spider_spawn_egg", "chicken_spawn_egg", "cod_spawn_egg", "cow_spawn_egg",
+    "creeper_spawn_egg", "dolphin_spawn_egg", "donkey_spawn_egg", "drowned_spawn_egg",
+    "elder_guardian_spawn_egg", "enderman_spawn_egg", "endermite_spawn_egg", "evoker_spawn_egg",
+    "fox_spawn_egg" since 451, "ghast_spawn_egg", "guardian_spawn_egg",
+    "hoglin_spawn_egg" since 735, "horse_spawn_egg", "husk_spawn_egg", "llama_spawn_egg",
+    "magma_cube_spawn_egg", "mooshroom_spawn_egg", "mule_spawn_egg", "ocelot_spawn_egg",
+    "panda_spawn_egg" since 451, "parrot_spawn_egg", "phantom_spawn_egg", "pig_spawn_egg",
+    "piglin_spawn_egg" since 735, "piglin_brute_spawn_egg" since 751,
+    "pillager_spawn_egg" since 451, "polar_bear_spawn_egg", "pufferfish_spawn_egg",
+    "rabbit_spawn_egg", "ravager_spawn_egg" since 451, "salmon_spawn_egg", "sheep_spawn_egg",
+    "shulker_spawn_egg", "silverfish_spawn_egg", "skeleton_spawn_egg", "skeleton_horse_spawn_egg",
+    "slime_spawn_egg", "spider_spawn_egg", "squid_spawn_egg", "stray_spawn_egg",
+    "strider_spawn_egg" since 735, "trader_llama_spawn_egg" since 451, "tropical_fish_spawn_egg",
+    "turtle_spawn_egg", "vex_spawn_egg", "villager_spawn_egg", "vindicator_spawn_egg",
+    "wandering_trader_spawn_egg" since 451, "witch_spawn_egg", "wither_skeleton_spawn_egg",
+    "wolf_spawn_egg", "zoglin_spawn_egg" since 735, "zombie_spawn_egg", "zombie_horse_spawn_egg",
+    "zombie_pigman_spawn_egg" until 735, "zombie_villager_spawn_egg",
+    "zombified_piglin_spawn_egg" since 735, "experience_bottle", "fire_charge", "writable_book",
+    "written_book", "emerald", "item_frame", "flower_pot", "carrot", "potato", "baked_potato",
+    "poisonous_potato", "map", "golden_carrot", "skeleton_skull", "wither_skeleton_skull",
+    "player_head", "zombie_head", "creeper_head", "dragon_head", "carrot_on_a_stick",
+    "warped_fungus_on_a_stick" since 735, "nether_star", "pumpkin_pie", "firework_rocket",
+    "firework_star", "enchanted_book", "nether_brick", "quartz", "tnt_minecart", "hopper_minecart",
+    "prismarine_shard", "prismarine_crystals", "rabbit", "cooked_rabbit", "rabbit_stew",
+    "rabbit_foot", "rabbit_hide", "armor_stand", "iron_horse_armor", "golden_horse_armor",
+    "diamond_horse_armor", "leather_horse_armor" since 451, "lead", "name_tag",
+    "command_block_minecart", "mutton", "cooked_mutton", "white_banner", "orange_banner",
+    "magenta_banner", "light_blue_banner", "yellow_banner", "lime_banner", "pink_banner",
+    "gray_banner", "light_gray_banner", "cyan_banner", "purple_banner", "blue_banner",
+    "brown_banner", "green_banner", "red_banner", "black_banner", "end_crystal", "chorus_fruit",
+    "popped_chorus_fruit", "beetroot", "beetroot_seeds", "beetroot_soup", "dragon_breath",
+    "splash_potion", "spectral_arrow", "tipped_arrow", "lingering_potion", "shield", "elytra",
+    "spruce_boat", "birch_boat", "jungle_boat", "acacia_boat", "dark_oak_boat", "totem_of_undying",
+    "shulker_shell", "iron_nugget", "knowledge_book", "debug_stick", "music_disc_13",
+    "music_disc_cat", "music_disc_blocks", "music_disc_chirp", "music_disc_far", "music_disc_mall",
+    "music_disc_mellohi", "music_disc_stal", "music_disc_strad", "music_disc_ward", "music_disc_11",
+    "music_disc_wait", "music_disc_pigstep" since 735, "trident", "phantom_membrane",
+    "nautilus_shell", "heart_of_the_sea", "crossbow" since 451, "suspicious_stew" since 451,
+    "loom" since 451, "flower_banner_pattern" since 451, "creeper_banner_pattern" since 451,
+    "skull_banner_pattern" since 451, "mojang_banner_pattern" since 451,
+    "globe_banner_pattern" since 451, "piglin_banner_pattern" since 735, "composter" since 451,
+    "barrel" since 451, "smoker" since 451, "blast_furnace" since 451,
+    "cartography_table" since 451, "fletching_table" since 451, "grindstone" since 451,
+    "lectern" since 451, "smithing_table" since 451, "stonecutter" since 451, "bell" since 451,
+    "lantern" since 451, "soul_lantern" since 735, "sweet_berries" since 451, "campfire" since 451,
+    "soul_campfire" since 735, "shroomlight" since 735, "honeycomb" since 573, "bee_nest" since 573,
+    "beehive" since 573, "honey_bottle" since 573, "honey_block" since 573,
+    "honeycomb_block" since 573, "lodestone" since 735, "netherite_block" since 735,
+    "ancient_debris" since 735, "target" since 735, "crying_obsidian" since 735,
+    "blackstone" since 735, "blackstone_slab" since 735, "blackstone_stairs" since 735,
+    "gilded_blackstone" since 735, "polished_blackstone" since 735,
+    "polished_blackstone_slab" since 735, "polished_blackstone_stairs" since 735,
+    "chiseled_polished_blackstone" since 735, "polished_blackstone_bricks" since 735,
+    "polished_blackstone_brick_slab" since 735, "polished_blackstone_brick_stairs" since 735,
+    "cracked_polished_blackstone_bricks" since 735, "respawn_anchor" since 735,
+};
+
+// 1.17 regrouped the items, so it starts over.
+#[rustfmt::skip]
+const ITEMS_1_17: &[FlatItem] = flat_items! {
+    "air", "stone", "granite", "polished_granite", "diorite", "polished_diorite", "andesite",
+    "polished_andesite", "deepslate", "cobbled_deepslate", "polished_deepslate", "calcite", "tuff",
+    "dripstone_block", "grass_block", "dirt", "coarse_dirt", "podzol", "rooted_dirt",
+    "crimson_nylium", "warped_nylium", "cobblestone", "oak_planks", "spruce_planks", "birch_planks",
+    "jungle_planks", "acacia_planks", "dark_oak_planks", "crimson_planks", "warped_planks",
+    "oak_sapling", "spruce_sapling", "birch_sapling", "jungle_sapling", "acacia_sapling",
+    "dark_oak_sapling", "bedrock", "sand", "red_sand", "gravel", "coal_ore", "deepslate_coal_ore",
+    "iron_ore", "deepslate_iron_ore", "copper_ore", "deepslate_copper_ore", "gold_ore",
+    "deepslate_gold_ore", "redstone_ore", "deepslate_redstone_ore", "emerald_ore",
+    "deepslate_emerald_ore", "lapis_ore", "deepslate_lapis_ore", "diamond_ore",
+    "deepslate_diamond_ore", "nether_gold_ore", "nether_quartz_ore", "ancient_debris", "coal_block",
+    "raw_iron_block", "raw_copper_block", "raw_gold_block", "amethyst_block", "budding_amethyst",
+    "iron_block", "copper_block", "gold_block", "diamond_block", "netherite_block",
+    "exposed_copper", "weathered_copper", "oxidized_copper", "cut_copper", "exposed_cut_copper",
+    "weathered_cut_copper", "oxidized_cut_copper", "cut_copper_stairs", "exposed_cut_copper_stairs",
+    "weathered_cut_copper_stairs", "oxidized_cut_copper_stairs", "cut_copper_slab",
+    "exposed_cut_copper_slab", "weathered_cut_copper_slab", "oxidized_cut_copper_slab",
+    "waxed_copper_block", "waxed_exposed_copper", "waxed_weathered_copper", "waxed_oxidized_copper",
+    "waxed_cut_copper", "waxed_exposed_cut_copper", "waxed_weathered_cut_copper",
+    "waxed_oxidized_cut_copper", "waxed_cut_copper_stairs", "waxed_exposed_cut_copper_stairs",
+    "waxed_weathered_cut_copper_stairs", "waxed_oxidized_cut_copper_stairs",
+    "waxed_cut_copper_slab", "waxed_exposed_cut_copper_slab", "waxed_weathered_cut_copper_slab",
+    "waxed_oxidized_cut_copper_slab", "oak_log", "spruce_log", "birch_log", "jungle_log",
+    "acacia_log", "dark_oak_log", "crimson_stem", "warped_stem", "stripped_oak_log",
+    "stripped_spruce_log", "stripped_birch_log", "stripped_jungle_log", "stripped_acacia_log",
+    "stripped_dark_oak_log", "stripped_crimson_stem", "stripped_warped_stem", "stripped_oak_wood",
+    "stripped_spruce_wood", "stripped_birch_wood", "stripped_jungle_wood", "stripped_acacia_wood",
+    "stripped_dark_oak_wood", "stripped_crimson_hyphae", "stripped_warped_hyphae", "oak_wood",
+    "spruce_wood", "birch_wood", "jungle_wood", "acacia_wood", "dark_oak_wood", "crimson_hyphae",
+    "warped_hyphae", "oak_leaves", "spruce_leaves", "birch_leaves", "jungle_leaves",
+    "acacia_leaves", "dark_oak_leaves", "azalea_leaves", "flowering_azalea_leaves", "sponge",
+    "wet_sponge", "glass", "tinted_glass", "lapis_block", "sandstone", "chiseled_sandstone",
+    "cut_sandstone", "cobweb", "grass", "fern", "azalea", "flowering_azalea", "dead_bush",
+    "seagrass", "sea_pickle", "white_wool", "orange_wool", "magenta_wool", "light_blue_wool",
+    "yellow_wool", "lime_wool", "pink_wool", "gray_wool", "light_gray_wool", "cyan_wool",
+    "purple_wool", "blue_wool", "brown_wool", "green_wool", "red_wool", "black_wool", "dandelion",
+    "poppy", "blue_orchid", "allium", "azure_bluet", "red_tulip", "orange_tulip", "white_tulip",
+    "pink_tulip", "oxeye_daisy", "cornflower", "lily_of_the_valley", "wither_rose", "spore_blossom",
+    "brown_mushroom", "red_mushroom", "crimson_fungus", "warped_fungus", "crimson_roots",
+    "warped_roots", "nether_sprouts", "weeping_vines", "twisting_vines", "sugar_cane", "kelp",
+    "moss_carpet", "moss_block", "hanging_roots", "big_dripleaf", "small_dripleaf", "bamboo",
+    "oak_slab", "spruce_slab", "birch_slab", "jungle_slab", "acacia_slab", "dark_oak_slab",
+    "crimson_slab", "warped_slab", "stone_slab", "smooth_stone_slab", "sandstone_slab",
+    "cut_sandstone_slab", "petrified_oak_slab", "cobblestone_slab", "brick_slab",
+    "stone_brick_slab", "nether_brick_slab", "quartz_slab", "red_sandstone_slab",
+    "cut_red_sandstone_slab", "purpur_slab", "prismarine_slab", "prismarine_brick_slab",
+    "dark_prismarine_slab", "smooth_quartz", "smooth_red_sandstone", "smooth_sandstone",
+    "smooth_stone", "bricks", "bookshelf", "mossy_cobblestone", "obsidian", "torch", "end_rod",
+    "chorus_plant", "chorus_flower", "purpur_block", "purpur_pillar", "purpur_stairs", "spawner",
+    "oak_stairs", "chest", "crafting_table", "farmland", "furnace", "ladder", "cobblestone_stairs",
+    "snow", "ice", "snow_block", "cactus", "clay", "jukebox", "oak_fence", "spruce_fence",
+    "birch_fence", "jungle_fence", "acacia_fence", "dark_oak_fence", "crimson_fence",
+    "warped_fence", "pumpkin", "carved_pumpkin", "jack_o_lantern", "netherrack", "soul_sand",
+    "soul_soil", "basalt", "polished_basalt", "smooth_basalt", "soul_torch", "glowstone",
+    "infested_stone", "infested_cobblestone", "infested_stone_bricks",
+    "infested_mossy_stone_bricks", "infested_cracked_stone_bricks",
+    "infested_chiseled_stone_bricks", "infested_deepslate", "stone_bricks", "mossy_stone_bricks",
+    "cracked_stone_bricks", "chiseled_stone_bricks", "deepslate_bricks", "cracked_deepslate_bricks",
+    "deepslate_tiles", "cracked_deepslate_tiles", "chiseled_deepslate", "brown_mushroom_block",
+    "red_mushroom_block", "mushroom_stem", "iron_bars", "chain", "glass_pane", "melon", "vine",
+    "glow_lichen", "brick_stairs", "stone_brick_stairs", "mycelium", "lily_pad", "nether_bricks",
+    "cracked_nether_bricks", "chiseled_nether_bricks", "nether_brick_fence", "nether_brick_stairs",
+    "enchanting_table", "end_portal_frame", "end_stone", "end_stone_bricks", "dragon_egg",
+    "sandstone_stairs", "ender_chest", "emerald_block", "spruce_stairs", "birch_stairs",
+    "jungle_stairs", "crimson_stairs", "warped_stairs", "command_block", "beacon",
+    "cobblestone_wall", "mossy_cobblestone_wall", "brick_wall", "prismarine_wall",
+    "red_sandstone_wall", "mossy_stone_brick_wall", "granite_wall", "stone_brick_wall",
+    "nether_brick_wall", "andesite_wall", "red_nether_brick_wall", "sandstone_wall",
+    "end_stone_brick_wall", "diorite_wall", "blackstone_wall", "polished_blackstone_wall",
+    "polished_blackstone_brick_wall", "cobbled_deepslate_wall", "polished_deepslate_wall",
+    "deepslate_brick_wall", "deepslate_tile_wall", "anvil", "chipped_anvil", "damaged_anvil",
+    "chiseled_quartz_block", "quartz_block", "quartz_bricks", "quartz_pillar", "quartz_stairs",
+    "white_terracotta", "orange_terracotta", "magenta_terracotta", "light_blue_terracotta",
+    "yellow_terracotta", "lime_terracotta", "pink_terracotta", "gray_terracotta",
+    "light_gray_terracotta", "cyan_terracotta", "purple_terracotta", "blue_terracotta",
+    "brown_terracotta", "green_terracotta", "red_terracotta", "black_terracotta", "barrier",
+    "light", "hay_block", "white_carpet", "orange_carpet", "magenta_carpet", "light_blue_carpet",
+    "yellow_carpet", "lime_carpet", "pink_carpet", "gray_carpet", "light_gray_carpet",
+    "cyan_carpet", "purple_carpet", "blue_carpet", "brown_carpet", "green_carpet", "red_carpet",
+    "black_carpet", "terracotta", "packed_ice", "acacia_stairs", "dark_oak_stairs", "dirt_path",
+    "sunflower", "lilac", "rose_bush", "peony", "tall_grass", "large_fern", "white_stained_glass",
+    "orange_stained_glass", "magenta_stained_glass", "light_blue_stained_glass",
+    "yellow_stained_glass", "lime_stained_glass", "pink_stained_glass", "gray_stained_glass",
+    "light_gray_stained_glass", "cyan_stained_glass", "purple_stained_glass", "blue_stained_glass",
+    "brown_stained_glass", "green_stained_glass", "red_stained_glass", "black_stained_glass",
+    "white_stained_glass_pane", "orange_stained_glass_pane", "magenta_stained_glass_pane",
+    "light_blue_stained_glass_pane", "yellow_stained_glass_pane", "lime_stained_glass_pane",
+    "pink_stained_glass_pane", "gray_stained_glass_pane", "light_gray_stained_glass_pane",
+    "cyan_stained_glass_pane", "purple_stained_glass_pane", "blue_stained_glass_pane",
+    "brown_stained_glass_pane", "green_stained_glass_pane", "red_stained_glass_pane",
+    "black_stained_glass_pane", "prismarine", "prismarine_bricks", "dark_prismarine",
+    "prismarine_stairs", "prismarine_brick_stairs", "dark_prismarine_stairs", "sea_lantern",
+    "red_sandstone", "chiseled_red_sandstone", "cut_red_sandstone", "red_sandstone_stairs",
+    "repeating_command_block", "chain_command_block", "magma_block", "nether_wart_block",
+    "warped_wart_block", "red_nether_bricks", "bone_block", "structure_void", "shulker_box",
+    "white_shulker_box", "orange_shulker_box", "magenta_shulker_box", "light_blue_shulker_box",
+    "yellow_shulker_box", "lime_shulker_box", "pink_shulker_box", "gray_shulker_box",
+    "light_gray_shulker_box", "cyan_shulker_box", "purple_shulker_box", "blue_shulker_box",
+    "brown_shulker_box", "green_shulker_box", "red_shulker_box", "black_shulker_box",
+    "white_glazed_terracotta", "orange_glazed_terracotta", "magenta_glazed_terracotta",
+    "light_blue_glazed_terracotta", "yellow_glazed_terracotta", "lime_glazed_terracotta",
+    "pink_glazed_terracotta", "gray_glazed_terracotta", "light_gray_glazed_terracotta",
+    "cyan_glazed_terracotta", "purple_glazed_terracotta", "blue_glazed_terracotta",
+    "brown_glazed_terracotta", "green_glazed_terracotta", "red_glazed_terracotta",
+    "black_glazed_terracotta", "white_concrete", "orange_concrete", "magenta_concrete",
+    "light_blue_concrete", "yellow_concrete", "lime_concrete", "pink_concrete", "gray_concrete",
+    "light_gray_concrete", "cyan_concrete", "purple_concrete", "blue_concrete", "brown_concrete",
+    "green_concrete", "red_concrete", "black_concrete", "white_concrete_powder",
+    "orange_concrete_powder", "magenta_concrete_powder", "light_blue_concrete_powder",
+    "yellow_concrete_powder", "lime_concrete_powder", "pink_concrete_powder",
+    "gray_concrete_powder", "light_gray_concrete_powder", "cyan_concrete_powder",
+    "purple_concrete_powder", "blue_concrete_powder", "brown_concrete_powder",
+    "green_concrete_powder", "red_concrete_powder", "black_concrete_powder", "turtle_egg",
+    "dead_tube_coral_block", "dead_brain_coral_block", "dead_bubble_coral_block",
+    "dead_fire_coral_block", "dead_horn_coral_block", "tube_coral_block", "brain_coral_block",
+    "bubble_coral_block", "fire_coral_block", "horn_coral_block", "tube_coral", "brain_coral",
+    "bubble_coral", "fire_coral", "horn_coral", "dead_brain_coral", "dead_bubble_coral",
+    "dead_fire_coral", "dead_horn_coral", "dead_tube_coral", "tube_coral_fan", "brain_coral_fan",
+    "bubble_coral_fan", "fire_coral_fan", "horn_coral_fan", "dead_tube_coral_fan",
+    "dead_brain_coral_fan", "dead_bubble_coral_fan", "dead_fire_coral_fan", "dead_horn_coral_fan",
+    "blue_ice", "conduit", "polished_granite_stairs", "smooth_red_sandstone_stairs",
+    "mossy_stone_brick_stairs", "polished_diorite_stairs", "mossy_cobblestone_stairs",
+    "end_stone_brick_stairs", "stone_stairs", "smooth_sandstone_stairs", "smooth_quartz_stairs",
+    "granite_stairs", "andesite_stairs", "red_nether_brick_stairs", "polished_andesite_stairs",
+    "diorite_stairs", "cobbled_deepslate_stairs", "polished_deepslate_stairs",
+    "deepslate_brick_stairs", "deepslate_tile_stairs", "polished_granite_slab",
+    "smooth_red_sandstone_slab", "mossy_stone_brick_slab", "polished_diorite_slab",
+    "mossy_cobblestone_slab", "end_stone_brick_slab", "smooth_sandstone_slab", "smooth_quartz_slab",
+    "granite_slab", "andesite_slab", "red_nether_brick_slab", "polished_andesite_slab",
+    "diorite_slab", "cobbled_deepslate_slab", "polished_deepslate_slab", "deepslate_brick_slab",
+    "deepslate_tile_slab", "scaffolding", "redstone", "redstone_torch", "redstone_block",
+    "repeater", "comparator", "piston", "sticky_piston", "slime_block", "honey_block", "observer",
+    "hopper", "dispenser", "dropper", "lectern", "target", "lever", "lightning_rod",
+    "daylight_detector", "sculk_sensor", "tripwire_hook", "trapped_chest", "tnt", "redstone_lamp",
+    "note_block", "stone_button", "polished_blackstone_button", "oak_button", "spruce_button",
+    "birch_button", "jungle_button", "acacia_button", "dark_oak_button", "crimson_button",
+    "warped_button", "stone_pressure_plate", "polished_blackstone_pressure_plate",
+    "light_weighted_pressure_plate", "heavy_weighted_pressure_plate", "oak_pressure_plate",
+    "spruce_pressure_plate", "birch_pressure_plate", "jungle_pressure_plate",
+    "acacia_pressure_plate", "dark_oak_pressure_plate", "crimson_pressure_plate",
+    "warped_pressure_plate", "iron_door", "oak_door", "spruce_door", "birch_door", "jungle_door",
+    "acacia_door", "dark_oak_door", "crimson_door", "warped_door", "iron_trapdoor", "oak_trapdoor",
+    "spruce_trapdoor", "birch_trapdoor", "jungle_trapdoor", "acacia_trapdoor", "dark_oak_trapdoor",
+    "crimson_trapdoor", "warped_trapdoor", "oak_fence_gate", "spruce_fence_gate",
+    "birch_fence_gate", "jungle_fence_gate", "acacia_fence_gate", "dark_oak_fence_gate",
+    "crimson_fence_gate", "warped_fence_gate", "powered_rail", "detector_rail", "rail",
+    "activator_rail", "saddle", "minecart", "chest_minecart", "furnace_minecart", "tnt_minecart",
+    "hopper_minecart", "carrot_on_a_stick", "warped_fungus_on_a_stick", "elytra", "oak_boat",
+    "spruce_boat", "birch_boat", "jungle_boat", "acacia_boat", "dark_oak_boat", "structure_block",
+    "jigsaw", "turtle_helmet", "scute", "flint_and_steel", "apple", "bow", "arrow", "coal",
+    "charcoal", "diamond", "emerald", "lapis_lazuli", "quartz", "amethyst_shard", "raw_iron",
+    "iron_ingot", "raw_copper", "copper_ingot", "raw_gold", "gold_ingot", "netherite_ingot",
+    "netherite_scrap", "wooden_sword", "wooden_shovel", "wooden_pickaxe", "wooden_axe",
+    "wooden_hoe", "stone_sword", "stone_shovel", "stone_pickaxe", "stone_axe", "stone_hoe",
+    "golden_sword", "golden_shovel", "golden_pickaxe", "golden_axe", "golden_hoe", "iron_sword",
+    "iron_shovel", "iron_pickaxe", "iron_axe", "iron_hoe", "diamond_sword", "diamond_shovel",
+    "diamond_pickaxe", "diamond_axe", "diamond_hoe", "netherite_sword", "netherite_shovel",
+    "netherite_pickaxe", "netherite_axe", "netherite_hoe", "stick", "bowl", "mushroom_stew",
+    "string", "feather", "gunpowder", "wheat_seeds", "wheat", "bread", "leather_helmet",
+    "leather_chestplate", "leather_leggings", "leather_boots", "chainmail_helmet",
+    "chainmail_chestplate", "chainmail_leggings", "chainmail_boots", "iron_helmet",
+    "iron_chestplate", "iron_leggings", "iron_boots", "diamond_helmet", "diamond_chestplate",
+    "diamond_leggings", "diamond_boots", "golden_helmet", "golden_chestplate", "golden_leggings",
+    "golden_boots", "netherite_helmet", "netherite_chestplate", "netherite_leggings",
+    "netherite_boots", "flint", "porkchop", "cooked_porkchop", "painting", "golden_apple",
+    "enchanted_golden_apple", "oak_sign", "spruce_sign", "birch_sign", "jungle_sign", "acacia_sign",
+    "dark_oak_sign", "crimson_sign", "warped_sign", "bucket", "water_bucket", "lava_bucket",
+    "powder_snow_bucket", "snowball", "leather", "milk_bucket", "pufferfish_bucket",
+    "salmon_bucket", "cod_bucket", "tropical_fish_bucket", "axolotl_bucket", "brick", "clay_ball",
+    "dried_kelp_block", "paper", "book", "slime_ball", "egg", "compass", "bundle", "fishing_rod",
+    "clock", "spyglass", "glowstone_dust", "cod", "salmon", "tropical_fish", "pufferfish",
+    "cooked_cod", "cooked_salmon", "ink_sac", "glow_ink_sac", "cocoa_beans", "white_dye",
+    "orange_dye", "magenta_dye", "light_blue_dye", "yellow_dye", "lime_dye", "pink_dye", "gray_dye",
+    "light_gray_dye", "cyan_dye", "purple_dye", "blue_dye", "brown_dye", "green_dye", "red_dye",
+    "black_dye", "bone_meal", "bone", "sugar", "cake", "white_bed", "orange_bed", "magenta_bed",
+    "light_blue_bed", "yellow_bed", "lime_bed", "pink_bed", "gray_bed", "light_gray_bed",
+    "cyan_bed", "purple_bed", "blue_bed", "brown_bed", "green_bed", "red_bed", "black_bed",
+    "cookie", "filled_map", "shears", "melon_slice", "dried_kelp", "pumpkin_seeds", "melon_seeds",
+    "beef", "cooked_beef", "chicken", "cooked_chicken", "rotten_flesh", "ender_pearl", "blaze_rod",
+    "ghast_tear", "gold_nugget", "nether_wart", "potion", "glass_bottle", "spider_eye",
+    "fermented_spider_eye", "blaze_powder", "magma_cream", "brewing_stand", "cauldron", "ender_eye",
+    "glistering_melon_slice", "axolotl_spawn_egg", "bat_spawn_egg", "bee_spawn_egg",
+    "blaze_spawn_egg", "cat_spawn_egg", "cave_spider_spawn_egg", "chicken_spawn_egg",
+    "cod_spawn_egg", "cow_spawn_egg", "creeper_spawn_egg", "dolphin_spawn_egg", "donkey_spawn_egg",
+    "drowned_spawn_egg", "elder_guardian_spawn_egg", "enderman_spawn_egg", "endermite_spawn_egg",
+    "evoker_spawn_egg", "fox_spawn_egg", "ghast_spawn_egg", "glow_squid_spawn_egg",
+    "goat_spawn_egg", "guardian_spawn_egg", "hoglin_spawn_egg", "horse_spawn_egg", "husk_spawn_egg",
+    "llama_spawn_egg", "magma_cube_spawn_egg", "mooshroom_spawn_egg", "mule_spawn_egg",
+    "ocelot_spawn_egg", "panda_spawn_egg", "parrot_spawn_egg", "phantom_spawn_egg", "pig_spawn_egg",
+    "piglin_spawn_egg", "piglin_brute_spawn_egg", "pillager_spawn_egg", "polar_bear_spawn_egg",
+    "pufferfish_spawn_egg", "rabbit_spawn_egg", "ravager_spawn_egg", "salmon_spawn_egg",
+    "sheep_spawn_egg", "shulker_spawn_egg", "silverfish_spawn_egg", "skeleton_spawn_egg",
+    "skeleton_horse_spawn_egg", "slime_spawn_egg", "spider_spawn_egg", "squid_spawn_egg",
+    "stray_spawn_egg", "strider_spawn_egg", "trader_llama_spawn_egg", "tropical_fish_spawn_egg",
+    "turtle_spawn_egg", "vex_spawn_egg", "villager_spawn_egg", "vindicator_spawn_egg",
+    "wandering_trader_spawn_egg", "witch_spawn_egg", "wither_skeleton_spawn_egg", "wolf_spawn_egg",
+    "zoglin_spawn_egg", "zombie_spawn_egg", "zombie_horse_spawn_egg", "zombie_villager_spawn_egg",
+    "zombified_piglin_spawn_egg", "experience_bottle", "fire_charge", "writable_book",
+    "written_book", "item_frame", "glow_item_frame", "flower_pot", "carrot", "potato",
+    "baked_potato", "poisonous_potato", "map", "golden_carrot", "skeleton_skull",
+    "wither_skeleton_skull", "player_head", "zombie_head", "creeper_head", "dragon_head",
+    "nether_star", "pumpkin_pie", "firework_rocket", "firework_star", "enchanted_book",
+    "nether_brick", "prismarine_shard", "prismarine_crystals", "rabbit", "cooked_rabbit",
+    "rabbit_stew", "rabbit_foot", "rabbit_hide", "armor_stand", "iron_horse_armor",
+    "golden_horse_armor", "diamond_horse_armor", "leather_horse_armor", "lead", "name_tag",
+    "command_block_minecart", "mutton", "cooked_mutton", "white_banner", "orange_banner",
+    "magenta_banner", "light_blue_banner", "yellow_banner", "lime_banner", "pink_banner",
+    "gray_banner", "light_gray_banner", "cyan_banner", "purple_banner", "blue_banner",
+    "brown_banner", "green_banner", "red_banner", "black_banner", "end_crystal", "chorus_fruit",
+    "popped_chorus_fruit", "beetroot", "beetroot_seeds", "beetroot_soup", "dragon_breath",
+    "splash_potion", "spectral_arrow", "tipped_arrow", "lingering_potion", "shield",
+    "totem_of_undying", "shulker_shell", "iron_nugget", "knowledge_book", "debug_stick",
+    "music_disc_13", "music_disc_cat", "music_disc_blocks", "music_disc_chirp", "music_disc_far",
+    "music_disc_mall", "music_disc_mellohi", "music_disc_stal", "music_disc_strad",
+    "music_disc_ward", "music_disc_11", "music_disc_wait", "music_disc_otherside" since 757,
+    "music_disc_pigstep", "trident", "phantom_membrane", "nautilus_shell", "heart_of_the_sea",
+    "crossbow", "suspicious_stew", "loom", "flower_banner_pattern", "creeper_banner_pattern",
+    "skull_banner_pattern", "mojang_banner_pattern", "globe_banner_pattern",
+    "piglin_banner_pattern", "composter", "barrel", "smoker", "blast_furnace", "cartography_table",
+    "fletching_table", "grindstone", "smithing_table", "stonecutter", "bell", "lantern",
+    "soul_lantern", "sweet_berries", "glow_berries", "campfire", "soul_campfire", "shroomlight",
+    "honeycomb", "bee_nest", "beehive", "honey_bottle", "honeycomb_block", "lodestone",
+    "crying_obsidian", "blackstone", "blackstone_slab", "blackstone_stairs", "gilded_blackstone",
+    "polished_blackstone", "polished_blackstone_slab", "polished_blackstone_stairs",
+    "chiseled_polished_blackstone", "polished_blackstone_bricks", "polished_blackstone_brick_slab",
+    "polished_blackstone_brick_stairs", "cracked_polished_blackstone_bricks", "respawn_anchor",
+    "candle", "white_candle", "orange_candle", "magenta_candle", "light_blue_candle",
+    "yellow_candle", "lime_candle", "pink_candle", "gray_candle", "light_gray_candle",
+    "cyan_candle", "purple_candle", "blue_candle", "brown_candle", "green_candle", "red_candle",
+    "black_candle", "small_amethyst_bud", "medium_amethyst_bud", "large_amethyst_bud",
+    "amethyst_cluster", "pointed_dripstone",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(id: isize, damage: Option<isize>) -> item::Stack {
+        item::Stack {
+            id,
+            count: 1,
+            damage,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flat_names() {
+        let items = ItemRegistry::new(404);
+        assert_eq!(items.name(0), Some("air"));
+        assert_eq!(items.name(1), Some("stone"));
+        assert_eq!(items.name(472), Some("iron_shovel"));
+        assert_eq!(items.name(-1), None);
+        assert_eq!(items.name(10000), None);
+
+        // Ids move as items are added before them
+        let items = ItemRegistry::new(754);
+        assert_eq!(items.name(12), Some("crimson_nylium"));
+        assert_eq!(items.name(597), Some("diamond_pickaxe"));
+
+        let items = ItemRegistry::new(758);
+        assert_eq!(items.name(8), Some("deepslate"));
+        assert_eq!(items.name(721), Some("diamond_pickaxe"));
+    }
+
+    #[test]
+    fn renamed_items() {
+        let id = |version, name| {
+            let items = ItemRegistry::new(version);
+            (0..2000).find(|&id| items.name(id) == Some(name))
+        };
+        assert!(id(404, "rose_red").is_some());
+        assert!(id(477, "rose_red").is_none());
+        assert!(id(477, "red_dye").is_some());
+        assert!(id(578, "zombie_pigman_spawn_egg").is_some());
+        assert!(id(735, "zombified_piglin_spawn_egg").is_some());
+        assert!(id(751, "piglin_brute_spawn_egg").is_some());
+        assert!(id(736, "piglin_brute_spawn_egg").is_none());
+        assert!(id(754, "grass_path").is_some());
+        assert!(id(756, "dirt_path").is_some());
+        assert!(id(756, "music_disc_otherside").is_none());
+        assert!(id(757, "music_disc_otherside").is_some());
+    }
+
+    #[test]
+    fn unique_names() {
+        for &version in crate::protocol::SUPPORTED_PROTOCOLS.iter() {
+            let names = super::flat_names(version);
+            let unique: std::collections::HashSet<_> = names.iter().collect();
+            assert_eq!(unique.len(), names.len(), "protocol version {}", version);
+        }
+    }
+
+    #[test]
+    fn legacy_names() {
+        let items = ItemRegistry::new(340);
+        assert_eq!(items.name(1), Some("stone"));
+        assert_eq!(items.name(255), Some("structure_block"));
+        assert_eq!(items.name(256), Some("iron_shovel"));
+        assert_eq!(items.name(359), Some("shears"));
+        assert_eq!(items.name(398), Some("carrot_on_a_stick"));
+        assert_eq!(items.name(451), None);
+        assert_eq!(items.name(453), Some("knowledge_book"));
+        assert_eq!(items.name(2267), Some("record_wait"));
+        assert_eq!(items.name(-1), None);
+    }
+
+    #[test]
+    fn model_names() {
+        let items = ItemRegistry::new(340);
+        let model = |id, damage| items.model_name(&stack(id, Some(damage)));
+        assert_eq!(model(35, 14).as_deref(), Some("red_wool"));
+        assert_eq!(model(162, 1).as_deref(), Some("dark_oak_log"));
+        assert_eq!(model(351, 0).as_deref(), Some("dye_black"));
+        assert_eq!(model(278, 12).as_deref(), Some("diamond_pickaxe"));
+
+        let items = ItemRegistry::new(758);
+        let model = |name| {
+            (0..2000)
+                .find(|&id| items.name(id) == Some(name))
+                .and_then(|id| items.model_name(&stack(id, None)))
+        };
+        assert_eq!(
+            model("light_gray_terracotta").as_deref(),
+            Some("silver_stained_hardened_clay")
+        );
+        assert_eq!(
+            model("light_gray_glazed_terracotta").as_deref(),
+            Some("silver_glazed_terracotta")
+        );
+        assert_eq!(model("red_dye").as_deref(), Some("dye_red"));
+        assert_eq!(model("oak_planks").as_deref(), Some("oak_planks"));
+    }
+
+    #[test]
+    fn tools_by_name() {
+        use crate::inventory::tool_for_item;
+        use crate::world::block::{Tool, ToolMaterial};
+        let items = ItemRegistry::new(758);
+        let tool = |id| tool_for_item(&items, Some(&stack(id, None)));
+        assert_eq!(tool(721), Some(Tool::Pickaxe(ToolMaterial::Diamond)));
+        assert_eq!(tool(848), Some(Tool::Shears));
+        assert_eq!(tool(212), None);
+        assert_eq!(tool(5000), None);
+
+        // Older versions keep their ids
+        let items = ItemRegistry::new(340);
+        assert_eq!(
+            tool_for_item(&items, Some(&stack(278, Some(0)))),
+            Some(Tool::Pickaxe(ToolMaterial::Diamond))
+        );
+        assert_eq!(
+            tool_for_item(&items, Some(&stack(285, Some(0)))),
+            Some(Tool::Pickaxe(ToolMaterial::Gold))
+        );
+    }
+}
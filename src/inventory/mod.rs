@@ -0,0 +1,325 @@
+//! Client side copy of the player's inventory and any open container.
+//!
+//! The server is authoritative over the contents of every window. Clicks
+//! are predicted locally so the inventory responds instantly and the
+//! server corrects any mistakes through slot updates afterwards.
+
+mod click;
+mod items;
+
+pub use self::click::{Click, ClickAction};
+pub use self::items::ItemRegistry;
+
+use crate::format;
+use crate::item;
+use crate::world::block;
+use std::collections::HashMap;
+
+/// The window id the server uses for the player's own inventory.
+pub const PLAYER_INVENTORY_ID: u8 = 0;
+/// The slot number used for clicks outside of the window.
+pub const OUTSIDE_SLOT: i16 = -999;
+
+/// Slots of the player inventory which appear at the end of every
+/// container window (main inventory followed by the hotbar).
+const PLAYER_SLOTS: usize = 36;
+/// The index of the first main inventory slot in the player window.
+const PLAYER_MAIN_START: usize = 9;
+/// The slot of the off hand in the player window, after the hotbar.
+const OFF_HAND_SLOT: i16 = 45;
+
+pub struct Window {
+    pub id: u8,
+    pub title: Option<format::Component>,
+    pub slots: Vec<Option<item::Stack>>,
+    pub properties: HashMap<i16, i16>,
+    next_action: i16,
+}
+
+impl Window {
+    fn new(id: u8, title: Option<format::Component>, size: usize) -> Window {
+        Window {
+            id,
+            title,
+            slots: vec![None; size],
+            properties: HashMap::new(),
+            next_action: 0,
+        }
+    }
+
+    /// Returns the number of slots which belong to the container itself,
+    /// excluding the player inventory shown below it.
+    pub fn container_size(&self) -> usize {
+        if self.id == PLAYER_INVENTORY_ID {
+            self.slots.len()
+        } else {
+            self.slots.len().saturating_sub(PLAYER_SLOTS)
+        }
+    }
+
+    /// Returns the window slot of the given hotbar index.
+    pub fn hotbar_slot(&self, index: u8) -> usize {
+        if self.id == PLAYER_INVENTORY_ID {
+            PLAYER_MAIN_START + 27 + index as usize
+        } else {
+            self.container_size() + 27 + index as usize
+        }
+    }
+
+    pub fn get(&self, slot: i16) -> Option<&item::Stack> {
+        if slot < 0 {
+            return None;
+        }
+        self.slots.get(slot as usize).and_then(|v| v.as_ref())
+    }
+
+    fn set(&mut self, slot: i16, item: Option<item::Stack>) {
+        if slot < 0 {
+            return;
+        }
+        let slot = slot as usize;
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, None);
+        }
+        self.slots[slot] = item;
+    }
+
+    fn next_action_number(&mut self) -> i16 {
+        self.next_action = self.next_action.wrapping_add(1);
+        self.next_action
+    }
+}
+
+pub struct InventoryContext {
+    pub player_inventory: Window,
+    pub open_window: Option<Window>,
+    /// Whether the player has their own inventory open. The server
+    /// isn't told about this until the inventory is closed.
+    pub player_inventory_open: bool,
+    pub cursor: Option<item::Stack>,
+    /// The selected hotbar slot, 0 to 8
+    pub hotbar_index: u8,
+    pub items: ItemRegistry,
+    state_id: i32,
+    drag: Option<click::Drag>,
+}
+
+impl InventoryContext {
+    pub fn new(protocol_version: i32) -> InventoryContext {
+        // Crafting output, crafting grid, armor, main inventory and hotbar.
+        // 1.9 added the off hand slot.
+        let size = if protocol_version >= 107 { 46 } else { 45 };
+        InventoryContext {
+            player_inventory: Window::new(PLAYER_INVENTORY_ID, None, size),
+            open_window: None,
+            player_inventory_open: false,
+            cursor: None,
+            hotbar_index: 0,
+            items: ItemRegistry::new(protocol_version),
+            state_id: 0,
+            drag: None,
+        }
+    }
+
+    /// Returns the item in the selected hotbar slot.
+    pub fn held_item(&self) -> Option<&item::Stack> {
+        let slot = self.player_inventory.hotbar_slot(self.hotbar_index);
+        self.player_inventory.get(slot as i16)
+    }
+
+    /// Returns the item in the off hand, which players have from 1.9.
+    pub fn off_hand_item(&self) -> Option<&item::Stack> {
+        self.player_inventory.get(OFF_HAND_SLOT)
+    }
+
+    /// Returns the items in the hotbar, from left to right.
+    pub fn hotbar(&self) -> impl Iterator<Item = Option<&item::Stack>> {
+        (0..9).map(move |index| {
+            let slot = self.player_inventory.hotbar_slot(index);
+            self.player_inventory.get(slot as i16)
+        })
+    }
+
+    /// Returns the window clicks currently apply to.
+    pub fn current_window(&self) -> &Window {
+        self.open_window.as_ref().unwrap_or(&self.player_inventory)
+    }
+
+    fn current_window_mut(&mut self) -> &mut Window {
+        self.open_window
+            .as_mut()
+            .unwrap_or(&mut self.player_inventory)
+    }
+
+    fn window_mut(&mut self, id: u8) -> Option<&mut Window> {
+        if id == PLAYER_INVENTORY_ID {
+            Some(&mut self.player_inventory)
+        } else {
+            self.open_window.as_mut().filter(|v| v.id == id)
+        }
+    }
+
+    /// Opens a container window. `slot_count` is the number of container
+    /// slots if the server sent it, 1.14+ only sizes the window when the
+    /// items are sent.
+    pub fn open(&mut self, id: u8, title: Option<format::Component>, slot_count: Option<usize>) {
+        let slot_count = slot_count.unwrap_or(0);
+        let mut window = Window::new(id, title, slot_count + PLAYER_SLOTS);
+        window.slots[slot_count..].clone_from_slice(
+            &self.player_inventory.slots[PLAYER_MAIN_START..PLAYER_MAIN_START + PLAYER_SLOTS],
+        );
+        self.open_window = Some(window);
+        self.player_inventory_open = false;
+        self.drag = None;
+    }
+
+    /// Closes the window with the given id after the server requested it.
+    pub fn on_close(&mut self, id: u8) {
+        if self.open_window.as_ref().map_or(false, |v| v.id == id) {
+            self.open_window = None;
+        }
+        if id == PLAYER_INVENTORY_ID {
+            self.player_inventory_open = false;
+        }
+        self.cursor = None;
+        self.drag = None;
+    }
+
+    /// Closes the current window returning the id to send to the server.
+    pub fn close(&mut self) -> Option<u8> {
+        let id = if let Some(window) = self.open_window.take() {
+            window.id
+        } else if self.player_inventory_open {
+            PLAYER_INVENTORY_ID
+        } else {
+            return None;
+        };
+        self.player_inventory_open = false;
+        self.cursor = None;
+        self.drag = None;
+        Some(id)
+    }
+
+    /// Opens the player's inventory or closes the current window, returning
+    /// the id of the window that has to be closed on the server.
+    pub fn toggle(&mut self) -> Option<u8> {
+        if self.open_window.is_some() || self.player_inventory_open {
+            self.close()
+        } else {
+            self.player_inventory_open = true;
+            None
+        }
+    }
+
+    pub fn set_items(&mut self, id: u8, items: Vec<Option<item::Stack>>, state_id: Option<i32>) {
+        if let Some(state_id) = state_id {
+            self.state_id = state_id;
+        }
+        if let Some(window) = self.window_mut(id) {
+            // Windows never shrink, a short list only covers the first slots
+            let size = window.slots.len().max(items.len());
+            window.slots = items;
+            window.slots.resize(size, None);
+        }
+        if id != PLAYER_INVENTORY_ID {
+            self.sync_from_container();
+        }
+    }
+
+    pub fn set_carried(&mut self, item: Option<item::Stack>) {
+        self.cursor = item;
+    }
+
+    pub fn set_slot(
+        &mut self,
+        id: u8,
+        slot: i16,
+        item: Option<item::Stack>,
+        state_id: Option<i32>,
+    ) {
+        if let Some(state_id) = state_id {
+            self.state_id = state_id;
+        }
+        match id as i8 {
+            // The cursor
+            -1 if slot == -1 => self.cursor = item,
+            // Sets a player inventory slot without the pickup animation
+            -2 => self.set_player_slot(slot, item),
+            _ => {
+                if id == PLAYER_INVENTORY_ID {
+                    self.set_player_slot(slot, item);
+                } else if let Some(window) = self.window_mut(id) {
+                    window.set(slot, item);
+                    self.sync_from_container();
+                }
+            }
+        }
+    }
+
+    pub fn set_property(&mut self, id: u8, property: i16, value: i16) {
+        if let Some(window) = self.window_mut(id) {
+            window.properties.insert(property, value);
+        }
+    }
+
+    /// Handles the server accepting or rejecting a click. Returns whether
+    /// the rejection has to be acknowledged.
+    pub fn on_confirm_transaction(&mut self, id: u8, accepted: bool) -> bool {
+        !accepted && self.window_mut(id).is_some()
+    }
+
+    /// Updates a slot of the player inventory, keeping the player section
+    /// of an open container in sync.
+    fn set_player_slot(&mut self, slot: i16, item: Option<item::Stack>) {
+        if let Some(window) = self.open_window.as_mut() {
+            let slot = slot as usize;
+            if (PLAYER_MAIN_START..PLAYER_MAIN_START + PLAYER_SLOTS).contains(&slot) {
+                let offset = window.container_size();
+                window.set((slot - PLAYER_MAIN_START + offset) as i16, item.clone());
+            }
+        }
+        self.player_inventory.set(slot, item);
+    }
+
+    /// Copies the player section of the open container back into the
+    /// player inventory.
+    fn sync_from_container(&mut self) {
+        if let Some(window) = self.open_window.as_ref() {
+            let offset = window.container_size();
+            if window.slots.len() >= offset + PLAYER_SLOTS {
+                self.player_inventory.slots[PLAYER_MAIN_START..PLAYER_MAIN_START + PLAYER_SLOTS]
+                    .clone_from_slice(&window.slots[offset..offset + PLAYER_SLOTS]);
+            }
+        }
+    }
+}
+
+/// Returns the tool an item acts as when breaking blocks.
+pub fn tool_for_item(items: &ItemRegistry, item: Option<&item::Stack>) -> Option<block::Tool> {
+    items.name(item?.id).and_then(tool_by_name)
+}
+
+fn tool_by_name(name: &str) -> Option<block::Tool> {
+    use self::block::{Tool, ToolMaterial::*};
+    if name == "shears" {
+        return Some(Tool::Shears);
+    }
+    let (material, kind) = name.split_once('_')?;
+    let material = match material {
+        "wooden" => Wood,
+        "stone" => Stone,
+        "golden" => Gold,
+        "iron" => Iron,
+        "diamond" => Diamond,
+        "netherite" => Netherite,
+        _ => return None,
+    };
+    Some(match kind {
+        "pickaxe" => Tool::Pickaxe(material),
+        "axe" => Tool::Axe(material),
+        "shovel" => Tool::Shovel(material),
+        "hoe" => Tool::Hoe(material),
+        "sword" => Tool::Sword(material),
+        _ => return None,
+    })
+}
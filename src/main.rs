@@ -28,17 +28,23 @@ extern crate steven_protocol;
 
 pub mod ecs;
 use steven_protocol::format;
+use steven_protocol::item;
 use steven_protocol::nbt;
 use steven_protocol::protocol;
 pub mod gl;
 use steven_protocol::types;
 pub mod auth;
+pub mod chat;
 pub mod chunk_builder;
+pub mod completion;
 pub mod console;
 pub mod entity;
+pub mod hud;
+pub mod inventory;
 pub mod model;
 pub mod render;
 pub mod resources;
+pub mod scoreboard;
 pub mod screen;
 pub mod server;
 pub mod settings;
@@ -69,11 +75,15 @@ pub struct Game {
     screen_sys: screen::ScreenSystem,
     resource_manager: Arc<RwLock<resources::Manager>>,
     console: Arc<Mutex<console::Console>>,
+    chat_overlay: chat::ChatOverlay,
+    hud: hud::Hud,
     vars: Rc<console::Vars>,
+    commands: Rc<console::Commands>,
     should_close: bool,
 
     server: server::Server,
     focused: bool,
+    death_screen_open: bool,
     chunk_builder: chunk_builder::ChunkBuilder,
 
     connect_reply: Option<mpsc::Receiver<Result<server::Server, protocol::Error>>>,
@@ -91,41 +101,45 @@ pub struct Game {
 
 impl Game {
     pub fn connect_to(&mut self, address: &str) {
-        let (protocol_version, forge_mods, fml_network_version) =
-            match protocol::Conn::new(address, self.default_protocol_version)
-                .and_then(|conn| conn.do_status())
-            {
-                Ok(res) => {
-                    info!(
-                        "Detected server protocol version {}",
-                        res.0.version.protocol
-                    );
-                    (
-                        res.0.version.protocol,
-                        res.0.forge_mods,
-                        res.0.fml_network_version,
-                    )
-                }
-                Err(err) => {
-                    warn!(
-                        "Error pinging server {} to get protocol version: {:?}, defaulting to {}",
-                        address, err, self.default_protocol_version
-                    );
-                    (self.default_protocol_version, vec![], None)
-                }
-            };
-
+        let resolver = settings::resolver(&self.vars);
         let (tx, rx) = mpsc::channel();
         self.connect_reply = Some(rx);
         let address = address.to_owned();
+        let default_protocol_version = self.default_protocol_version;
         let resources = self.resource_manager.clone();
         let profile = mojang::Profile {
             username: self.vars.get(auth::CL_USERNAME).clone(),
             id: self.vars.get(auth::CL_UUID).clone(),
             access_token: self.vars.get(auth::AUTH_TOKEN).clone(),
         };
+        // Pinging a slow server would otherwise freeze the window
         thread::spawn(move || {
+            let (protocol_version, forge_mods, fml_network_version) =
+                match protocol::Conn::connect_with(&resolver, &address, default_protocol_version)
+                    .and_then(|conn| conn.do_status())
+                {
+                    Ok(res) => {
+                        info!(
+                            "Detected server protocol version {}",
+                            res.0.version.protocol
+                        );
+                        (
+                            res.0.version.protocol,
+                            res.0.forge_mods,
+                            res.0.fml_network_version,
+                        )
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Error pinging server {} to get protocol version: {:?}, defaulting to {}",
+                            address, err, default_protocol_version
+                        );
+                        (default_protocol_version, vec![], None)
+                    }
+                };
+
             tx.send(server::Server::connect(
+                &resolver,
                 resources,
                 profile,
                 &address,
@@ -147,12 +161,35 @@ impl Game {
 
         if let Some(disconnect_reason) = self.server.disconnect_reason.take() {
             self.screen_sys
-                .replace_screen(Box::new(screen::ServerList::new(Some(disconnect_reason))));
+                .replace_screen(Box::new(screen::ServerList::new(
+                    self.vars.clone(),
+                    Some(disconnect_reason),
+                )));
         }
         if !self.server.is_connected() {
             self.focused = false;
         }
 
+        let dead = self.server.is_connected() && self.server.player_stats.dead;
+        if dead != self.death_screen_open {
+            self.death_screen_open = dead;
+            if dead {
+                let message = self.server.player_stats.death_message.clone();
+                self.screen_sys
+                    .add_screen(Box::new(screen::death::Death::new(message)));
+                self.focused = false;
+            } else if self.server.is_connected() {
+                self.screen_sys.pop_screen();
+                self.focused = true;
+            }
+        }
+
+        if let Some(position) = self.server.take_sign_editor() {
+            self.screen_sys
+                .add_screen(Box::new(screen::sign_editor::SignEditor::new(position)));
+            self.focused = false;
+        }
+
         let mut clear_reply = false;
         if let Some(ref recv) = self.connect_reply {
             if let Ok(server) = recv.try_recv() {
@@ -165,16 +202,13 @@ impl Game {
                         self.server = val;
                     }
                     Err(err) => {
-                        let msg = match err {
-                            protocol::Error::Disconnect(val) => val,
-                            err => {
-                                let mut msg = format::TextComponent::new(&format!("{}", err));
-                                msg.modifier.color = Some(format::Color::Red);
-                                format::Component::Text(msg)
-                            }
-                        };
+                        error!("Failed to connect: {}", err);
+                        let msg = server::disconnect_reason(err);
                         self.screen_sys
-                            .replace_screen(Box::new(screen::ServerList::new(Some(msg))));
+                            .replace_screen(Box::new(screen::ServerList::new(
+                                self.vars.clone(),
+                                Some(msg),
+                            )));
                     }
                 }
             }
@@ -265,6 +299,11 @@ fn main2() {
         let vsync = *vars.get(settings::R_VSYNC);
         (Rc::new(vars), vsync)
     };
+    let commands = {
+        let mut commands = console::Commands::new();
+        console::register_commands(&mut commands);
+        Rc::new(commands)
+    };
 
     let (res, mut resui) = resources::Manager::new();
     let resource_manager = Arc::new(RwLock::new(res));
@@ -359,7 +398,7 @@ fn main2() {
 
         #[cfg(target_arch = "wasm32")]
         {
-            screen_sys.add_screen(Box::new(screen::ServerList::new(None)));
+            screen_sys.add_screen(Box::new(screen::ServerList::new(vars.clone(), None)));
         }
     }
 
@@ -374,11 +413,15 @@ fn main2() {
     let mut game = Game {
         server: server::Server::dummy_server(resource_manager.clone()),
         focused: false,
+        death_screen_open: false,
         renderer,
         screen_sys,
         resource_manager: resource_manager.clone(),
         console: con,
+        chat_overlay: chat::ChatOverlay::new(),
+        hud: hud::Hud::new(),
         vars,
+        commands,
         should_close: false,
         chunk_builder: chunk_builder::ChunkBuilder::new(resource_manager, textures),
         connect_reply: None,
@@ -392,6 +435,9 @@ fn main2() {
         is_fullscreen: false,
         default_protocol_version,
     };
+    game.renderer
+        .particles
+        .set_block_models(game.chunk_builder.get_models());
     game.renderer.camera.pos = cgmath::Point3::new(0.5, 13.2, 0.5);
 
     if opt.network_debug {
@@ -575,9 +621,14 @@ fn tick_all(
         //vsync = vsync_changed;
     }
     let fps_cap = *game.vars.get(settings::R_MAX_FPS);
+    game.renderer.particles.max_particles =
+        (*game.vars.get(settings::R_MAX_PARTICLES)).max(0) as usize;
 
     game.tick(delta);
     game.server.tick(&mut game.renderer, delta);
+    if let Some((request, completed)) = game.server.take_tab_completion() {
+        game.screen_sys.on_tab_complete(&request, &completed);
+    }
 
     // Check if window is valid, it might be minimized
     if physical_width == 0 || physical_height == 0 {
@@ -591,14 +642,9 @@ fn tick_all(
 
     game.screen_sys
         .tick(delta, &mut game.renderer, ui_container);
-    /* TODO: open console for chat messages
-    if let Some(received_chat_at) = game.server.received_chat_at {
-        if Instant::now().duration_since(received_chat_at).as_secs() < 5 {
-            game.console.lock().unwrap().activate()
-            // TODO: automatically deactivate the console after inactivity
-        }
-    }
-    */
+    game.hud.tick(&game.server, ui_container, &game.renderer);
+    game.chat_overlay
+        .tick(&game.server.chat, ui_container, &game.renderer);
     game.console
         .lock()
         .unwrap()
@@ -700,7 +746,9 @@ fn handle_window_event<T>(
                 }
 
                 WindowEvent::ReceivedCharacter(codepoint) => {
-                    if !game.focused && !game.is_ctrl_pressed && !game.is_logo_pressed {
+                    if game.console.lock().unwrap().is_active() {
+                        game.console.lock().unwrap().key_type(codepoint);
+                    } else if !game.focused && !game.is_ctrl_pressed && !game.is_logo_pressed {
                         ui_container.key_type(game, codepoint);
                     }
 
@@ -751,7 +799,6 @@ fn handle_window_event<T>(
                     (ElementState::Released, MouseButton::Right) => {
                         if game.focused {
                             game.server.on_right_mouse_button(false);
-                            game.server.on_right_click(&mut game.renderer);
                         }
                     }
                     (ElementState::Pressed, MouseButton::Right) => {
@@ -776,14 +823,18 @@ fn handle_window_event<T>(
                 }
                 WindowEvent::MouseWheel { delta, .. } => {
                     // TODO: line vs pixel delta? does pixel scrolling (e.g. touchpad) need scaling?
-                    match delta {
-                        MouseScrollDelta::LineDelta(x, y) => {
-                            game.screen_sys.on_scroll(x.into(), y.into());
-                        }
+                    let (x, y, lines) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => (x.into(), y.into(), y.into()),
                         MouseScrollDelta::PixelDelta(position) => {
                             let (x, y) = position.into();
-                            game.screen_sys.on_scroll(x, y);
+                            // Roughly the height of a line of text
+                            (x, y, y / 20.0)
                         }
+                    };
+                    if game.focused {
+                        game.server.scroll_hotbar(lines);
+                    } else {
+                        game.screen_sys.on_scroll(x, y);
                     }
                 }
                 WindowEvent::KeyboardInput { input, .. } => {
@@ -802,7 +853,7 @@ fn handle_window_event<T>(
                                 window.set_cursor_grab(cursor_grab_mode).unwrap();
                                 window.set_cursor_visible(false);
                                 game.focused = true;
-                                game.screen_sys.pop_screen();
+                                game.screen_sys.close_screen(&mut game.server);
                             }
                         }
                         (ElementState::Pressed, Some(VirtualKeyCode::Grave)) => {
@@ -821,16 +872,37 @@ fn handle_window_event<T>(
 
                             game.is_fullscreen = !game.is_fullscreen;
                         }
+                        (ElementState::Pressed, Some(key))
+                            if game.console.lock().unwrap().is_active() =>
+                        {
+                            if key == VirtualKeyCode::Tab {
+                                let commands = game.commands.clone();
+                                let vars = game.vars.clone();
+                                game.console
+                                    .lock()
+                                    .unwrap()
+                                    .complete(|input| commands.complete(&vars, input));
+                            }
+                            let line = game.console.lock().unwrap().key_press(key);
+                            if let Some(line) = line {
+                                let commands = game.commands.clone();
+                                commands.execute(game, &line);
+                            }
+                        }
+                        (ElementState::Released, Some(_))
+                            if game.console.lock().unwrap().is_active() => {}
                         (ElementState::Pressed, Some(key)) => {
                             if game.focused {
                                 if let Some(steven_key) =
                                     settings::Stevenkey::get_by_keycode(key, &game.vars)
                                 {
+                                    game.hud.key_press(true, &steven_key);
                                     game.server.key_press(true, steven_key);
                                 }
                             } else {
                                 let ctrl_pressed = game.is_ctrl_pressed || game.is_logo_pressed;
                                 ui_container.key_press(game, key, true, ctrl_pressed);
+                                game.screen_sys.on_key_press(key, true);
                             }
                         }
                         (ElementState::Released, Some(key)) => {
@@ -838,11 +910,32 @@ fn handle_window_event<T>(
                                 if let Some(steven_key) =
                                     settings::Stevenkey::get_by_keycode(key, &game.vars)
                                 {
+                                    // Opened on release so the typed character
+                                    // doesn't end up in the chat box
+                                    let initial_input = match steven_key {
+                                        settings::Stevenkey::Chat => Some(""),
+                                        settings::Stevenkey::Command => Some("/"),
+                                        _ => None,
+                                    };
+                                    if let Some(initial_input) = initial_input {
+                                        if game.server.is_connected() {
+                                            game.focused = false;
+                                            game.screen_sys.add_screen(Box::new(
+                                                screen::chat::Chat::new(
+                                                    game.chat_overlay.scroll_handle(),
+                                                    game.server.chat.sent_history(),
+                                                    initial_input,
+                                                ),
+                                            ));
+                                        }
+                                    }
+                                    game.hud.key_press(false, &steven_key);
                                     game.server.key_press(false, steven_key);
                                 }
                             } else {
                                 let ctrl_pressed = game.is_ctrl_pressed;
                                 ui_container.key_press(game, key, false, ctrl_pressed);
+                                game.screen_sys.on_key_press(key, false);
                             }
                         }
                         (_, None) => (),
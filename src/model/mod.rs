@@ -214,6 +214,27 @@ impl Factory {
         ret
     }
 
+    /// Returns the texture of the pieces that fly off the block when it is
+    /// hit or broken.
+    pub fn get_particle_texture(
+        models: &Arc<RwLock<Factory>>,
+        block: Block,
+    ) -> Option<render::Texture> {
+        let (plugin, name) = block.get_model();
+        let key = Key(plugin.to_owned(), name.to_owned());
+        {
+            let m = models.read().unwrap();
+            if let Some(model) = m.models.get(&key) {
+                return model.get_particle_texture(block);
+            }
+        }
+        let mut m = models.write().unwrap();
+        if !m.models.contains_key(&key) && !m.load_model(&plugin, &name) {
+            return None;
+        }
+        m.models.get(&key)?.get_particle_texture(block)
+    }
+
     fn load_model(&mut self, plugin: &str, name: &str) -> bool {
         let file = match self
             .resources
@@ -542,10 +563,16 @@ impl Factory {
     }
 
     fn process_model(&self, mut raw: RawModel) -> Model {
+        let particle = raw.lookup_texture("#particle");
         let mut model = Model {
             faces: vec![],
             ambient_occlusion: raw.ambient_occlusion,
             weight: raw.weight,
+            particle: if particle.is_empty() {
+                None
+            } else {
+                Some(render::Renderer::get_texture(&self.textures, &particle))
+            },
         };
         let elements = std::mem::take(&mut raw.elements);
         for el in elements {
@@ -794,6 +821,41 @@ impl Factory {
     }
 }
 
+/// Returns the texture to draw an item with in the inventory. This is the
+/// flat texture of its item model or, for blocks, the particle texture of
+/// their block model.
+pub fn item_texture(resources: &resources::Manager, name: &str) -> Option<String> {
+    let mut textures: HashMap<String, String> = HashMap::new();
+    let mut model = format!("item/{}", name);
+    // Models only nest a few deep, this stops loops in broken packs
+    for _ in 0..8 {
+        let file = resources.open("minecraft", &format!("models/{}.json", model))?;
+        let v: serde_json::Value = serde_json::from_reader(file).ok()?;
+        // Textures set by a model replace those of its parents
+        if let Some(vars) = v.get("textures").and_then(|v| v.as_object()) {
+            for (k, v) in vars {
+                if let Some(v) = v.as_str() {
+                    textures.entry(k.clone()).or_insert_with(|| v.to_owned());
+                }
+            }
+        }
+        match v.get("parent").and_then(|v| v.as_str()) {
+            Some(parent) if !parent.starts_with("builtin/") => model = parent.to_owned(),
+            _ => break,
+        }
+    }
+    let mut texture = textures
+        .get("layer0")
+        .or_else(|| textures.get("particle"))?;
+    for _ in 0..8 {
+        match texture.strip_prefix('#') {
+            Some(var) => texture = textures.get(var)?,
+            None => return Some(texture.clone()),
+        }
+    }
+    None
+}
+
 const FACE_ROTATION: &[Direction] = &[
     Direction::North,
     Direction::East,
@@ -833,6 +895,19 @@ impl StateModel {
     pub fn get_variants(&self, name: &str) -> Option<&Variants> {
         self.variants.get(name)
     }
+
+    fn get_particle_texture(&self, block: Block) -> Option<render::Texture> {
+        let variants = if self.multipart.is_empty() {
+            self.get_variants(&block.get_model_variant())?
+        } else {
+            &self
+                .multipart
+                .iter()
+                .find(|rule| Factory::eval_rules(block, &rule.rules))?
+                .apply
+        };
+        variants.models.first()?.particle.clone()
+    }
 }
 
 #[derive(Clone)]
@@ -940,6 +1015,8 @@ struct Model {
     ambient_occlusion: bool,
     #[allow(dead_code)]
     weight: f64,
+    /// The texture of the pieces that fly off the block
+    particle: Option<render::Texture>,
 }
 
 #[derive(Clone, Debug)]
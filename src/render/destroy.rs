@@ -0,0 +1,171 @@
+//! The cracks drawn over blocks while they are being broken.
+
+use super::model;
+use crate::shared::{Direction, Position};
+use crate::types::hash::FNVHash;
+use crate::world;
+use crate::world::block::Block;
+use collision::Aabb;
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+use std::sync::{Arc, RwLock};
+
+/// The number of stages a block goes through before it breaks, each with
+/// its own texture.
+pub const STAGES: u8 = 10;
+// How far the cracks are drawn out from the block's faces, to stop them
+// fighting with the block itself
+const GROW: f64 = 0.002;
+
+/// Who is breaking a block. Everyone can only break one block at a time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Breaker {
+    /// The player, digging the block themselves
+    Local,
+    /// Another entity, by the id the server gave it
+    Entity(i32),
+}
+
+struct Overlay {
+    position: Position,
+    stage: u8,
+    /// The block the model was built for
+    block: Option<Block>,
+    model: Option<model::ModelKey>,
+}
+
+pub struct Manager {
+    overlays: HashMap<Breaker, Overlay, BuildHasherDefault<FNVHash>>,
+    textures: Arc<RwLock<super::TextureManager>>,
+}
+
+impl Manager {
+    pub fn new(textures: Arc<RwLock<super::TextureManager>>) -> Manager {
+        Manager {
+            overlays: HashMap::with_hasher(BuildHasherDefault::default()),
+            textures,
+        }
+    }
+
+    /// Shows the block at the position as broken up to the stage, which is
+    /// between zero and `STAGES - 1`. Anything larger removes the breaker's
+    /// cracks instead.
+    pub fn set(
+        &mut self,
+        models: &mut model::Manager,
+        breaker: Breaker,
+        position: Position,
+        stage: u8,
+    ) {
+        if stage >= STAGES {
+            self.remove(models, breaker);
+            return;
+        }
+        let overlay = self.overlays.entry(breaker).or_insert(Overlay {
+            position,
+            stage,
+            block: None,
+            model: None,
+        });
+        if overlay.position != position || overlay.stage != stage {
+            overlay.position = position;
+            overlay.stage = stage;
+            // Rebuilt next tick
+            overlay.block = None;
+        }
+    }
+
+    pub fn remove(&mut self, models: &mut model::Manager, breaker: Breaker) {
+        if let Some(model) = self.overlays.remove(&breaker).and_then(|v| v.model) {
+            models.remove_model(model);
+        }
+    }
+
+    pub fn clear(&mut self, models: &mut model::Manager) {
+        for (_, overlay) in self.overlays.drain() {
+            if let Some(model) = overlay.model {
+                models.remove_model(model);
+            }
+        }
+    }
+
+    pub fn tick(&mut self, world: &world::World, models: &mut model::Manager) {
+        let textures = &self.textures;
+        self.overlays.retain(|_, overlay| {
+            let block = world.get_block(overlay.position);
+            let bounds = block.get_collision_boxes();
+            // Nothing left to crack
+            if bounds.is_empty() {
+                if let Some(model) = overlay.model.take() {
+                    models.remove_model(model);
+                }
+                return false;
+            }
+            if overlay.block != Some(block) {
+                overlay.block = Some(block);
+                let texture = super::Renderer::get_texture(
+                    textures,
+                    &format!("blocks/destroy_stage_{}", overlay.stage),
+                );
+                let pos = cgmath::Vector3::new(
+                    f64::from(overlay.position.x),
+                    f64::from(overlay.position.y),
+                    f64::from(overlay.position.z),
+                );
+                let mut verts = vec![];
+                for bound in bounds {
+                    let bound = bound.add_v(pos);
+                    let size = bound.max - bound.min;
+                    // Only the part of the texture covering the box is used,
+                    // so smaller blocks aren't given squashed cracks
+                    model::append_box_texture_scale(
+                        &mut verts,
+                        (bound.min.x - GROW) as f32,
+                        (bound.min.y - GROW) as f32,
+                        (bound.min.z - GROW) as f32,
+                        (size.x + GROW * 2.0) as f32,
+                        (size.y + GROW * 2.0) as f32,
+                        (size.z + GROW * 2.0) as f32,
+                        [
+                            Some(texture.clone()),
+                            Some(texture.clone()),
+                            Some(texture.clone()),
+                            Some(texture.clone()),
+                            Some(texture.clone()),
+                            Some(texture.clone()),
+                        ],
+                        [
+                            [size.x, size.z],
+                            [size.x, size.z],
+                            [size.x, size.y],
+                            [size.x, size.y],
+                            [size.z, size.y],
+                            [size.z, size.y],
+                        ],
+                    );
+                }
+                match overlay.model {
+                    Some(model) => models.set_verts(model, verts),
+                    None => overlay.model = Some(models.create_model(model::DEFAULT, vec![verts])),
+                }
+            }
+            // Solid blocks have no light of their own, so the brightest
+            // side is used instead
+            if let Some(model) = overlay.model.and_then(|v| models.get_model(v)) {
+                let (block_light, sky_light) = Direction::all()
+                    .into_iter()
+                    .map(|dir| overlay.position.shift(dir))
+                    .chain(std::iter::once(overlay.position))
+                    .fold((0, 0), |(block, sky), pos| {
+                        (
+                            block.max(world.get_block_light(pos)),
+                            sky.max(world.get_sky_light(pos)),
+                        )
+                    });
+                model.block_light = f32::from(block_light);
+                model.sky_light = f32::from(sky_light);
+            }
+            true
+        });
+    }
+}
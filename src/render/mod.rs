@@ -17,7 +17,9 @@ pub mod glsl;
 #[macro_use]
 pub mod shaders;
 pub mod clouds;
+pub mod destroy;
 pub mod model;
+pub mod particles;
 pub mod ui;
 
 use crate::gl;
@@ -51,6 +53,8 @@ pub struct Renderer {
     textures: Arc<RwLock<TextureManager>>,
     pub ui: ui::UIState,
     pub model: model::Manager,
+    pub particles: particles::Manager,
+    pub destroy: destroy::Manager,
     pub clouds: Option<clouds::Clouds>,
 
     gl_texture: gl::Texture,
@@ -202,6 +206,8 @@ impl Renderer {
         Renderer {
             resource_version: version,
             model: model::Manager::new(&greg),
+            particles: particles::Manager::new(textures.clone()),
+            destroy: destroy::Manager::new(textures.clone()),
             clouds,
             textures,
             ui,
@@ -360,6 +366,9 @@ impl Renderer {
 
         // Line rendering
         // Model rendering
+        self.particles
+            .tick(world, &mut self.model, self.view_vector, delta);
+        self.destroy.tick(world, &mut self.model);
         self.model.draw(
             &self.frustum,
             &self.perspective_matrix,
@@ -149,14 +149,7 @@ impl Manager {
         };
 
         Self::rebuild_model(&mut model);
-        if self.max_index < model.count as usize {
-            let (data, ty) = super::generate_element_buffer(model.count as usize);
-            self.index_buffer.bind(gl::ELEMENT_ARRAY_BUFFER);
-            self.index_buffer
-                .set_data(gl::ELEMENT_ARRAY_BUFFER, &data, gl::DYNAMIC_DRAW);
-            self.max_index = model.count as usize;
-            self.index_type = ty;
-        }
+        self.ensure_index_buffer(model.count as usize);
 
         let collection = &mut self.collections[ckey.0];
         let key = ModelKey(ckey, collection.next_id);
@@ -166,6 +159,31 @@ impl Manager {
         key
     }
 
+    /// Replaces the vertices of a model made of a single part, for models
+    /// that change every frame.
+    pub fn set_verts(&mut self, key: ModelKey, verts: Vec<Vertex>) {
+        let collection = &mut self.collections[(key.0).0];
+        let model = match collection.models.get_mut(&key) {
+            Some(model) => model,
+            None => return,
+        };
+        model.verts = verts;
+        Self::rebuild_model(model);
+        let count = model.count as usize;
+        self.ensure_index_buffer(count);
+    }
+
+    fn ensure_index_buffer(&mut self, count: usize) {
+        if self.max_index < count {
+            let (data, ty) = super::generate_element_buffer(count);
+            self.index_buffer.bind(gl::ELEMENT_ARRAY_BUFFER);
+            self.index_buffer
+                .set_data(gl::ELEMENT_ARRAY_BUFFER, &data, gl::DYNAMIC_DRAW);
+            self.max_index = count;
+            self.index_type = ty;
+        }
+    }
+
     pub fn remove_model(&mut self, key: ModelKey) {
         let collection = &mut self.collections[(key.0).0];
         collection.models.remove(&key);
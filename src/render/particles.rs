@@ -0,0 +1,707 @@
+//! Short lived sprites, like smoke and the pieces of broken blocks, drawn
+//! as quads facing the camera through the model renderer.
+
+use super::model;
+use crate::entity::physics;
+use crate::model::{BlockVertex, Factory};
+use crate::shared::{Direction, Position};
+use crate::types::hash::FNVHash;
+use crate::world;
+use crate::world::block::{Block, TintType};
+use cgmath::{InnerSpace, Point3, Vector3};
+use collision::Aabb3;
+use rand::Rng;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::hash::BuildHasherDefault;
+use std::sync::{Arc, RwLock};
+
+// Particles move by their velocity once a game tick, which is 3 frames
+// at 60fps
+const TICK: f64 = 3.0;
+// Particles collide with blocks as a box of this width and height
+const BOUNDS_SIZE: f64 = 0.2;
+
+/// The kinds of particles that can be drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    /// Pieces of a block
+    Block(Block),
+    Smoke,
+    LargeSmoke,
+    Poof,
+    Cloud,
+    Explosion,
+    Dust {
+        red: f32,
+        green: f32,
+        blue: f32,
+        scale: f32,
+    },
+    Flame,
+    Lava,
+    Note,
+    Crit,
+    EnchantedHit,
+    Heart,
+    AngryVillager,
+    HappyVillager,
+    Bubble,
+    Splash,
+    DrippingWater,
+    DrippingLava,
+    Portal,
+    Effect,
+    Witch,
+    Enchant,
+    EndRod,
+    Firework,
+}
+
+impl Kind {
+    /// Returns the particle with the name used by the protocol, taking
+    /// both the names from before and after 1.13.
+    pub fn from_name(name: &str) -> Option<Kind> {
+        Some(match name {
+            "smoke" => Kind::Smoke,
+            "large_smoke" | "largesmoke" => Kind::LargeSmoke,
+            "poof" | "explode" | "snowballpoof" => Kind::Poof,
+            "cloud" => Kind::Cloud,
+            "explosion" | "explosion_emitter" | "largeexplode" | "hugeexplosion" => Kind::Explosion,
+            "dust" | "reddust" => Kind::Dust {
+                red: 1.0,
+                green: 0.0,
+                blue: 0.0,
+                scale: 1.0,
+            },
+            "flame" => Kind::Flame,
+            "lava" => Kind::Lava,
+            "note" => Kind::Note,
+            "crit" | "damage_indicator" | "damageIndicator" => Kind::Crit,
+            "enchanted_hit" | "magicCrit" => Kind::EnchantedHit,
+            "heart" => Kind::Heart,
+            "angry_villager" | "angryVillager" => Kind::AngryVillager,
+            "happy_villager" | "happyVillager" => Kind::HappyVillager,
+            "bubble" => Kind::Bubble,
+            "splash" | "rain" | "fishing" | "wake" => Kind::Splash,
+            "dripping_water" | "dripWater" => Kind::DrippingWater,
+            "dripping_lava" | "dripLava" => Kind::DrippingLava,
+            "portal" => Kind::Portal,
+            "effect"
+            | "instant_effect"
+            | "entity_effect"
+            | "ambient_entity_effect"
+            | "spell"
+            | "instantSpell"
+            | "mobSpell"
+            | "mobSpellAmbient" => Kind::Effect,
+            "witch" | "witchMagic" => Kind::Witch,
+            "enchant" | "enchantmenttable" => Kind::Enchant,
+            "end_rod" | "endRod" => Kind::EndRod,
+            "firework" | "fireworksSpark" => Kind::Firework,
+            _ => return None,
+        })
+    }
+}
+
+/// Particles waiting to be added, for code without access to the
+/// renderer.
+pub enum Spawn {
+    /// Scatters `count` particles around `position` by about `offset`,
+    /// moving in random directions at about `speed`. Without a count a
+    /// single particle moves by `offset` scaled by `speed` instead.
+    Burst {
+        kind: Kind,
+        position: Vector3<f64>,
+        offset: Vector3<f64>,
+        speed: f64,
+        count: i32,
+    },
+    /// Pieces of a block flying out from where it was broken
+    BreakBlock { block: Block, position: Position },
+    /// A piece of a block knocked off the face being dug into
+    HitBlock {
+        block: Block,
+        position: Position,
+        face: Direction,
+    },
+}
+
+enum Sprite {
+    /// Frames on the particle sheet played through backwards over the
+    /// life of the particle
+    Sheet {
+        first: usize,
+        frames: usize,
+    },
+    Texture(super::Texture),
+}
+
+struct Particle {
+    position: Vector3<f64>,
+    velocity: Vector3<f64>,
+    sprite: Sprite,
+    color: (f64, f64, f64),
+    /// Half the width of the quad drawn
+    size: f64,
+    /// How long the particle has existed, in ticks
+    age: f64,
+    lifetime: f64,
+    /// Taken from the vertical velocity every tick
+    gravity: f64,
+    /// The part of the velocity kept every tick
+    drag: f64,
+    collides: bool,
+    /// Drawn at full brightness instead of the light it is in
+    glows: bool,
+    on_ground: bool,
+}
+
+impl Particle {
+    fn new(kind: Kind, position: Vector3<f64>, velocity: Vector3<f64>) -> Particle {
+        let mut rng = rand::thread_rng();
+        let mut p = Particle {
+            position,
+            velocity,
+            sprite: Sprite::Sheet {
+                first: 0,
+                frames: 8,
+            },
+            color: (1.0, 1.0, 1.0),
+            size: 0.1 * (rng.gen::<f64>() * 0.5 + 0.5),
+            age: 0.0,
+            lifetime: 8.0 / (rng.gen::<f64>() * 0.8 + 0.2),
+            gravity: 0.0,
+            drag: 0.96,
+            collides: true,
+            glows: false,
+            on_ground: false,
+        };
+        let sheet = |first| Sprite::Sheet { first, frames: 1 };
+        match kind {
+            // Pieces of blocks get their texture when added
+            Kind::Block(_) => {
+                p.velocity = scatter(velocity);
+                p.lifetime = 4.0 / (rng.gen::<f64>() * 0.9 + 0.1);
+                p.gravity = 0.04;
+                p.drag = 0.98;
+                p.color = (0.6, 0.6, 0.6);
+            }
+            Kind::Smoke | Kind::LargeSmoke | Kind::Cloud => {
+                p.velocity = velocity + random_vector(0.1);
+                p.gravity = -0.004;
+                let grey = rng.gen::<f64>() * 0.3;
+                p.color = (grey, grey, grey);
+                p.size *= 0.75;
+                if kind == Kind::LargeSmoke {
+                    p.size *= 2.5;
+                } else if kind == Kind::Cloud {
+                    let white = 1.0 - rng.gen::<f64>() * 0.3;
+                    p.color = (white, white, white);
+                    p.size *= 2.5;
+                }
+            }
+            Kind::Poof => {
+                p.velocity = velocity + random_vector(0.05);
+                p.lifetime = 16.0 / (rng.gen::<f64>() * 0.8 + 0.2) + 2.0;
+                p.gravity = -0.004;
+                p.drag = 0.9;
+                let white = rng.gen::<f64>() * 0.3 + 0.7;
+                p.color = (white, white, white);
+                p.size *= rng.gen::<f64>() * rng.gen::<f64>() * 6.0 + 1.0;
+            }
+            Kind::Explosion => {
+                p.velocity = Vector3::new(0.0, 0.0, 0.0);
+                p.lifetime = 6.0 + rng.gen::<f64>() * 4.0;
+                let white = rng.gen::<f64>() * 0.6 + 0.4;
+                p.color = (white, white, white);
+                p.size = 1.0 - rng.gen::<f64>() * 0.5;
+                p.collides = false;
+                p.glows = true;
+            }
+            Kind::Dust {
+                red,
+                green,
+                blue,
+                scale,
+            } => {
+                p.velocity = velocity * 0.1 + random_vector(0.01);
+                let shade = rng.gen::<f64>() * 0.4 + 0.6;
+                p.color = (
+                    f64::from(red) * shade,
+                    f64::from(green) * shade,
+                    f64::from(blue) * shade,
+                );
+                p.size *= 0.75 * f64::from(scale);
+            }
+            Kind::Flame => {
+                p.sprite = sheet(48);
+                p.lifetime = 8.0 / (rng.gen::<f64>() * 0.8 + 0.2) + 4.0;
+                p.collides = false;
+                p.glows = true;
+            }
+            Kind::Lava => {
+                p.sprite = sheet(49);
+                p.velocity = Vector3::new(
+                    velocity.x * 0.8,
+                    rng.gen::<f64>() * 0.4 + 0.05,
+                    velocity.z * 0.8,
+                );
+                p.lifetime = 16.0 / (rng.gen::<f64>() * 0.8 + 0.2);
+                p.gravity = 0.03;
+                p.drag = 0.999;
+                p.size *= rng.gen::<f64>() * 2.0 + 0.2;
+                p.glows = true;
+            }
+            Kind::Note => {
+                p.sprite = sheet(64);
+                // The note picks the color, from green around to green again
+                let hue = velocity.x * 2.0 * PI;
+                p.color = (
+                    (hue.sin() * 0.65 + 0.35).max(0.0),
+                    ((hue + PI * 2.0 / 3.0).sin() * 0.65 + 0.35).max(0.0),
+                    ((hue + PI * 4.0 / 3.0).sin() * 0.65 + 0.35).max(0.0),
+                );
+                p.velocity = Vector3::new(0.0, 0.2, 0.0);
+                p.lifetime = 6.0;
+                p.drag = 0.66;
+                p.size *= 1.5;
+                p.collides = false;
+            }
+            Kind::Crit | Kind::EnchantedHit => {
+                p.sprite = sheet(65);
+                p.velocity = random_vector(0.1) + velocity * 0.4;
+                p.lifetime = 6.0 / (rng.gen::<f64>() * 0.8 + 0.6);
+                p.gravity = 0.02;
+                p.drag = 0.7;
+                let shade = rng.gen::<f64>() * 0.3 + 0.6;
+                p.color = if kind == Kind::EnchantedHit {
+                    (shade * 0.3, shade * 0.8, shade)
+                } else {
+                    (shade, shade, shade)
+                };
+            }
+            Kind::Heart | Kind::AngryVillager => {
+                p.sprite = sheet(if kind == Kind::Heart { 80 } else { 81 });
+                p.velocity = Vector3::new(0.0, 0.1, 0.0) + velocity * 0.01;
+                p.lifetime = 16.0;
+                p.drag = 0.86;
+                p.size *= 1.5;
+                p.collides = false;
+            }
+            Kind::HappyVillager => {
+                p.sprite = sheet(82);
+                p.velocity = velocity * 0.1;
+                p.lifetime = 20.0 / (rng.gen::<f64>() * 0.8 + 0.2);
+                p.drag = 0.99;
+            }
+            Kind::Bubble => {
+                p.sprite = sheet(32);
+                p.velocity = velocity * 0.2 + random_vector(0.02);
+                p.gravity = -0.002;
+                p.drag = 0.85;
+                p.size *= rng.gen::<f64>() * 0.6 + 0.2;
+            }
+            Kind::Splash => {
+                p.sprite = sheet(19 + rng.gen_range(0..4));
+                p.velocity = Vector3::new(
+                    velocity.x * 0.3,
+                    rng.gen::<f64>() * 0.2 + 0.1,
+                    velocity.z * 0.3,
+                );
+                p.gravity = 0.06;
+                p.drag = 0.98;
+            }
+            Kind::DrippingWater | Kind::DrippingLava => {
+                p.sprite = sheet(113);
+                p.velocity = Vector3::new(0.0, 0.0, 0.0);
+                p.lifetime = 40.0;
+                p.gravity = 0.06;
+                p.drag = 0.98;
+                p.color = if kind == Kind::DrippingWater {
+                    (0.2, 0.3, 1.0)
+                } else {
+                    (1.0, 0.3, 0.0)
+                };
+            }
+            Kind::Portal | Kind::Enchant => {
+                p.sprite = if kind == Kind::Portal {
+                    sheet(rng.gen_range(0..8))
+                } else {
+                    sheet(225 + rng.gen_range(0..26))
+                };
+                p.lifetime = rng.gen::<f64>() * 10.0 + 40.0;
+                p.drag = 1.0;
+                let shade = rng.gen::<f64>() * 0.6 + 0.4;
+                p.color = if kind == Kind::Portal {
+                    (shade * 0.9, shade * 0.3, shade)
+                } else {
+                    (shade * 0.9, shade * 0.9, shade)
+                };
+                p.collides = false;
+                p.glows = true;
+            }
+            Kind::Effect | Kind::Witch => {
+                p.sprite = Sprite::Sheet {
+                    first: 128,
+                    frames: 8,
+                };
+                p.velocity = Vector3::new(velocity.x, velocity.y.max(0.0), velocity.z) * 0.2;
+                p.gravity = -0.004;
+                if kind == Kind::Witch {
+                    let shade = rng.gen::<f64>() * 0.5 + 0.35;
+                    p.color = (shade, 0.0, shade);
+                }
+            }
+            Kind::EndRod | Kind::Firework => {
+                p.sprite = Sprite::Sheet {
+                    first: if kind == Kind::EndRod { 176 } else { 160 },
+                    frames: 8,
+                };
+                p.lifetime = 48.0 + rng.gen::<f64>() * 12.0;
+                p.gravity = if kind == Kind::Firework { 0.004 } else { 0.0 };
+                p.drag = 0.91;
+                p.glows = true;
+            }
+        }
+        p
+    }
+
+    fn bounds(&self) -> Aabb3<f64> {
+        let half = BOUNDS_SIZE / 2.0;
+        Aabb3::new(
+            Point3::new(
+                self.position.x - half,
+                self.position.y,
+                self.position.z - half,
+            ),
+            Point3::new(
+                self.position.x + half,
+                self.position.y + BOUNDS_SIZE,
+                self.position.z + half,
+            ),
+        )
+    }
+
+    /// Moves the particle on by `ticks`, which needn't be whole.
+    fn tick(&mut self, world: &world::World, ticks: f64) {
+        self.age += ticks;
+        self.velocity.y -= self.gravity * ticks;
+        let offset = self.velocity * ticks;
+        let moved = if self.collides {
+            physics::slide_bounds(world, self.bounds(), offset)
+        } else {
+            offset
+        };
+        self.on_ground = moved.y != offset.y && offset.y < 0.0;
+        if moved.x != offset.x {
+            self.velocity.x = 0.0;
+        }
+        if moved.y != offset.y {
+            self.velocity.y = 0.0;
+        }
+        if moved.z != offset.z {
+            self.velocity.z = 0.0;
+        }
+        self.position += moved;
+
+        self.velocity *= self.drag.powf(ticks);
+        if self.on_ground {
+            let friction = 0.7_f64.powf(ticks);
+            self.velocity.x *= friction;
+            self.velocity.z *= friction;
+        }
+    }
+}
+
+/// Returns a vector with each part between `-max` and `max`.
+fn random_vector(max: f64) -> Vector3<f64> {
+    let mut rng = rand::thread_rng();
+    Vector3::new(
+        (rng.gen::<f64>() * 2.0 - 1.0) * max,
+        (rng.gen::<f64>() * 2.0 - 1.0) * max,
+        (rng.gen::<f64>() * 2.0 - 1.0) * max,
+    )
+}
+
+/// Picks a random velocity heading roughly along `velocity` and slightly
+/// upwards, the way vanilla throws out most particles.
+fn scatter(velocity: Vector3<f64>) -> Vector3<f64> {
+    let mut rng = rand::thread_rng();
+    let direction = velocity + random_vector(0.4);
+    let speed = (rng.gen::<f64>() + rng.gen::<f64>() + 1.0) * 0.15 * 0.4;
+    let direction = if direction.magnitude2() > 0.0 {
+        direction.normalize()
+    } else {
+        Vector3::new(0.0, 1.0, 0.0)
+    };
+    direction * speed + Vector3::new(0.0, 0.1, 0.0)
+}
+
+/// A random number with a normal distribution around zero.
+fn gaussian() -> f64 {
+    let mut rng = rand::thread_rng();
+    let u = 1.0 - rng.gen::<f64>();
+    let v = rng.gen::<f64>();
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+pub struct Manager {
+    particles: Vec<Particle>,
+    /// A model for each light level particles are drawn in
+    models: HashMap<(u8, u8), model::ModelKey, BuildHasherDefault<FNVHash>>,
+    textures: Arc<RwLock<super::TextureManager>>,
+    /// Where pieces of blocks get their textures from
+    block_models: Option<Arc<RwLock<Factory>>>,
+    /// The most particles that can exist at once, the oldest ones are
+    /// removed to make room for more
+    pub max_particles: usize,
+}
+
+impl Manager {
+    pub fn new(textures: Arc<RwLock<super::TextureManager>>) -> Manager {
+        Manager {
+            particles: vec![],
+            models: HashMap::with_hasher(BuildHasherDefault::default()),
+            textures,
+            block_models: None,
+            max_particles: 4000,
+        }
+    }
+
+    /// Sets the block models that pieces of blocks get their textures from.
+    pub fn set_block_models(&mut self, block_models: Arc<RwLock<Factory>>) {
+        self.block_models = Some(block_models);
+    }
+
+    pub fn add(&mut self, spawn: Spawn) {
+        match spawn {
+            Spawn::Burst {
+                kind,
+                position,
+                offset,
+                speed,
+                count,
+            } => {
+                if count <= 0 {
+                    self.add_particle(kind, position, offset * speed);
+                    return;
+                }
+                for _ in 0..count.min(self.max_particles as i32) {
+                    let spread = Vector3::new(
+                        gaussian() * offset.x,
+                        gaussian() * offset.y,
+                        gaussian() * offset.z,
+                    );
+                    let velocity = Vector3::new(gaussian(), gaussian(), gaussian()) * speed;
+                    self.add_particle(kind, position + spread, velocity);
+                }
+            }
+            Spawn::BreakBlock { block, position } => {
+                // A four by four by four grid of pieces, all thrown away
+                // from the middle of the block
+                for x in 0..4 {
+                    for y in 0..4 {
+                        for z in 0..4 {
+                            let offset = Vector3::new(
+                                (f64::from(x) + 0.5) / 4.0,
+                                (f64::from(y) + 0.5) / 4.0,
+                                (f64::from(z) + 0.5) / 4.0,
+                            );
+                            self.add_particle(
+                                Kind::Block(block),
+                                Vector3::new(
+                                    f64::from(position.x),
+                                    f64::from(position.y),
+                                    f64::from(position.z),
+                                ) + offset,
+                                offset - Vector3::new(0.5, 0.5, 0.5),
+                            );
+                        }
+                    }
+                }
+            }
+            Spawn::HitBlock {
+                block,
+                position,
+                face,
+            } => {
+                let block_at = Vector3::new(
+                    f64::from(position.x),
+                    f64::from(position.y),
+                    f64::from(position.z),
+                );
+                let mut at = block_at + Vector3::new(0.5, 0.5, 0.5) + random_vector(0.4);
+                // Just outside of the face
+                let (ox, oy, oz) = face.get_offset();
+                for (axis, offset) in [ox, oy, oz].iter().enumerate() {
+                    if *offset != 0 {
+                        at[axis] = block_at[axis] + 0.5 + f64::from(*offset) * 0.6;
+                    }
+                }
+                if let Some(p) =
+                    self.add_particle(Kind::Block(block), at, Vector3::new(0.0, 0.0, 0.0))
+                {
+                    p.velocity *= 0.2;
+                    p.size *= 0.6;
+                }
+            }
+        }
+    }
+
+    fn add_particle(
+        &mut self,
+        kind: Kind,
+        position: Vector3<f64>,
+        velocity: Vector3<f64>,
+    ) -> Option<&mut Particle> {
+        if self.max_particles == 0 {
+            return None;
+        }
+        let mut particle = Particle::new(kind, position, velocity);
+        if let Kind::Block(block) = kind {
+            if let Block::Air {} | Block::Missing {} = block {
+                return None;
+            }
+            let texture = Factory::get_particle_texture(self.block_models.as_ref()?, block)?;
+            // A random quarter of the texture
+            let mut rng = rand::thread_rng();
+            particle.sprite = Sprite::Texture(texture.relative(
+                rng.gen::<f32>() * 0.75,
+                rng.gen::<f32>() * 0.75,
+                0.25,
+                0.25,
+            ));
+            // Grass blocks break into plain dirt
+            let tint = match block {
+                Block::Grass { .. } => TintType::Default,
+                _ => block.get_tint(),
+            };
+            let (r, g, b) = match tint {
+                TintType::Default => (255, 255, 255),
+                TintType::Color { r, g, b } => (r, g, b),
+                TintType::Grass => (0x91, 0xBD, 0x59),
+                TintType::Foliage => (0x77, 0xAB, 0x2F),
+            };
+            particle.color = (
+                particle.color.0 * f64::from(r) / 255.0,
+                particle.color.1 * f64::from(g) / 255.0,
+                particle.color.2 * f64::from(b) / 255.0,
+            );
+        }
+        if self.particles.len() >= self.max_particles {
+            let excess = self.particles.len() + 1 - self.max_particles;
+            self.particles.drain(..excess);
+        }
+        self.particles.push(particle);
+        self.particles.last_mut()
+    }
+
+    pub fn clear(&mut self, models: &mut model::Manager) {
+        self.particles.clear();
+        for (_, key) in self.models.drain() {
+            models.remove_model(key);
+        }
+    }
+
+    /// Moves the particles on by `delta` and rebuilds their models to face
+    /// the camera looking along `view`.
+    pub fn tick(
+        &mut self,
+        world: &world::World,
+        models: &mut model::Manager,
+        view: Vector3<f32>,
+        delta: f64,
+    ) {
+        let ticks = delta.min(5.0) / TICK;
+        for particle in &mut self.particles {
+            particle.tick(world, ticks);
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+        if self.particles.len() > self.max_particles {
+            let excess = self.particles.len() - self.max_particles;
+            self.particles.drain(..excess);
+        }
+
+        // The sides of the quads, across and up the screen
+        let view = view.cast::<f64>().unwrap();
+        let right = view.cross(Vector3::unit_y());
+        let right = if right.magnitude2() > 0.0001 {
+            right.normalize()
+        } else {
+            Vector3::unit_x()
+        };
+        let up = right.cross(view).normalize();
+
+        let sheet = super::Renderer::get_texture(&self.textures, "particle/particles");
+        let mut verts: HashMap<(u8, u8), Vec<model::Vertex>, BuildHasherDefault<FNVHash>> =
+            HashMap::with_hasher(BuildHasherDefault::default());
+        for p in &self.particles {
+            let light = if p.glows {
+                (15, 15)
+            } else {
+                let at = Position::new(
+                    p.position.x.floor() as i32,
+                    p.position.y.floor() as i32,
+                    p.position.z.floor() as i32,
+                );
+                (world.get_block_light(at), world.get_sky_light(at))
+            };
+            let texture = match p.sprite {
+                Sprite::Sheet { first, frames } => {
+                    let frame = frames
+                        - 1
+                        - ((p.age * frames as f64 / p.lifetime) as usize).min(frames - 1);
+                    let index = first + frame;
+                    sheet.relative(
+                        (index % 16) as f32 / 16.0,
+                        (index / 16) as f32 / 16.0,
+                        1.0 / 16.0,
+                        1.0 / 16.0,
+                    )
+                }
+                Sprite::Texture(ref texture) => texture.clone(),
+            };
+            // Resting on its position rather than around it
+            let center = p.position + Vector3::new(0.0, p.size, 0.0);
+            let verts = verts.entry(light).or_default();
+            for vert in BlockVertex::face_by_direction(Direction::North) {
+                let corner = center - right * ((f64::from(vert.x) - 0.5) * p.size * 2.0)
+                    + up * ((f64::from(vert.y) - 0.5) * p.size * 2.0);
+                verts.push(model::Vertex {
+                    x: corner.x as f32,
+                    y: corner.y as f32,
+                    z: corner.z as f32,
+                    texture: texture.clone(),
+                    texture_x: vert.toffsetx as f64,
+                    texture_y: vert.toffsety as f64,
+                    r: (p.color.0.min(1.0) * 255.0) as u8,
+                    g: (p.color.1.min(1.0) * 255.0) as u8,
+                    b: (p.color.2.min(1.0) * 255.0) as u8,
+                    a: 255,
+                    id: 0,
+                });
+            }
+        }
+
+        self.models.retain(|light, key| {
+            let keep = verts.contains_key(light);
+            if !keep {
+                models.remove_model(*key);
+            }
+            keep
+        });
+        for (light, verts) in verts {
+            match self.models.get(&light) {
+                Some(key) => models.set_verts(*key, verts),
+                None => {
+                    let key = models.create_model(model::DEFAULT, vec![verts]);
+                    let model = models.get_model(key).unwrap();
+                    model.block_light = f32::from(light.0);
+                    model.sky_light = f32::from(light.1);
+                    self.models.insert(light, key);
+                }
+            }
+        }
+    }
+}
@@ -76,14 +76,7 @@ unsafe impl Sync for Manager {}
 
 impl Manager {
     pub fn new() -> (Manager, ManagerUI) {
-        let mut m = Manager {
-            packs: Vec::new(),
-            version: 0,
-            vanilla_chan: None,
-            vanilla_assets_chan: None,
-            vanilla_progress: Arc::new(Mutex::new(Progress { tasks: vec![] })),
-        };
-        m.add_pack(Box::new(InternalPack));
+        let mut m = Manager::internal_only();
         #[cfg(not(target_arch = "wasm32"))]
         {
             m.download_vanilla();
@@ -98,6 +91,20 @@ impl Manager {
         )
     }
 
+    /// Creates a manager with only the resources built into the client,
+    /// without downloading the vanilla ones.
+    pub fn internal_only() -> Manager {
+        let mut m = Manager {
+            packs: Vec::new(),
+            version: 0,
+            vanilla_chan: None,
+            vanilla_assets_chan: None,
+            vanilla_progress: Arc::new(Mutex::new(Progress { tasks: vec![] })),
+        };
+        m.add_pack(Box::new(InternalPack));
+        m
+    }
+
     /// Returns the 'version' of the manager. The version is
     /// increase everytime a pack is added or removed.
     pub fn version(&self) -> usize {
@@ -0,0 +1,388 @@
+//! Scoreboard objectives, scores and teams sent by the server.
+
+use crate::format::{Color, Component, TextComponent};
+use std::collections::HashMap;
+
+/// The most entries shown in the sidebar, like vanilla
+const MAX_SIDEBAR_ENTRIES: usize = 15;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectiveType {
+    Integer,
+    Hearts,
+}
+
+impl ObjectiveType {
+    /// Parses the type as sent before 1.13.
+    pub fn from_name(name: &str) -> ObjectiveType {
+        match name {
+            "hearts" => ObjectiveType::Hearts,
+            _ => ObjectiveType::Integer,
+        }
+    }
+
+    pub fn from_id(id: i32) -> ObjectiveType {
+        match id {
+            1 => ObjectiveType::Hearts,
+            _ => ObjectiveType::Integer,
+        }
+    }
+}
+
+pub struct Objective {
+    pub name: String,
+    pub display_name: Component,
+    pub ty: ObjectiveType,
+}
+
+/// Where an objective is displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DisplaySlot {
+    List,
+    Sidebar,
+    BelowName,
+    /// The sidebar shown only to members of teams with the color
+    TeamSidebar(Color),
+}
+
+impl DisplaySlot {
+    pub fn from_id(id: u8) -> Option<DisplaySlot> {
+        match id {
+            0 => Some(DisplaySlot::List),
+            1 => Some(DisplaySlot::Sidebar),
+            2 => Some(DisplaySlot::BelowName),
+            3..=18 => color_from_id(id as i32 - 3).map(DisplaySlot::TeamSidebar),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameTagVisibility {
+    Always,
+    Never,
+    HideForOtherTeams,
+    HideForOwnTeam,
+}
+
+impl NameTagVisibility {
+    pub fn from_name(name: &str) -> NameTagVisibility {
+        match name {
+            "never" => NameTagVisibility::Never,
+            "hideForOtherTeams" => NameTagVisibility::HideForOtherTeams,
+            "hideForOwnTeam" => NameTagVisibility::HideForOwnTeam,
+            _ => NameTagVisibility::Always,
+        }
+    }
+}
+
+pub struct Team {
+    pub name: String,
+    pub display_name: Component,
+    pub prefix: Component,
+    pub suffix: Component,
+    /// The color of member's names, `None` leaves them unchanged
+    pub color: Option<Color>,
+    /// 0x1 - allow friendly fire, 0x2 - see invisible team members
+    pub flags: u8,
+    pub name_tag_visibility: NameTagVisibility,
+    pub members: Vec<String>,
+}
+
+impl Team {
+    pub fn new(name: &str) -> Team {
+        Team {
+            name: name.to_owned(),
+            display_name: Component::Text(TextComponent::new(name)),
+            prefix: Default::default(),
+            suffix: Default::default(),
+            color: None,
+            flags: 0,
+            name_tag_visibility: NameTagVisibility::Always,
+            members: vec![],
+        }
+    }
+}
+
+/// The state of the scoreboard, updated by the server.
+#[derive(Default)]
+pub struct Scoreboard {
+    objectives: HashMap<String, Objective>,
+    /// Scores of each entry by objective
+    scores: HashMap<String, HashMap<String, i32>>,
+    display: HashMap<DisplaySlot, String>,
+    teams: HashMap<String, Team>,
+    /// The team of each member
+    member_teams: HashMap<String, String>,
+    generation: usize,
+}
+
+impl Scoreboard {
+    pub fn new() -> Scoreboard {
+        Default::default()
+    }
+
+    /// Increases every time the scoreboard changes, to allow anything
+    /// drawing it to avoid rebuilding every frame.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Adds an objective or updates an existing one.
+    pub fn set_objective(&mut self, name: &str, display_name: Component, ty: ObjectiveType) {
+        self.objectives.insert(
+            name.to_owned(),
+            Objective {
+                name: name.to_owned(),
+                display_name,
+                ty,
+            },
+        );
+        self.changed();
+    }
+
+    /// Removes an objective along with its scores.
+    pub fn remove_objective(&mut self, name: &str) {
+        self.objectives.remove(name);
+        self.scores.remove(name);
+        self.display.retain(|_, v| v != name);
+        self.changed();
+    }
+
+    pub fn objective(&self, name: &str) -> Option<&Objective> {
+        self.objectives.get(name)
+    }
+
+    pub fn set_score(&mut self, entry: &str, objective: &str, value: i32) {
+        self.scores
+            .entry(objective.to_owned())
+            .or_default()
+            .insert(entry.to_owned(), value);
+        self.changed();
+    }
+
+    /// Removes the score of an entry for the objective, or for every
+    /// objective if the name is empty.
+    pub fn remove_score(&mut self, entry: &str, objective: &str) {
+        if objective.is_empty() {
+            for scores in self.scores.values_mut() {
+                scores.remove(entry);
+            }
+        } else if let Some(scores) = self.scores.get_mut(objective) {
+            scores.remove(entry);
+        }
+        self.changed();
+    }
+
+    pub fn score(&self, entry: &str, objective: &str) -> Option<i32> {
+        self.scores.get(objective)?.get(entry).copied()
+    }
+
+    /// Shows the objective in the slot, an empty name clears the slot.
+    pub fn set_display(&mut self, slot: DisplaySlot, objective: &str) {
+        if objective.is_empty() {
+            self.display.remove(&slot);
+        } else {
+            self.display.insert(slot, objective.to_owned());
+        }
+        self.changed();
+    }
+
+    pub fn displayed(&self, slot: DisplaySlot) -> Option<&Objective> {
+        self.objectives.get(self.display.get(&slot)?)
+    }
+
+    /// Returns the objective shown in the sidebar to the player along with
+    /// the entries to show, highest score first.
+    pub fn sidebar(&self, player: &str) -> Option<(&Objective, Vec<(&str, i32)>)> {
+        let team_slot = self
+            .team_of(player)
+            .and_then(|v| v.color)
+            .map(DisplaySlot::TeamSidebar)
+            .and_then(|v| self.displayed(v));
+        let objective = team_slot.or_else(|| self.displayed(DisplaySlot::Sidebar))?;
+        let mut entries: Vec<(&str, i32)> = self
+            .scores
+            .get(&objective.name)
+            .into_iter()
+            .flatten()
+            // Entries starting with '#' are hidden
+            .filter(|v| !v.0.starts_with('#'))
+            .map(|(entry, score)| (entry.as_str(), *score))
+            .collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries.truncate(MAX_SIDEBAR_ENTRIES);
+        Some((objective, entries))
+    }
+
+    /// Adds a team or replaces the properties of an existing one, keeping
+    /// its members.
+    pub fn set_team(&mut self, team: Team) {
+        let name = team.name.clone();
+        let (members, new_members) = match self.teams.remove(&name) {
+            Some(existing) => (existing.members, vec![]),
+            None => (vec![], team.members.clone()),
+        };
+        self.teams.insert(name.clone(), Team { members, ..team });
+        self.add_team_members(&name, &new_members);
+        self.changed();
+    }
+
+    pub fn remove_team(&mut self, name: &str) {
+        if let Some(team) = self.teams.remove(name) {
+            for member in team.members {
+                self.member_teams.remove(&member);
+            }
+        }
+        self.changed();
+    }
+
+    /// Adds members to a team, removing them from any team they were in.
+    pub fn add_team_members(&mut self, name: &str, members: &[String]) {
+        if !self.teams.contains_key(name) {
+            return;
+        }
+        for member in members {
+            if let Some(old) = self.member_teams.insert(member.clone(), name.to_owned()) {
+                if let Some(team) = self.teams.get_mut(&old) {
+                    team.members.retain(|v| v != member);
+                }
+            }
+            if let Some(team) = self.teams.get_mut(name) {
+                team.members.push(member.clone());
+            }
+        }
+        self.changed();
+    }
+
+    pub fn remove_team_members(&mut self, name: &str, members: &[String]) {
+        if let Some(team) = self.teams.get_mut(name) {
+            team.members.retain(|v| !members.contains(v));
+            for member in members {
+                if self.member_teams.get(member).map(|v| v.as_str()) == Some(name) {
+                    self.member_teams.remove(member);
+                }
+            }
+        }
+        self.changed();
+    }
+
+    pub fn team_of(&self, member: &str) -> Option<&Team> {
+        self.teams.get(self.member_teams.get(member)?)
+    }
+
+    /// Returns the name with the prefix, suffix and color of the member's
+    /// team applied.
+    pub fn decorate_name(&self, member: &str, name: Component) -> Component {
+        let team = match self.team_of(member) {
+            Some(team) => team,
+            None => return name,
+        };
+        let mut name = name;
+        if let (Some(color), Component::Text(text)) = (team.color, &mut name) {
+            text.modifier.color.get_or_insert(color);
+        }
+        let mut decorated = TextComponent::new("");
+        decorated.modifier.extra = Some(vec![team.prefix.clone(), name, team.suffix.clone()]);
+        Component::Text(decorated)
+    }
+
+    /// Returns whether `viewer` should see the name tag of `member`.
+    pub fn is_name_tag_visible(&self, member: &str, viewer: &str) -> bool {
+        let team = match self.team_of(member) {
+            Some(team) => team,
+            None => return true,
+        };
+        let same_team = self.member_teams.get(viewer) == Some(&team.name);
+        match team.name_tag_visibility {
+            NameTagVisibility::Always => true,
+            NameTagVisibility::Never => false,
+            NameTagVisibility::HideForOtherTeams => same_team,
+            NameTagVisibility::HideForOwnTeam => !same_team,
+        }
+    }
+
+    fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+/// Returns one of the 16 chat colors by its index, as used by team
+/// colors and display slots.
+pub fn color_from_id(id: i32) -> Option<Color> {
+    Some(match id {
+        0 => Color::Black,
+        1 => Color::DarkBlue,
+        2 => Color::DarkGreen,
+        3 => Color::DarkAqua,
+        4 => Color::DarkRed,
+        5 => Color::DarkPurple,
+        6 => Color::Gold,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::Blue,
+        10 => Color::Green,
+        11 => Color::Aqua,
+        12 => Color::Red,
+        13 => Color::LightPurple,
+        14 => Color::Yellow,
+        15 => Color::White,
+        _ => return None,
+    })
+}
+
+/// Returns the color active at the end of a component, used to carry the
+/// color of a legacy team prefix on to the member's name.
+pub fn trailing_color(component: &Component) -> Option<Color> {
+    let Component::Text(text) = component;
+    text.modifier
+        .extra
+        .iter()
+        .flatten()
+        .rev()
+        .find_map(trailing_color)
+        .or(text.modifier.color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn team_membership() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.set_team(Team::new("red"));
+        scoreboard.set_team(Team::new("blue"));
+        scoreboard.add_team_members("red", &["alice".to_owned(), "bob".to_owned()]);
+        scoreboard.add_team_members("blue", &["bob".to_owned()]);
+        assert_eq!(scoreboard.team_of("alice").unwrap().name, "red");
+        assert_eq!(scoreboard.team_of("bob").unwrap().name, "blue");
+        assert_eq!(scoreboard.teams["red"].members, vec!["alice".to_owned()]);
+
+        // Updating a team keeps its members
+        scoreboard.set_team(Team::new("red"));
+        assert_eq!(scoreboard.team_of("alice").unwrap().name, "red");
+
+        scoreboard.remove_team("red");
+        assert!(scoreboard.team_of("alice").is_none());
+        assert_eq!(scoreboard.team_of("bob").unwrap().name, "blue");
+    }
+
+    #[test]
+    fn sidebar_order() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.set_objective("kills", Default::default(), ObjectiveType::Integer);
+        scoreboard.set_display(DisplaySlot::Sidebar, "kills");
+        scoreboard.set_score("bob", "kills", 3);
+        scoreboard.set_score("alice", "kills", 3);
+        scoreboard.set_score("carol", "kills", 7);
+        scoreboard.set_score("#hidden", "kills", 10);
+        let (objective, entries) = scoreboard.sidebar("alice").unwrap();
+        assert_eq!(objective.name, "kills");
+        assert_eq!(entries, vec![("carol", 7), ("alice", 3), ("bob", 3)]);
+
+        scoreboard.remove_score("carol", "");
+        scoreboard.remove_objective("kills");
+        assert!(scoreboard.sidebar("alice").is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::chat::ChatScroll;
+use crate::render;
+use crate::ui;
+use winit::event::VirtualKeyCode;
+
+pub struct Chat {
+    elements: Option<UIElements>,
+    scroll: ChatScroll,
+    initial_input: String,
+    sent: Vec<String>,
+    /// Position in the sent history while recalling lines
+    history_index: Option<usize>,
+}
+
+struct UIElements {
+    input: ui::TextBoxRef,
+}
+
+impl Chat {
+    pub fn new(scroll: ChatScroll, sent: &[String], initial_input: &str) -> Chat {
+        Chat {
+            elements: None,
+            scroll,
+            initial_input: initial_input.to_owned(),
+            sent: sent.to_vec(),
+            history_index: None,
+        }
+    }
+}
+
+impl super::Screen for Chat {
+    fn on_active(&mut self, _renderer: &mut render::Renderer, ui_container: &mut ui::Container) {
+        let input = ui::TextBoxBuilder::new()
+            .input(&self.initial_input)
+            .position(2.0, 2.0)
+            .size(600.0, 30.0)
+            .alignment(ui::VAttach::Bottom, ui::HAttach::Left)
+            .create(ui_container);
+        ui::TextBox::make_focusable(&input, ui_container);
+        input.borrow_mut().add_submit_func(|textbox, game| {
+            let line = textbox.input.trim().to_owned();
+            if !line.is_empty() {
+                game.server.send_chat(&line);
+            }
+            game.screen_sys.pop_screen();
+            game.focused = true;
+        });
+
+        input.borrow_mut().add_complete_func(|textbox, game| {
+            if let Some(completed) = game.server.tab_complete(&textbox.input) {
+                textbox.input = completed;
+            }
+        });
+
+        self.scroll.set(Some(0));
+        self.elements = Some(UIElements { input });
+    }
+
+    fn on_deactive(&mut self, _renderer: &mut render::Renderer, _ui_container: &mut ui::Container) {
+        self.scroll.set(None);
+        self.elements = None;
+    }
+
+    fn tick(
+        &mut self,
+        _delta: f64,
+        _renderer: &mut render::Renderer,
+        _ui_container: &mut ui::Container,
+    ) -> Option<Box<dyn super::Screen>> {
+        None
+    }
+
+    fn on_scroll(&mut self, _x: f64, y: f64) {
+        if let Some(scroll) = self.scroll.get() {
+            let scroll = if y > 0.0 {
+                scroll + 1
+            } else {
+                scroll.saturating_sub(1)
+            };
+            self.scroll.set(Some(scroll));
+        }
+    }
+
+    fn on_key_press(&mut self, key: VirtualKeyCode, down: bool) {
+        if !down || self.sent.is_empty() {
+            return;
+        }
+        let index = match (key, self.history_index) {
+            (VirtualKeyCode::Up, None) => Some(self.sent.len() - 1),
+            (VirtualKeyCode::Up, Some(index)) => Some(index.saturating_sub(1)),
+            (VirtualKeyCode::Down, Some(index)) if index + 1 < self.sent.len() => Some(index + 1),
+            (VirtualKeyCode::Down, Some(_)) => None,
+            _ => return,
+        };
+        self.history_index = index;
+        if let Some(elements) = self.elements.as_ref() {
+            elements.input.borrow_mut().input = index.map_or_else(
+                || self.initial_input.clone(),
+                |index| self.sent[index].clone(),
+            );
+        }
+    }
+
+    fn on_tab_complete(&mut self, request: &str, completed: &str) {
+        if let Some(elements) = self.elements.as_ref() {
+            let mut input = elements.input.borrow_mut();
+            // Ignore replies to input which has been changed since
+            if input.input == request {
+                input.input = completed.to_owned();
+            }
+        }
+    }
+
+    fn is_closable(&self) -> bool {
+        true
+    }
+}
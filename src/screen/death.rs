@@ -0,0 +1,125 @@
+use crate::format::Component;
+use crate::render;
+use crate::ui;
+
+pub struct Death {
+    elements: Option<UIElements>,
+    message: Option<Component>,
+}
+
+struct UIElements {
+    background: ui::ImageRef,
+    _title: ui::TextRef,
+    _message: Option<ui::FormattedRef>,
+    _buttons: Vec<ui::ButtonRef>,
+}
+
+impl Death {
+    pub fn new(message: Option<Component>) -> Death {
+        Death {
+            elements: None,
+            message,
+        }
+    }
+}
+
+impl super::Screen for Death {
+    fn on_active(&mut self, _renderer: &mut render::Renderer, ui_container: &mut ui::Container) {
+        let background = ui::ImageBuilder::new()
+            .texture("steven:solid")
+            .position(0.0, 0.0)
+            .size(854.0, 480.0)
+            .colour((128, 0, 0, 100))
+            .create(ui_container);
+
+        let title = ui::TextBuilder::new()
+            .text("You died!")
+            .position(0.0, -100.0)
+            .scale_x(2.0)
+            .scale_y(2.0)
+            .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+            .create(ui_container);
+
+        let message = self.message.clone().map(|message| {
+            ui::FormattedBuilder::new()
+                .text(message)
+                .position(0.0, -50.0)
+                .max_width(600.0)
+                .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+                .create(ui_container)
+        });
+
+        let mut buttons = vec![];
+        let respawn = ui::ButtonBuilder::new()
+            .position(0.0, 20.0)
+            .size(300.0, 40.0)
+            .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+            .create(ui_container);
+        {
+            let mut respawn = respawn.borrow_mut();
+            let txt = ui::TextBuilder::new()
+                .text("Respawn")
+                .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+                .attach(&mut *respawn);
+            respawn.add_text(txt);
+            // The screen is closed once the server respawns the player
+            respawn.add_click_func(|_, game| {
+                game.server.respawn_player();
+                true
+            });
+        }
+        buttons.push(respawn);
+
+        let disconnect = ui::ButtonBuilder::new()
+            .position(0.0, 70.0)
+            .size(300.0, 40.0)
+            .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+            .create(ui_container);
+        {
+            let mut disconnect = disconnect.borrow_mut();
+            let txt = ui::TextBuilder::new()
+                .text("Disconnect")
+                .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+                .attach(&mut *disconnect);
+            disconnect.add_text(txt);
+            disconnect.add_click_func(|_, game| {
+                game.server.disconnect(None);
+                game.screen_sys
+                    .replace_screen(Box::new(super::ServerList::new(game.vars.clone(), None)));
+                true
+            });
+        }
+        buttons.push(disconnect);
+
+        self.elements = Some(UIElements {
+            background,
+            _title: title,
+            _message: message,
+            _buttons: buttons,
+        });
+    }
+
+    fn on_deactive(&mut self, _renderer: &mut render::Renderer, _ui_container: &mut ui::Container) {
+        self.elements = None;
+    }
+
+    fn tick(
+        &mut self,
+        _delta: f64,
+        renderer: &mut render::Renderer,
+        ui_container: &mut ui::Container,
+    ) -> Option<Box<dyn super::Screen>> {
+        let elements = self.elements.as_mut().unwrap();
+        let mode = ui_container.mode;
+        let mut background = elements.background.borrow_mut();
+        background.width = match mode {
+            ui::Mode::Unscaled(scale) => 854.0 / scale,
+            ui::Mode::Scaled => renderer.width as f64,
+        };
+        background.height = match mode {
+            ui::Mode::Unscaled(scale) => 480.0 / scale,
+            ui::Mode::Scaled => renderer.height as f64,
+        };
+        None
+    }
+}
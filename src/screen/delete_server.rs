@@ -102,7 +102,7 @@ impl super::Screen for DeleteServerEntry {
             confirm.add_click_func(move |_, game| {
                 Self::delete_server(index);
                 game.screen_sys
-                    .replace_screen(Box::new(super::ServerList::new(None)));
+                    .replace_screen(Box::new(super::ServerList::new(game.vars.clone(), None)));
                 true
             });
         }
@@ -122,7 +122,7 @@ impl super::Screen for DeleteServerEntry {
             cancel.add_text(txt);
             cancel.add_click_func(|_, game| {
                 game.screen_sys
-                    .replace_screen(Box::new(super::ServerList::new(None)));
+                    .replace_screen(Box::new(super::ServerList::new(game.vars.clone(), None)));
                 true
             });
         }
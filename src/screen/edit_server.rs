@@ -132,7 +132,7 @@ impl super::Screen for EditServerEntry {
                     &server_address.borrow().input,
                 );
                 game.screen_sys
-                    .replace_screen(Box::new(super::ServerList::new(None)));
+                    .replace_screen(Box::new(super::ServerList::new(game.vars.clone(), None)));
                 true
             });
         }
@@ -152,7 +152,7 @@ impl super::Screen for EditServerEntry {
             cancel.add_text(txt);
             cancel.add_click_func(|_, game| {
                 game.screen_sys
-                    .replace_screen(Box::new(super::ServerList::new(None)));
+                    .replace_screen(Box::new(super::ServerList::new(game.vars.clone(), None)));
                 true
             });
         }
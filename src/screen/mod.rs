@@ -17,15 +17,20 @@ pub use self::server_list::*;
 mod login;
 pub use self::login::*;
 
+pub mod chat;
 pub mod connecting;
+pub mod death;
 pub mod delete_server;
 pub mod edit_server;
+pub mod sign_editor;
 
 pub mod settings_menu;
 pub use self::settings_menu::{AudioSettingsMenu, SettingsMenu, VideoSettingsMenu};
 
 use crate::render;
+use crate::server;
 use crate::ui;
+use winit::event::VirtualKeyCode;
 
 pub trait Screen {
     // Called once
@@ -46,6 +51,11 @@ pub trait Screen {
 
     // Events
     fn on_scroll(&mut self, _x: f64, _y: f64) {}
+    fn on_key_press(&mut self, _key: VirtualKeyCode, _down: bool) {}
+    /// Called when the server replies to a tab completion request
+    fn on_tab_complete(&mut self, _request: &str, _completed: &str) {}
+    /// Called when the player closes the screen, before it is removed
+    fn on_close(&mut self, _server: &mut server::Server) {}
 
     fn is_closable(&self) -> bool {
         false
@@ -88,6 +98,15 @@ impl ScreenSystem {
         self.add_screen(screen);
     }
 
+    /// Closes the current screen because the player asked to, letting it
+    /// tell the server first.
+    pub fn close_screen(&mut self, server: &mut server::Server) {
+        if let Some(current) = self.screens.last_mut() {
+            current.screen.on_close(server);
+        }
+        self.pop_screen();
+    }
+
     pub fn is_current_closable(&self) -> bool {
         if let Some(last) = self.screens.last() {
             last.screen.is_closable()
@@ -147,4 +166,16 @@ impl ScreenSystem {
         let current = self.screens.last_mut().unwrap();
         current.screen.on_scroll(x, y);
     }
+
+    pub fn on_key_press(&mut self, key: VirtualKeyCode, down: bool) {
+        if let Some(current) = self.screens.last_mut() {
+            current.screen.on_key_press(key, down);
+        }
+    }
+
+    pub fn on_tab_complete(&mut self, request: &str, completed: &str) {
+        if let Some(current) = self.screens.last_mut() {
+            current.screen.on_tab_complete(request, completed);
+        }
+    }
 }
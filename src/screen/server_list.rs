@@ -18,10 +18,12 @@ use std::sync::mpsc;
 use std::thread;
 use std_or_web::fs;
 
+use crate::console;
 use crate::format;
 use crate::format::{Component, TextComponent};
 use crate::protocol;
 use crate::render;
+use crate::settings;
 use crate::ui;
 
 use instant::Duration;
@@ -29,6 +31,7 @@ use rand::Rng;
 
 pub struct ServerList {
     elements: Option<UIElements>,
+    vars: Rc<console::Vars>,
     disconnect_reason: Option<Component>,
 
     needs_reload: Rc<RefCell<bool>>,
@@ -86,9 +89,10 @@ impl Server {
 }
 
 impl ServerList {
-    pub fn new(disconnect_reason: Option<Component>) -> ServerList {
+    pub fn new(vars: Rc<console::Vars>, disconnect_reason: Option<Component>) -> ServerList {
         ServerList {
             elements: None,
+            vars,
             disconnect_reason,
             needs_reload: Rc::new(RefCell::new(false)),
         }
@@ -266,9 +270,14 @@ impl ServerList {
             offset += 1.0;
 
             // Don't block the main thread whilst pinging the server
+            let resolver = settings::resolver(&self.vars);
             thread::spawn(move || {
-                match protocol::Conn::new(&address, protocol::SUPPORTED_PROTOCOLS[0])
-                    .and_then(|conn| conn.do_status())
+                match protocol::Conn::connect_with(
+                    &resolver,
+                    &address,
+                    protocol::SUPPORTED_PROTOCOLS[0],
+                )
+                .and_then(|conn| conn.do_status())
                 {
                     Ok(res) => {
                         let mut desc = res.0.description;
@@ -145,7 +145,7 @@ impl super::Screen for SettingsMenu {
                 disconnect_button.add_click_func(|_, game| {
                     game.server.disconnect(None);
                     game.screen_sys
-                        .replace_screen(Box::new(super::ServerList::new(None)));
+                        .replace_screen(Box::new(super::ServerList::new(game.vars.clone(), None)));
                     true
                 });
             }
@@ -0,0 +1,214 @@
+use crate::format::{self, Component, TextComponent};
+use crate::render;
+use crate::server;
+use crate::shared::Position;
+use crate::ui;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+// The sign is drawn at twice the size of the font, like vanilla
+const SCALE: f64 = 2.0;
+const BOARD_WIDTH: f64 = 192.0 * SCALE;
+const BOARD_HEIGHT: f64 = 96.0 * SCALE;
+const LINE_HEIGHT: f64 = 20.0 * SCALE;
+// Lines longer than this won't fit on the sign
+const MAX_LINE_WIDTH: f64 = 180.0;
+
+/// Lets the player write on a sign they placed, showing what it will
+/// look like as they type.
+pub struct SignEditor {
+    elements: Option<UIElements>,
+    position: Position,
+}
+
+struct UIElements {
+    background: ui::ImageRef,
+    _title: ui::TextRef,
+    _board: ui::ImageRef,
+    inputs: Vec<ui::TextBoxRef>,
+    previews: Vec<ui::FormattedRef>,
+    last_lines: Vec<String>,
+    _done: ui::ButtonRef,
+}
+
+impl SignEditor {
+    pub fn new(position: Position) -> SignEditor {
+        SignEditor {
+            elements: None,
+            position,
+        }
+    }
+}
+
+fn line_component(line: &str) -> Component {
+    let mut text = TextComponent::new(line);
+    text.modifier.color = Some(format::Color::Black);
+    let mut component = Component::Text(text);
+    format::convert_legacy(&mut component);
+    component
+}
+
+/// Sends the lines of the sign and closes the editor. `submitted` is the
+/// text box enter was pressed in, which is already borrowed.
+fn finish(
+    position: Position,
+    inputs: &[Weak<RefCell<ui::TextBox>>],
+    submitted: Option<&ui::TextBox>,
+    game: &mut crate::Game,
+) {
+    let lines: Vec<String> = inputs
+        .iter()
+        .map(|v| match v.upgrade().as_ref().map(|v| v.try_borrow()) {
+            Some(Ok(input)) => input.input.clone(),
+            Some(Err(_)) => submitted.map_or_else(String::new, |v| v.input.clone()),
+            None => String::new(),
+        })
+        .collect();
+    game.server.set_sign(position, &lines);
+    game.screen_sys.pop_screen();
+    game.focused = true;
+}
+
+impl super::Screen for SignEditor {
+    fn on_active(&mut self, _renderer: &mut render::Renderer, ui_container: &mut ui::Container) {
+        let background = ui::ImageBuilder::new()
+            .texture("steven:solid")
+            .position(0.0, 0.0)
+            .size(854.0, 480.0)
+            .colour((0, 0, 0, 100))
+            .create(ui_container);
+
+        let title = ui::TextBuilder::new()
+            .text("Edit sign message")
+            .position(0.0, 20.0)
+            .alignment(ui::VAttach::Top, ui::HAttach::Center)
+            .create(ui_container);
+
+        let board = ui::ImageBuilder::new()
+            .texture("entity/sign")
+            .texture_coords((2.0 / 64.0, 2.0 / 32.0, 24.0 / 64.0, 12.0 / 32.0))
+            .position(0.0, 60.0)
+            .size(BOARD_WIDTH, BOARD_HEIGHT)
+            .alignment(ui::VAttach::Top, ui::HAttach::Center)
+            .create(ui_container);
+
+        let mut inputs = vec![];
+        let mut previews = vec![];
+        for index in 0..4 {
+            let offset = index as f64 * LINE_HEIGHT;
+            previews.push(
+                ui::FormattedBuilder::new()
+                    .text(line_component(""))
+                    .position(0.0, 60.0 + 8.0 * SCALE + offset)
+                    .scale_x(SCALE)
+                    .scale_y(SCALE)
+                    .alignment(ui::VAttach::Top, ui::HAttach::Center)
+                    .create(ui_container),
+            );
+            let input = ui::TextBoxBuilder::new()
+                .position(0.0, 80.0 + BOARD_HEIGHT + offset * 1.25)
+                .size(400.0, 40.0)
+                .alignment(ui::VAttach::Top, ui::HAttach::Center)
+                .create(ui_container);
+            ui::TextBox::make_focusable(&input, ui_container);
+            inputs.push(input);
+        }
+        // The text boxes only hold on to each other weakly so they are freed
+        // with the screen
+        let weak_inputs: Vec<_> = inputs.iter().map(Rc::downgrade).collect();
+        for input in &inputs {
+            let position = self.position;
+            let all = weak_inputs.clone();
+            input
+                .borrow_mut()
+                .add_submit_func(move |textbox, game| finish(position, &all, Some(textbox), game));
+        }
+
+        let done = ui::ButtonBuilder::new()
+            .position(0.0, 40.0)
+            .size(200.0, 40.0)
+            .alignment(ui::VAttach::Bottom, ui::HAttach::Center)
+            .create(ui_container);
+        {
+            let mut done = done.borrow_mut();
+            let txt = ui::TextBuilder::new()
+                .text("Done")
+                .alignment(ui::VAttach::Middle, ui::HAttach::Center)
+                .attach(&mut *done);
+            done.add_text(txt);
+            let position = self.position;
+            let all = weak_inputs;
+            done.add_click_func(move |_, game| {
+                finish(position, &all, None, game);
+                true
+            });
+        }
+
+        self.elements = Some(UIElements {
+            background,
+            _title: title,
+            _board: board,
+            inputs,
+            previews,
+            last_lines: vec![String::new(); 4],
+            _done: done,
+        });
+    }
+
+    fn on_deactive(&mut self, _renderer: &mut render::Renderer, _ui_container: &mut ui::Container) {
+        self.elements = None;
+    }
+
+    fn tick(
+        &mut self,
+        _delta: f64,
+        renderer: &mut render::Renderer,
+        ui_container: &mut ui::Container,
+    ) -> Option<Box<dyn super::Screen>> {
+        let elements = self.elements.as_mut().unwrap();
+        for (index, input) in elements.inputs.iter().enumerate() {
+            let mut input = input.borrow_mut();
+            if input.input == elements.last_lines[index] {
+                continue;
+            }
+            // Refuse characters that would run off the edge of the sign
+            while ui::Formatted::compute_size(renderer, &line_component(&input.input), -1.0).0
+                > MAX_LINE_WIDTH
+            {
+                input.input.pop();
+            }
+            elements.last_lines[index] = input.input.clone();
+            elements.previews[index]
+                .borrow_mut()
+                .set_text(line_component(&input.input));
+        }
+
+        let mode = ui_container.mode;
+        let mut background = elements.background.borrow_mut();
+        background.width = match mode {
+            ui::Mode::Unscaled(scale) => 854.0 / scale,
+            ui::Mode::Scaled => renderer.width as f64,
+        };
+        background.height = match mode {
+            ui::Mode::Unscaled(scale) => 480.0 / scale,
+            ui::Mode::Scaled => renderer.height as f64,
+        };
+        None
+    }
+
+    /// Closing the editor keeps what has been written so far, like vanilla
+    fn on_close(&mut self, server: &mut server::Server) {
+        if let Some(elements) = self.elements.as_ref() {
+            let lines: Vec<String> = elements
+                .inputs
+                .iter()
+                .map(|v| v.borrow().input.clone())
+                .collect();
+            server.set_sign(self.position, &lines);
+        }
+    }
+
+    fn is_closable(&self) -> bool {
+        true
+    }
+}
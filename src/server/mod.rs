@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::chat;
+use crate::completion;
 use crate::ecs;
 use crate::entity;
 use crate::format;
+use crate::hud;
+use crate::inventory;
 use crate::protocol::{self, forge, mojang, packet};
 use crate::render;
 use crate::resources;
+use crate::scoreboard;
 use crate::settings::Stevenkey;
 use crate::shared::{Axis, Position};
 use crate::types::hash::FNVHash;
@@ -25,7 +30,7 @@ use crate::types::Gamemode;
 use crate::world;
 use crate::world::block;
 use cgmath::prelude::*;
-use instant::Instant;
+use collision::Aabb;
 use log::{debug, error, info, warn};
 use rand::{self, Rng};
 use std::collections::HashMap;
@@ -35,12 +40,17 @@ use std::sync::mpsc;
 use std::sync::{Arc, RwLock};
 use std::thread;
 
+/// How far away entities can be attacked or interacted with
+const ENTITY_REACH: f64 = 3.0;
+
+mod particle;
 pub mod plugin_messages;
 mod sun;
 pub mod target;
 
 pub struct Server {
     uuid: protocol::UUID,
+    username: String,
     conn: Arc<RwLock<Option<protocol::Conn>>>,
     protocol_version: i32,
     forge_mods: Vec<forge::ForgeMod>,
@@ -61,36 +71,86 @@ pub struct Server {
     // Entity accessors
     game_info: ecs::Key<entity::GameInfo>,
     player_movement: ecs::Key<entity::player::PlayerMovement>,
+    player_actions: ecs::Key<entity::player::PlayerActions>,
+    riding: ecs::Key<entity::Riding>,
     mouse_buttons: ecs::Key<entity::MouseButtons>,
+    digging: ecs::Key<entity::Digging>,
     gravity: ecs::Key<entity::Gravity>,
     position: ecs::Key<entity::Position>,
     target_position: ecs::Key<entity::TargetPosition>,
     velocity: ecs::Key<entity::Velocity>,
     gamemode: ecs::Key<Gamemode>,
+    bounds: ecs::Key<entity::Bounds>,
+    entity_type: ecs::Key<entity::EntityType>,
     pub rotation: ecs::Key<entity::Rotation>,
     target_rotation: ecs::Key<entity::TargetRotation>,
+    entity_metadata: ecs::Key<entity::metadata::EntityMetadata>,
     //
+    pub inventory_context: inventory::InventoryContext,
+    pub player_stats: hud::PlayerStats,
+    pub boss_bars: hud::BossBars,
+    pub titles: hud::Titles,
+    pub scoreboard: scoreboard::Scoreboard,
+    pub player_list_header: Option<format::Component>,
+    pub player_list_footer: Option<format::Component>,
     pub player: Option<ecs::Entity>,
     entity_map: HashMap<i32, ecs::Entity, BuildHasherDefault<FNVHash>>,
+    entity_id_map: entity::EntityIDMap,
     players: HashMap<protocol::UUID, PlayerInfo, BuildHasherDefault<FNVHash>>,
 
     tick_timer: f64,
     entity_tick_timer: f64,
-    pub received_chat_at: Option<Instant>,
+    /// Scrolling not yet applied to the hotbar, in lines
+    hotbar_scroll: f64,
+    pub chat: chat::Chat,
+    tab_completer: completion::TabCompleter,
+    /// The input a tab completion was requested from the server for
+    tab_complete_request: Option<String>,
+    tab_complete_id: i32,
+    tab_completion: Option<(String, String)>,
+    /// A sign the server asked the player to write on
+    sign_editor: Option<Position>,
+    command_tree: Option<completion::CommandTree>,
 
     sun_model: Option<sun::SunModel>,
+    /// Particles to hand to the renderer on the next tick
+    particles: Vec<render::particles::Spawn>,
+    /// Stages of blocks being broken by other entities, by the entity's id,
+    /// to hand to the renderer on the next tick
+    break_stages: Vec<(i32, Position, i8)>,
     target_info: target::Info,
+    entity_target_info: target::EntityInfo,
+    target_entity: Option<TargetEntity>,
+}
+
+/// The entity the player is looking at.
+#[derive(Clone, Copy)]
+struct TargetEntity {
+    id: i32,
+    entity: ecs::Entity,
+    /// Where the player is looking at on the entity, relative to its position
+    at: cgmath::Vector3<f64>,
 }
 
 #[derive(Debug)]
+/// The properties of a team shared by the different versions of `Teams`.
+struct TeamInfo {
+    display_name: Option<String>,
+    prefix: Option<String>,
+    suffix: Option<String>,
+    flags: Option<u8>,
+    name_tag_visibility: Option<String>,
+    color: Option<format::Color>,
+}
+
 pub struct PlayerInfo {
-    name: String,
-    uuid: protocol::UUID,
-    skin_url: Option<String>,
+    pub name: String,
+    pub uuid: protocol::UUID,
+    pub skin_url: Option<String>,
 
-    display_name: Option<format::Component>,
-    ping: i32,
-    gamemode: Gamemode,
+    pub display_name: Option<format::Component>,
+    pub ping: i32,
+    pub gamemode: Gamemode,
 }
 
 macro_rules! handle_packet {
@@ -106,22 +166,82 @@ macro_rules! handle_packet {
     )
 }
 
+/// Returns the reason shown to the player for an error that ended their
+/// connection.
+pub fn disconnect_reason(err: protocol::Error) -> format::Component {
+    let text = match err {
+        protocol::Error::Disconnect(reason) => return reason,
+        // Depending on whether the client was reading or writing when the
+        // server went away
+        protocol::Error::IOError(ref e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::UnexpectedEof
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+            ) =>
+        {
+            "Connection closed by the server".to_owned()
+        }
+        protocol::Error::Packet { error, .. } => {
+            format!("Failed to read a packet from the server: {}", error)
+        }
+        err => format!("{}", err),
+    };
+    let mut msg = format::TextComponent::new(&text);
+    msg.modifier.color = Some(format::Color::Red);
+    format::Component::Text(msg)
+}
+
 impl Server {
     pub fn connect(
+        resolver: &protocol::resolve::Resolver,
+        resources: Arc<RwLock<resources::Manager>>,
+        profile: mojang::Profile,
+        address: &str,
+        protocol_version: i32,
+        forge_mods: Vec<forge::ForgeMod>,
+        fml_network_version: Option<i64>,
+    ) -> Result<Server, protocol::Error> {
+        Self::connect_with_session(
+            resolver,
+            resources,
+            profile,
+            address,
+            protocol_version,
+            forge_mods,
+            fml_network_version,
+            true,
+        )
+    }
+
+    /// Connects like `connect`, but only tells the session server about
+    /// joining when `join_session` is set. Servers in online mode kick
+    /// players that didn't, so this is only for offline test servers.
+    #[allow(clippy::too_many_arguments)]
+    fn connect_with_session(
+        resolver: &protocol::resolve::Resolver,
         resources: Arc<RwLock<resources::Manager>>,
         profile: mojang::Profile,
         address: &str,
         protocol_version: i32,
         forge_mods: Vec<forge::ForgeMod>,
         fml_network_version: Option<i64>,
+        join_session: bool,
     ) -> Result<Server, protocol::Error> {
-        let mut conn = protocol::Conn::new(address, protocol_version)?;
+        let mut conn = protocol::Conn::connect_with(resolver, address, protocol_version)?;
 
         let tag = match fml_network_version {
             Some(1) => "\0FML\0",
             Some(2) => "\0FML2\0",
             None => "",
-            _ => panic!("unsupported FML network version: {:?}", fml_network_version),
+            _ => {
+                return Err(protocol::Error::Err(format!(
+                    "unsupported FML network version: {:?}",
+                    fml_network_version
+                )))
+            }
         };
 
         let host = conn.host.clone() + tag;
@@ -168,6 +288,7 @@ impl Server {
                         protocol_version,
                         forge_mods,
                         protocol::UUID::from_str(&val.uuid).unwrap(),
+                        val.username,
                         resources,
                         Arc::new(RwLock::new(Some(write))),
                         Some(rx),
@@ -185,6 +306,7 @@ impl Server {
                         protocol_version,
                         forge_mods,
                         val.uuid,
+                        val.username,
                         resources,
                         Arc::new(RwLock::new(Some(write))),
                         Some(rx),
@@ -203,8 +325,8 @@ impl Server {
         let shared_e = rsa_public_encrypt_pkcs1::encrypt(&public_key, &shared).unwrap();
         let token_e = rsa_public_encrypt_pkcs1::encrypt(&public_key, &verify_token).unwrap();
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
+        if join_session {
+            #[cfg(not(target_arch = "wasm32"))]
             profile.join_server(&server_id, &shared, &public_key)?;
         }
 
@@ -228,7 +350,7 @@ impl Server {
         read.enable_encyption(&shared, true);
         write.enable_encyption(&shared, false);
 
-        let uuid;
+        let (uuid, username);
         let compression_threshold = read.compression_threshold;
         loop {
             match read.read_packet()? {
@@ -239,6 +361,7 @@ impl Server {
                 protocol::packet::Packet::LoginSuccess_String(val) => {
                     debug!("Login: {} {}", val.username, val.uuid);
                     uuid = protocol::UUID::from_str(&val.uuid).unwrap();
+                    username = val.username;
                     read.state = protocol::State::Play;
                     write.state = protocol::State::Play;
                     break;
@@ -246,6 +369,7 @@ impl Server {
                 protocol::packet::Packet::LoginSuccess_UUID(val) => {
                     debug!("Login: {} {:?}", val.username, val.uuid);
                     uuid = val.uuid;
+                    username = val.username;
                     read.state = protocol::State::Play;
                     write.state = protocol::State::Play;
                     break;
@@ -307,16 +431,28 @@ impl Server {
                                                 Some(&Acknowledgement),
                                             )?;
                                         }
-                                        _ => unimplemented!(),
+                                        packet => {
+                                            return Err(protocol::Error::Err(format!(
+                                                "unexpected FML handshake packet: {:?}",
+                                                packet
+                                            )))
+                                        }
                                     }
                                 }
-                                _ => panic!(
-                                    "unknown LoginPluginRequest fml:loginwrapper channel: {:?}",
-                                    channel
-                                ),
+                                _ => {
+                                    return Err(protocol::Error::Err(format!(
+                                        "unknown LoginPluginRequest fml:loginwrapper channel: {:?}",
+                                        channel
+                                    )))
+                                }
                             }
                         }
-                        _ => panic!("unsupported LoginPluginRequest channel: {:?}", req.channel),
+                        _ => {
+                            return Err(protocol::Error::Err(format!(
+                                "unsupported LoginPluginRequest channel: {:?}",
+                                req.channel
+                            )))
+                        }
                     }
                 }
                 val => return Err(protocol::Error::Err(format!("Wrong packet 2: {:?}", val))),
@@ -329,6 +465,7 @@ impl Server {
             protocol_version,
             forge_mods,
             uuid,
+            username,
             resources,
             Arc::new(RwLock::new(Some(write))),
             Some(rx),
@@ -357,6 +494,7 @@ impl Server {
             protocol::SUPPORTED_PROTOCOLS[0],
             vec![],
             protocol::UUID::default(),
+            String::new(),
             resources,
             Arc::new(RwLock::new(None)),
             None,
@@ -445,6 +583,7 @@ impl Server {
         protocol_version: i32,
         forge_mods: Vec<forge::ForgeMod>,
         uuid: protocol::UUID,
+        username: String,
         resources: Arc<RwLock<resources::Manager>>,
         conn: Arc<RwLock<Option<protocol::Conn>>>,
         read_queue: Option<mpsc::Receiver<Result<packet::Packet, protocol::Error>>>,
@@ -460,6 +599,7 @@ impl Server {
         let version = resources.read().unwrap().version();
         Server {
             uuid,
+            username,
             conn,
             protocol_version,
             forge_mods,
@@ -479,26 +619,51 @@ impl Server {
             // Entity accessors
             game_info,
             player_movement: entities.get_key(),
+            player_actions: entities.get_key(),
+            riding: entities.get_key(),
             mouse_buttons: entities.get_key(),
+            digging: entities.get_key(),
             gravity: entities.get_key(),
             position: entities.get_key(),
             target_position: entities.get_key(),
             velocity: entities.get_key(),
             gamemode: entities.get_key(),
+            bounds: entities.get_key(),
+            entity_type: entities.get_key(),
             rotation: entities.get_key(),
             target_rotation: entities.get_key(),
+            entity_metadata: entities.get_key(),
             //
+            inventory_context: inventory::InventoryContext::new(protocol_version),
+            player_stats: hud::PlayerStats::new(),
+            boss_bars: hud::BossBars::new(),
+            titles: hud::Titles::new(),
+            scoreboard: scoreboard::Scoreboard::new(),
+            player_list_header: None,
+            player_list_footer: None,
             entities,
             player: None,
             entity_map: HashMap::with_hasher(BuildHasherDefault::default()),
+            entity_id_map: entity::EntityIDMap::new(protocol_version),
             players: HashMap::with_hasher(BuildHasherDefault::default()),
 
             tick_timer: 0.0,
             entity_tick_timer: 0.0,
-            received_chat_at: None,
+            hotbar_scroll: 0.0,
+            chat: chat::Chat::new(),
+            tab_completer: completion::TabCompleter::new(),
+            tab_complete_request: None,
+            tab_complete_id: 0,
+            tab_completion: None,
+            sign_editor: None,
+            command_tree: None,
             sun_model: None,
+            particles: vec![],
+            break_stages: vec![],
 
             target_info: target::Info::new(),
+            entity_target_info: target::EntityInfo::new(),
+            target_entity: None,
         }
     }
 
@@ -511,6 +676,16 @@ impl Server {
         self.just_disconnected = true;
     }
 
+    /// Disconnects because something went wrong with the connection. The
+    /// player is only shown a short reason, the full error is logged.
+    fn disconnect_with_error(&mut self, err: protocol::Error) {
+        match err {
+            protocol::Error::Disconnect(ref reason) => info!("Disconnected: {}", reason),
+            ref err => error!("Lost connection: {}", err),
+        }
+        self.disconnect(Some(disconnect_reason(err)));
+    }
+
     pub fn is_connected(&self) -> bool {
         self.conn.read().unwrap().is_some()
     }
@@ -536,6 +711,20 @@ impl Server {
             renderer.camera.pitch = rotation.pitch;
         }
         self.entity_tick(renderer, delta);
+        self.titles.tick();
+        for spawn in self.particles.drain(..) {
+            renderer.particles.add(spawn);
+        }
+        for (id, position, stage) in self.break_stages.drain(..) {
+            let breaker = render::destroy::Breaker::Entity(id);
+            // Negative stages stop the animation
+            match u8::try_from(stage) {
+                Ok(stage) => renderer
+                    .destroy
+                    .set(&mut renderer.model, breaker, position, stage),
+                Err(_) => renderer.destroy.remove(&mut renderer.model, breaker),
+            }
+        }
 
         self.tick_timer += delta;
         while self.tick_timer >= 3.0 && self.is_connected() {
@@ -551,32 +740,77 @@ impl Server {
 
         self.world.tick(&mut self.entities);
 
+        self.target_entity = None;
         if self.player.is_some() {
-            if let Some((pos, bl, _, _)) = target::trace_ray(
-                &self.world,
-                4.0,
-                renderer.camera.pos.to_vec(),
-                renderer.view_vector.cast().unwrap(),
-                target::test_block,
-            ) {
+            let camera = renderer.camera.pos.to_vec();
+            let view = renderer.view_vector.cast().unwrap();
+            let block = target::trace_ray(&self.world, 4.0, camera, view, target::test_block);
+            // Entities in front of the block being looked at take priority
+            let reach = block.map_or(ENTITY_REACH, |(pos, _, _, at)| {
+                let hit = cgmath::Vector3::new(pos.x as f64, pos.y as f64, pos.z as f64) + at;
+                (hit - camera).magnitude().min(ENTITY_REACH)
+            });
+            self.target_entity = self.trace_entity(camera, view, reach);
+            if let Some(target) = self.target_entity {
+                let position = self.entities.get_component(target.entity, self.position);
+                let bounds = self.entities.get_component(target.entity, self.bounds);
+                if let (Some(position), Some(bounds)) = (position, bounds) {
+                    self.entity_target_info.update(
+                        renderer,
+                        target.entity,
+                        bounds.bounds,
+                        position.position,
+                    );
+                }
+                self.target_info.clear(renderer);
+            } else if let Some((pos, bl, _, _)) = block {
                 self.target_info.update(renderer, pos, bl);
+                self.entity_target_info.clear(renderer);
             } else {
                 self.target_info.clear(renderer);
+                self.entity_target_info.clear(renderer);
             }
         } else {
             self.target_info.clear(renderer);
+            self.entity_target_info.clear(renderer);
         }
     }
 
-    fn entity_tick(&mut self, renderer: &mut render::Renderer, delta: f64) {
-        let world_entity = self.entities.get_world();
-        // Update the game's state for entities to read
-        self.entities
-            .get_component_mut(world_entity, self.game_info)
-            .unwrap()
-            .delta = delta;
+    /// Finds the closest entity the player is looking at within `reach`.
+    fn trace_entity(
+        &self,
+        camera: cgmath::Vector3<f64>,
+        view: cgmath::Vector3<f64>,
+        reach: f64,
+    ) -> Option<TargetEntity> {
+        let mut closest: Option<(f64, TargetEntity)> = None;
+        for (&id, &entity) in &self.entity_map {
+            if Some(entity) == self.player {
+                continue;
+            }
+            let targetable = self
+                .entities
+                .get_component(entity, self.entity_type)
+                .map_or(true, |v| v.is_targetable());
+            let position = self.entities.get_component(entity, self.position);
+            let bounds = self.entities.get_component(entity, self.bounds);
+            let (position, bounds) = match (position, bounds) {
+                (Some(position), Some(bounds)) if targetable => (position.position, bounds.bounds),
+                _ => continue,
+            };
+            if let Some(hit) = target::test_entity(bounds.add_v(position), camera, view, reach) {
+                let distance = (hit - camera).magnitude();
+                if closest.as_ref().map_or(true, |v| distance < v.0) {
+                    let at = hit - position;
+                    closest = Some((distance, TargetEntity { id, entity, at }));
+                }
+            }
+        }
+        closest.map(|v| v.1)
+    }
 
-        // Packets modify entities so need to handled here
+    /// Handles the packets the server has sent since the last call.
+    fn read_packets(&mut self) {
         if let Some(rx) = self.read_queue.take() {
             while let Ok(pck) = rx.try_recv() {
                 match pck {
@@ -614,6 +848,8 @@ impl Server {
                             ChunkUnload => on_chunk_unload,
                             BlockChange_VarInt => on_block_change_varint,
                             BlockChange_u8 => on_block_change_u8,
+                            Explosion_VarInt => on_explosion_varint,
+                            Explosion_i32 => on_explosion_i32,
                             MultiBlockChange_Packed => on_multi_block_change_packed,
                             MultiBlockChange_VarInt => on_multi_block_change_varint,
                             MultiBlockChange_u16 => on_multi_block_change_u16,
@@ -626,15 +862,75 @@ impl Server {
                             UpdateBlockEntity_VarInt => on_block_entity_update_varint,
                             UpdateBlockEntity_u8 => on_block_entity_update_u8,
                             UpdateBlockEntity_Data => on_block_entity_update_data,
+                            BlockAction => on_block_action,
+                            BlockAction_u16 => on_block_action_u16,
+                            BlockBreakAnimation => on_block_break_animation,
+                            BlockBreakAnimation_i32 => on_block_break_animation_i32,
+                            Effect => on_effect,
+                            Effect_u8y => on_effect_u8y,
+                            Particle_f64 => on_particle_f64,
+                            Particle_f32 => on_particle_f32,
+                            Particle_VarIntArray => on_particle_varintarray,
+                            Particle_Named => on_particle_named,
                             UpdateLight_Arrays => on_update_light_arrays,
                             UpdateSign => on_sign_update,
                             UpdateSign_u16 => on_sign_update_u16,
+                            SignEditorOpen => on_sign_editor_open,
+                            SignEditorOpen_i32 => on_sign_editor_open_i32,
                             PlayerInfo => on_player_info,
                             PlayerInfo_String => on_player_info_string,
+                            PlayerListHeaderFooter => on_player_list_header_footer,
                             ServerMessage_NoPosition => on_servermessage_noposition,
                             ServerMessage_Position => on_servermessage_position,
                             ServerMessage_Sender => on_servermessage_sender,
                             Disconnect => on_disconnect,
+                            TabCompleteReply => on_tab_complete_reply,
+                            TabCompleteReply_Transaction => on_tab_complete_reply_transaction,
+                            DeclareCommands => on_declare_commands,
+                            // Inventory
+                            WindowOpen => on_window_open,
+                            WindowOpen_u8 => on_window_open_u8,
+                            WindowOpen_VarInt => on_window_open_varint,
+                            WindowOpenHorse => on_window_open_horse,
+                            WindowClose => on_window_close,
+                            WindowItems_i16 => on_window_items_i16,
+                            WindowItems_StateCarry => on_window_items_statecarry,
+                            WindowSetSlot => on_window_set_slot,
+                            WindowSetSlot_State => on_window_set_slot_state,
+                            WindowProperty => on_window_property,
+                            ConfirmTransaction => on_confirm_transaction,
+                            SetCurrentHotbarSlot => on_set_current_hotbar_slot,
+                            PlayerAbilities => on_player_abilities,
+                            BossBar => on_boss_bar,
+                            Title => on_title,
+                            Title_notext => on_title_notext,
+                            TitleText => on_title_text,
+                            TitleSubtitle => on_title_subtitle,
+                            TitleTimes => on_title_times,
+                            ClearTitles => on_clear_titles,
+                            ActionBar => on_action_bar,
+                            // Scoreboard
+                            ScoreboardObjective => on_scoreboard_objective,
+                            ScoreboardObjective_VarInt => on_scoreboard_objective_varint,
+                            ScoreboardObjective_NoMode => on_scoreboard_objective_nomode,
+                            UpdateScore_VarInt => on_update_score_varint,
+                            UpdateScore_u8 => on_update_score_u8,
+                            UpdateScore_i32 => on_update_score_i32,
+                            ScoreboardDisplay => on_scoreboard_display,
+                            Teams_VarInt => on_teams_varint,
+                            Teams_u8 => on_teams_u8,
+                            Teams_NoCollision => on_teams_nocollision,
+                            Teams_NoVisColor => on_teams_noviscolor,
+                            // Player status
+                            UpdateHealth => on_update_health,
+                            UpdateHealth_u16 => on_update_health_u16,
+                            SetExperience => on_set_experience,
+                            SetExperience_i16 => on_set_experience_i16,
+                            CombatEvent => on_combat_event,
+                            CombatEventDeath => on_combat_event_death,
+                            EntityProperties_VarIntVarInt => on_entity_properties_varintvarint,
+                            EntityProperties_VarInt => on_entity_properties_varint,
+                            EntityProperties_i32 => on_entity_properties_i32,
                             // Entities
                             EntityDestroy => on_entity_destroy,
                             EntityDestroy_u8 => on_entity_destroy_u8,
@@ -643,10 +939,31 @@ impl Server {
                             SpawnPlayer_i32 => on_player_spawn_i32,
                             SpawnPlayer_i32_HeldItem => on_player_spawn_i32_helditem,
                             SpawnPlayer_i32_HeldItem_String => on_player_spawn_i32_helditem_string,
+                            SpawnObject => on_object_spawn,
+                            SpawnObject_i32 => on_object_spawn_i32,
+                            SpawnObject_i32_NoUUID => on_object_spawn_i32_nouuid,
+                            SpawnObject_VarInt => on_object_spawn_varint,
+                            SpawnExperienceOrb => on_experience_orb_spawn,
+                            SpawnExperienceOrb_i32 => on_experience_orb_spawn_i32,
+                            SpawnGlobalEntity => on_global_entity_spawn,
+                            SpawnGlobalEntity_i32 => on_global_entity_spawn_i32,
+                            SpawnMob_NoMeta => on_mob_spawn_nometa,
+                            SpawnMob_WithMeta => on_mob_spawn_withmeta,
+                            SpawnMob_u8 => on_mob_spawn_u8,
+                            SpawnMob_u8_i32 => on_mob_spawn_u8_i32,
+                            SpawnMob_u8_i32_NoUUID => on_mob_spawn_u8_i32_nouuid,
+                            SpawnPainting_VarInt => on_painting_spawn_varint,
+                            SpawnPainting_String => on_painting_spawn_string,
+                            SpawnPainting_NoUUID => on_painting_spawn_nouuid,
+                            SpawnPainting_NoUUID_i32 => on_painting_spawn_nouuid_i32,
+                            EntityMetadata => on_entity_metadata_varint,
+                            EntityMetadata_i32 => on_entity_metadata_i32,
                             EntityTeleport_f64 => on_entity_teleport_f64,
                             EntityTeleport_i32 => on_entity_teleport_i32,
                             EntityTeleport_i32_i32_NoGround => on_entity_teleport_i32_i32_noground,
                             EntityMove_i16 => on_entity_move_i16,
+                            EntityVelocity => on_entity_velocity,
+                            EntityVelocity_i32 => on_entity_velocity_i32,
                             EntityMove_i8 => on_entity_move_i8,
                             EntityMove_i8_i32_NoGround => on_entity_move_i8_i32_noground,
                             EntityLook_VarInt => on_entity_look_varint,
@@ -654,9 +971,12 @@ impl Server {
                             EntityLookAndMove_i16 => on_entity_look_and_move_i16,
                             EntityLookAndMove_i8 => on_entity_look_and_move_i8,
                             EntityLookAndMove_i8_i32_NoGround => on_entity_look_and_move_i8_i32_noground,
+                            EntityAttach_leashed => on_entity_attach_leashed,
+                            SetPassengers => on_set_passengers,
+                            VehicleTeleport => on_vehicle_teleport,
                         }
                     },
-                    Err(err) => panic!("Err: {:?}", err),
+                    Err(err) => self.disconnect_with_error(err),
                 }
                 // Disconnected
                 if self.conn.read().unwrap().is_none() {
@@ -668,10 +988,24 @@ impl Server {
                 self.read_queue = Some(rx);
             }
         }
+    }
+
+    fn entity_tick(&mut self, renderer: &mut render::Renderer, delta: f64) {
+        let world_entity = self.entities.get_world();
+        // Update the game's state for entities to read
+        self.entities
+            .get_component_mut(world_entity, self.game_info)
+            .unwrap()
+            .delta = delta;
+
+        // Packets modify entities so need to handled here
+        self.read_packets();
 
         if self.is_connected() || self.just_disconnected {
             // Allow an extra tick when disconnected to clean up
             self.just_disconnected = false;
+            self.update_held_tool();
+            self.update_steering();
             self.entity_tick_timer += delta;
             while self.entity_tick_timer >= 3.0 {
                 self.entities.tick(&mut self.world, renderer);
@@ -688,6 +1022,9 @@ impl Server {
             sun_model.remove(renderer);
         }
         self.target_info.clear(renderer);
+        self.entity_target_info.clear(renderer);
+        renderer.particles.clear(&mut renderer.model);
+        renderer.destroy.clear(&mut renderer.model);
     }
 
     fn update_time(&mut self, renderer: &mut render::Renderer, delta: f64) {
@@ -734,6 +1071,11 @@ impl Server {
     pub fn minecraft_tick(&mut self) {
         use std::f32::consts::PI;
         if let Some(player) = self.player {
+            self.sync_actions(player);
+            if self.entities.get_component(player, self.riding).is_some() {
+                self.write_vehicle_input(player);
+                return;
+            }
             let movement = self
                 .entities
                 .get_component_mut(player, self.player_movement)
@@ -785,7 +1127,160 @@ impl Server {
         }
     }
 
+    /// Sends what the player does while riding in place of their position.
+    fn write_vehicle_input(&mut self, player: ecs::Entity) {
+        use std::f32::consts::PI;
+        let riding = self.entities.get_component(player, self.riding).unwrap();
+        let movement = self
+            .entities
+            .get_component(player, self.player_movement)
+            .unwrap();
+        let rotation = self.entities.get_component(player, self.rotation).unwrap();
+        self.write_packet(packet::play::serverbound::PlayerLook {
+            yaw: -(rotation.yaw as f32) * (180.0 / PI),
+            pitch: (-rotation.pitch as f32) * (180.0 / PI) + 180.0,
+            on_ground: false,
+        });
+
+        // Sneaking gets off the vehicle
+        let (sideways, forward) = movement.steering();
+        let jump = movement.is_key_pressed(Stevenkey::Jump);
+        let unmount = movement.is_key_pressed(Stevenkey::Sneak);
+        if self.protocol_version >= 47 {
+            let mut flags = 0;
+            if jump {
+                flags |= 0x01;
+            }
+            if unmount {
+                flags |= 0x02;
+            }
+            self.write_packet(packet::play::serverbound::SteerVehicle {
+                sideways,
+                forward,
+                flags,
+            });
+        } else {
+            self.write_packet(packet::play::serverbound::SteerVehicle_jump_unmount {
+                sideways,
+                forward,
+                jump,
+                unmount,
+            });
+        }
+
+        if !riding.steering {
+            return;
+        }
+        let position = self
+            .entities
+            .get_component(riding.vehicle, self.target_position);
+        let vehicle_rotation = self
+            .entities
+            .get_component(riding.vehicle, self.target_rotation);
+        if let (Some(position), Some(vehicle_rotation)) = (position, vehicle_rotation) {
+            self.write_packet(packet::play::serverbound::VehicleMove {
+                x: position.position.x,
+                y: position.position.y,
+                z: position.position.z,
+                yaw: -(vehicle_rotation.yaw as f32) * (180.0 / PI),
+                pitch: 0.0,
+            });
+        }
+        if self
+            .entities
+            .get_component(riding.vehicle, self.entity_type)
+            != Some(&entity::EntityType::Boat)
+        {
+            return;
+        }
+        // Only moves the paddles, like vanilla
+        let left = movement.is_key_pressed(Stevenkey::Left);
+        let right = movement.is_key_pressed(Stevenkey::Right);
+        let forward = movement.is_key_pressed(Stevenkey::Forward);
+        self.write_packet(packet::play::serverbound::SteerBoat {
+            left_paddle_turning: (right && !left) || forward,
+            right_paddle_turning: (left && !right) || forward,
+        });
+    }
+
+    /// Tells the server when the player starts or stops sneaking, sprinting
+    /// or flying.
+    fn sync_actions(&mut self, player: ecs::Entity) {
+        let flying = self
+            .entities
+            .get_component(player, self.player_movement)
+            .map_or(false, |v| v.flying);
+        let actions = match self.entities.get_component_mut(player, self.player_actions) {
+            Some(actions) => actions,
+            None => return,
+        };
+        let mut action_ids = vec![];
+        if actions.sneaking != actions.sent_sneaking {
+            actions.sent_sneaking = actions.sneaking;
+            action_ids.push(if actions.sneaking { 0 } else { 1 });
+        }
+        if actions.sprinting != actions.sent_sprinting {
+            actions.sent_sprinting = actions.sprinting;
+            action_ids.push(if actions.sprinting { 3 } else { 4 });
+        }
+        let abilities = if flying != actions.sent_flying {
+            actions.sent_flying = flying;
+            let mut flags = if flying { 0x02 } else { 0x00 };
+            if actions.can_fly {
+                flags |= 0x04;
+            }
+            Some((flags, actions.fly_speed, actions.walk_speed))
+        } else {
+            None
+        };
+
+        if !action_ids.is_empty() {
+            if let Some(entity_id) = self.player_entity_id() {
+                for action_id in action_ids {
+                    self.write_entity_action(entity_id, action_id);
+                }
+            }
+        }
+        if let Some((flags, flying_speed, walking_speed)) = abilities {
+            if self.protocol_version >= 735 {
+                self.write_packet(packet::play::serverbound::ClientAbilities_u8 { flags });
+            } else {
+                self.write_packet(packet::play::serverbound::ClientAbilities_f32 {
+                    flags,
+                    flying_speed,
+                    walking_speed,
+                });
+            }
+        }
+    }
+
+    /// Sends one of the player's actions, using the ids of 1.8 onwards.
+    fn write_entity_action(&mut self, entity_id: i32, action_id: i32) {
+        if self.protocol_version >= 47 {
+            self.write_packet(packet::play::serverbound::PlayerAction {
+                entity_id: protocol::VarInt(entity_id),
+                action_id: protocol::VarInt(action_id),
+                jump_boost: protocol::VarInt(0),
+            });
+        } else {
+            // 1.7 counts the actions from 1
+            self.write_packet(packet::play::serverbound::PlayerAction_i32 {
+                entity_id,
+                action_id: (action_id + 1) as i8,
+                jump_boost: 0,
+            });
+        }
+    }
+
     pub fn key_press(&mut self, down: bool, key: Stevenkey) {
+        if let (true, Stevenkey::Hotbar(index)) = (down, &key) {
+            self.set_hotbar_index(*index);
+        }
+        if down && key == Stevenkey::OpenInv {
+            if let Some(id) = self.inventory_context.toggle() {
+                self.write_packet(packet::play::serverbound::CloseWindow { id });
+            }
+        }
         if let Some(player) = self.player {
             if let Some(movement) = self
                 .entities
@@ -796,7 +1291,237 @@ impl Server {
         }
     }
 
+    /// Selects a hotbar slot and tells the server about it.
+    pub fn set_hotbar_index(&mut self, index: u8) {
+        if index > 8 || index == self.inventory_context.hotbar_index {
+            return;
+        }
+        self.inventory_context.hotbar_index = index;
+        self.write_packet(packet::play::serverbound::HeldItemChange { slot: index as i16 });
+    }
+
+    /// Moves the hotbar selection with the mouse wheel, scrolling down
+    /// moves to the right.
+    pub fn scroll_hotbar(&mut self, lines: f64) {
+        self.hotbar_scroll += lines;
+        let steps = self.hotbar_scroll.trunc();
+        self.hotbar_scroll -= steps;
+        if steps != 0.0 {
+            let index = (self.inventory_context.hotbar_index as i32 - steps as i32).rem_euclid(9);
+            self.set_hotbar_index(index as u8);
+        }
+    }
+
+    fn update_held_tool(&mut self) {
+        if let Some(player) = self.player {
+            let tool = inventory::tool_for_item(
+                &self.inventory_context.items,
+                self.inventory_context.held_item(),
+            );
+            if let Some(digging) = self.entities.get_component_mut(player, self.digging) {
+                digging.tool = tool;
+            }
+        }
+    }
+
+    /// Sends a chat message, or a command if it starts with a '/'.
+    pub fn send_chat(&mut self, message: &str) {
+        self.chat.add_sent(message);
+        // 1.11 raised the limit from 100 characters
+        let max_length = if self.protocol_version >= 315 {
+            256
+        } else {
+            100
+        };
+        let message: String = message.chars().take(max_length).collect();
+        self.write_packet(packet::play::serverbound::ChatMessage { message });
+    }
+
+    /// Completes the word at the end of the chat input. Returns the new
+    /// input if a match is already known, otherwise the server is asked
+    /// and the result is returned by `take_tab_completion` later.
+    pub fn tab_complete(&mut self, input: &str) -> Option<String> {
+        if let Some(completed) = self.tab_completer.cycle(input) {
+            return Some(completed);
+        }
+        if let (Some(tree), Some(command)) = (self.command_tree.as_ref(), input.strip_prefix('/')) {
+            let (start, matches) = tree.complete(command);
+            if !matches.is_empty() {
+                return self.tab_completer.set_matches(input, start + 1, matches);
+            }
+        }
+
+        self.tab_complete_request = Some(input.to_owned());
+        let text = input.to_owned();
+        if self.protocol_version >= 404 {
+            self.tab_complete_id += 1;
+            self.write_packet(packet::play::serverbound::TabComplete_Transaction {
+                transaction_id: protocol::VarInt(self.tab_complete_id),
+                text,
+            });
+        } else if self.protocol_version >= 107 {
+            self.write_packet(packet::play::serverbound::TabComplete {
+                text,
+                assume_command: false,
+                has_target: false,
+                target: None,
+            });
+        } else if self.protocol_version >= 47 {
+            self.write_packet(packet::play::serverbound::TabComplete_NoAssume {
+                text,
+                has_target: false,
+                target: None,
+            });
+        } else {
+            self.write_packet(packet::play::serverbound::TabComplete_NoAssume_NoTarget { text });
+        }
+        None
+    }
+
+    /// Returns the input a completion was requested for and the completed
+    /// input once the server has replied.
+    pub fn take_tab_completion(&mut self) -> Option<(String, String)> {
+        self.tab_completion.take()
+    }
+
+    /// Returns the location of a sign the player should be asked to write
+    /// on, once the server opens the sign editor.
+    pub fn take_sign_editor(&mut self) -> Option<Position> {
+        self.sign_editor.take()
+    }
+
+    /// Sends the text the player wrote on a sign.
+    pub fn set_sign(&mut self, location: Position, lines: &[String]) {
+        let line = |index: usize| {
+            let line = lines.get(index).cloned().unwrap_or_default();
+            // 1.8 sends lines as components, other versions as plain text
+            if self.protocol_version >= 47 && self.protocol_version < 107 {
+                format::Component::Text(format::TextComponent::new(&line))
+                    .to_value()
+                    .to_string()
+            } else {
+                line
+            }
+        };
+        if self.protocol_version >= 47 {
+            self.write_packet(packet::play::serverbound::SetSign {
+                location,
+                line1: line(0),
+                line2: line(1),
+                line3: line(2),
+                line4: line(3),
+            });
+        } else {
+            self.write_packet(packet::play::serverbound::SetSign_i16y {
+                x: location.x,
+                y: location.y as i16,
+                z: location.z,
+                line1: line(0),
+                line2: line(1),
+                line3: line(2),
+                line4: line(3),
+            });
+        }
+    }
+
+    /// Clicks a slot in the open window, or the player's inventory if no
+    /// window is open.
+    pub fn click_window(&mut self, slot: i16, click: inventory::Click) {
+        let action = self.inventory_context.click(slot, click);
+        if self.protocol_version >= 755 {
+            self.write_packet(packet::play::serverbound::ClickWindow_State {
+                id: action.window_id,
+                slot: action.slot,
+                state: protocol::VarInt(action.state_id),
+                button: action.button,
+                mode: protocol::VarInt(action.mode as i32),
+                slots: protocol::LenPrefixed::new(
+                    action
+                        .changed_slots
+                        .into_iter()
+                        .map(|(slot_number, slot_data)| packet::NumberedSlot {
+                            slot_number,
+                            slot_data,
+                        })
+                        .collect(),
+                ),
+                clicked_item: action.carried,
+            });
+        } else if self.protocol_version >= 107 {
+            self.write_packet(packet::play::serverbound::ClickWindow {
+                id: action.window_id,
+                slot: action.slot,
+                button: action.button,
+                action_number: action.action_number as u16,
+                mode: protocol::VarInt(action.mode as i32),
+                clicked_item: action.clicked_item,
+            });
+        } else {
+            self.write_packet(packet::play::serverbound::ClickWindow_u8 {
+                id: action.window_id,
+                slot: action.slot,
+                button: action.button,
+                action_number: action.action_number as u16,
+                mode: action.mode,
+                clicked_item: action.clicked_item,
+            });
+        }
+    }
+
+    /// Asks the server to respawn the player after dying.
+    pub fn respawn_player(&mut self) {
+        // Action 0 is perform respawn
+        if self.protocol_version >= 47 {
+            self.write_packet(packet::play::serverbound::ClientStatus {
+                action_id: protocol::VarInt(0),
+            });
+        } else {
+            self.write_packet(packet::play::serverbound::ClientStatus_u8 { action_id: 0 });
+        }
+    }
+
+    /// Returns the gamemode of the local player.
+    /// The name of the local player as given by the server when logging in.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Every player in the player list, in no particular order.
+    pub fn players(&self) -> impl Iterator<Item = &PlayerInfo> {
+        self.players.values()
+    }
+
+    pub fn gamemode(&self) -> Option<Gamemode> {
+        self.player
+            .and_then(|player| self.entities.get_component(player, self.gamemode))
+            .copied()
+    }
+
+    /// Closes the open window, or the player's inventory, and tells the
+    /// server about it.
+    pub fn close_window(&mut self) {
+        if let Some(id) = self.inventory_context.close() {
+            self.write_packet(packet::play::serverbound::CloseWindow { id });
+        }
+    }
+
+    /// Returns the id the server gave the player's entity.
+    fn player_entity_id(&self) -> Option<i32> {
+        let player = self.player?;
+        self.entity_map
+            .iter()
+            .find(|v| *v.1 == player)
+            .map(|v| *v.0)
+    }
+
     pub fn on_left_mouse_button(&mut self, pressed: bool) {
+        if pressed {
+            if let Some(target) = self.target_entity {
+                self.swing_arm();
+                self.write_use_entity(&target, 1);
+                return;
+            }
+        }
         if let Some(player) = self.player {
             if let Some(mouse_buttons) = self.entities.get_component_mut(player, self.mouse_buttons)
             {
@@ -814,8 +1539,77 @@ impl Server {
         }
     }
 
+    fn swing_arm(&mut self) {
+        if self.protocol_version >= 49 {
+            self.write_packet(packet::play::serverbound::ArmSwing {
+                hand: protocol::VarInt(0),
+            });
+        } else if self.protocol_version >= 47 {
+            self.write_packet(packet::play::serverbound::ArmSwing_Handsfree { empty: () });
+        } else if let Some(entity_id) = self.player_entity_id() {
+            self.write_packet(packet::play::serverbound::ArmSwing_Handsfree_ID {
+                entity_id,
+                animation: 1,
+            });
+        }
+    }
+
+    /// Sends `UseEntity` for the targeted entity, `ty` is 0 to interact,
+    /// 1 to attack and 2 to interact at the point being looked at.
+    fn write_use_entity(&mut self, target: &TargetEntity, ty: i32) {
+        use protocol::VarInt;
+        let (target_x, target_y, target_z) =
+            (target.at.x as f32, target.at.y as f32, target.at.z as f32);
+        if self.protocol_version >= 735 {
+            let sneaking = self
+                .player
+                .and_then(|v| self.entities.get_component(v, self.player_actions))
+                .map_or(false, |v| v.sneaking);
+            self.write_packet(packet::play::serverbound::UseEntity_Sneakflag {
+                target_id: VarInt(target.id),
+                ty: VarInt(ty),
+                target_x,
+                target_y,
+                target_z,
+                hand: VarInt(0),
+                sneaking,
+            });
+        } else if self.protocol_version >= 49 {
+            self.write_packet(packet::play::serverbound::UseEntity_Hand {
+                target_id: VarInt(target.id),
+                ty: VarInt(ty),
+                target_x,
+                target_y,
+                target_z,
+                hand: VarInt(0),
+            });
+        } else if self.protocol_version >= 47 {
+            self.write_packet(packet::play::serverbound::UseEntity_Handsfree {
+                target_id: VarInt(target.id),
+                ty: VarInt(ty),
+                target_x,
+                target_y,
+                target_z,
+            });
+        } else {
+            self.write_packet(packet::play::serverbound::UseEntity_Handsfree_i32 {
+                target_id: target.id,
+                ty: ty as u8,
+            });
+        }
+    }
+
     pub fn on_right_click(&mut self, renderer: &mut render::Renderer) {
         use crate::shared::Direction;
+        if let Some(target) = self.target_entity {
+            // Like vanilla, try where the entity was clicked first for
+            // things like armor stands. 1.7 only has plain interactions.
+            if self.protocol_version >= 47 {
+                self.write_use_entity(&target, 2);
+            }
+            self.write_use_entity(&target, 0);
+            return;
+        }
         if self.player.is_some() {
             if let Some((pos, _, face, at)) = target::trace_ray(
                 &self.world,
@@ -921,9 +1715,14 @@ impl Server {
         }
     }
 
-    pub fn write_packet<T: protocol::PacketType>(&self, p: T) {
-        let mut conn = self.conn.write().unwrap();
-        let _ = conn.as_mut().unwrap().write_packet(p); // TODO handle errors
+    pub fn write_packet<T: protocol::PacketType>(&mut self, p: T) {
+        let result = match self.conn.write().unwrap().as_mut() {
+            Some(conn) => conn.write_packet(p),
+            None => return,
+        };
+        if let Err(err) = result {
+            self.disconnect_with_error(err);
+        }
     }
 
     fn on_keep_alive_i64(
@@ -979,8 +1778,10 @@ impl Server {
             "REGISTER" => {}   // TODO
             "UNREGISTER" => {} // TODO
             "FML|HS" => {
-                let msg = crate::protocol::Serializable::read_from(&mut std::io::Cursor::new(data))
-                    .unwrap();
+                let ctx = protocol::Context::new(self.protocol_version);
+                let msg =
+                    protocol::Serializable::read_from_ctx(&mut std::io::Cursor::new(data), &ctx)
+                        .unwrap();
                 //debug!("FML|HS msg={:?}", msg);
 
                 use forge::FmlHs::*;
@@ -1070,13 +1871,23 @@ impl Server {
 
     // TODO: remove wrappers and directly call on Conn
     fn write_fmlhs_plugin_message(&mut self, msg: &forge::FmlHs) {
-        let mut conn = self.conn.write().unwrap();
-        let _ = conn.as_mut().unwrap().write_fmlhs_plugin_message(msg); // TODO handle errors
+        let result = match self.conn.write().unwrap().as_mut() {
+            Some(conn) => conn.write_fmlhs_plugin_message(msg),
+            None => return,
+        };
+        if let Err(err) = result {
+            self.disconnect_with_error(err);
+        }
     }
 
     fn write_plugin_message(&mut self, channel: &str, data: &[u8]) {
-        let mut conn = self.conn.write().unwrap();
-        let _ = conn.as_mut().unwrap().write_plugin_message(channel, data); // TODO handle errors
+        let result = match self.conn.write().unwrap().as_mut() {
+            Some(conn) => conn.write_plugin_message(channel, data),
+            None => return,
+        };
+        if let Err(err) = result {
+            self.disconnect_with_error(err);
+        }
     }
 
     fn on_game_join_worldnames_ishard_simdist(
@@ -1084,7 +1895,8 @@ impl Server {
         join: packet::play::clientbound::JoinGame_WorldNames_IsHard_SimDist,
     ) {
         self.world.load_dimension_type(join.dimension);
-        self.on_game_join(join.gamemode, join.entity_id)
+        self.on_game_join(join.gamemode, join.entity_id);
+        self.player_stats.hardcore = join.is_hardcore;
     }
 
     fn on_game_join_worldnames_ishard(
@@ -1092,7 +1904,8 @@ impl Server {
         join: packet::play::clientbound::JoinGame_WorldNames_IsHard,
     ) {
         self.world.load_dimension_type(join.dimension);
-        self.on_game_join(join.gamemode, join.entity_id)
+        self.on_game_join(join.gamemode, join.entity_id);
+        self.player_stats.hardcore = join.is_hardcore;
     }
 
     fn on_game_join_worldnames(&mut self, join: packet::play::clientbound::JoinGame_WorldNames) {
@@ -1126,8 +1939,16 @@ impl Server {
     }
 
     fn on_game_join(&mut self, gamemode: u8, entity_id: i32) {
+        self.player_stats = hud::PlayerStats::new();
+        // Before 1.16 hardcore is flagged in the gamemode
+        self.player_stats.hardcore = gamemode & 0x8 != 0;
         let gamemode = Gamemode::from_int((gamemode & 0x7) as i32);
         let player = entity::player::create_local(&mut self.entities);
+        self.entities.add_component(
+            player,
+            self.entity_metadata,
+            entity::metadata::EntityMetadata::new(self.protocol_version),
+        );
         if let Some(info) = self.players.get(&self.uuid) {
             let model = self
                 .entities
@@ -1154,7 +1975,7 @@ impl Server {
         };
         // TODO: refactor with write_plugin_message
         if self.protocol_version >= 47 {
-            self.write_packet(brand.into_message());
+            self.write_packet(brand.into_message(self.protocol_version));
         } else {
             self.write_packet(brand.into_message17());
         }
@@ -1178,6 +1999,7 @@ impl Server {
 
     fn respawn(&mut self, gamemode_u8: u8) {
         self.world = world::World::new(self.protocol_version);
+        self.player_stats.respawn();
         let gamemode = Gamemode::from_int((gamemode_u8 & 0x7) as i32);
 
         if let Some(player) = self.player {
@@ -1193,41 +2015,313 @@ impl Server {
         }
     }
 
-    fn on_disconnect(&mut self, disconnect: packet::play::clientbound::Disconnect) {
-        self.disconnect(Some(disconnect.reason));
-    }
-
-    fn on_time_update(&mut self, time_update: packet::play::clientbound::TimeUpdate) {
-        self.world_age = time_update.time_of_day;
-        self.world_time_target = (time_update.time_of_day % 24000) as f64;
-        if self.world_time_target < 0.0 {
-            self.world_time_target = -self.world_time_target;
-            self.tick_time = false;
-        } else {
-            self.tick_time = true;
+    fn on_set_current_hotbar_slot(
+        &mut self,
+        slot: packet::play::clientbound::SetCurrentHotbarSlot,
+    ) {
+        if slot.slot < 9 {
+            self.inventory_context.hotbar_index = slot.slot;
         }
     }
 
-    fn on_game_state_change(&mut self, game_state: packet::play::clientbound::ChangeGameState) {
-        if game_state.reason == 3 {
-            if let Some(player) = self.player {
-                let gamemode = Gamemode::from_int(game_state.value as i32);
-                *self
-                    .entities
-                    .get_component_mut(player, self.gamemode)
-                    .unwrap() = gamemode;
-                // TODO: Temp
-                self.entities
-                    .get_component_mut(player, self.player_movement)
-                    .unwrap()
-                    .flying = gamemode.can_fly();
+    fn on_player_abilities(&mut self, abilities: packet::play::clientbound::PlayerAbilities) {
+        if let Some(player) = self.player {
+            let flying = abilities.flags & 0x02 != 0;
+            if let Some(actions) = self.entities.get_component_mut(player, self.player_actions) {
+                actions.can_fly = abilities.flags & 0x04 != 0;
+                actions.fly_speed = abilities.flying_speed;
+                actions.walk_speed = abilities.walking_speed;
+                // The server already knows, so there is nothing to send back
+                actions.sent_flying = flying;
+            }
+            if let Some(movement) = self
+                .entities
+                .get_component_mut(player, self.player_movement)
+            {
+                movement.flying = flying;
+                movement.want_to_fly = flying;
             }
         }
     }
 
-    fn on_entity_destroy(&mut self, entity_destroy: packet::play::clientbound::EntityDestroy) {
-        for id in entity_destroy.entity_ids.data {
-            if let Some(entity) = self.entity_map.remove(&id.0) {
+    fn on_update_health(&mut self, health: packet::play::clientbound::UpdateHealth) {
+        self.player_stats
+            .set_health(health.health, health.food.0, health.food_saturation);
+    }
+
+    fn on_update_health_u16(&mut self, health: packet::play::clientbound::UpdateHealth_u16) {
+        self.player_stats
+            .set_health(health.health, health.food as i32, health.food_saturation);
+    }
+
+    fn on_set_experience(&mut self, experience: packet::play::clientbound::SetExperience) {
+        self.player_stats.set_experience(
+            experience.experience_bar,
+            experience.level.0,
+            experience.total_experience.0,
+        );
+    }
+
+    fn on_set_experience_i16(&mut self, experience: packet::play::clientbound::SetExperience_i16) {
+        self.player_stats.set_experience(
+            experience.experience_bar,
+            experience.level as i32,
+            experience.total_experience as i32,
+        );
+    }
+
+    fn is_local_player(&self, entity_id: i32) -> bool {
+        self.player.is_some() && self.entity_map.get(&entity_id) == self.player.as_ref()
+    }
+
+    fn on_combat_event(&mut self, combat: packet::play::clientbound::CombatEvent) {
+        // Event 2 is the death of an entity
+        if combat.event.0 != 2 {
+            return;
+        }
+        if let Some(player_id) = combat.player_id {
+            if self.is_local_player(player_id.0) {
+                self.player_stats.die(combat.message);
+            }
+        }
+    }
+
+    fn on_combat_event_death(&mut self, death: packet::play::clientbound::CombatEventDeath) {
+        if self.is_local_player(death.player_id.0) {
+            self.player_stats
+                .die(Some(format::Component::from_string(&death.message)));
+        }
+    }
+
+    fn on_entity_properties_varintvarint(
+        &mut self,
+        properties: packet::play::clientbound::EntityProperties_VarIntVarInt,
+    ) {
+        self.on_entity_properties(
+            properties.entity_id.0,
+            properties
+                .properties
+                .data
+                .into_iter()
+                .map(|v| (v.key, v.value, v.modifiers.data)),
+        );
+    }
+
+    fn on_entity_properties_varint(
+        &mut self,
+        properties: packet::play::clientbound::EntityProperties_VarInt,
+    ) {
+        self.on_entity_properties(
+            properties.entity_id.0,
+            properties
+                .properties
+                .data
+                .into_iter()
+                .map(|v| (v.key, v.value, v.modifiers.data)),
+        );
+    }
+
+    fn on_entity_properties_i32(
+        &mut self,
+        properties: packet::play::clientbound::EntityProperties_i32,
+    ) {
+        self.on_entity_properties(
+            properties.entity_id,
+            properties
+                .properties
+                .data
+                .into_iter()
+                .map(|v| (v.key, v.value, v.modifiers.data)),
+        );
+    }
+
+    fn on_entity_properties<I>(&mut self, entity_id: i32, properties: I)
+    where
+        I: IntoIterator<Item = (String, f64, Vec<packet::PropertyModifier>)>,
+    {
+        if !self.is_local_player(entity_id) {
+            return;
+        }
+        for (key, base, modifiers) in properties {
+            let value = attribute_value(base, &modifiers);
+            // 1.16 namespaced the keys and renamed some of them
+            match key.trim_start_matches("minecraft:") {
+                "generic.maxHealth" | "generic.max_health" => {
+                    self.player_stats.max_health = value as f32
+                }
+                "generic.armor" => self.player_stats.armor = value as i32,
+                _ => {}
+            }
+        }
+    }
+
+    fn on_disconnect(&mut self, disconnect: packet::play::clientbound::Disconnect) {
+        self.disconnect(Some(disconnect.reason));
+    }
+
+    fn on_window_open(&mut self, window_open: packet::play::clientbound::WindowOpen) {
+        self.inventory_context.open(
+            window_open.id,
+            Some(window_open.title),
+            Some(window_open.slot_count as usize),
+        );
+    }
+
+    fn on_window_open_u8(&mut self, window_open: packet::play::clientbound::WindowOpen_u8) {
+        self.inventory_context.open(
+            window_open.id,
+            Some(window_open.title),
+            Some(window_open.slot_count as usize),
+        );
+    }
+
+    fn on_window_open_varint(&mut self, window_open: packet::play::clientbound::WindowOpen_VarInt) {
+        // The size isn't sent, the window is resized once the items arrive
+        self.inventory_context
+            .open(window_open.id.0 as u8, Some(window_open.title), None);
+    }
+
+    fn on_window_open_horse(&mut self, window_open: packet::play::clientbound::WindowOpenHorse) {
+        self.inventory_context.open(
+            window_open.window_id,
+            None,
+            Some(window_open.number_of_slots.0 as usize),
+        );
+    }
+
+    fn on_window_close(&mut self, window_close: packet::play::clientbound::WindowClose) {
+        self.inventory_context.on_close(window_close.id);
+    }
+
+    fn on_window_items_i16(&mut self, window_items: packet::play::clientbound::WindowItems_i16) {
+        self.inventory_context
+            .set_items(window_items.id, window_items.items.data, None);
+    }
+
+    fn on_window_items_statecarry(
+        &mut self,
+        window_items: packet::play::clientbound::WindowItems_StateCarry,
+    ) {
+        self.inventory_context.set_items(
+            window_items.id,
+            window_items.items.data,
+            Some(window_items.state_id.0),
+        );
+        self.inventory_context
+            .set_carried(window_items.carried_item);
+    }
+
+    fn on_window_set_slot(&mut self, set_slot: packet::play::clientbound::WindowSetSlot) {
+        self.inventory_context
+            .set_slot(set_slot.id, set_slot.property, set_slot.item, None);
+    }
+
+    fn on_window_set_slot_state(
+        &mut self,
+        set_slot: packet::play::clientbound::WindowSetSlot_State,
+    ) {
+        self.inventory_context.set_slot(
+            set_slot.id,
+            set_slot.property,
+            set_slot.item,
+            Some(set_slot.state_id.0),
+        );
+    }
+
+    fn on_window_property(&mut self, property: packet::play::clientbound::WindowProperty) {
+        self.inventory_context
+            .set_property(property.id, property.property, property.value);
+    }
+
+    fn on_confirm_transaction(
+        &mut self,
+        transaction: packet::play::clientbound::ConfirmTransaction,
+    ) {
+        // Rejected clicks have to be acknowledged before the server
+        // resends the window contents
+        if self
+            .inventory_context
+            .on_confirm_transaction(transaction.id, transaction.accepted)
+        {
+            self.write_packet(packet::play::serverbound::ConfirmTransactionServerbound {
+                id: transaction.id,
+                action_number: transaction.action_number,
+                accepted: true,
+            });
+        }
+    }
+
+    fn on_tab_complete_reply(&mut self, reply: packet::play::clientbound::TabCompleteReply) {
+        if let Some(input) = self.tab_complete_request.take() {
+            // Matches replace the last word, including the '/' when
+            // completing a command name
+            let start = completion::last_word_start(&input);
+            self.set_tab_complete_matches(input, start, reply.matches.data);
+        }
+    }
+
+    fn on_tab_complete_reply_transaction(
+        &mut self,
+        reply: packet::play::clientbound::TabCompleteReply_Transaction,
+    ) {
+        if reply.transaction_id.0 != self.tab_complete_id {
+            return;
+        }
+        if let Some(input) = self.tab_complete_request.take() {
+            // The start is given in characters
+            let start = input
+                .char_indices()
+                .nth(reply.start.0.max(0) as usize)
+                .map_or(input.len(), |(i, _)| i);
+            let matches = reply.matches.data.into_iter().map(|v| v.text).collect();
+            self.set_tab_complete_matches(input, start, matches);
+        }
+    }
+
+    fn set_tab_complete_matches(&mut self, input: String, start: usize, matches: Vec<String>) {
+        if let Some(completed) = self.tab_completer.set_matches(&input, start, matches) {
+            self.tab_completion = Some((input, completed));
+        }
+    }
+
+    fn on_declare_commands(&mut self, commands: packet::play::clientbound::DeclareCommands) {
+        self.command_tree = Some(completion::CommandTree::new(
+            commands.nodes.data,
+            commands.root_index.0 as usize,
+        ));
+    }
+
+    fn on_time_update(&mut self, time_update: packet::play::clientbound::TimeUpdate) {
+        self.world_age = time_update.time_of_day;
+        self.world_time_target = (time_update.time_of_day % 24000) as f64;
+        if self.world_time_target < 0.0 {
+            self.world_time_target = -self.world_time_target;
+            self.tick_time = false;
+        } else {
+            self.tick_time = true;
+        }
+    }
+
+    fn on_game_state_change(&mut self, game_state: packet::play::clientbound::ChangeGameState) {
+        if game_state.reason == 3 {
+            if let Some(player) = self.player {
+                let gamemode = Gamemode::from_int(game_state.value as i32);
+                *self
+                    .entities
+                    .get_component_mut(player, self.gamemode)
+                    .unwrap() = gamemode;
+                // TODO: Temp
+                self.entities
+                    .get_component_mut(player, self.player_movement)
+                    .unwrap()
+                    .flying = gamemode.can_fly();
+            }
+        }
+    }
+
+    fn on_entity_destroy(&mut self, entity_destroy: packet::play::clientbound::EntityDestroy) {
+        for id in entity_destroy.entity_ids.data {
+            if let Some(entity) = self.entity_map.remove(&id.0) {
                 self.entities.remove_entity(entity);
             }
         }
@@ -1244,6 +2338,31 @@ impl Server {
         }
     }
 
+    fn on_entity_metadata_varint(
+        &mut self,
+        entity_metadata: packet::play::clientbound::EntityMetadata,
+    ) {
+        self.on_entity_metadata(entity_metadata.entity_id.0, entity_metadata.metadata)
+    }
+
+    fn on_entity_metadata_i32(
+        &mut self,
+        entity_metadata: packet::play::clientbound::EntityMetadata_i32,
+    ) {
+        self.on_entity_metadata(entity_metadata.entity_id, entity_metadata.metadata)
+    }
+
+    fn on_entity_metadata(&mut self, entity_id: i32, metadata: crate::types::Metadata) {
+        if let Some(entity) = self.entity_map.get(&entity_id) {
+            if let Some(entity_metadata) = self
+                .entities
+                .get_component_mut(*entity, self.entity_metadata)
+            {
+                entity_metadata.update(metadata);
+            }
+        }
+    }
+
     fn on_entity_teleport_f64(
         &mut self,
         entity_telport: packet::play::clientbound::EntityTeleport_f64,
@@ -1301,6 +2420,9 @@ impl Server {
         _on_ground: bool,
     ) {
         use std::f64::consts::PI;
+        if self.is_steering(entity_id) {
+            return;
+        }
         if let Some(entity) = self.entity_map.get(&entity_id) {
             let target_position = self
                 .entities
@@ -1313,6 +2435,7 @@ impl Server {
             target_position.position.x = x;
             target_position.position.y = y;
             target_position.position.z = z;
+            target_position.predicted = cgmath::Vector3::new(0.0, 0.0, 0.0);
             target_rotation.yaw = -(yaw / 256.0) * PI * 2.0;
             target_rotation.pitch = -(pitch / 256.0) * PI * 2.0;
         }
@@ -1349,14 +2472,209 @@ impl Server {
     }
 
     fn on_entity_move(&mut self, entity_id: i32, delta_x: f64, delta_y: f64, delta_z: f64) {
+        if self.is_steering(entity_id) {
+            return;
+        }
         if let Some(entity) = self.entity_map.get(&entity_id) {
             let position = self
                 .entities
                 .get_component_mut(*entity, self.target_position)
                 .unwrap();
-            position.position.x += delta_x;
-            position.position.y += delta_y;
-            position.position.z += delta_z;
+            // Moves are from where the server had the entity, which is
+            // without what its velocity moved it on our side since
+            position.position += cgmath::Vector3::new(delta_x, delta_y, delta_z);
+            position.position -= position.predicted;
+            position.predicted = cgmath::Vector3::new(0.0, 0.0, 0.0);
+        }
+    }
+
+    /// Returns whether the player is steering the entity, in which case the
+    /// server's movement of it is ignored in favour of the player's.
+    fn is_steering(&self, entity_id: i32) -> bool {
+        let riding = self
+            .player
+            .and_then(|v| self.entities.get_component(v, self.riding));
+        match (riding, self.entity_map.get(&entity_id)) {
+            (Some(riding), Some(entity)) => riding.steering && riding.vehicle == *entity,
+            _ => false,
+        }
+    }
+
+    /// Puts the passenger on the vehicle, or takes it off what it is riding
+    /// when there is no vehicle. Whether the player steers it is worked out
+    /// every tick by `update_steering`.
+    fn set_vehicle(
+        &mut self,
+        passenger: ecs::Entity,
+        vehicle: Option<ecs::Entity>,
+        controlling: bool,
+    ) {
+        let riding = self.entities.get_component_mut(passenger, self.riding);
+        match (riding, vehicle) {
+            (Some(riding), Some(vehicle)) => {
+                riding.vehicle = vehicle;
+                riding.controlling = controlling;
+                riding.steering = false;
+            }
+            (None, Some(vehicle)) => {
+                self.entities.add_component(
+                    passenger,
+                    self.riding,
+                    entity::Riding {
+                        vehicle,
+                        controlling,
+                        steering: false,
+                    },
+                );
+            }
+            (Some(_), None) => {
+                self.entities.remove_component(passenger, self.riding);
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Works out whether the player steers the vehicle they ride, which
+    /// changes as it is saddled and with the item they hold.
+    fn update_steering(&mut self) {
+        let riding = self
+            .player
+            .and_then(|v| self.entities.get_component(v, self.riding));
+        let (vehicle, controlling) = match riding {
+            Some(riding) => (riding.vehicle, riding.controlling),
+            None => return,
+        };
+        let steering = controlling && self.can_steer(vehicle);
+        if let Some(riding) = self
+            .player
+            .and_then(|v| self.entities.get_component_mut(v, self.riding))
+        {
+            riding.steering = steering;
+        }
+    }
+
+    /// Returns whether the vehicle is moved by the client of its first
+    /// passenger rather than by the server.
+    fn can_steer(&self, vehicle: ecs::Entity) -> bool {
+        use crate::entity::EntityType::*;
+        let ty = match self.entities.get_component(vehicle, self.entity_type) {
+            Some(ty) => *ty,
+            None => return false,
+        };
+        let saddled = self
+            .entities
+            .get_component(vehicle, self.entity_metadata)
+            .map_or(false, |v| v.is_saddled(ty));
+        // Pigs and striders follow the item dangled in front of them
+        let holding = |name| {
+            let items = &self.inventory_context.items;
+            [
+                self.inventory_context.held_item(),
+                self.inventory_context.off_hand_item(),
+            ]
+            .iter()
+            .flatten()
+            .any(|v| items.name(v.id) == Some(name))
+        };
+        match ty {
+            Boat => true,
+            Horse | Donkey | Mule | SkeletonHorse | ZombieHorse | Llama | TraderLlama => saddled,
+            Pig => saddled && holding("carrot_on_a_stick"),
+            Strider => saddled && holding("warped_fungus_on_a_stick"),
+            _ => false,
+        }
+    }
+
+    fn on_entity_attach_leashed(
+        &mut self,
+        attach: packet::play::clientbound::EntityAttach_leashed,
+    ) {
+        // Leashes aren't drawn
+        if attach.leash {
+            return;
+        }
+        if let Some(passenger) = self.entity_map.get(&attach.entity_id).copied() {
+            // Vehicles are moved by the server before 1.9
+            let vehicle = self.entity_map.get(&attach.vehicle).copied();
+            self.set_vehicle(passenger, vehicle, false);
+        }
+    }
+
+    fn on_set_passengers(&mut self, set: packet::play::clientbound::SetPassengers) {
+        let vehicle = match self.entity_map.get(&set.entity_id.0) {
+            Some(vehicle) => *vehicle,
+            None => return,
+        };
+        let passengers: Vec<ecs::Entity> = set
+            .passengers
+            .data
+            .iter()
+            .filter_map(|v| self.entity_map.get(&v.0).copied())
+            .collect();
+        let dismounted: Vec<ecs::Entity> = self
+            .entity_map
+            .values()
+            .copied()
+            .filter(|v| !passengers.contains(v))
+            .filter(|v| {
+                self.entities
+                    .get_component(*v, self.riding)
+                    .map_or(false, |v| v.vehicle == vehicle)
+            })
+            .collect();
+        for passenger in dismounted {
+            self.set_vehicle(passenger, None, false);
+        }
+        for (index, passenger) in passengers.into_iter().enumerate() {
+            self.set_vehicle(passenger, Some(vehicle), index == 0);
+        }
+        self.update_steering();
+    }
+
+    fn on_vehicle_teleport(&mut self, teleport: packet::play::clientbound::VehicleTeleport) {
+        let vehicle = match self
+            .player
+            .and_then(|v| self.entities.get_component(v, self.riding))
+        {
+            Some(riding) => riding.vehicle,
+            None => return,
+        };
+        let position = cgmath::Vector3::new(teleport.x, teleport.y, teleport.z);
+        if let Some(target_position) = self
+            .entities
+            .get_component_mut(vehicle, self.target_position)
+        {
+            target_position.position = position;
+        }
+        if let Some(current) = self.entities.get_component_mut(vehicle, self.position) {
+            current.position = position;
+        }
+        if let Some(rotation) = self
+            .entities
+            .get_component_mut(vehicle, self.target_rotation)
+        {
+            rotation.yaw = -(teleport.yaw as f64).to_radians();
+        }
+    }
+
+    fn on_entity_velocity(&mut self, v: packet::play::clientbound::EntityVelocity) {
+        self.on_entity_velocity_change(v.entity_id.0, v.velocity_x, v.velocity_y, v.velocity_z)
+    }
+
+    fn on_entity_velocity_i32(&mut self, v: packet::play::clientbound::EntityVelocity_i32) {
+        self.on_entity_velocity_change(v.entity_id, v.velocity_x, v.velocity_y, v.velocity_z)
+    }
+
+    fn on_entity_velocity_change(&mut self, entity_id: i32, x: i16, y: i16, z: i16) {
+        if let Some(entity) = self.entity_map.get(&entity_id) {
+            if let Some(velocity) = self.entities.get_component_mut(*entity, self.velocity) {
+                // Sent in 1/8000 of a block per tick
+                velocity.velocity = cgmath::Vector3::new(
+                    f64::from(x) / 8000.0,
+                    f64::from(y) / 8000.0,
+                    f64::from(z) / 8000.0,
+                );
+            }
         }
     }
 
@@ -1435,6 +2753,9 @@ impl Server {
         pitch: f64,
     ) {
         use std::f64::consts::PI;
+        if self.is_steering(entity_id) {
+            return;
+        }
         if let Some(entity) = self.entity_map.get(&entity_id) {
             let position = self
                 .entities
@@ -1444,9 +2765,9 @@ impl Server {
                 .entities
                 .get_component_mut(*entity, self.target_rotation)
                 .unwrap();
-            position.position.x += delta_x;
-            position.position.y += delta_y;
-            position.position.z += delta_z;
+            position.position += cgmath::Vector3::new(delta_x, delta_y, delta_z);
+            position.position -= position.predicted;
+            position.predicted = cgmath::Vector3::new(0.0, 0.0, 0.0);
             rotation.yaw = -(yaw / 256.0) * PI * 2.0;
             rotation.pitch = -(pitch / 256.0) * PI * 2.0;
         }
@@ -1476,7 +2797,8 @@ impl Server {
             spawn.z,
             spawn.yaw as f64,
             spawn.pitch as f64,
-        )
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
     }
 
     fn on_player_spawn_i32(&mut self, spawn: packet::play::clientbound::SpawnPlayer_i32) {
@@ -1488,7 +2810,8 @@ impl Server {
             f64::from(spawn.z),
             spawn.yaw as f64,
             spawn.pitch as f64,
-        )
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
     }
 
     fn on_player_spawn_i32_helditem(
@@ -1503,22 +2826,33 @@ impl Server {
             f64::from(spawn.z),
             spawn.yaw as f64,
             spawn.pitch as f64,
-        )
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
     }
 
     fn on_player_spawn_i32_helditem_string(
         &mut self,
         spawn: packet::play::clientbound::SpawnPlayer_i32_HeldItem_String,
     ) {
-        // 1.7.10: populate the player list here, since we only now know the UUID
+        // 1.7.10: the player list only has names, so move the entry to the
+        // real UUID now that it is known
         let uuid = protocol::UUID::from_str(&spawn.uuid).unwrap();
+        let old_uuid = self.player_uuid_by_name(&spawn.name);
+        let ping = match self.players.get(&old_uuid) {
+            Some(info) if old_uuid != uuid => {
+                let ping = info.ping;
+                self.players.remove(&old_uuid);
+                ping
+            }
+            _ => 0,
+        };
         self.players.entry(uuid.clone()).or_insert(PlayerInfo {
             name: spawn.name.clone(),
             uuid,
             skin_url: None,
 
             display_name: None,
-            ping: 0, // TODO: don't overwrite from PlayerInfo_String
+            ping,
             gamemode: Gamemode::from_int(0),
         });
 
@@ -1530,7 +2864,8 @@ impl Server {
             f64::from(spawn.z),
             spawn.yaw as f64,
             spawn.pitch as f64,
-        )
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
     }
 
     fn on_player_spawn(
@@ -1551,6 +2886,11 @@ impl Server {
             &mut self.entities,
             self.players.get(&uuid).map_or("MISSING", |v| &v.name),
         );
+        self.entities.add_component(
+            entity,
+            self.entity_metadata,
+            entity::metadata::EntityMetadata::new(self.protocol_version),
+        );
         let position = self
             .entities
             .get_component_mut(entity, self.position)
@@ -1585,6 +2925,283 @@ impl Server {
             model.set_skin(info.skin_url.clone());
         }
         self.entity_map.insert(entity_id, entity);
+        self.update_name_tags();
+    }
+
+    fn on_object_spawn(&mut self, spawn: packet::play::clientbound::SpawnObject) {
+        let ty = self
+            .entity_id_map
+            .object_by_vanilla_id(spawn.ty as usize, spawn.data);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            spawn.x,
+            spawn.y,
+            spawn.z,
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        )
+    }
+
+    fn on_object_spawn_i32(&mut self, spawn: packet::play::clientbound::SpawnObject_i32) {
+        let ty = self
+            .entity_id_map
+            .object_by_vanilla_id(spawn.ty as usize, spawn.data);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            f64::from(spawn.x),
+            f64::from(spawn.y),
+            f64::from(spawn.z),
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        )
+    }
+
+    fn on_object_spawn_i32_nouuid(
+        &mut self,
+        spawn: packet::play::clientbound::SpawnObject_i32_NoUUID,
+    ) {
+        let ty = self
+            .entity_id_map
+            .object_by_vanilla_id(spawn.ty as usize, spawn.data);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            f64::from(spawn.x),
+            f64::from(spawn.y),
+            f64::from(spawn.z),
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        )
+    }
+
+    fn on_object_spawn_varint(&mut self, spawn: packet::play::clientbound::SpawnObject_VarInt) {
+        let ty = self
+            .entity_id_map
+            .object_by_vanilla_id(spawn.ty.0 as usize, spawn.data);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            spawn.x,
+            spawn.y,
+            spawn.z,
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        )
+    }
+
+    fn on_experience_orb_spawn(&mut self, spawn: packet::play::clientbound::SpawnExperienceOrb) {
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            Some(entity::EntityType::ExperienceOrb),
+            spawn.x,
+            spawn.y,
+            spawn.z,
+            0.0,
+            0.0,
+        )
+    }
+
+    fn on_experience_orb_spawn_i32(
+        &mut self,
+        spawn: packet::play::clientbound::SpawnExperienceOrb_i32,
+    ) {
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            Some(entity::EntityType::ExperienceOrb),
+            f64::from(spawn.x),
+            f64::from(spawn.y),
+            f64::from(spawn.z),
+            0.0,
+            0.0,
+        )
+    }
+
+    fn on_global_entity_spawn(&mut self, spawn: packet::play::clientbound::SpawnGlobalEntity) {
+        let ty = self.entity_id_map.global_by_vanilla_id(spawn.ty as usize);
+        self.on_entity_spawn(spawn.entity_id.0, ty, spawn.x, spawn.y, spawn.z, 0.0, 0.0)
+    }
+
+    fn on_global_entity_spawn_i32(
+        &mut self,
+        spawn: packet::play::clientbound::SpawnGlobalEntity_i32,
+    ) {
+        let ty = self.entity_id_map.global_by_vanilla_id(spawn.ty as usize);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            f64::from(spawn.x),
+            f64::from(spawn.y),
+            f64::from(spawn.z),
+            0.0,
+            0.0,
+        )
+    }
+
+    fn on_mob_spawn_nometa(&mut self, spawn: packet::play::clientbound::SpawnMob_NoMeta) {
+        let ty = self.entity_id_map.by_vanilla_id(spawn.ty.0 as usize);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            spawn.x,
+            spawn.y,
+            spawn.z,
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        )
+    }
+
+    fn on_mob_spawn_withmeta(&mut self, spawn: packet::play::clientbound::SpawnMob_WithMeta) {
+        let ty = self.entity_id_map.by_vanilla_id(spawn.ty.0 as usize);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            spawn.x,
+            spawn.y,
+            spawn.z,
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
+    }
+
+    fn on_mob_spawn_u8(&mut self, spawn: packet::play::clientbound::SpawnMob_u8) {
+        let ty = self.entity_id_map.by_vanilla_id(spawn.ty as usize);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            spawn.x,
+            spawn.y,
+            spawn.z,
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
+    }
+
+    fn on_mob_spawn_u8_i32(&mut self, spawn: packet::play::clientbound::SpawnMob_u8_i32) {
+        let ty = self.entity_id_map.by_vanilla_id(spawn.ty as usize);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            f64::from(spawn.x),
+            f64::from(spawn.y),
+            f64::from(spawn.z),
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
+    }
+
+    fn on_mob_spawn_u8_i32_nouuid(
+        &mut self,
+        spawn: packet::play::clientbound::SpawnMob_u8_i32_NoUUID,
+    ) {
+        let ty = self.entity_id_map.by_vanilla_id(spawn.ty as usize);
+        self.on_entity_spawn(
+            spawn.entity_id.0,
+            ty,
+            f64::from(spawn.x),
+            f64::from(spawn.y),
+            f64::from(spawn.z),
+            spawn.yaw as f64,
+            spawn.pitch as f64,
+        );
+        self.on_entity_metadata(spawn.entity_id.0, spawn.metadata);
+    }
+
+    fn on_painting_spawn_varint(&mut self, spawn: packet::play::clientbound::SpawnPainting_VarInt) {
+        self.on_painting_spawn(spawn.entity_id.0, spawn.location, spawn.direction as i32)
+    }
+
+    fn on_painting_spawn_string(&mut self, spawn: packet::play::clientbound::SpawnPainting_String) {
+        self.on_painting_spawn(spawn.entity_id.0, spawn.location, spawn.direction as i32)
+    }
+
+    fn on_painting_spawn_nouuid(&mut self, spawn: packet::play::clientbound::SpawnPainting_NoUUID) {
+        self.on_painting_spawn(spawn.entity_id.0, spawn.location, spawn.direction as i32)
+    }
+
+    fn on_painting_spawn_nouuid_i32(
+        &mut self,
+        spawn: packet::play::clientbound::SpawnPainting_NoUUID_i32,
+    ) {
+        self.on_painting_spawn(
+            spawn.entity_id.0,
+            Position::new(spawn.x, spawn.y, spawn.z),
+            spawn.direction,
+        )
+    }
+
+    fn on_painting_spawn(&mut self, entity_id: i32, location: Position, direction: i32) {
+        // Paintings face south, west, north or east, given as quarter
+        // turns. Convert to the 1/256th of a turn used by other spawns.
+        self.on_entity_spawn(
+            entity_id,
+            Some(entity::EntityType::Painting),
+            location.x as f64 + 0.5,
+            location.y as f64,
+            location.z as f64 + 0.5,
+            (direction * 64) as f64,
+            0.0,
+        )
+    }
+
+    fn on_entity_spawn(
+        &mut self,
+        entity_id: i32,
+        ty: Option<entity::EntityType>,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: f64,
+        pitch: f64,
+    ) {
+        use std::f64::consts::PI;
+        if let Some(entity) = self.entity_map.remove(&entity_id) {
+            self.entities.remove_entity(entity);
+        }
+        let ty = match ty {
+            Some(ty) => ty,
+            None => {
+                debug!("Unknown entity type for entity {}", entity_id);
+                return;
+            }
+        };
+        let entity = ty.create_entity(&mut self.entities);
+        self.entities.add_component(
+            entity,
+            self.entity_metadata,
+            entity::metadata::EntityMetadata::new(self.protocol_version),
+        );
+        let position = self
+            .entities
+            .get_component_mut(entity, self.position)
+            .unwrap();
+        let target_position = self
+            .entities
+            .get_component_mut(entity, self.target_position)
+            .unwrap();
+        let rotation = self
+            .entities
+            .get_component_mut(entity, self.rotation)
+            .unwrap();
+        let target_rotation = self
+            .entities
+            .get_component_mut(entity, self.target_rotation)
+            .unwrap();
+        position.position.x = x;
+        position.position.y = y;
+        position.position.z = z;
+        target_position.position.x = x;
+        target_position.position.y = y;
+        target_position.position.z = z;
+        rotation.yaw = -(yaw / 256.0) * PI * 2.0;
+        rotation.pitch = -(pitch / 256.0) * PI * 2.0;
+        target_rotation.yaw = rotation.yaw;
+        target_rotation.pitch = rotation.pitch;
+        self.entity_map.insert(entity_id, entity);
     }
 
     fn on_teleport_player_withdismount(
@@ -1721,70 +3338,255 @@ impl Server {
         });
     }
 
-    fn on_block_entity_update_data(
-        &mut self,
-        _block_update: packet::play::clientbound::UpdateBlockEntity_Data,
-    ) {
-        // TODO: handle UpdateBlockEntity_Data for 1.7, decompress gzipped_nbt
+    fn on_block_entity_update_data(
+        &mut self,
+        block_update: packet::play::clientbound::UpdateBlockEntity_Data,
+    ) {
+        let mut data = flate2::read::GzDecoder::new(&block_update.gzipped_nbt[..]);
+        let nbt: Option<crate::nbt::NamedTag> = match protocol::Serializable::read_from(&mut data) {
+            Ok(nbt) => nbt,
+            Err(err) => {
+                warn!("Failed to read block entity data, {:?}", err);
+                return;
+            }
+        };
+        self.on_block_entity_update_u8(packet::play::clientbound::UpdateBlockEntity_u8 {
+            location: Position::new(block_update.x, block_update.y as i32, block_update.z),
+            action: block_update.action,
+            nbt,
+        });
+    }
+
+    fn on_block_entity_update_u8(
+        &mut self,
+        block_update: packet::play::clientbound::UpdateBlockEntity_u8,
+    ) {
+        // The action says what kind of block entity the data is for, but
+        // each block entity picks out what it needs from the data itself
+        let action = match block_update.nbt {
+            // NBT is null, so we need to remove the block entity
+            None => world::BlockEntityAction::Remove(block_update.location),
+            Some(nbt) => {
+                world::BlockEntityAction::UpdateData(Box::new((block_update.location, nbt)))
+            }
+        };
+        self.world.add_block_entity_action(action);
+    }
+
+    fn on_block_action(&mut self, block_action: packet::play::clientbound::BlockAction) {
+        // Chests and shulker boxes send the number of players looking
+        // inside them as action 1
+        if block_action.byte1 == 1 {
+            self.world
+                .add_block_entity_action(world::BlockEntityAction::SetViewers(
+                    block_action.location,
+                    block_action.byte2,
+                ));
+        }
+    }
+
+    fn on_block_action_u16(&mut self, block_action: packet::play::clientbound::BlockAction_u16) {
+        self.on_block_action(packet::play::clientbound::BlockAction {
+            location: Position::new(block_action.x, block_action.y as i32, block_action.z),
+            byte1: block_action.byte1,
+            byte2: block_action.byte2,
+            block_type: block_action.block_type,
+        });
+    }
+
+    fn on_block_break_animation(
+        &mut self,
+        animation: packet::play::clientbound::BlockBreakAnimation,
+    ) {
+        self.break_stages
+            .push((animation.entity_id.0, animation.location, animation.stage));
+    }
+
+    fn on_block_break_animation_i32(
+        &mut self,
+        animation: packet::play::clientbound::BlockBreakAnimation_i32,
+    ) {
+        self.on_block_break_animation(packet::play::clientbound::BlockBreakAnimation {
+            entity_id: animation.entity_id,
+            location: Position::new(animation.x, animation.y, animation.z),
+            stage: animation.stage,
+        });
+    }
+
+    fn on_effect(&mut self, effect: packet::play::clientbound::Effect) {
+        // The sound and particles of a block being broken
+        if effect.effect_id == 2001 {
+            let block = self.block_by_state(effect.data);
+            self.particles.push(render::particles::Spawn::BreakBlock {
+                block,
+                position: effect.location,
+            });
+        }
+    }
+
+    fn on_effect_u8y(&mut self, effect: packet::play::clientbound::Effect_u8y) {
+        self.on_effect(packet::play::clientbound::Effect {
+            effect_id: effect.effect_id,
+            location: Position::new(effect.x, effect.y as i32, effect.z),
+            data: effect.data,
+            disable_relative: effect.disable_relative,
+        });
+    }
+
+    /// Returns the block sent with effects and particles. Before 1.13 the
+    /// metadata is packed in above the block's id.
+    fn block_by_state(&self, data: i32) -> block::Block {
+        let id = if self.protocol_version >= 404 {
+            data
+        } else {
+            ((data & 0xfff) << 4) | ((data >> 12) & 0xf)
+        };
+        self.world
+            .id_map
+            .by_vanilla_id(id as usize, &self.world.modded_block_ids)
+    }
+
+    fn on_particle_f64(&mut self, particle: packet::play::clientbound::Particle_f64) {
+        let name = match particle::name_by_id(self.protocol_version, particle.particle_id) {
+            Some(name) => name,
+            None => return,
+        };
+        self.on_particle(
+            name,
+            cgmath::Vector3::new(particle.x, particle.y, particle.z),
+            (particle.offset_x, particle.offset_y, particle.offset_z),
+            particle.speed,
+            particle.count,
+            Some(self.block_by_state(particle.block_state.0)),
+            Some((particle.red, particle.green, particle.blue, particle.scale)),
+        );
+    }
+
+    fn on_particle_f32(&mut self, particle: packet::play::clientbound::Particle_f32) {
+        self.on_particle_f64(packet::play::clientbound::Particle_f64 {
+            particle_id: particle.particle_id,
+            long_distance: particle.long_distance,
+            x: f64::from(particle.x),
+            y: f64::from(particle.y),
+            z: f64::from(particle.z),
+            offset_x: particle.offset_x,
+            offset_y: particle.offset_y,
+            offset_z: particle.offset_z,
+            speed: particle.speed,
+            count: particle.count,
+            block_state: particle.block_state,
+            red: particle.red,
+            green: particle.green,
+            blue: particle.blue,
+            scale: particle.scale,
+            ..Default::default()
+        });
+    }
+
+    fn on_particle_varintarray(
+        &mut self,
+        particle: packet::play::clientbound::Particle_VarIntArray,
+    ) {
+        let name = match particle::name_by_id(self.protocol_version, particle.particle_id) {
+            Some(name) => name,
+            None => return,
+        };
+        self.on_particle(
+            name,
+            cgmath::Vector3::new(
+                f64::from(particle.x),
+                f64::from(particle.y),
+                f64::from(particle.z),
+            ),
+            (particle.offset_x, particle.offset_y, particle.offset_z),
+            particle.speed,
+            particle.count,
+            Some(self.block_by_state(particle.data1.0)),
+            None,
+        );
+    }
+
+    fn on_particle_named(&mut self, particle: packet::play::clientbound::Particle_Named) {
+        // Pieces of blocks have the id and metadata of the block in the
+        // name, like blockcrack_1_0
+        let mut parts = particle.particle_id.split('_');
+        let name = parts.next().unwrap_or_default();
+        let mut number = || parts.next().and_then(|v| v.parse::<i32>().ok());
+        let block = match (number(), number()) {
+            (Some(id), data) => Some(self.world.id_map.by_vanilla_id(
+                ((id << 4) | (data.unwrap_or(0) & 0xf)) as usize,
+                &self.world.modded_block_ids,
+            )),
+            _ => None,
+        };
+        self.on_particle(
+            name,
+            cgmath::Vector3::new(
+                f64::from(particle.x),
+                f64::from(particle.y),
+                f64::from(particle.z),
+            ),
+            (particle.offset_x, particle.offset_y, particle.offset_z),
+            particle.speed,
+            particle.count,
+            block,
+            None,
+        );
     }
 
-    fn on_block_entity_update_u8(
+    #[allow(clippy::too_many_arguments)]
+    fn on_particle(
         &mut self,
-        block_update: packet::play::clientbound::UpdateBlockEntity_u8,
+        name: &str,
+        position: cgmath::Vector3<f64>,
+        offset: (f32, f32, f32),
+        speed: f32,
+        count: i32,
+        block: Option<block::Block>,
+        dust: Option<(f32, f32, f32, f32)>,
     ) {
-        match block_update.nbt {
-            None => {
-                // NBT is null, so we need to remove the block entity
-                self.world
-                    .add_block_entity_action(world::BlockEntityAction::Remove(
-                        block_update.location,
-                    ));
+        use render::particles::Kind;
+        let mut offset = cgmath::Vector3::new(
+            f64::from(offset.0),
+            f64::from(offset.1),
+            f64::from(offset.2),
+        );
+        let mut dust = dust.filter(|dust| dust.3 > 0.0);
+        // Before 1.13 a single redstone dust particle takes its color from
+        // the offset instead, with no red being full red
+        if name == "reddust" && count == 0 {
+            let red = if offset.x == 0.0 {
+                1.0
+            } else {
+                offset.x as f32
+            };
+            dust = Some((red, offset.y as f32, offset.z as f32, 1.0));
+            offset = cgmath::Vector3::zero();
+        }
+        let kind = if particle::is_block(name) {
+            match block {
+                Some(block) => Kind::Block(block),
+                None => return,
             }
-            Some(nbt) => {
-                match block_update.action {
-                    // TODO: support more block update actions
-                    //1 => // Mob spawner
-                    //2 => // Command block text
-                    //3 => // Beacon
-                    //4 => // Mob head
-                    //5 => // Conduit
-                    //6 => // Banner
-                    //7 => // Structure
-                    //8 => // Gateway
-                    9 => {
-                        // Sign
-                        let line1 = format::Component::from_string(
-                            nbt.1.get("Text1").unwrap().as_str().unwrap(),
-                        );
-                        let line2 = format::Component::from_string(
-                            nbt.1.get("Text2").unwrap().as_str().unwrap(),
-                        );
-                        let line3 = format::Component::from_string(
-                            nbt.1.get("Text3").unwrap().as_str().unwrap(),
-                        );
-                        let line4 = format::Component::from_string(
-                            nbt.1.get("Text4").unwrap().as_str().unwrap(),
-                        );
-                        self.world.add_block_entity_action(
-                            world::BlockEntityAction::UpdateSignText(Box::new((
-                                block_update.location,
-                                line1,
-                                line2,
-                                line3,
-                                line4,
-                            ))),
-                        );
-                    }
-                    //10 => // Unused
-                    //11 => // Jigsaw
-                    //12 => // Campfire
-                    //14 => // Beehive
-                    _ => {
-                        debug!("Unsupported block entity action: {}", block_update.action);
-                    }
-                }
+        } else {
+            match (Kind::from_name(name), dust) {
+                (Some(Kind::Dust { .. }), Some((red, green, blue, scale))) => Kind::Dust {
+                    red,
+                    green,
+                    blue,
+                    scale,
+                },
+                (Some(kind), _) => kind,
+                (None, _) => return,
             }
-        }
+        };
+        self.particles.push(render::particles::Spawn::Burst {
+            kind,
+            position,
+            offset,
+            speed: f64::from(speed),
+            count,
+        });
     }
 
     fn on_sign_update(&mut self, mut update_sign: packet::play::clientbound::UpdateSign) {
@@ -1817,27 +3619,52 @@ impl Server {
             ))));
     }
 
-    fn on_player_info_string(
-        &mut self,
-        _player_info: packet::play::clientbound::PlayerInfo_String,
-    ) {
-        // TODO: track online players, for 1.7.10 - this is for the <tab> online player list
-        // self.players in 1.7.10 will be only spawned players (within client range)
-        /*
+    fn on_sign_editor_open(&mut self, open: packet::play::clientbound::SignEditorOpen) {
+        self.sign_editor = Some(open.location);
+    }
+
+    fn on_sign_editor_open_i32(&mut self, open: packet::play::clientbound::SignEditorOpen_i32) {
+        self.sign_editor = Some(Position::new(open.x, open.y, open.z));
+    }
+
+    /// Returns the key of the player with the name, for 1.7.10 where the
+    /// player list only contains names.
+    fn player_uuid_by_name(&self, name: &str) -> protocol::UUID {
+        if name == self.username {
+            return self.uuid.clone();
+        }
+        self.players
+            .values()
+            .find(|v| v.name == name)
+            .map_or_else(|| protocol::UUID::from_name(name), |v| v.uuid.clone())
+    }
+
+    fn on_player_info_string(&mut self, player_info: packet::play::clientbound::PlayerInfo_String) {
+        let uuid = self.player_uuid_by_name(&player_info.name);
         if player_info.online {
-            self.players.entry(uuid.clone()).or_insert(PlayerInfo {
+            let info = self.players.entry(uuid.clone()).or_insert(PlayerInfo {
                 name: player_info.name.clone(),
                 uuid,
                 skin_url: None,
 
                 display_name: None,
-                ping: player_info.ping as i32,
+                ping: 0,
                 gamemode: Gamemode::from_int(0),
             });
+            info.ping = player_info.ping as i32;
         } else {
             self.players.remove(&uuid);
         }
-        */
+    }
+
+    fn on_player_list_header_footer(
+        &mut self,
+        header_footer: packet::play::clientbound::PlayerListHeaderFooter,
+    ) {
+        // Empty text hides the header or footer
+        let non_empty = |v: format::Component| Some(v).filter(|v| !v.to_string().is_empty());
+        self.player_list_header = non_empty(header_footer.header);
+        self.player_list_footer = non_empty(header_footer.footer);
     }
 
     fn on_player_info(&mut self, player_info: packet::play::clientbound::PlayerInfo) {
@@ -1876,26 +3703,8 @@ impl Server {
                         // authlib. We could download authlib on startup and extract
                         // the key but this seems like overkill compared to just
                         // whitelisting Mojang's texture servers instead.
-                        let skin_blob_result = &base64::decode(&prop.value);
-                        let skin_blob = match skin_blob_result {
-                            Ok(val) => val,
-                            Err(err) => {
-                                error!("Failed to decode skin blob, {:?}", err);
-                                continue;
-                            }
-                        };
-                        let skin_blob: serde_json::Value = match serde_json::from_slice(skin_blob) {
-                            Ok(val) => val,
-                            Err(err) => {
-                                error!("Failed to parse skin blob, {:?}", err);
-                                continue;
-                            }
-                        };
-                        if let Some(skin_url) = skin_blob
-                            .pointer("/textures/SKIN/url")
-                            .and_then(|v| v.as_str())
-                        {
-                            info.skin_url = Some(skin_url.to_owned());
+                        if let Some(skin_url) = entity::player::skin_url(&prop.value) {
+                            info.skin_url = Some(skin_url);
                         }
                     }
 
@@ -1954,11 +3763,343 @@ impl Server {
     fn on_servermessage(
         &mut self,
         message: &format::Component,
-        _position: Option<u8>,
+        position: Option<u8>,
         _sender: Option<protocol::UUID>,
     ) {
+        if position == Some(2) {
+            self.titles.set_action_bar(message.clone());
+            return;
+        }
         info!("Received chat message: {}", message);
-        self.received_chat_at = Some(Instant::now());
+        self.chat.add_message(message.clone());
+    }
+
+    fn on_boss_bar(&mut self, bar: packet::play::clientbound::BossBar) {
+        match bar.action.0 {
+            0 => self.boss_bars.add(
+                bar.uuid,
+                hud::BossBar {
+                    title: bar.title,
+                    health: bar.health,
+                    color: hud::BossBarColor::from_id(bar.color.0),
+                    style: hud::BossBarStyle::from_id(bar.style.0),
+                    flags: bar.flags,
+                },
+            ),
+            1 => self.boss_bars.remove(&bar.uuid),
+            2 => self.boss_bars.update(&bar.uuid, |v| v.health = bar.health),
+            3 => {
+                let title = bar.title;
+                self.boss_bars.update(&bar.uuid, |v| v.title = title)
+            }
+            4 => self.boss_bars.update(&bar.uuid, |v| {
+                v.color = hud::BossBarColor::from_id(bar.color.0);
+                v.style = hud::BossBarStyle::from_id(bar.style.0);
+            }),
+            5 => self.boss_bars.update(&bar.uuid, |v| v.flags = bar.flags),
+            action => warn!("Unknown boss bar action {}", action),
+        }
+    }
+
+    fn on_title(&mut self, title: packet::play::clientbound::Title) {
+        match title.action.0 {
+            0 => self.titles.set_title(title.title.unwrap_or_default()),
+            1 => self
+                .titles
+                .set_subtitle(title.sub_title.unwrap_or_default()),
+            2 => self
+                .titles
+                .set_action_bar(title.action_bar_text.unwrap_or_default()),
+            3 => self.titles.set_times(
+                title.fade_in.unwrap_or_default(),
+                title.fade_stay.unwrap_or_default(),
+                title.fade_out.unwrap_or_default(),
+            ),
+            4 => self.titles.hide(),
+            5 => self.titles.reset(),
+            action => warn!("Unknown title action {}", action),
+        }
+    }
+
+    /// Titles before 1.11, which had no action bar action.
+    fn on_title_notext(&mut self, title: packet::play::clientbound::Title_notext) {
+        match title.action.0 {
+            0 => self.titles.set_title(title.title.unwrap_or_default()),
+            1 => self
+                .titles
+                .set_subtitle(title.sub_title.unwrap_or_default()),
+            2 => self.titles.set_times(
+                title.fade_in.unwrap_or_default(),
+                title.fade_stay.unwrap_or_default(),
+                title.fade_out.unwrap_or_default(),
+            ),
+            3 => self.titles.hide(),
+            4 => self.titles.reset(),
+            action => warn!("Unknown title action {}", action),
+        }
+    }
+
+    fn on_title_text(&mut self, title: packet::play::clientbound::TitleText) {
+        self.titles.set_title(title.title);
+    }
+
+    fn on_title_subtitle(&mut self, subtitle: packet::play::clientbound::TitleSubtitle) {
+        self.titles.set_subtitle(subtitle.subtitle);
+    }
+
+    fn on_title_times(&mut self, times: packet::play::clientbound::TitleTimes) {
+        self.titles
+            .set_times(times.fade_in, times.stay, times.fade_out);
+    }
+
+    fn on_clear_titles(&mut self, clear: packet::play::clientbound::ClearTitles) {
+        if clear.reset {
+            self.titles.reset();
+        } else {
+            self.titles.hide();
+        }
+    }
+
+    fn on_action_bar(&mut self, action_bar: packet::play::clientbound::ActionBar) {
+        self.titles.set_action_bar(action_bar.text);
+    }
+
+    /// Scoreboard and team text is legacy formatted before 1.13 and json
+    /// after.
+    fn scoreboard_text(&self, text: &str) -> format::Component {
+        if self.protocol_version >= 404 {
+            format::Component::from_string(text)
+        } else {
+            let mut component = format::Component::Text(format::TextComponent::new(text));
+            format::convert_legacy(&mut component);
+            component
+        }
+    }
+
+    fn on_scoreboard_objective(
+        &mut self,
+        objective: packet::play::clientbound::ScoreboardObjective,
+    ) {
+        let ty = scoreboard::ObjectiveType::from_name(&objective.ty);
+        self.set_objective(&objective.name, objective.mode, &objective.value, ty);
+    }
+
+    fn on_scoreboard_objective_varint(
+        &mut self,
+        objective: packet::play::clientbound::ScoreboardObjective_VarInt,
+    ) {
+        let ty = scoreboard::ObjectiveType::from_id(objective.ty.0);
+        self.set_objective(&objective.name, objective.mode, &objective.value, ty);
+    }
+
+    fn on_scoreboard_objective_nomode(
+        &mut self,
+        objective: packet::play::clientbound::ScoreboardObjective_NoMode,
+    ) {
+        // 1.7.10 doesn't have a type, the mode is sent in its place
+        self.set_objective(
+            &objective.name,
+            objective.ty,
+            &objective.value,
+            scoreboard::ObjectiveType::Integer,
+        );
+    }
+
+    fn set_objective(
+        &mut self,
+        name: &str,
+        mode: u8,
+        display_name: &str,
+        ty: scoreboard::ObjectiveType,
+    ) {
+        match mode {
+            0 | 2 => {
+                let display_name = self.scoreboard_text(display_name);
+                self.scoreboard.set_objective(name, display_name, ty);
+            }
+            1 => self.scoreboard.remove_objective(name),
+            mode => warn!("Unknown scoreboard objective mode {}", mode),
+        }
+    }
+
+    fn on_update_score_varint(&mut self, score: packet::play::clientbound::UpdateScore_VarInt) {
+        self.update_score(
+            &score.name,
+            score.action.0,
+            &score.object_name,
+            score.value.map(|v| v.0),
+        );
+    }
+
+    fn on_update_score_u8(&mut self, score: packet::play::clientbound::UpdateScore_u8) {
+        self.update_score(
+            &score.name,
+            score.action as i32,
+            &score.object_name,
+            score.value.map(|v| v.0),
+        );
+    }
+
+    fn on_update_score_i32(&mut self, score: packet::play::clientbound::UpdateScore_i32) {
+        self.update_score(
+            &score.name,
+            score.action as i32,
+            &score.object_name,
+            score.value,
+        );
+    }
+
+    fn update_score(&mut self, entry: &str, action: i32, objective: &str, value: Option<i32>) {
+        match action {
+            0 => self
+                .scoreboard
+                .set_score(entry, objective, value.unwrap_or_default()),
+            1 => self.scoreboard.remove_score(entry, objective),
+            action => warn!("Unknown score action {}", action),
+        }
+    }
+
+    fn on_scoreboard_display(&mut self, display: packet::play::clientbound::ScoreboardDisplay) {
+        if let Some(slot) = scoreboard::DisplaySlot::from_id(display.position) {
+            self.scoreboard.set_display(slot, &display.name);
+        }
+    }
+
+    fn on_teams_varint(&mut self, teams: packet::play::clientbound::Teams_VarInt) {
+        // The color is followed by formatting codes, which are ignored
+        let color = teams
+            .formatting
+            .and_then(|v| scoreboard::color_from_id(v.0));
+        self.update_team(
+            teams.name,
+            teams.mode,
+            TeamInfo {
+                display_name: teams.display_name,
+                prefix: teams.prefix,
+                suffix: teams.suffix,
+                flags: teams.flags,
+                name_tag_visibility: teams.name_tag_visibility,
+                color,
+            },
+            teams.players.map(|v| v.data),
+        );
+    }
+
+    fn on_teams_u8(&mut self, teams: packet::play::clientbound::Teams_u8) {
+        let color = teams
+            .color
+            .and_then(|v| scoreboard::color_from_id(v as i32));
+        self.update_team(
+            teams.name,
+            teams.mode,
+            TeamInfo {
+                display_name: teams.display_name,
+                prefix: teams.prefix,
+                suffix: teams.suffix,
+                flags: teams.flags,
+                name_tag_visibility: teams.name_tag_visibility,
+                color,
+            },
+            teams.players.map(|v| v.data),
+        );
+    }
+
+    fn on_teams_nocollision(&mut self, teams: packet::play::clientbound::Teams_NoCollision) {
+        let color = teams
+            .color
+            .and_then(|v| scoreboard::color_from_id(v as i32));
+        self.update_team(
+            teams.name,
+            teams.mode,
+            TeamInfo {
+                display_name: teams.display_name,
+                prefix: teams.prefix,
+                suffix: teams.suffix,
+                flags: teams.flags,
+                name_tag_visibility: teams.name_tag_visibility,
+                color,
+            },
+            teams.players.map(|v| v.data),
+        );
+    }
+
+    fn on_teams_noviscolor(&mut self, teams: packet::play::clientbound::Teams_NoVisColor) {
+        self.update_team(
+            teams.name,
+            teams.mode,
+            TeamInfo {
+                display_name: teams.display_name,
+                prefix: teams.prefix,
+                suffix: teams.suffix,
+                flags: teams.flags,
+                name_tag_visibility: None,
+                color: None,
+            },
+            teams.players.map(|v| v.data),
+        );
+    }
+
+    fn update_team(
+        &mut self,
+        name: String,
+        mode: u8,
+        info: TeamInfo,
+        players: Option<Vec<String>>,
+    ) {
+        let players = players.unwrap_or_default();
+        match mode {
+            0 | 2 => {
+                let mut team = scoreboard::Team::new(&name);
+                if let Some(display_name) = info.display_name {
+                    team.display_name = self.scoreboard_text(&display_name);
+                }
+                team.prefix = self.scoreboard_text(info.prefix.as_deref().unwrap_or(""));
+                team.suffix = self.scoreboard_text(info.suffix.as_deref().unwrap_or(""));
+                team.flags = info.flags.unwrap_or_default();
+                team.name_tag_visibility = scoreboard::NameTagVisibility::from_name(
+                    info.name_tag_visibility.as_deref().unwrap_or("always"),
+                );
+                // Before 1.13 the name takes the color left at the end of the
+                // prefix instead
+                team.color = info.color.or_else(|| {
+                    if self.protocol_version < 404 {
+                        scoreboard::trailing_color(&team.prefix)
+                    } else {
+                        None
+                    }
+                });
+                team.members = players;
+                self.scoreboard.set_team(team);
+            }
+            1 => self.scoreboard.remove_team(&name),
+            3 => self.scoreboard.add_team_members(&name, &players),
+            4 => self.scoreboard.remove_team_members(&name, &players),
+            mode => warn!("Unknown team mode {}", mode),
+        }
+        self.update_name_tags();
+    }
+
+    /// Applies the team of every player to the name shown above them.
+    fn update_name_tags(&mut self) {
+        let local_name = self.username().to_owned();
+        for entity in self.entity_map.values() {
+            let model = match self
+                .entities
+                .get_component_mut_direct::<entity::player::PlayerModel>(*entity)
+            {
+                Some(model) => model,
+                None => continue,
+            };
+            let name = model.name().to_owned();
+            let name_tag = if self.scoreboard.is_name_tag_visible(&name, &local_name) {
+                let mut text = format::Component::Text(format::TextComponent::new(&name));
+                format::convert_legacy(&mut text);
+                Some(self.scoreboard.decorate_name(&name, text))
+            } else {
+                None
+            };
+            model.set_name_tag(name_tag);
+        }
     }
 
     fn load_block_entities(&mut self, block_entities: Vec<Option<crate::nbt::NamedTag>>) {
@@ -1966,25 +4107,11 @@ impl Server {
             let x = block_entity.1.get("x").unwrap().as_int().unwrap();
             let y = block_entity.1.get("y").unwrap().as_int().unwrap();
             let z = block_entity.1.get("z").unwrap().as_int().unwrap();
-            if let Some(tile_id) = block_entity.1.get("id") {
-                let tile_id = tile_id.as_str().unwrap();
-                let action = match tile_id {
-                    // Fake a sign update
-                    "Sign" => 9,
-                    // Not something we care about, so break the loop
-                    _ => continue,
-                };
-                self.on_block_entity_update_u8(packet::play::clientbound::UpdateBlockEntity_u8 {
-                    location: Position::new(x, y, z),
-                    action,
-                    nbt: Some(block_entity.clone()),
-                });
-            } else {
-                warn!(
-                    "Block entity at ({},{},{}) missing id tag: {:?}",
-                    x, y, z, block_entity
-                );
-            }
+            self.world
+                .add_block_entity_action(world::BlockEntityAction::UpdateData(Box::new((
+                    Position::new(x, y, z),
+                    block_entity,
+                ))));
         }
     }
 
@@ -2000,7 +4127,20 @@ impl Server {
                 chunk_data.data.data,
             )
             .unwrap();
-        //self.load_block_entities(chunk_data.block_entities.data); // TODO: load entities
+        // Block entities only have their position within the chunk
+        for block_entity in chunk_data.block_entities.data {
+            if let Some(data) = block_entity.data {
+                let position = Position::new(
+                    (chunk_data.chunk_x << 4) | (block_entity.packed_xz >> 4) as i32,
+                    block_entity.y as i32,
+                    (chunk_data.chunk_z << 4) | (block_entity.packed_xz & 0xF) as i32,
+                );
+                self.world
+                    .add_block_entity_action(world::BlockEntityAction::UpdateData(Box::new((
+                        position, data,
+                    ))));
+            }
+        }
 
         // Set block light data
         self.world.set_light_data(
@@ -2210,6 +4350,65 @@ impl Server {
         )
     }
 
+    fn on_explosion_varint(&mut self, explosion: packet::play::clientbound::Explosion_VarInt) {
+        self.on_explosion(
+            (explosion.x, explosion.y, explosion.z),
+            &explosion.records.data,
+            (
+                explosion.velocity_x,
+                explosion.velocity_y,
+                explosion.velocity_z,
+            ),
+        )
+    }
+
+    fn on_explosion_i32(&mut self, explosion: packet::play::clientbound::Explosion_i32) {
+        self.on_explosion(
+            (explosion.x, explosion.y, explosion.z),
+            &explosion.records.data,
+            (
+                explosion.velocity_x,
+                explosion.velocity_y,
+                explosion.velocity_z,
+            ),
+        )
+    }
+
+    /// Removes the blocks destroyed by an explosion and pushes the player
+    /// away from it.
+    fn on_explosion(
+        &mut self,
+        center: (f32, f32, f32),
+        records: &[packet::ExplosionRecord],
+        motion: (f32, f32, f32),
+    ) {
+        // Records are offsets from the block the explosion is in
+        let (x, y, z) = (
+            center.0.floor() as i32,
+            center.1.floor() as i32,
+            center.2.floor() as i32,
+        );
+        for record in records {
+            self.world.set_block(
+                Position::new(
+                    x + i32::from(record.x),
+                    y + i32::from(record.y),
+                    z + i32::from(record.z),
+                ),
+                block::Air {},
+            );
+        }
+        if let Some(player) = self.player {
+            if let Some(velocity) = self.entities.get_component_mut(player, self.velocity) {
+                velocity.velocity += cgmath::Vector3::new(
+                    f64::from(motion.0),
+                    f64::from(motion.1),
+                    f64::from(motion.2),
+                );
+            }
+        }
+    }
+
     fn on_block_change_varint(
         &mut self,
         block_change: packet::play::clientbound::BlockChange_VarInt,
@@ -2352,3 +4551,357 @@ fn calculate_relative_teleport(flag: TeleportFlag, flags: u8, base: f64, val: f6
         base + val
     }
 }
+
+/// Applies the modifiers of an entity attribute to its base value.
+fn attribute_value(base: f64, modifiers: &[packet::PropertyModifier]) -> f64 {
+    let with_operation = |operation| modifiers.iter().filter(move |v| v.operation == operation);
+    let added = base + with_operation(0).map(|v| v.amount).sum::<f64>();
+    let mut value = added + with_operation(1).map(|v| added * v.amount).sum::<f64>();
+    for modifier in with_operation(2) {
+        value *= 1.0 + modifier.amount;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use steven_mock_server::{script, MockServer, Running, Session};
+
+    /// Starts the mock server and connects to it.
+    fn connect(mock: MockServer, protocol_version: i32) -> (Server, Running) {
+        let address = mock.address();
+        let running = mock.spawn();
+
+        // The mock server doesn't check with the session server, so neither
+        // does the client
+        let profile = mojang::Profile {
+            username: "Tester".to_owned(),
+            id: String::new(),
+            access_token: String::new(),
+        };
+        let resources = Arc::new(RwLock::new(resources::Manager::internal_only()));
+        let server = Server::connect_with_session(
+            &protocol::resolve::Resolver::new(),
+            resources,
+            profile,
+            &address,
+            protocol_version,
+            vec![],
+            None,
+            false,
+        )
+        .unwrap();
+        (server, running)
+    }
+
+    /// Reads packets until the server disconnects, returning why.
+    fn wait_for_disconnect(server: &mut Server) -> format::TextComponent {
+        let deadline = Instant::now() + steven_mock_server::TIMEOUT;
+        while server.is_connected() {
+            server.read_packets();
+            assert!(Instant::now() < deadline, "timed out waiting to disconnect");
+            thread::sleep(Duration::from_millis(10));
+        }
+        match server.disconnect_reason.clone() {
+            Some(format::Component::Text(reason)) => reason,
+            None => panic!("disconnected without a reason"),
+        }
+    }
+
+    /// Joins a mock server that spawns the player, loads a chunk (if there
+    /// is a fixture for the version) and teleports the player into it.
+    /// Returns what the client sent once it has handled all of that.
+    fn join(protocol_version: i32, encryption: bool) -> Session {
+        let fixture = script::ChunkFixture::for_version(protocol_version);
+        let (chunk_x, chunk_z) = fixture.map_or((0, 0), |f| (f.x, f.z));
+        let target = cgmath::Vector3::new(
+            (chunk_x << 4) as f64 + 8.0,
+            100.0,
+            (chunk_z << 4) as f64 + 8.0,
+        );
+
+        let mut mock = MockServer::bind(protocol_version)
+            .unwrap()
+            .compression(256)
+            .send(script::join_game(protocol_version, 1, 1));
+        if encryption {
+            mock = mock.encryption();
+        }
+        if let Some(fixture) = fixture {
+            mock = mock.send(fixture.packet().unwrap());
+        }
+        let mock = mock.send(script::teleport(
+            protocol_version,
+            target.x,
+            target.y,
+            target.z,
+        ));
+        let (mut server, running) = connect(mock, protocol_version);
+
+        let deadline = Instant::now() + steven_mock_server::TIMEOUT;
+        loop {
+            server.read_packets();
+            assert!(
+                server.is_connected(),
+                "disconnected from protocol version {}: {:?}",
+                protocol_version,
+                server.disconnect_reason
+            );
+            let position = server
+                .player
+                .and_then(|player| {
+                    server
+                        .entities
+                        .get_component(player, server.target_position)
+                })
+                .map(|v| v.position);
+            let loaded = fixture.is_none() || server.world.is_chunk_loaded(chunk_x, chunk_z);
+            if position == Some(target) && loaded {
+                break;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "timed out joining protocol version {}",
+                protocol_version
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        running.finish().unwrap()
+    }
+
+    /// Returns a server that isn't connected, to feed packets to directly.
+    fn offline(protocol_version: i32) -> Server {
+        let resources = Arc::new(RwLock::new(resources::Manager::internal_only()));
+        Server::new(
+            protocol_version,
+            vec![],
+            protocol::UUID::default(),
+            "Tester".to_owned(),
+            resources,
+            Arc::new(RwLock::new(None)),
+            None,
+        )
+    }
+
+    #[test]
+    fn explosion_with_negative_center() {
+        let mut server = offline(protocol::SUPPORTED_PROTOCOLS[0]);
+        let stone = block::Stone {
+            variant: block::StoneVariant::Normal,
+        };
+        server.world.set_block(Position::new(-1, 64, -1), stone);
+        server.world.set_block(Position::new(0, 64, 0), stone);
+        server.world.set_block(Position::new(-2, 65, -1), stone);
+
+        // The center is in the block at -1, 64, -1, not the one at 0, 64, 0
+        // that rounding towards zero would give
+        let records = [
+            packet::ExplosionRecord { x: 0, y: 0, z: 0 },
+            packet::ExplosionRecord { x: -1, y: 1, z: 0 },
+        ];
+        server.on_explosion((-0.5, 64.5, -0.5), &records, (0.0, 0.0, 0.0));
+        assert_eq!(
+            server.world.get_block(Position::new(-1, 64, -1)),
+            block::Air {}
+        );
+        assert_eq!(
+            server.world.get_block(Position::new(-2, 65, -1)),
+            block::Air {}
+        );
+        assert_eq!(server.world.get_block(Position::new(0, 64, 0)), stone);
+    }
+
+    #[test]
+    fn join_all_versions() {
+        for &version in protocol::SUPPORTED_PROTOCOLS.iter() {
+            let session = join(version, false);
+            assert_eq!(session.username.as_deref(), Some("Tester"));
+
+            let confirm =
+                packet::Packet::TeleportConfirm(packet::play::serverbound::TeleportConfirm {
+                    teleport_id: protocol::VarInt(1),
+                });
+            if confirm.id(version).is_some() {
+                assert!(
+                    session.received.contains(&confirm),
+                    "protocol version {} didn't confirm the teleport",
+                    version
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn join_encrypted() {
+        let session = join(protocol::SUPPORTED_PROTOCOLS[0], true);
+        assert_eq!(session.username.as_deref(), Some("Tester"));
+        let session = join(5, true);
+        assert_eq!(session.username.as_deref(), Some("Tester"));
+    }
+
+    /// A stack of stone as the protocol version reads it back.
+    fn stone(protocol_version: i32) -> crate::item::Stack {
+        crate::item::Stack {
+            id: 1,
+            count: 3,
+            damage: if protocol_version >= 404 {
+                None
+            } else {
+                Some(0)
+            },
+            tag: None,
+        }
+    }
+
+    /// Receives the items, which should put stone in the first hotbar slot,
+    /// clicks that slot and returns what the client sent. The mock server
+    /// rejects the first click on versions with transactions.
+    fn click_stone(protocol_version: i32, items: packet::Packet) -> Session {
+        let mut mock = MockServer::bind(protocol_version)
+            .unwrap()
+            .send(script::join_game(protocol_version, 1, 1))
+            .send(items);
+        let reject =
+            packet::Packet::ConfirmTransaction(packet::play::clientbound::ConfirmTransaction {
+                id: 0,
+                action_number: 1,
+                accepted: false,
+            });
+        if reject.id(protocol_version).is_some() {
+            mock = mock.send(reject);
+        }
+        let (mut server, running) = connect(mock, protocol_version);
+
+        let deadline = Instant::now() + steven_mock_server::TIMEOUT;
+        while server.inventory_context.player_inventory.get(36).is_none() {
+            server.read_packets();
+            assert!(server.is_connected(), "{:?}", server.disconnect_reason);
+            assert!(Instant::now() < deadline, "timed out waiting for items");
+            thread::sleep(Duration::from_millis(10));
+        }
+        server.click_window(36, inventory::Click::Left);
+        running.finish().unwrap()
+    }
+
+    #[test]
+    fn click_window_with_action_numbers() {
+        let version = 340;
+        let mut items = vec![None; 46];
+        items[36] = Some(stone(version));
+        let session = click_stone(
+            version,
+            packet::Packet::WindowItems_i16(packet::play::clientbound::WindowItems_i16 {
+                id: 0,
+                items: protocol::LenPrefixed::new(items),
+            }),
+        );
+        let click = packet::Packet::ClickWindow(packet::play::serverbound::ClickWindow {
+            id: 0,
+            slot: 36,
+            button: 0,
+            action_number: 1,
+            mode: protocol::VarInt(0),
+            clicked_item: Some(stone(version)),
+        });
+        assert!(session.received.contains(&click), "{:?}", session.received);
+        let confirm = packet::Packet::ConfirmTransactionServerbound(
+            packet::play::serverbound::ConfirmTransactionServerbound {
+                id: 0,
+                action_number: 1,
+                accepted: true,
+            },
+        );
+        assert!(
+            session.received.contains(&confirm),
+            "{:?}",
+            session.received
+        );
+    }
+
+    #[test]
+    fn click_window_with_state() {
+        let version = protocol::SUPPORTED_PROTOCOLS[0];
+        let mut items = vec![None; 46];
+        items[36] = Some(stone(version));
+        let session = click_stone(
+            version,
+            packet::Packet::WindowItems_StateCarry(
+                packet::play::clientbound::WindowItems_StateCarry {
+                    id: 0,
+                    state_id: protocol::VarInt(5),
+                    items: protocol::LenPrefixed::new(items),
+                    carried_item: None,
+                },
+            ),
+        );
+        let click =
+            packet::Packet::ClickWindow_State(packet::play::serverbound::ClickWindow_State {
+                id: 0,
+                slot: 36,
+                state: protocol::VarInt(5),
+                button: 0,
+                mode: protocol::VarInt(0),
+                slots: protocol::LenPrefixed::new(vec![packet::NumberedSlot {
+                    slot_number: 36,
+                    slot_data: None,
+                }]),
+                clicked_item: Some(stone(version)),
+            });
+        assert!(session.received.contains(&click), "{:?}", session.received);
+        // Transactions were replaced by the state id in 1.17
+        assert!(!session
+            .received
+            .iter()
+            .any(|v| matches!(v, packet::Packet::ConfirmTransactionServerbound(_))));
+    }
+
+    #[test]
+    fn closed_connection_reason() {
+        let err = protocol::Error::IOError(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        match disconnect_reason(err) {
+            format::Component::Text(reason) => {
+                assert_eq!(reason.text, "Connection closed by the server");
+                assert_eq!(reason.modifier.color, Some(format::Color::Red));
+            }
+        }
+    }
+
+    #[test]
+    fn disconnect_on_unknown_packet() {
+        let version = protocol::SUPPORTED_PROTOCOLS[0];
+        let mock = MockServer::bind(version)
+            .unwrap()
+            .send(script::join_game(version, 1, 1))
+            .send_raw(0x7f, vec![1, 2, 3]);
+        let (mut server, running) = connect(mock, version);
+
+        let reason = wait_for_disconnect(&mut server);
+        assert!(
+            reason
+                .text
+                .starts_with("Failed to read a packet from the server: "),
+            "unexpected reason: {}",
+            reason.text
+        );
+        assert_eq!(reason.modifier.color, Some(format::Color::Red));
+        assert!(server.player.is_none());
+        running.finish().unwrap();
+    }
+
+    #[test]
+    fn disconnect_on_closed_connection() {
+        let version = protocol::SUPPORTED_PROTOCOLS[0];
+        let mock = MockServer::bind(version)
+            .unwrap()
+            .send(script::join_game(version, 1, 1));
+        let (mut server, running) = connect(mock, version);
+
+        // Finishing drops the connection on the server's side
+        running.finish().unwrap();
+        let reason = wait_for_disconnect(&mut server);
+        assert_eq!(reason.text, "Connection closed by the server");
+        assert_eq!(reason.modifier.color, Some(format::Color::Red));
+    }
+}
@@ -0,0 +1,376 @@
+//! The names of particles by the ids used for them in each version of the
+//! protocol.
+
+// Numbered particles from 1.8 until 1.13
+const LEGACY: &[&str] = &[
+    "explode",
+    "largeexplode",
+    "hugeexplosion",
+    "fireworksSpark",
+    "bubble",
+    "splash",
+    "wake",
+    "suspended",
+    "depthsuspend",
+    "crit",
+    "magicCrit",
+    "smoke",
+    "largesmoke",
+    "spell",
+    "instantSpell",
+    "mobSpell",
+    "mobSpellAmbient",
+    "witchMagic",
+    "dripWater",
+    "dripLava",
+    "angryVillager",
+    "happyVillager",
+    "townaura",
+    "note",
+    "portal",
+    "enchantmenttable",
+    "flame",
+    "lava",
+    "footstep",
+    "cloud",
+    "reddust",
+    "snowballpoof",
+    "snowshovel",
+    "slime",
+    "heart",
+    "barrier",
+    "iconcrack",
+    "blockcrack",
+    "blockdust",
+    "droplet",
+    "take",
+    "mobappearance",
+    "dragonbreath",
+    "endRod",
+    "damageIndicator",
+    "sweepAttack",
+    "fallingdust",
+    "totem",
+    "spit",
+];
+
+const V1_13: &[&str] = &[
+    "ambient_entity_effect",
+    "angry_villager",
+    "barrier",
+    "block",
+    "bubble",
+    "cloud",
+    "crit",
+    "damage_indicator",
+    "dragon_breath",
+    "dripping_lava",
+    "dripping_water",
+    "dust",
+    "effect",
+    "elder_guardian",
+    "enchanted_hit",
+    "enchant",
+    "end_rod",
+    "entity_effect",
+    "explosion_emitter",
+    "explosion",
+    "falling_dust",
+    "firework",
+    "fishing",
+    "flame",
+    "happy_villager",
+    "heart",
+    "instant_effect",
+    "item",
+    "item_slime",
+    "item_snowball",
+    "large_smoke",
+    "lava",
+    "mycelium",
+    "note",
+    "poof",
+    "portal",
+    "rain",
+    "smoke",
+    "spit",
+    "squid_ink",
+    "sweep_attack",
+    "totem_of_undying",
+    "underwater",
+    "splash",
+    "witch",
+];
+
+const V1_14: &[&str] = &[
+    "ambient_entity_effect",
+    "angry_villager",
+    "barrier",
+    "block",
+    "bubble",
+    "cloud",
+    "crit",
+    "damage_indicator",
+    "dragon_breath",
+    "dripping_lava",
+    "falling_lava",
+    "landing_lava",
+    "dripping_water",
+    "falling_water",
+    "dust",
+    "effect",
+    "elder_guardian",
+    "enchanted_hit",
+    "enchant",
+    "end_rod",
+    "entity_effect",
+    "explosion_emitter",
+    "explosion",
+    "falling_dust",
+    "firework",
+    "fishing",
+    "flame",
+    "flash",
+    "happy_villager",
+    "composter",
+    "heart",
+    "instant_effect",
+    "item",
+    "item_slime",
+    "item_snowball",
+    "large_smoke",
+    "lava",
+    "mycelium",
+    "note",
+    "poof",
+    "portal",
+    "rain",
+    "smoke",
+    "sneeze",
+    "spit",
+    "squid_ink",
+    "sweep_attack",
+    "totem_of_undying",
+    "underwater",
+    "splash",
+    "witch",
+];
+
+const V1_16: &[&str] = &[
+    "ambient_entity_effect",
+    "angry_villager",
+    "barrier",
+    "block",
+    "bubble",
+    "cloud",
+    "crit",
+    "damage_indicator",
+    "dragon_breath",
+    "dripping_lava",
+    "falling_lava",
+    "landing_lava",
+    "dripping_water",
+    "falling_water",
+    "dust",
+    "effect",
+    "elder_guardian",
+    "enchanted_hit",
+    "enchant",
+    "end_rod",
+    "entity_effect",
+    "explosion_emitter",
+    "explosion",
+    "falling_dust",
+    "firework",
+    "fishing",
+    "flame",
+    "soul_fire_flame",
+    "soul",
+    "flash",
+    "happy_villager",
+    "composter",
+    "heart",
+    "instant_effect",
+    "item",
+    "item_slime",
+    "item_snowball",
+    "large_smoke",
+    "lava",
+    "mycelium",
+    "note",
+    "poof",
+    "portal",
+    "rain",
+    "smoke",
+    "sneeze",
+    "spit",
+    "squid_ink",
+    "sweep_attack",
+    "totem_of_undying",
+    "underwater",
+    "splash",
+    "witch",
+];
+
+const V1_17: &[&str] = &[
+    "ambient_entity_effect",
+    "angry_villager",
+    "barrier",
+    "light",
+    "block",
+    "bubble",
+    "cloud",
+    "crit",
+    "damage_indicator",
+    "dragon_breath",
+    "dripping_lava",
+    "falling_lava",
+    "landing_lava",
+    "dripping_water",
+    "falling_water",
+    "dust",
+    "dust_color_transition",
+    "effect",
+    "elder_guardian",
+    "enchanted_hit",
+    "enchant",
+    "end_rod",
+    "entity_effect",
+    "explosion_emitter",
+    "explosion",
+    "falling_dust",
+    "firework",
+    "fishing",
+    "flame",
+    "soul_fire_flame",
+    "soul",
+    "flash",
+    "happy_villager",
+    "composter",
+    "heart",
+    "instant_effect",
+    "item",
+    "vibration",
+    "item_slime",
+    "item_snowball",
+    "large_smoke",
+    "lava",
+    "mycelium",
+    "note",
+    "poof",
+    "portal",
+    "rain",
+    "smoke",
+    "sneeze",
+    "spit",
+    "squid_ink",
+    "sweep_attack",
+    "totem_of_undying",
+    "underwater",
+    "splash",
+    "witch",
+];
+
+// Barriers and light were merged into block markers
+const V1_18: &[&str] = &[
+    "ambient_entity_effect",
+    "angry_villager",
+    "block",
+    "block_marker",
+    "bubble",
+    "cloud",
+    "crit",
+    "damage_indicator",
+    "dragon_breath",
+    "dripping_lava",
+    "falling_lava",
+    "landing_lava",
+    "dripping_water",
+    "falling_water",
+    "dust",
+    "dust_color_transition",
+    "effect",
+    "elder_guardian",
+    "enchanted_hit",
+    "enchant",
+    "end_rod",
+    "entity_effect",
+    "explosion_emitter",
+    "explosion",
+    "falling_dust",
+    "firework",
+    "fishing",
+    "flame",
+    "soul_fire_flame",
+    "soul",
+    "flash",
+    "happy_villager",
+    "composter",
+    "heart",
+    "instant_effect",
+    "item",
+    "vibration",
+    "item_slime",
+    "item_snowball",
+    "large_smoke",
+    "lava",
+    "mycelium",
+    "note",
+    "poof",
+    "portal",
+    "rain",
+    "smoke",
+    "sneeze",
+    "spit",
+    "squid_ink",
+    "sweep_attack",
+    "totem_of_undying",
+    "underwater",
+    "splash",
+    "witch",
+];
+
+/// Returns the name of the particle with the id used by the protocol
+/// version, if it is one we know about.
+pub fn name_by_id(protocol_version: i32, id: i32) -> Option<&'static str> {
+    let names = match protocol_version {
+        v if v >= 757 => V1_18,
+        v if v >= 755 => V1_17,
+        v if v >= 735 => V1_16,
+        v if v >= 477 => V1_14,
+        v if v >= 393 => V1_13,
+        _ => LEGACY,
+    };
+    usize::try_from(id)
+        .ok()
+        .and_then(|id| names.get(id))
+        .copied()
+}
+
+/// Returns whether the particle is made of pieces of a block, and has the
+/// block with it.
+pub fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "block" | "falling_dust" | "blockcrack" | "blockdust" | "fallingdust"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_particles() {
+        assert_eq!(name_by_id(340, 37), Some("blockcrack"));
+        assert_eq!(name_by_id(404, 3), Some("block"));
+        assert_eq!(name_by_id(404, 20), Some("falling_dust"));
+        assert_eq!(name_by_id(578, 3), Some("block"));
+        assert_eq!(name_by_id(754, 23), Some("falling_dust"));
+        assert_eq!(name_by_id(756, 4), Some("block"));
+        assert_eq!(name_by_id(757, 2), Some("block"));
+        assert_eq!(name_by_id(757, 24), Some("falling_dust"));
+        assert_eq!(name_by_id(757, 14), Some("dust"));
+        assert_eq!(name_by_id(757, -1), None);
+    }
+}
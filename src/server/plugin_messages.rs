@@ -7,9 +7,7 @@ pub struct Brand {
 }
 
 impl Brand {
-    pub fn into_message(self) -> PluginMessageServerbound {
-        let protocol_version = crate::protocol::current_protocol_version();
-
+    pub fn into_message(self, protocol_version: i32) -> PluginMessageServerbound {
         let channel_name = if protocol_version >= 404 {
             "minecraft:brand"
         } else {
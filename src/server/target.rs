@@ -1,3 +1,4 @@
+use crate::ecs;
 use crate::render;
 use crate::render::model;
 use crate::shared::{Direction, Position};
@@ -42,96 +43,161 @@ impl Info {
         if let Some(model) = self.model.take() {
             renderer.model.remove_model(model);
         }
-        let mut parts = vec![];
-
-        const LINE_SIZE: f64 = 1.0 / 128.0;
-        let tex = render::Renderer::get_texture(renderer.get_textures_ref(), "steven:solid");
-
-        for bound in bl.get_collision_boxes() {
-            let bound = bound.add_v(cgmath::Vector3::new(
+        let bounds = bl.get_collision_boxes().into_iter().map(|v| {
+            v.add_v(cgmath::Vector3::new(
                 pos.x as f64,
                 pos.y as f64,
                 pos.z as f64,
-            ));
-            for point in [
-                (bound.min.x, bound.min.z),
-                (bound.min.x, bound.max.z),
-                (bound.max.x, bound.min.z),
-                (bound.max.x, bound.max.z),
-            ]
-            .iter()
-            {
-                model::append_box(
-                    &mut parts,
-                    (point.0 - LINE_SIZE) as f32,
-                    (bound.min.y - LINE_SIZE) as f32,
-                    (point.1 - LINE_SIZE) as f32,
-                    (LINE_SIZE * 2.0) as f32,
-                    ((bound.max.y - bound.min.y) + LINE_SIZE * 2.0) as f32,
-                    (LINE_SIZE * 2.0) as f32,
-                    [
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                    ],
-                );
-            }
+            ))
+        });
+        let parts = outline(renderer, bounds);
+        self.model = Some(renderer.model.create_model(model::DEFAULT, vec![parts]));
+    }
+}
 
-            for point in [
-                (bound.min.x, bound.min.z, bound.max.x, bound.min.z),
-                (bound.min.x, bound.max.z, bound.max.x, bound.max.z),
-                (bound.min.x, bound.min.z, bound.min.x, bound.max.z),
-                (bound.max.x, bound.min.z, bound.max.x, bound.max.z),
-            ]
-            .iter()
-            {
-                model::append_box(
-                    &mut parts,
-                    (point.0 - LINE_SIZE) as f32,
-                    (bound.min.y - LINE_SIZE) as f32,
-                    (point.1 - LINE_SIZE) as f32,
-                    ((point.2 - point.0) + (LINE_SIZE * 2.0)) as f32,
-                    (LINE_SIZE * 2.0) as f32,
-                    ((point.3 - point.1) + (LINE_SIZE * 2.0)) as f32,
-                    [
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                    ],
-                );
-                model::append_box(
-                    &mut parts,
-                    (point.0 - LINE_SIZE) as f32,
-                    (bound.max.y - LINE_SIZE) as f32,
-                    (point.1 - LINE_SIZE) as f32,
-                    ((point.2 - point.0) + (LINE_SIZE * 2.0)) as f32,
-                    (LINE_SIZE * 2.0) as f32,
-                    ((point.3 - point.1) + (LINE_SIZE * 2.0)) as f32,
-                    [
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                        Some(tex.clone()),
-                    ],
-                );
-            }
+/// Builds black lines along the edges of the boxes.
+fn outline<I>(renderer: &render::Renderer, bounds: I) -> Vec<model::Vertex>
+where
+    I: IntoIterator<Item = collision::Aabb3<f64>>,
+{
+    let mut parts = vec![];
+
+    const LINE_SIZE: f64 = 1.0 / 128.0;
+    let tex = render::Renderer::get_texture(renderer.get_textures_ref(), "steven:solid");
+
+    for bound in bounds {
+        for point in [
+            (bound.min.x, bound.min.z),
+            (bound.min.x, bound.max.z),
+            (bound.max.x, bound.min.z),
+            (bound.max.x, bound.max.z),
+        ]
+        .iter()
+        {
+            model::append_box(
+                &mut parts,
+                (point.0 - LINE_SIZE) as f32,
+                (bound.min.y - LINE_SIZE) as f32,
+                (point.1 - LINE_SIZE) as f32,
+                (LINE_SIZE * 2.0) as f32,
+                ((bound.max.y - bound.min.y) + LINE_SIZE * 2.0) as f32,
+                (LINE_SIZE * 2.0) as f32,
+                [
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                ],
+            );
         }
 
-        for part in &mut parts {
-            part.r = 0;
-            part.g = 0;
-            part.b = 0;
+        for point in [
+            (bound.min.x, bound.min.z, bound.max.x, bound.min.z),
+            (bound.min.x, bound.max.z, bound.max.x, bound.max.z),
+            (bound.min.x, bound.min.z, bound.min.x, bound.max.z),
+            (bound.max.x, bound.min.z, bound.max.x, bound.max.z),
+        ]
+        .iter()
+        {
+            model::append_box(
+                &mut parts,
+                (point.0 - LINE_SIZE) as f32,
+                (bound.min.y - LINE_SIZE) as f32,
+                (point.1 - LINE_SIZE) as f32,
+                ((point.2 - point.0) + (LINE_SIZE * 2.0)) as f32,
+                (LINE_SIZE * 2.0) as f32,
+                ((point.3 - point.1) + (LINE_SIZE * 2.0)) as f32,
+                [
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                ],
+            );
+            model::append_box(
+                &mut parts,
+                (point.0 - LINE_SIZE) as f32,
+                (bound.max.y - LINE_SIZE) as f32,
+                (point.1 - LINE_SIZE) as f32,
+                ((point.2 - point.0) + (LINE_SIZE * 2.0)) as f32,
+                (LINE_SIZE * 2.0) as f32,
+                ((point.3 - point.1) + (LINE_SIZE * 2.0)) as f32,
+                [
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                    Some(tex.clone()),
+                ],
+            );
         }
+    }
 
-        self.model = Some(renderer.model.create_model(model::DEFAULT, vec![parts]));
+    for part in &mut parts {
+        part.r = 0;
+        part.g = 0;
+        part.b = 0;
+    }
+
+    parts
+}
+
+/// Outlines the entity the player is looking at.
+pub struct EntityInfo {
+    model: Option<model::ModelKey>,
+    last_entity: Option<(ecs::Entity, collision::Aabb3<f64>)>,
+}
+
+impl Default for EntityInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntityInfo {
+    pub fn new() -> EntityInfo {
+        EntityInfo {
+            model: None,
+            last_entity: None,
+        }
+    }
+
+    pub fn clear(&mut self, renderer: &mut render::Renderer) {
+        self.last_entity = None;
+        if let Some(model) = self.model.take() {
+            renderer.model.remove_model(model);
+        }
+    }
+
+    /// Outlines `bounds`, relative to the entity, at the entity's current
+    /// position.
+    pub fn update(
+        &mut self,
+        renderer: &mut render::Renderer,
+        entity: ecs::Entity,
+        bounds: collision::Aabb3<f64>,
+        position: cgmath::Vector3<f64>,
+    ) {
+        if self.last_entity != Some((entity, bounds)) {
+            self.clear(renderer);
+            self.last_entity = Some((entity, bounds));
+            let parts = outline(renderer, Some(bounds));
+            self.model = Some(renderer.model.create_model(model::DEFAULT, vec![parts]));
+        }
+        // Only move the outline as the entity moves instead of rebuilding it
+        if let Some(model) = self.model {
+            let model = renderer.model.get_model(model).unwrap();
+            model.matrix[0] = cgmath::Matrix4::from_translation(cgmath::Vector3::new(
+                position.x as f32,
+                -position.y as f32,
+                position.z as f32,
+            ));
+        }
     }
 }
 
@@ -158,6 +224,18 @@ pub fn test_block(
     (false, None)
 }
 
+/// Returns where a ray first hits the bounds of an entity, if that is
+/// within `max` blocks of its start.
+pub fn test_entity(
+    bounds: collision::Aabb3<f64>,
+    s: cgmath::Vector3<f64>,
+    d: cgmath::Vector3<f64>,
+    max: f64,
+) -> Option<cgmath::Vector3<f64>> {
+    use cgmath::InnerSpace;
+    intersects_line(bounds, s, d).filter(|hit| (hit - s).magnitude() <= max)
+}
+
 fn find_face(bound: collision::Aabb3<f64>, hit: cgmath::Vector3<f64>) -> Direction {
     if (bound.min.x - hit.x).abs() < 0.01 {
         Direction::West
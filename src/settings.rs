@@ -1,4 +1,6 @@
 use crate::console;
+use crate::protocol;
+use instant::Duration;
 use std::marker::PhantomData;
 use winit::event::VirtualKeyCode;
 
@@ -29,6 +31,33 @@ pub const R_VSYNC: console::CVar<bool> = console::CVar {
     default: &|| false,
 };
 
+pub const R_MAX_PARTICLES: console::CVar<i64> = console::CVar {
+    ty: PhantomData,
+    name: "r_max_particles",
+    description: "The maximum number of particles drawn at once",
+    mutable: true,
+    serializable: true,
+    default: &|| 4000,
+};
+
+pub const CL_CONNECT_TIMEOUT: console::CVar<i64> = console::CVar {
+    ty: PhantomData,
+    name: "cl_connect_timeout",
+    description: "Seconds to wait for each of a server's addresses to accept a connection",
+    mutable: true,
+    serializable: true,
+    default: &|| 10,
+};
+
+pub const CL_READ_TIMEOUT: console::CVar<i64> = console::CVar {
+    ty: PhantomData,
+    name: "cl_read_timeout",
+    description: "Seconds without hearing from a server before disconnecting, 0 waits forever",
+    mutable: true,
+    serializable: true,
+    default: &|| 30,
+};
+
 pub const CL_MASTER_VOLUME: console::CVar<i64> = console::CVar {
     ty: PhantomData,
     name: "cl_master_volume",
@@ -70,6 +99,64 @@ pub const CL_KEYBIND_SPRINT: console::CVar<i64> =
     create_keybind!(LControl, "cl_keybind_sprint", "Keybinding for sprinting");
 pub const CL_KEYBIND_JUMP: console::CVar<i64> =
     create_keybind!(Space, "cl_keybind_jump", "Keybinding for jumping");
+pub const CL_KEYBIND_CHAT: console::CVar<i64> =
+    create_keybind!(T, "cl_keybind_chat", "Keybinding for opening the chat");
+pub const CL_KEYBIND_COMMAND: console::CVar<i64> = create_keybind!(
+    Slash,
+    "cl_keybind_command",
+    "Keybinding for opening the chat to type a command"
+);
+pub const CL_KEYBIND_PLAYER_LIST: console::CVar<i64> = create_keybind!(
+    Tab,
+    "cl_keybind_player_list",
+    "Keybinding for showing the player list while held"
+);
+
+pub const CL_KEYBIND_HOTBAR_1: console::CVar<i64> = create_keybind!(
+    Key1,
+    "cl_keybind_hotbar_1",
+    "Keybinding for selecting hotbar slot 1"
+);
+pub const CL_KEYBIND_HOTBAR_2: console::CVar<i64> = create_keybind!(
+    Key2,
+    "cl_keybind_hotbar_2",
+    "Keybinding for selecting hotbar slot 2"
+);
+pub const CL_KEYBIND_HOTBAR_3: console::CVar<i64> = create_keybind!(
+    Key3,
+    "cl_keybind_hotbar_3",
+    "Keybinding for selecting hotbar slot 3"
+);
+pub const CL_KEYBIND_HOTBAR_4: console::CVar<i64> = create_keybind!(
+    Key4,
+    "cl_keybind_hotbar_4",
+    "Keybinding for selecting hotbar slot 4"
+);
+pub const CL_KEYBIND_HOTBAR_5: console::CVar<i64> = create_keybind!(
+    Key5,
+    "cl_keybind_hotbar_5",
+    "Keybinding for selecting hotbar slot 5"
+);
+pub const CL_KEYBIND_HOTBAR_6: console::CVar<i64> = create_keybind!(
+    Key6,
+    "cl_keybind_hotbar_6",
+    "Keybinding for selecting hotbar slot 6"
+);
+pub const CL_KEYBIND_HOTBAR_7: console::CVar<i64> = create_keybind!(
+    Key7,
+    "cl_keybind_hotbar_7",
+    "Keybinding for selecting hotbar slot 7"
+);
+pub const CL_KEYBIND_HOTBAR_8: console::CVar<i64> = create_keybind!(
+    Key8,
+    "cl_keybind_hotbar_8",
+    "Keybinding for selecting hotbar slot 8"
+);
+pub const CL_KEYBIND_HOTBAR_9: console::CVar<i64> = create_keybind!(
+    Key9,
+    "cl_keybind_hotbar_9",
+    "Keybinding for selecting hotbar slot 9"
+);
 
 pub const DOUBLE_JUMP_MS: u32 = 100;
 
@@ -77,6 +164,9 @@ pub fn register_vars(vars: &mut console::Vars) {
     vars.register(R_MAX_FPS);
     vars.register(R_FOV);
     vars.register(R_VSYNC);
+    vars.register(R_MAX_PARTICLES);
+    vars.register(CL_CONNECT_TIMEOUT);
+    vars.register(CL_READ_TIMEOUT);
     vars.register(CL_MASTER_VOLUME);
     vars.register(CL_KEYBIND_FORWARD);
     vars.register(CL_KEYBIND_BACKWARD);
@@ -86,6 +176,30 @@ pub fn register_vars(vars: &mut console::Vars) {
     vars.register(CL_KEYBIND_SNEAK);
     vars.register(CL_KEYBIND_SPRINT);
     vars.register(CL_KEYBIND_JUMP);
+    vars.register(CL_KEYBIND_CHAT);
+    vars.register(CL_KEYBIND_COMMAND);
+    vars.register(CL_KEYBIND_PLAYER_LIST);
+    vars.register(CL_KEYBIND_HOTBAR_1);
+    vars.register(CL_KEYBIND_HOTBAR_2);
+    vars.register(CL_KEYBIND_HOTBAR_3);
+    vars.register(CL_KEYBIND_HOTBAR_4);
+    vars.register(CL_KEYBIND_HOTBAR_5);
+    vars.register(CL_KEYBIND_HOTBAR_6);
+    vars.register(CL_KEYBIND_HOTBAR_7);
+    vars.register(CL_KEYBIND_HOTBAR_8);
+    vars.register(CL_KEYBIND_HOTBAR_9);
+}
+
+/// Returns a resolver using the connection timeouts from the settings.
+pub fn resolver(vars: &console::Vars) -> protocol::resolve::Resolver {
+    let mut resolver = protocol::resolve::Resolver::new();
+    let connect_timeout = (*vars.get(CL_CONNECT_TIMEOUT)).max(1) as u64;
+    resolver.connect_timeout = Duration::from_secs(connect_timeout);
+    resolver.read_timeout = match *vars.get(CL_READ_TIMEOUT) {
+        v if v <= 0 => None,
+        v => Some(Duration::from_secs(v as u64)),
+    };
+    resolver
 }
 
 #[derive(Hash, PartialEq, Eq, Debug)]
@@ -98,6 +212,11 @@ pub enum Stevenkey {
     Sneak,
     Sprint,
     Jump,
+    Chat,
+    Command,
+    PlayerList,
+    /// Selects the hotbar slot with the given index
+    Hotbar(u8),
 }
 
 impl Stevenkey {
@@ -111,6 +230,18 @@ impl Stevenkey {
             Stevenkey::Sneak,
             Stevenkey::Sprint,
             Stevenkey::Jump,
+            Stevenkey::Chat,
+            Stevenkey::Command,
+            Stevenkey::PlayerList,
+            Stevenkey::Hotbar(0),
+            Stevenkey::Hotbar(1),
+            Stevenkey::Hotbar(2),
+            Stevenkey::Hotbar(3),
+            Stevenkey::Hotbar(4),
+            Stevenkey::Hotbar(5),
+            Stevenkey::Hotbar(6),
+            Stevenkey::Hotbar(7),
+            Stevenkey::Hotbar(8),
         ]
     }
 
@@ -130,6 +261,18 @@ impl Stevenkey {
             Stevenkey::Sneak => CL_KEYBIND_SNEAK,
             Stevenkey::Sprint => CL_KEYBIND_SPRINT,
             Stevenkey::Jump => CL_KEYBIND_JUMP,
+            Stevenkey::Chat => CL_KEYBIND_CHAT,
+            Stevenkey::Command => CL_KEYBIND_COMMAND,
+            Stevenkey::PlayerList => CL_KEYBIND_PLAYER_LIST,
+            Stevenkey::Hotbar(0) => CL_KEYBIND_HOTBAR_1,
+            Stevenkey::Hotbar(1) => CL_KEYBIND_HOTBAR_2,
+            Stevenkey::Hotbar(2) => CL_KEYBIND_HOTBAR_3,
+            Stevenkey::Hotbar(3) => CL_KEYBIND_HOTBAR_4,
+            Stevenkey::Hotbar(4) => CL_KEYBIND_HOTBAR_5,
+            Stevenkey::Hotbar(5) => CL_KEYBIND_HOTBAR_6,
+            Stevenkey::Hotbar(6) => CL_KEYBIND_HOTBAR_7,
+            Stevenkey::Hotbar(7) => CL_KEYBIND_HOTBAR_8,
+            Stevenkey::Hotbar(_) => CL_KEYBIND_HOTBAR_9,
         }
     }
 }
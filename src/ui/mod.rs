@@ -218,6 +218,17 @@ macro_rules! define_elements {
                 }
             }
 
+            fn handles_tab(&self) -> bool {
+                match *self {
+                    $(
+                        Element::$name(ref inner) => {
+                            let el = inner.borrow();
+                            el.handles_tab()
+                        },
+                    )*
+                }
+            }
+
             fn set_focused(&self, val: bool) {
                 match *self {
                     $(
@@ -435,7 +446,12 @@ impl Container {
         down: bool,
         ctrl_pressed: bool,
     ) {
-        if key == VirtualKeyCode::Tab {
+        let focused_handles_tab = self
+            .focusable_elements
+            .iter()
+            .flat_map(|v| v.upgrade())
+            .any(|v| v.is_focused() && v.handles_tab());
+        if key == VirtualKeyCode::Tab && !focused_handles_tab {
             if !down {
                 self.cycle_focus();
             }
@@ -520,6 +536,10 @@ trait UIElement {
     ) {
     }
     fn key_type(&mut self, _game: &mut crate::Game, _c: char) {}
+    /// Whether tab is passed to the element instead of moving the focus
+    fn handles_tab(&self) -> bool {
+        false
+    }
     fn tick(&mut self, renderer: &mut render::Renderer);
 }
 
@@ -1081,12 +1101,14 @@ element! {
         pub scale_x: f64,
         pub scale_y: f64,
         pub max_width: f64,
+        pub alpha: u8,
         priv text: format::Component,
         priv text_elements: Vec<Element>,
         priv last_text: format::Component,
         priv last_scale_x: f64,
         priv last_scale_y: f64,
         priv last_max_width: f64,
+        priv last_alpha: u8,
         priv dirty: bool,
     }
     builder FormattedBuilder {
@@ -1097,11 +1119,13 @@ element! {
         hardcode last_scale_x = 0.0,
         hardcode last_scale_y = 0.0,
         hardcode last_max_width = -1.0,
+        hardcode last_alpha = 255,
         hardcode dirty = true,
         simple text: format::Component,
         optional scale_x: f64 = 1.0,
         optional scale_y: f64 = 1.0,
         optional max_width: f64 = -1.0,
+        optional alpha: u8 = 255,
     }
 }
 
@@ -1127,6 +1151,9 @@ impl UIElement for Formatted {
                     offset: 0.0,
                     text: Vec::new(),
                     max_width: self.max_width,
+                    scale_x: self.scale_x,
+                    scale_y: self.scale_y,
+                    alpha: self.alpha,
                     renderer,
                 };
                 state.build(&self.text, format::Color::White);
@@ -1147,6 +1174,7 @@ impl UIElement for Formatted {
             self.last_scale_x = self.scale_x;
             self.last_scale_y = self.scale_y;
             self.last_max_width = self.max_width;
+            self.last_alpha = self.alpha;
             self.dirty = false;
         }
         &mut self.data
@@ -1173,6 +1201,7 @@ impl UIElement for Formatted {
             || self.last_scale_x != self.scale_x
             || self.last_scale_y != self.scale_y
             || self.last_max_width != self.max_width
+            || self.last_alpha != self.alpha
     }
 }
 
@@ -1193,6 +1222,9 @@ impl Formatted {
             offset: 0.0,
             text: Vec::new(),
             max_width,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            alpha: 255,
             renderer,
         };
         state.build(text, format::Color::White);
@@ -1202,6 +1234,9 @@ impl Formatted {
 
 struct FormatState<'a> {
     max_width: f64,
+    scale_x: f64,
+    scale_y: f64,
+    alpha: u8,
     lines: usize,
     offset: f64,
     width: f64,
@@ -1240,8 +1275,13 @@ impl<'a> FormatState<'a> {
                 let (rr, gg, bb) = color.to_rgb();
                 TextBuilder::new()
                     .text(&txt[last..i])
-                    .position(self.offset, (self.lines * 18 + 1) as f64)
-                    .colour((rr, gg, bb, 255))
+                    .position(
+                        self.offset * self.scale_x,
+                        (self.lines * 18 + 1) as f64 * self.scale_y,
+                    )
+                    .scale_x(self.scale_x)
+                    .scale_y(self.scale_y)
+                    .colour((rr, gg, bb, self.alpha))
                     .create(self);
                 last = i;
                 if c == '\n' {
@@ -1261,8 +1301,13 @@ impl<'a> FormatState<'a> {
             let (rr, gg, bb) = color.to_rgb();
             TextBuilder::new()
                 .text(&txt[last..])
-                .position(self.offset, (self.lines * 18 + 1) as f64)
-                .colour((rr, gg, bb, 255))
+                .position(
+                    self.offset * self.scale_x,
+                    (self.lines * 18 + 1) as f64 * self.scale_y,
+                )
+                .scale_x(self.scale_x)
+                .scale_y(self.scale_y)
+                .colour((rr, gg, bb, self.alpha))
                 .create(self);
             self.offset += self.renderer.ui.size_of_string(&txt[last..]) + 2.0;
             if self.offset > self.width {
@@ -1495,6 +1540,7 @@ impl Button {
 }
 
 type SubmitFunc = dyn Fn(&mut TextBox, &mut crate::Game);
+type CompleteFunc = dyn Fn(&mut TextBox, &mut crate::Game);
 
 element! {
     ref TextBoxRef
@@ -1508,6 +1554,7 @@ element! {
         priv was_focused: bool,
         priv cursor_tick: f64,
         priv submit_funcs: Vec<Box<SubmitFunc>>,
+        priv complete_funcs: Vec<Box<CompleteFunc>>,
     }
     builder TextBoxBuilder {
         hardcode button = None,
@@ -1515,6 +1562,7 @@ element! {
         hardcode was_focused = false,
         hardcode cursor_tick = 0.0,
         hardcode submit_funcs = vec![],
+        hardcode complete_funcs = vec![],
         optional input: String = "".into(),
         optional password: bool = false,
         noset width: f64 = |b| b.width.expect("Missing required field width"),
@@ -1548,6 +1596,15 @@ impl UIElement for TextBox {
                 }
                 self.submit_funcs.append(&mut temp);
             }
+            (VirtualKeyCode::Tab, true) => {
+                use std::mem;
+                let len = self.complete_funcs.len();
+                let mut temp = mem::replace(&mut self.complete_funcs, Vec::with_capacity(len));
+                for func in &temp {
+                    (func)(self, game);
+                }
+                self.complete_funcs.append(&mut temp);
+            }
             // TODO: wasm clipboard pasting, Clipboard API: https://www.w3.org/TR/clipboard-apis/
             #[cfg(not(target_arch = "wasm32"))]
             (VirtualKeyCode::V, true) => {
@@ -1618,6 +1675,10 @@ impl UIElement for TextBox {
         self.focused || self.was_focused
     }
 
+    fn handles_tab(&self) -> bool {
+        !self.complete_funcs.is_empty()
+    }
+
     fn post_init(s: Rc<RefCell<Self>>) {
         let mut textbox = s.borrow_mut();
         textbox.button = Some(
@@ -1643,6 +1704,12 @@ impl TextBox {
         self.submit_funcs.push(Box::new(f));
     }
 
+    /// Adds a function called when tab is pressed while the textbox is
+    /// focused, instead of moving the focus to the next element.
+    pub fn add_complete_func<F: Fn(&mut TextBox, &mut crate::Game) + 'static>(&mut self, f: F) {
+        self.complete_funcs.push(Box::new(f));
+    }
+
     fn transform_input(&self) -> String {
         if self.password {
             "*".repeat(self.input.len())
@@ -22,6 +22,7 @@ use crate::chunk_builder;
 use crate::ecs;
 use crate::entity::block_entity;
 use crate::format;
+use crate::nbt;
 use crate::protocol;
 use crate::render;
 use crate::shared::{Direction, Position};
@@ -73,6 +74,11 @@ pub enum BlockEntityAction {
             format::Component,
         )>,
     ),
+    /// Data sent by the server for the block entity, such as the patterns
+    /// on a banner
+    UpdateData(Box<(Position, nbt::NamedTag)>),
+    /// Sets the number of players with a chest or shulker box open
+    SetViewers(Position, u8),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -270,6 +276,21 @@ impl World {
                         }
                     }
                 }
+                BlockEntityAction::UpdateData(bx) => {
+                    let (pos, data) = *bx;
+                    if let Some(chunk) = self.chunks.get(&CPos(pos.x >> 4, pos.z >> 4)) {
+                        if let Some(entity) = chunk.block_entities.get(&pos) {
+                            block_entity::update_data(m, *entity, &data.1, self.protocol_version);
+                        }
+                    }
+                }
+                BlockEntityAction::SetViewers(pos, viewers) => {
+                    if let Some(chunk) = self.chunks.get(&CPos(pos.x >> 4, pos.z >> 4)) {
+                        if let Some(entity) = chunk.block_entities.get(&pos) {
+                            block_entity::set_viewers(m, *entity, viewers);
+                        }
+                    }
+                }
             }
         }
     }